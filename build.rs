@@ -0,0 +1,13 @@
+use std::{env, error::Error, path::PathBuf};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Uses the bundled protoc, so building does not need one installed.
+    env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("authentication_descriptor.bin"))
+        .compile_protos(&["proto/auth/v1/authentication.proto"], &["proto"])?;
+
+    Ok(())
+}
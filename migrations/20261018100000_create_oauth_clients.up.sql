@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE TABLE
+	"oauth_clients" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	client_id VARCHAR(255) NOT NULL UNIQUE,
+	name VARCHAR(255) NOT NULL,
+	client_type VARCHAR(20) NOT NULL CHECK (client_type IN ('public', 'confidential')),
+	redirect_uris TEXT[] NOT NULL DEFAULT '{}',
+	allowed_scopes TEXT[] NOT NULL DEFAULT '{}',
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+	updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+	);
+
+CREATE INDEX oauth_clients_client_id_idx ON oauth_clients (client_id);
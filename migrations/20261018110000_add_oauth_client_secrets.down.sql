@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "oauth_clients" DROP COLUMN client_secret_hash;
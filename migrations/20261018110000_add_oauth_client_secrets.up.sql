@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE "oauth_clients" ADD COLUMN client_secret_hash VARCHAR(255);
@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE
+	"user_identities" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	provider VARCHAR(100) NOT NULL,
+	subject VARCHAR(255) NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+	UNIQUE (provider, subject)
+	);
+
+CREATE INDEX user_identities_user_id_idx ON user_identities (user_id);
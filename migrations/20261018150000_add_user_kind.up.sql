@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE "users"
+	ADD COLUMN kind VARCHAR(20) NOT NULL DEFAULT 'human' CHECK (kind IN ('human', 'service')),
+	ADD COLUMN owner_id UUID REFERENCES users (id) ON DELETE CASCADE;
+
+CREATE INDEX users_owner_id_idx ON users (owner_id);
@@ -0,0 +1,32 @@
+-- Add up migration script here
+CREATE TABLE
+	"organizations" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	name VARCHAR(100) NOT NULL,
+	slug VARCHAR(63) NOT NULL UNIQUE,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+	);
+
+CREATE TABLE
+	"organization_memberships" (
+	organization_id UUID NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+	user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	role VARCHAR(20) NOT NULL CHECK (role IN ('owner', 'admin', 'member')),
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+	PRIMARY KEY (organization_id, user_id)
+	);
+
+CREATE INDEX organization_memberships_user_id_idx ON organization_memberships (user_id);
+
+CREATE TABLE
+	"organization_invitations" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	organization_id UUID NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+	email VARCHAR(255) NOT NULL,
+	role VARCHAR(20) NOT NULL CHECK (role IN ('owner', 'admin', 'member')),
+	token_hash VARCHAR(64) NOT NULL UNIQUE,
+	invited_by UUID REFERENCES users (id) ON DELETE SET NULL,
+	expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+	UNIQUE (organization_id, email)
+	);
@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE
+	"registration_invitations" (
+	id UUID NOT NULL PRIMARY KEY,
+	email VARCHAR(255) NOT NULL,
+	organization_id UUID REFERENCES organizations (id) ON DELETE CASCADE,
+	role VARCHAR(20) NOT NULL DEFAULT 'member' CHECK (role IN ('owner', 'admin', 'member')),
+	token_hash VARCHAR(64) NOT NULL UNIQUE,
+	created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+	expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+	);
@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS "admin_audit_log";
+DROP TABLE IF EXISTS "password_resets";
+ALTER TABLE "users"
+	DROP COLUMN sessions_revoked_at,
+	DROP COLUMN password_reset_required,
+	DROP COLUMN status;
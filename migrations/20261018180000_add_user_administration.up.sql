@@ -0,0 +1,28 @@
+-- Add up migration script here
+ALTER TABLE "users"
+	ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'suspended')),
+	ADD COLUMN password_reset_required BOOLEAN NOT NULL DEFAULT FALSE,
+	ADD COLUMN sessions_revoked_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE
+	"password_resets" (
+	token_hash VARCHAR(64) NOT NULL PRIMARY KEY,
+	user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+	);
+
+CREATE INDEX password_resets_user_id_idx ON password_resets (user_id);
+
+CREATE TABLE
+	"admin_audit_log" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	actor_id UUID REFERENCES users (id) ON DELETE SET NULL,
+	method VARCHAR(10) NOT NULL,
+	route VARCHAR(255) NOT NULL,
+	path VARCHAR(2048) NOT NULL,
+	status SMALLINT NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+	);
+
+CREATE INDEX admin_audit_log_created_at_idx ON admin_audit_log (created_at);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE "admin_audit_log" DROP COLUMN IF EXISTS subject_id;
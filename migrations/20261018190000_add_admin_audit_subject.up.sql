@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE "admin_audit_log"
+	ADD COLUMN subject_id UUID REFERENCES users (id) ON DELETE SET NULL;
@@ -0,0 +1,34 @@
+-- Add down migration script here
+
+CREATE TABLE
+	"admin_audit_log" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	actor_id UUID REFERENCES users (id) ON DELETE SET NULL,
+	method VARCHAR(10) NOT NULL,
+	route VARCHAR(255) NOT NULL,
+	path VARCHAR(2048) NOT NULL,
+	status SMALLINT NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+	subject_id UUID REFERENCES users (id) ON DELETE SET NULL
+	);
+
+CREATE INDEX admin_audit_log_created_at_idx ON admin_audit_log (created_at);
+
+INSERT INTO
+	admin_audit_log (actor_id, subject_id, method, route, path, status, created_at)
+SELECT
+	actors.id,
+	subjects.id,
+	details ->> 'method',
+	details ->> 'route',
+	details ->> 'path',
+	(details ->> 'status')::SMALLINT,
+	audit_events.created_at
+FROM
+	audit_events
+	LEFT JOIN users AS actors ON actors.id = audit_events.actor_id
+	LEFT JOIN users AS subjects ON subjects.id = audit_events.user_id
+WHERE
+	event_type = 'admin_action';
+
+DROP TABLE IF EXISTS "audit_events";
@@ -0,0 +1,36 @@
+-- Add up migration script here
+CREATE TABLE
+	"audit_events" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	event_type VARCHAR(50) NOT NULL,
+	outcome VARCHAR(10) NOT NULL CHECK (outcome IN ('success', 'failure')),
+	reason VARCHAR(255),
+	user_id UUID,
+	actor_id UUID,
+	ip_address VARCHAR(64),
+	user_agent VARCHAR(512),
+	request_id VARCHAR(128),
+	details JSONB NOT NULL DEFAULT '{}',
+	created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
+CREATE INDEX audit_events_created_at_idx ON audit_events (created_at);
+
+CREATE INDEX audit_events_user_id_created_at_idx ON audit_events (user_id, created_at);
+
+CREATE INDEX audit_events_actor_id_created_at_idx ON audit_events (actor_id, created_at);
+
+INSERT INTO
+	audit_events (event_type, outcome, reason, user_id, actor_id, details, created_at)
+SELECT
+	'admin_action',
+	CASE WHEN status < 400 THEN 'success' ELSE 'failure' END,
+	CASE WHEN status < 400 THEN NULL ELSE 'HTTP ' || status END,
+	subject_id,
+	actor_id,
+	jsonb_build_object('method', method, 'route', route, 'path', path, 'status', status),
+	COALESCE(created_at, NOW())
+FROM
+	admin_audit_log;
+
+DROP TABLE IF EXISTS "admin_audit_log";
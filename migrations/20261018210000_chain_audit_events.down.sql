@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "audit_checkpoints";
+
+ALTER TABLE "audit_events"
+	DROP COLUMN IF EXISTS hash,
+	DROP COLUMN IF EXISTS previous_hash,
+	DROP COLUMN IF EXISTS sequence;
@@ -0,0 +1,32 @@
+-- Add up migration script here
+ALTER TABLE "audit_events"
+	ADD COLUMN sequence BIGINT,
+	ADD COLUMN previous_hash VARCHAR(64),
+	ADD COLUMN hash VARCHAR(64);
+
+-- Events recorded before the chain existed keep their order but are left unhashed.
+UPDATE audit_events
+SET
+	sequence = numbered.sequence
+FROM
+	(
+		SELECT
+			id,
+			ROW_NUMBER() OVER (ORDER BY created_at, id) AS sequence
+		FROM
+			audit_events
+	) AS numbered
+WHERE
+	audit_events.id = numbered.id;
+
+ALTER TABLE "audit_events"
+	ALTER COLUMN sequence SET NOT NULL,
+	ADD CONSTRAINT audit_events_sequence_key UNIQUE (sequence);
+
+CREATE TABLE
+	"audit_checkpoints" (
+	sequence BIGINT NOT NULL PRIMARY KEY,
+	hash VARCHAR(64) NOT NULL,
+	signature TEXT NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
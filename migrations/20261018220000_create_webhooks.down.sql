@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "webhook_outbox";
+
+DROP TABLE IF EXISTS "webhook_subscriptions";
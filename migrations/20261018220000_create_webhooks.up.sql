@@ -0,0 +1,29 @@
+-- Add up migration script here
+CREATE TABLE
+	"webhook_subscriptions" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	url TEXT NOT NULL,
+	events TEXT[] NOT NULL,
+	secret VARCHAR(128) NOT NULL,
+	created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+	created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+	);
+
+CREATE TABLE
+	"webhook_outbox" (
+	id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+	subscription_id UUID NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+	event_id UUID NOT NULL,
+	event_type VARCHAR(50) NOT NULL,
+	payload JSONB NOT NULL,
+	status VARCHAR(10) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'dead')),
+	attempts INTEGER NOT NULL DEFAULT 0,
+	next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+	last_error TEXT,
+	created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+	delivered_at TIMESTAMP WITH TIME ZONE
+	);
+
+CREATE INDEX webhook_outbox_pending_idx ON webhook_outbox (next_attempt_at) WHERE status = 'pending';
+
+CREATE INDEX webhook_outbox_subscription_id_created_at_idx ON webhook_outbox (subscription_id, created_at);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DELETE FROM "user_identities"
+WHERE
+	provider = 'ldap';
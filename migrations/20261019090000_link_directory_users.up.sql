@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- Users provisioned by LDAP logins are the users without a password nor an identity, as
+-- federated and SAML users are always linked to theirs.
+INSERT INTO
+	"user_identities" (user_id, provider, subject)
+SELECT
+	id,
+	'ldap',
+	email
+FROM
+	users
+WHERE
+	password = ''
+	AND kind = 'human'
+	AND NOT EXISTS (
+		SELECT
+			1
+		FROM
+			user_identities
+		WHERE
+			user_identities.user_id = users.id
+	);
@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    api::{
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::admin_user::{ListUsersSchema, UpdateUserSchema},
+        utils::extractors::{ApiJson, ApiPath, ApiQuery},
+    },
+    application::AppState,
+    domain::{
+        admin_user_service::AdminUserService,
+        auth_service::AuthService,
+        model::{
+            admin_user::{AdminUser, DeleteUserRequest, UserPage},
+            auth_middleware::AuthMiddleware,
+            password_reset::PasswordResetResponse,
+            user_id::UserId,
+        },
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users",
+    tag = "admin",
+    summary = "Lists users",
+    params(
+        ListUsersSchema,
+    ),
+    responses(
+        (status = 200, description = "A page of users", body = ApiResponse<UserPage>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 422, description = "Invalid page or page size", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_users_handler<AS: AuthService + AdminUserService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiQuery(params): ApiQuery<ListUsersSchema>,
+) -> Result<ApiResponse<UserPage>, ApiError> {
+    state
+        .auth_service
+        .list_users(&params.into_domain())
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{user_id}",
+    tag = "admin",
+    summary = "Returns a user",
+    params(
+        ("user_id" = uuid::Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "The user", body = ApiResponse<AdminUser>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn get_user_handler<AS: AuthService + AdminUserService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(user_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<AdminUser>, ApiError> {
+    state
+        .auth_service
+        .fetch_user(&UserId::new(user_id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/users/{user_id}",
+    tag = "admin",
+    summary = "Updates a user",
+    params(
+        ("user_id" = uuid::Uuid, Path, description = "The id of the user"),
+    ),
+    request_body = UpdateUserSchema,
+    responses(
+        (status = 200, description = "The updated user", body = ApiResponse<AdminUser>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse),
+        (status = 422, description = "Nothing to update, a self suspension, or the email is already registered", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn update_user_handler<AS: AuthService + AdminUserService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(user_id): ApiPath<uuid::Uuid>,
+    ApiJson(body): ApiJson<UpdateUserSchema>,
+) -> Result<ApiResponse<AdminUser>, ApiError> {
+    let admin = auth_guard.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+    let domain_request = body.try_into_domain(admin.id, user_id)?;
+
+    state
+        .auth_service
+        .update_user(&domain_request)
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{user_id}",
+    tag = "admin",
+    summary = "Deletes a user",
+    params(
+        ("user_id" = uuid::Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "The user is deleted", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse),
+        (status = 422, description = "Administrators cannot delete their own account", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn delete_user_handler<AS: AuthService + AdminUserService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(user_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    let admin = auth_guard.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+
+    state
+        .auth_service
+        .delete_user(&DeleteUserRequest {
+            actor_id: UserId::new(admin.id),
+            user_id: UserId::new(user_id),
+        })
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("User deleted"))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{user_id}/password-reset",
+    tag = "admin",
+    summary = "Forces a user to reset their password",
+    params(
+        ("user_id" = uuid::Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "The reset token, whose value is only ever returned here", body = ApiResponse<PasswordResetResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn force_password_reset_handler<AS: AuthService + AdminUserService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(user_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<PasswordResetResponse>, ApiError> {
+    state
+        .auth_service
+        .force_password_reset(&UserId::new(user_id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{user_id}/sessions",
+    tag = "admin",
+    summary = "Revokes every session of a user",
+    params(
+        ("user_id" = uuid::Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "The sessions are revoked", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn revoke_user_sessions_handler<AS: AuthService + AdminUserService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(user_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    state
+        .auth_service
+        .revoke_sessions(&UserId::new(user_id))
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("User sessions revoked"))
+}
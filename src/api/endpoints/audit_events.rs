@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    api::{
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::audit_event::ListAuditEventsSchema,
+        utils::extractors::ApiQuery,
+    },
+    application::AppState,
+    domain::{
+        audit_service::AuditService,
+        auth_service::AuthService,
+        model::{
+            audit::{AuditChainReport, AuditEvent, AuditEventPage},
+            auth_middleware::AuthMiddleware,
+            user_id::UserId,
+        },
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit-events",
+    tag = "admin",
+    summary = "Lists audit events",
+    params(
+        ListAuditEventsSchema,
+    ),
+    responses(
+        (status = 200, description = "A page of audit events, newest first", body = ApiResponse<AuditEventPage>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 422, description = "Invalid page, page size or time range", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_audit_events_handler<AS: AuthService + AuditService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiQuery(params): ApiQuery<ListAuditEventsSchema>,
+) -> Result<ApiResponse<AuditEventPage>, ApiError> {
+    state
+        .auth_service
+        .list_audit_events(&params.into_domain())
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/activity",
+    tag = "users",
+    summary = "Lists the recent activity of the authenticated user",
+    responses(
+        (status = 200, description = "The most recent audit events of the authenticated user", body = ApiResponse<Vec<AuditEvent>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The token lacks the profile scope or belongs to an OAuth client", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn recent_activity_handler<AS: AuthService + AuditService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<Vec<AuditEvent>>, ApiError> {
+    let user = auth_guard.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+
+    state
+        .auth_service
+        .list_recent_activity(&UserId::new(user.id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+/// Walks the whole audit log chain. A broken chain is still a successful verification, so
+/// the report is returned with `200 OK` either way.
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit-events/verify",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The verification report", body = ApiResponse<AuditChainReport>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn verify_audit_chain_handler<AS: AuthService + AuditService>(
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<AuditChainReport>, ApiError> {
+    state
+        .auth_service
+        .verify_audit_chain()
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    api::{
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::change_password::ChangePasswordSchema,
+        utils::extractors::ApiJson,
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{audit::RequestContext, auth_middleware::AuthMiddleware},
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/users/me/password",
+    tag = "users",
+    summary = "Changes the password of the authenticated user",
+    request_body = ChangePasswordSchema,
+    responses(
+        (status = 200, description = "The password is changed", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "Not allowed while impersonating a user", body = ApiErrorResponse),
+        (status = 422, description = "Wrong current password, or an invalid new password", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn change_password_handler<AS: AuthService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<ChangePasswordSchema>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    let user = auth_guard
+        .user()
+        .filter(|_| auth_guard.is_login_session())
+        .ok_or_else(|| {
+            ApiError::Forbidden(ApiErrorBody::new(
+                ErrorCode::AuthLoginSessionRequired,
+                "Only available to login sessions",
+            ))
+        })?;
+    let domain_request = body.try_into_domain(user.id, context)?;
+
+    state
+        .auth_service
+        .change_password(&domain_request)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("Password changed"))
+}
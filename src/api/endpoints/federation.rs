@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use axum::{
+    extract::State,
+    http::{header, Response, StatusCode},
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+
+use crate::{
+    api::{
+        endpoints::login::set_cookies_in_header,
+        model::{
+            api_error::{ApiError, ApiErrorResponse},
+            api_response::ApiResponse,
+        },
+        schemas::federation::{FederatedCallbackSchema, FederatedLoginSchema},
+        utils::extractors::{ApiPath, ApiQuery},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        federation_service::FederationService,
+        model::{
+            federation::{FederatedCallbackResponse, FederationError},
+            login_response::LoginResponse,
+        },
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/federation/{provider}/login",
+    tag = "federation",
+    summary = "Starts a login with an external identity provider",
+    params(
+        ("provider" = String, Path, description = "The name of the identity provider"),
+        FederatedLoginSchema,
+    ),
+    responses(
+        (status = 303, description = "Redirects to the identity provider, setting the `login_state` cookie"),
+        (status = 404, description = "Unknown identity provider", body = ApiErrorResponse),
+    )
+)]
+pub async fn federated_login_handler<AS: AuthService + FederationService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(provider): ApiPath<String>,
+    cookie_jar: CookieJar,
+    ApiQuery(params): ApiQuery<FederatedLoginSchema>,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = state
+        .auth_service
+        .federated_login(&params.into_domain(provider))
+        .await?;
+
+    let mut cookie =
+        login_state_cookie(FEDERATION_COOKIE_PATH, SameSite::Lax, response.state_digest);
+    cookie.set_max_age(time::Duration::seconds(response.state_max_age));
+
+    Ok((
+        cookie_jar.add(cookie),
+        Redirect::to(&response.authorization_url),
+    ))
+}
+
+/// Completes a federated login, see `login_response`.
+#[utoipa::path(
+    get,
+    path = "/api/federation/{provider}/callback",
+    tag = "federation",
+    params(
+        ("provider" = String, Path, description = "The name of the identity provider"),
+        FederatedCallbackSchema,
+    ),
+    responses(
+        (status = 200, description = "The session's tokens, also set as cookies", body = ApiResponse<LoginResponse>),
+        (status = 401, description = "The identity provider rejected the login", body = ApiErrorResponse),
+        (status = 404, description = "Unknown identity provider", body = ApiErrorResponse),
+        (status = 422, description = "Invalid state or code, or the email belongs to another account", body = ApiErrorResponse),
+    )
+)]
+pub async fn federated_callback_handler<AS: AuthService + FederationService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(provider): ApiPath<String>,
+    cookie_jar: CookieJar,
+    ApiQuery(params): ApiQuery<FederatedCallbackSchema>,
+) -> impl IntoResponse {
+    let state_digest = cookie_jar
+        .get(LOGIN_STATE_COOKIE)
+        .map(|cookie| cookie.value().to_string());
+
+    let response = state
+        .auth_service
+        .federated_callback(&params.into_domain(provider, state_digest))
+        .await
+        .map_err(ApiError::from)
+        .and_then(login_response);
+
+    let cookie = login_state_cookie(FEDERATION_COOKIE_PATH, SameSite::Lax, String::new());
+    (cookie_jar.remove(cookie), response)
+}
+
+/// Cookie holding the digest of the state of a login in progress, see
+/// `FederatedLoginResponse`. It is cleared when the provider's response arrives.
+pub(crate) const LOGIN_STATE_COOKIE: &str = "login_state";
+
+const FEDERATION_COOKIE_PATH: &str = "/api/federation";
+
+/// The `LOGIN_STATE_COOKIE` of logins whose response arrives under `path`.
+///
+/// `SameSite=None` cookies are also marked `Secure`, as browsers only accept them then.
+pub(crate) fn login_state_cookie(
+    path: &'static str,
+    same_site: SameSite,
+    state_digest: String,
+) -> Cookie<'static> {
+    Cookie::build((LOGIN_STATE_COOKIE, state_digest))
+        .path(path)
+        .same_site(same_site)
+        .secure(same_site == SameSite::None)
+        .http_only(true)
+        .build()
+}
+
+/// Builds the response to a completed federated login, setting the same cookies as a password
+/// login. The user is sent on to `return_to` when the login started with one, otherwise the
+/// tokens are returned in the body like `/api/login` does.
+pub(crate) fn login_response(
+    response: FederatedCallbackResponse,
+) -> Result<Response<String>, ApiError> {
+    let headers = set_cookies_in_header(&response.login).map_err(|e| {
+        ApiError::from(FederationError::Unknown(
+            anyhow!(e).context("Failed to set cookies in header"),
+        ))
+    })?;
+
+    let mut http_response = match &response.return_to {
+        Some(return_to) => {
+            let mut redirect = Response::new(String::new());
+            *redirect.status_mut() = StatusCode::SEE_OTHER;
+            redirect.headers_mut().insert(
+                header::LOCATION,
+                return_to.parse::<header::HeaderValue>().map_err(|e| {
+                    ApiError::from(FederationError::Unknown(
+                        anyhow!(e).context("Invalid return_to location"),
+                    ))
+                })?,
+            );
+            redirect
+        }
+        None => Response::new(ApiResponse::success(response.login).to_json().to_string()),
+    };
+    http_response.headers_mut().extend(headers);
+
+    Ok(http_response)
+}
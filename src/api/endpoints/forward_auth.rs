@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, HeaderName, HeaderValue, Request},
+    response::IntoResponse,
+};
+use axum_extra::extract::CookieJar;
+
+use crate::{
+    api::{
+        middlewares::authentication::extract_access_token,
+        model::api_error::{ApiError, ApiErrorResponse},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        forward_auth_service::ForwardAuthService,
+        model::auth::{AuthRequest, AuthorizationError},
+    },
+};
+
+/// Answers the authorization subrequests of reverse proxies: 200 with the identity headers
+/// when the request carries a valid access token, as the `auth` middleware reads it, and 401
+/// otherwise.
+///
+/// Any method and any path below `/api/verify` is accepted, as Envoy's HTTP `ext_authz`
+/// forwards the method and path of the original request, appended to its `path_prefix`.
+#[utoipa::path(
+    method(get, post),
+    path = "/api/verify",
+    tag = "forward-auth",
+    responses(
+        (status = 200, description = "The request is allowed, with the identity in the configured headers"),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn verify_handler<AS: AuthService + ForwardAuthService>(
+    cookie_jar: CookieJar,
+    State(state): State<Arc<AppState<AS>>>,
+    req: Request<Body>,
+) -> Result<impl IntoResponse, ApiError> {
+    let access_token = extract_access_token(cookie_jar, &req)?;
+
+    let response = state
+        .auth_service
+        .forward_auth(&AuthRequest::new(access_token))
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in response.headers {
+        let name = HeaderName::try_from(name).map_err(|e| {
+            AuthorizationError::Unknown(anyhow!(e).context("Invalid forward auth header name"))
+        })?;
+        let value = HeaderValue::try_from(value).map_err(|e| {
+            AuthorizationError::Unknown(anyhow!(e).context("Invalid forward auth header value"))
+        })?;
+        headers.insert(name, value);
+    }
+
+    Ok(headers)
+}
@@ -1,13 +1,34 @@
 use axum::Extension;
 
 use crate::{
-    api::model::{api_error::ApiError, api_response::ApiResponse},
+    api::model::{
+        api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+        api_response::ApiResponse,
+    },
     domain::model::{auth_middleware::AuthMiddleware, user::FilteredUser},
 };
 
+#[utoipa::path(
+    get,
+    path = "/api/users/me",
+    tag = "users",
+    summary = "Returns the authenticated user",
+    responses(
+        (status = 200, description = "The authenticated user", body = ApiResponse<FilteredUser>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The token lacks the profile scope or belongs to an OAuth client", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
 pub async fn get_me_handler(
     Extension(jwt): Extension<AuthMiddleware>,
 ) -> Result<ApiResponse<FilteredUser>, ApiError> {
-    let filtered_user = FilteredUser::from(&jwt.user);
+    let user = jwt.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+    let filtered_user = FilteredUser::from(user);
     Ok(ApiResponse::success(filtered_user))
 }
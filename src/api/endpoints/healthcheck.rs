@@ -1,6 +1,15 @@
 use axum::response::IntoResponse;
 use axum::Json;
 
+#[utoipa::path(
+    get,
+    path = "/api/healthcheck",
+    tag = "health",
+    summary = "Checks the service is up",
+    responses(
+        (status = 200, description = "The service is up", body = String),
+    )
+)]
 pub async fn healthcheck() -> impl IntoResponse {
     Json("Hello World")
 }
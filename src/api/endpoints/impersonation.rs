@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    api::model::{
+        api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+        api_response::ApiResponse,
+    },
+    api::utils::extractors::ApiPath,
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        impersonation_service::ImpersonationService,
+        model::{
+            auth_middleware::AuthMiddleware,
+            impersonation::{ImpersonationResponse, StartImpersonationRequest},
+            token_uuid::TokenUuid,
+            user_id::UserId,
+        },
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{user_id}/impersonate",
+    tag = "admin",
+    summary = "Starts impersonating a user",
+    params(
+        ("user_id" = uuid::Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "A short lived access token acting as the user", body = ApiResponse<ImpersonationResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse),
+        (status = 403, description = "Only active users without the admin role can be impersonated", body = ApiErrorResponse),
+        (status = 422, description = "Administrators cannot impersonate themselves", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn start_impersonation_handler<AS: AuthService + ImpersonationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(user_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<ImpersonationResponse>, ApiError> {
+    let admin = auth_guard.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+
+    state
+        .auth_service
+        .start_impersonation(&StartImpersonationRequest {
+            actor_id: UserId::new(admin.id),
+            user_id: UserId::new(user_id),
+        })
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/impersonation",
+    tag = "users",
+    summary = "Ends an impersonation session",
+    responses(
+        (status = 200, description = "The impersonation session is revoked", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 422, description = "The access token is not an impersonation token", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn end_impersonation_handler<AS: AuthService + ImpersonationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    if !auth_guard.is_impersonated() {
+        return Err(ApiError::UnprocessableEntity(ApiErrorBody::new(
+            ErrorCode::ImpersonationNotImpersonating,
+            "The access token is not an impersonation token",
+        )));
+    }
+
+    state
+        .auth_service
+        .end_impersonation(&TokenUuid::new(auth_guard.access_token_uuid))
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("Impersonation ended"))
+}
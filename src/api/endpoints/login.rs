@@ -1,12 +1,16 @@
 use crate::{
     api::{
-        model::{api_error::ApiError, api_response::ApiResponse},
+        model::{
+            api_error::{ApiError, ApiErrorResponse},
+            api_response::ApiResponse,
+        },
         schemas::login_user::LoginUserSchema,
+        utils::extractors::ApiJson,
     },
     application::AppState,
     domain::{
         auth_service::AuthService,
-        model::{login_response::LoginResponse, login_user::LoginUserError},
+        model::{audit::RequestContext, login_response::LoginResponse, login_user::LoginUserError},
     },
 };
 use anyhow::anyhow;
@@ -14,16 +18,28 @@ use axum::{
     extract::State,
     http::{header, HeaderMap, Response},
     response::IntoResponse,
-    Json,
 };
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use std::sync::Arc;
 
+#[utoipa::path(
+    post,
+    path = "/api/login",
+    tag = "auth",
+    summary = "Logs a user in",
+    request_body = LoginUserSchema,
+    responses(
+        (status = 200, description = "The session's tokens, also set as the `access_token` and `refresh_token` cookies", body = ApiResponse<LoginResponse>),
+        (status = 401, description = "Invalid credentials", body = ApiErrorResponse),
+        (status = 403, description = "The user is locked out, suspended or must reset their password", body = ApiErrorResponse),
+    )
+)]
 pub async fn login_handler<AS: AuthService>(
     State(state): State<Arc<AppState<AS>>>,
-    Json(body): Json<LoginUserSchema>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<LoginUserSchema>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let domain_request = body.try_into_domain()?;
+    let domain_request = body.try_into_domain()?.with_context(context);
     let login_response = state
         .auth_service
         .login(&domain_request)
@@ -42,7 +58,7 @@ pub async fn login_handler<AS: AuthService>(
     Ok(response)
 }
 
-fn set_cookies_in_header(details: &LoginResponse) -> anyhow::Result<HeaderMap> {
+pub(crate) fn set_cookies_in_header(details: &LoginResponse) -> anyhow::Result<HeaderMap> {
     let access_cookie = Cookie::build(("access_token", details.access_token.to_string()))
         .path("/")
         .max_age(time::Duration::minutes(details.access_token_max_age))
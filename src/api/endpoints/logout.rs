@@ -10,23 +10,43 @@ use axum::{
 use axum_extra::extract::cookie::{Cookie, SameSite};
 
 use crate::{
-    api::model::{api_error::ApiError, api_response::ApiResponse},
+    api::model::{
+        api_error::{ApiError, ApiErrorResponse},
+        api_response::ApiResponse,
+    },
     application::AppState,
     domain::{
         auth_service::AuthService,
         model::{
+            audit::RequestContext,
             auth::AuthorizationError,
             auth_middleware::AuthMiddleware,
             logout::{LogoutRequest, LogoutResponse},
+            session_revocation::session_id,
         },
     },
 };
 
+#[utoipa::path(
+    get,
+    path = "/api/logout",
+    tag = "auth",
+    summary = "Logs out of the current session",
+    responses(
+        (status = 200, description = "The session is revoked and its cookies cleared", body = ApiResponse<LogoutResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
 pub async fn logout_handler<AS: AuthService>(
     Extension(auth_guard): Extension<AuthMiddleware>,
     State(state): State<Arc<AppState<AS>>>,
+    context: RequestContext,
 ) -> Result<impl IntoResponse, ApiError> {
-    let domain_request = LogoutRequest::new(auth_guard.access_token_uuid);
+    let domain_request = LogoutRequest::new(auth_guard.access_token_uuid)
+        .with_user(auth_guard.user().map(|user| user.id))
+        .with_session(session_id(&auth_guard.claims), auth_guard.expires_at)
+        .with_context(context);
 
     let response = state
         .auth_service
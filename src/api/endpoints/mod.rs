@@ -1,6 +1,26 @@
+pub mod admin_users;
+pub mod audit_events;
+pub mod change_password;
+pub mod federation;
+pub mod forward_auth;
 pub mod get_me;
 pub mod healthcheck;
+pub mod impersonation;
 pub mod login;
 pub mod logout;
+pub mod oauth_authorize;
+pub mod oauth_device;
+pub mod oauth_introspect;
+pub mod oauth_revoke;
+pub mod oauth_token;
+pub mod oidc_discovery;
+pub mod oidc_userinfo;
+pub mod organizations;
+pub mod password_reset;
+pub mod personal_access_tokens;
 pub mod refresh;
 pub mod register;
+pub mod registration_invitations;
+pub mod saml;
+pub mod service_accounts;
+pub mod webhooks;
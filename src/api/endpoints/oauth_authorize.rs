@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{OriginalUri, Query, State},
+    response::Redirect,
+    Extension,
+};
+
+use crate::{
+    api::{
+        model::oauth_error::{OAuthApiError, OAuthErrorBody},
+        schemas::authorize::AuthorizeSchema,
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{
+            auth_middleware::AuthMiddleware, authentication_context::AuthenticationContext,
+            authorize::AuthorizeSession, oauth_errors::OAuthError,
+        },
+        oauth_service::OAuthService,
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/oauth/authorize",
+    tag = "oauth",
+    summary = "Authorizes a client with the authorization code flow",
+    params(
+        AuthorizeSchema,
+    ),
+    responses(
+        (status = 303, description = "Redirects to the client with a code or an error, or to the login page"),
+        (status = 400, description = "Invalid client or redirect URI", body = OAuthErrorBody),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []), ())
+)]
+pub async fn authorize_handler<AS: AuthService + OAuthService>(
+    auth_guard: Option<Extension<AuthMiddleware>>,
+    State(state): State<Arc<AppState<AS>>>,
+    OriginalUri(uri): OriginalUri,
+    Query(params): Query<AuthorizeSchema>,
+) -> Result<Redirect, OAuthApiError> {
+    // Only a login session can grant access to a client. Scoped tokens, clients and personal
+    // access tokens are treated as if no one was signed in, which sends the user to log in.
+    let session = match auth_guard.filter(|Extension(auth_guard)| auth_guard.is_login_session()) {
+        Some(Extension(auth_guard)) => {
+            let user = auth_guard.user().ok_or_else(|| {
+                OAuthApiError::from(OAuthError::AccessDenied {
+                    description: "Only users can authorize clients".to_string(),
+                })
+            })?;
+            if auth_guard.is_impersonated() {
+                return Err(OAuthApiError::from(OAuthError::AccessDenied {
+                    description: "Impersonated sessions cannot authorize clients".to_string(),
+                }));
+            }
+
+            Some(AuthorizeSession {
+                user_id: user.id,
+                context: AuthenticationContext::from_claims(&auth_guard.claims),
+            })
+        }
+        None => None,
+    };
+
+    let domain_request = params.into_domain(session);
+
+    match state.auth_service.authorize(&domain_request).await {
+        Ok(response) => Ok(Redirect::to(&response.location())),
+        Err(error) => {
+            if let Some(location) = error.location() {
+                return Ok(Redirect::to(&location));
+            }
+            match error.login_location(&return_to(uri.path(), uri.query())) {
+                Some(location) => Ok(Redirect::to(&location)),
+                None => Err(OAuthApiError::from(error.error)),
+            }
+        }
+    }
+}
+
+/// Rebuilds the authorization request URI for the login page to return to, without `prompt`,
+/// so that `prompt=login` does not send the user back to the login page after logging in.
+fn return_to(path: &str, query: Option<&str>) -> String {
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(
+            url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+                .filter(|(name, _)| name != "prompt"),
+        )
+        .finish();
+
+    format!("{}?{}", path, query)
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Extension, Form, Json,
+};
+
+use crate::{
+    api::{
+        model::oauth_error::{OAuthApiError, OAuthErrorBody},
+        schemas::device_authorization::{DeviceAuthorizationSchema, DeviceVerificationSchema},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{
+            auth_middleware::AuthMiddleware,
+            device_authorization::{DeviceAuthorizationResponse, DeviceVerificationResponse},
+        },
+        oauth_service::OAuthService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/oauth/device_authorization",
+    tag = "oauth",
+    summary = "Starts a device authorization (RFC 8628)",
+    request_body(content = DeviceAuthorizationSchema, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "The device and user codes", body = DeviceAuthorizationResponse),
+        (status = 400, description = "Invalid scope", body = OAuthErrorBody),
+        (status = 401, description = "Invalid client credentials", body = OAuthErrorBody),
+    ),
+    security(("client_basic" = []), ())
+)]
+pub async fn device_authorization_handler<AS: AuthService + OAuthService>(
+    State(state): State<Arc<AppState<AS>>>,
+    headers: HeaderMap,
+    Form(body): Form<DeviceAuthorizationSchema>,
+) -> Result<impl IntoResponse, OAuthApiError> {
+    let domain_request = body.try_into_domain(&headers)?;
+
+    let response = state
+        .auth_service
+        .device_authorization(&domain_request)
+        .await?;
+
+    Ok((
+        [
+            (header::CACHE_CONTROL, "no-store"),
+            (header::PRAGMA, "no-cache"),
+        ],
+        Json(response),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/oauth/device",
+    tag = "oauth",
+    summary = "Approves or denies a device authorization",
+    request_body = DeviceVerificationSchema,
+    responses(
+        (status = 200, description = "The device authorization is approved or denied", body = DeviceVerificationResponse),
+        (status = 400, description = "Unknown or expired user code", body = OAuthErrorBody),
+        (status = 401, description = "Missing, invalid or revoked access token", body = OAuthErrorBody),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn device_verification_handler<AS: AuthService + OAuthService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    Json(body): Json<DeviceVerificationSchema>,
+) -> Result<Json<DeviceVerificationResponse>, OAuthApiError> {
+    let domain_request = body.try_into_domain(&auth_guard)?;
+
+    let response = state.auth_service.verify_device(&domain_request).await?;
+
+    Ok(Json(response))
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Form, Json,
+};
+
+use crate::{
+    api::{
+        model::oauth_error::{OAuthApiError, OAuthErrorBody},
+        schemas::token_introspection::TokenIntrospectionSchema,
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService, model::introspection::IntrospectionResponse,
+        oauth_service::OAuthService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/oauth/introspect",
+    tag = "oauth",
+    summary = "Introspects a token (RFC 7662)",
+    request_body(content = TokenIntrospectionSchema, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "The state of the token, only `active: false` if it is not valid", body = IntrospectionResponse),
+        (status = 401, description = "Invalid client credentials", body = OAuthErrorBody),
+    ),
+    security(("client_basic" = []), ())
+)]
+pub async fn introspect_handler<AS: AuthService + OAuthService>(
+    State(state): State<Arc<AppState<AS>>>,
+    headers: HeaderMap,
+    Form(body): Form<TokenIntrospectionSchema>,
+) -> Result<impl IntoResponse, OAuthApiError> {
+    let domain_request = body.try_into_introspection(&headers)?;
+
+    let response = state.auth_service.introspect(&domain_request).await?;
+
+    Ok((
+        [
+            (header::CACHE_CONTROL, "no-store"),
+            (header::PRAGMA, "no-cache"),
+        ],
+        Json(response),
+    ))
+}
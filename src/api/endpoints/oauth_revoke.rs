@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    Form,
+};
+
+use crate::{
+    api::{
+        model::oauth_error::{OAuthApiError, OAuthErrorBody},
+        schemas::token_introspection::TokenIntrospectionSchema,
+    },
+    application::AppState,
+    domain::{auth_service::AuthService, oauth_service::OAuthService},
+};
+
+#[utoipa::path(
+    post,
+    path = "/oauth/revoke",
+    tag = "oauth",
+    summary = "Revokes a token (RFC 7009)",
+    request_body(content = TokenIntrospectionSchema, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "The token is revoked, or was not valid"),
+        (status = 401, description = "Invalid client credentials", body = OAuthErrorBody),
+    ),
+    security(("client_basic" = []), ())
+)]
+pub async fn revoke_handler<AS: AuthService + OAuthService>(
+    State(state): State<Arc<AppState<AS>>>,
+    headers: HeaderMap,
+    Form(body): Form<TokenIntrospectionSchema>,
+) -> Result<StatusCode, OAuthApiError> {
+    let domain_request = body.try_into_revocation(&headers)?;
+
+    state.auth_service.revoke(&domain_request).await?;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Form, Json,
+};
+
+use crate::{
+    api::{
+        model::oauth_error::{OAuthApiError, OAuthErrorBody},
+        schemas::token_request::TokenRequestSchema,
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService, model::oauth_token::TokenResponse, oauth_service::OAuthService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/oauth/token",
+    tag = "oauth",
+    summary = "Issues tokens for a grant",
+    request_body(content = TokenRequestSchema, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "The issued tokens", body = TokenResponse),
+        (status = 400, description = "Invalid grant or request", body = OAuthErrorBody),
+        (status = 401, description = "Invalid client credentials", body = OAuthErrorBody),
+    ),
+    security(("client_basic" = []), ())
+)]
+pub async fn token_handler<AS: AuthService + OAuthService>(
+    State(state): State<Arc<AppState<AS>>>,
+    headers: HeaderMap,
+    Form(body): Form<TokenRequestSchema>,
+) -> Result<impl IntoResponse, OAuthApiError> {
+    let domain_request = body.try_into_domain(&headers)?;
+
+    let response = state.auth_service.token(&domain_request).await?;
+
+    Ok((
+        [
+            (header::CACHE_CONTROL, "no-store"),
+            (header::PRAGMA, "no-cache"),
+        ],
+        Json(response),
+    ))
+}
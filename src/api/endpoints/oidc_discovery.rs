@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Json};
+
+use crate::{
+    api::model::oauth_error::OAuthApiError,
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{jwks::JwkSet, provider_metadata::ProviderMetadata},
+        oidc_service::OidcService,
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/openid-configuration",
+    tag = "oidc",
+    summary = "Returns the OpenID Connect discovery document",
+    responses(
+        (status = 200, description = "The OpenID provider metadata", body = ProviderMetadata),
+    )
+)]
+pub async fn openid_configuration_handler<AS: AuthService + OidcService>(
+    State(state): State<Arc<AppState<AS>>>,
+) -> Json<ProviderMetadata> {
+    Json(state.auth_service.provider_metadata().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "oidc",
+    summary = "Returns the JSON Web Key Set",
+    responses(
+        (status = 200, description = "The public keys verifying issued tokens", body = JwkSet),
+    )
+)]
+pub async fn jwks_handler<AS: AuthService + OidcService>(
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<Json<JwkSet>, OAuthApiError> {
+    let jwks = state.auth_service.jwks().await?;
+
+    Ok(Json(jwks))
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use crate::{
+    api::model::oauth_error::{OAuthApiError, OAuthErrorBody},
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{auth_middleware::AuthMiddleware, userinfo::UserInfo},
+        oidc_service::OidcService,
+    },
+};
+
+#[utoipa::path(
+    method(get, post),
+    path = "/userinfo",
+    tag = "oidc",
+    summary = "Returns the OpenID Connect claims of the authenticated user",
+    responses(
+        (status = 200, description = "The claims of the authenticated user", body = UserInfo),
+        (status = 401, description = "Missing, invalid or revoked access token", body = OAuthErrorBody),
+        (status = 403, description = "The access token lacks the `openid` scope", body = OAuthErrorBody),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn userinfo_handler<AS: AuthService + OidcService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<Json<UserInfo>, OAuthApiError> {
+    let userinfo = state.auth_service.userinfo(&auth_guard).await?;
+
+    Ok(Json(userinfo))
+}
@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use axum::{extract::State, http::Response, response::IntoResponse, Extension};
+
+use crate::{
+    api::{
+        endpoints::login::set_cookies_in_header,
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::organization::{
+            AcceptInvitationSchema, CreateOrganizationSchema, InviteMemberSchema,
+        },
+        utils::extractors::{ApiJson, ApiPath},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{
+            auth_middleware::AuthMiddleware,
+            login_response::LoginResponse,
+            organization::{
+                CreateInvitationResponse, Organization, OrganizationError, OrganizationMember,
+                OrganizationMembersRequest, OrganizationMembership, RemoveMemberRequest,
+                SwitchOrganizationRequest,
+            },
+            user::User,
+            user_id::UserId,
+        },
+        organization_service::OrganizationService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/organizations",
+    tag = "organizations",
+    summary = "Creates an organization",
+    request_body = CreateOrganizationSchema,
+    responses(
+        (status = 200, description = "The organization, owned by the authenticated user", body = ApiResponse<Organization>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 422, description = "Invalid name or slug, or the slug is already taken", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn create_organization_handler<AS: AuthService + OrganizationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiJson(body): ApiJson<CreateOrganizationSchema>,
+) -> Result<ApiResponse<Organization>, ApiError> {
+    let user = session_user(&auth_guard)?;
+
+    state
+        .auth_service
+        .create_organization(&body.into_domain(UserId::new(user.id)))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/organizations",
+    tag = "organizations",
+    summary = "Lists the organizations of the authenticated user",
+    responses(
+        (status = 200, description = "The organizations of the authenticated user", body = ApiResponse<Vec<OrganizationMembership>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_organizations_handler<AS: AuthService + OrganizationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<Vec<OrganizationMembership>>, ApiError> {
+    let user = session_user(&auth_guard)?;
+
+    state
+        .auth_service
+        .list_organizations(&UserId::new(user.id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/members",
+    tag = "organizations",
+    summary = "Lists the members of an organization",
+    params(
+        ("org_id" = uuid::Uuid, Path, description = "The id of the organization"),
+    ),
+    responses(
+        (status = 200, description = "The members of the organization", body = ApiResponse<Vec<OrganizationMember>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 404, description = "Organization not found, or not a member of it", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_members_handler<AS: AuthService + OrganizationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(organization_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<Vec<OrganizationMember>>, ApiError> {
+    let domain_request = OrganizationMembersRequest {
+        organization_id,
+        actor_id: UserId::new(session_user(&auth_guard)?.id),
+    };
+
+    state
+        .auth_service
+        .list_members(&domain_request)
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/invitations",
+    tag = "organizations",
+    summary = "Invites a user to an organization",
+    params(
+        ("org_id" = uuid::Uuid, Path, description = "The id of the organization"),
+    ),
+    request_body = InviteMemberSchema,
+    responses(
+        (status = 200, description = "The invitation, whose token is only ever returned here", body = ApiResponse<CreateInvitationResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The member's role cannot invite members with this role", body = ApiErrorResponse),
+        (status = 404, description = "Organization not found, or not a member of it", body = ApiErrorResponse),
+        (status = 422, description = "Invalid email", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn invite_member_handler<AS: AuthService + OrganizationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(organization_id): ApiPath<uuid::Uuid>,
+    ApiJson(body): ApiJson<InviteMemberSchema>,
+) -> Result<ApiResponse<CreateInvitationResponse>, ApiError> {
+    let user = session_user(&auth_guard)?;
+
+    state
+        .auth_service
+        .invite_member(&body.into_domain(organization_id, UserId::new(user.id)))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/organizations/invitations/accept",
+    tag = "organizations",
+    summary = "Accepts an organization invitation",
+    request_body = AcceptInvitationSchema,
+    responses(
+        (status = 200, description = "The membership granted by the invitation", body = ApiResponse<OrganizationMembership>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 404, description = "Unknown, expired or already accepted invitation, or one for another email", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn accept_invitation_handler<AS: AuthService + OrganizationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiJson(body): ApiJson<AcceptInvitationSchema>,
+) -> Result<ApiResponse<OrganizationMembership>, ApiError> {
+    let user = session_user(&auth_guard)?;
+
+    state
+        .auth_service
+        .accept_invitation(&body.into_domain(user))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/members/{user_id}",
+    tag = "organizations",
+    summary = "Removes a member from an organization",
+    params(
+        ("org_id" = uuid::Uuid, Path, description = "The id of the organization"),
+        ("user_id" = uuid::Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "The member is removed", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The member's role cannot remove this member, or they are the last owner", body = ApiErrorResponse),
+        (status = 404, description = "Organization or member not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn remove_member_handler<AS: AuthService + OrganizationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath((organization_id, user_id)): ApiPath<(uuid::Uuid, uuid::Uuid)>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    let domain_request = RemoveMemberRequest {
+        organization_id,
+        actor_id: UserId::new(session_user(&auth_guard)?.id),
+        user_id: UserId::new(user_id),
+    };
+
+    state
+        .auth_service
+        .remove_member(&domain_request)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("Member removed"))
+}
+
+/// Reissues the session's tokens for another organization, setting the cookies like a login.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/switch",
+    tag = "organizations",
+    params(
+        ("org_id" = uuid::Uuid, Path, description = "The id of the organization"),
+    ),
+    responses(
+        (status = 200, description = "The session's tokens for the organization, also set as cookies", body = ApiResponse<LoginResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "Not allowed while impersonating a user", body = ApiErrorResponse),
+        (status = 404, description = "Organization not found, or not a member of it", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn switch_organization_handler<AS: AuthService + OrganizationService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(organization_id): ApiPath<uuid::Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let domain_request = SwitchOrganizationRequest {
+        user_id: UserId::new(session_user(&auth_guard)?.id),
+        organization_id,
+        access_token_uuid: auth_guard.access_token_uuid,
+        claims: auth_guard.claims,
+    };
+
+    let login_response = state
+        .auth_service
+        .switch_organization(&domain_request)
+        .await
+        .map_err(ApiError::from)?;
+
+    let headers = set_cookies_in_header(&login_response).map_err(|e| {
+        ApiError::from(OrganizationError::Unknown(
+            anyhow!(e).context("Failed to set cookies in header"),
+        ))
+    })?;
+
+    let mut response = Response::new(ApiResponse::success(login_response).to_json().to_string());
+    response.headers_mut().extend(headers);
+
+    Ok(response)
+}
+
+/// Organizations are managed from login sessions only, like personal access tokens, so a
+/// scoped token cannot change the memberships of its owner.
+fn session_user(auth_guard: &AuthMiddleware) -> Result<&User, ApiError> {
+    auth_guard
+        .user()
+        .filter(|_| auth_guard.is_login_session())
+        .ok_or_else(|| {
+            ApiError::Forbidden(ApiErrorBody::new(
+                ErrorCode::AuthLoginSessionRequired,
+                "Organizations can only be managed from a login session",
+            ))
+        })
+}
@@ -0,0 +1,39 @@
+use crate::{
+    api::model::{
+        api_error::{ApiError, ApiErrorResponse},
+        api_response::ApiResponse,
+    },
+    api::schemas::password_reset::ResetPasswordSchema,
+    api::utils::extractors::ApiJson,
+    application::AppState,
+    domain::{auth_service::AuthService, model::audit::RequestContext},
+};
+use axum::extract::State;
+use std::sync::Arc;
+
+#[utoipa::path(
+    post,
+    path = "/api/password/reset",
+    tag = "auth",
+    summary = "Resets a password with a reset token",
+    request_body = ResetPasswordSchema,
+    responses(
+        (status = 200, description = "The password is reset", body = ApiResponse<String>),
+        (status = 422, description = "Invalid or expired reset token, or an invalid password", body = ApiErrorResponse),
+    )
+)]
+pub async fn reset_password_handler<AS: AuthService>(
+    State(state): State<Arc<AppState<AS>>>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<ResetPasswordSchema>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    let domain_request = body.try_into_domain(context)?;
+
+    state
+        .auth_service
+        .reset_password(&domain_request)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("Password reset"))
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    api::{
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::personal_access_token::CreatePersonalAccessTokenSchema,
+        utils::extractors::{ApiJson, ApiPath},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{
+            auth_middleware::AuthMiddleware,
+            personal_access_token::{
+                CreatePersonalAccessTokenResponse, PersonalAccessToken,
+                RevokePersonalAccessTokenRequest,
+            },
+            user_id::UserId,
+        },
+        personal_access_token_service::PersonalAccessTokenService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/users/me/tokens",
+    tag = "users",
+    summary = "Creates a personal access token",
+    request_body = CreatePersonalAccessTokenSchema,
+    responses(
+        (status = 200, description = "The token, whose secret is only ever returned here", body = ApiResponse<CreatePersonalAccessTokenResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "Not allowed while impersonating a user", body = ApiErrorResponse),
+        (status = 422, description = "Invalid name, scopes or expiry", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn create_personal_access_token_handler<AS: AuthService + PersonalAccessTokenService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiJson(body): ApiJson<CreatePersonalAccessTokenSchema>,
+) -> Result<ApiResponse<CreatePersonalAccessTokenResponse>, ApiError> {
+    let domain_request = body.try_into_domain(session_user_id(&auth_guard)?)?;
+
+    state
+        .auth_service
+        .create_personal_access_token(&domain_request)
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/tokens",
+    tag = "users",
+    summary = "Lists personal access tokens",
+    responses(
+        (status = 200, description = "The personal access tokens of the authenticated user", body = ApiResponse<Vec<PersonalAccessToken>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "Not allowed while impersonating a user", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_personal_access_tokens_handler<AS: AuthService + PersonalAccessTokenService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<Vec<PersonalAccessToken>>, ApiError> {
+    state
+        .auth_service
+        .list_personal_access_tokens(&session_user_id(&auth_guard)?)
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/tokens/{token_id}",
+    tag = "users",
+    summary = "Revokes a personal access token",
+    params(
+        ("token_id" = uuid::Uuid, Path, description = "The id of the token"),
+    ),
+    responses(
+        (status = 200, description = "The token is revoked", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "Not allowed while impersonating a user", body = ApiErrorResponse),
+        (status = 404, description = "Personal access token not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn revoke_personal_access_token_handler<AS: AuthService + PersonalAccessTokenService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(token_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    let domain_request = RevokePersonalAccessTokenRequest {
+        user_id: session_user_id(&auth_guard)?,
+        token_id,
+    };
+
+    state
+        .auth_service
+        .revoke_personal_access_token(&domain_request)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message(
+        "Personal access token revoked",
+    ))
+}
+
+/// Tokens can only be managed from a login session, so a leaked token cannot be used to mint
+/// new ones or to revoke the owner's others.
+fn session_user_id(auth_guard: &AuthMiddleware) -> Result<UserId, ApiError> {
+    auth_guard
+        .user()
+        .filter(|_| auth_guard.is_login_session())
+        .map(|user| UserId::new(user.id))
+        .ok_or_else(|| {
+            ApiError::Forbidden(ApiErrorBody::new(
+                ErrorCode::AuthLoginSessionRequired,
+                "Personal access tokens can only be managed from a login session",
+            ))
+        })
+}
@@ -12,22 +12,40 @@ use axum_extra::extract::{
 };
 
 use crate::{
-    api::model::{api_error::ApiError, api_response::ApiResponse},
+    api::model::{
+        api_error::{ApiError, ApiErrorResponse},
+        api_response::ApiResponse,
+    },
     application::AppState,
     domain::{
         auth_service::AuthService,
-        model::refresh_token::{RefreshRequest, RefreshResponse, RefreshTokenError},
+        model::{
+            audit::RequestContext,
+            refresh_token::{RefreshRequest, RefreshResponse, RefreshTokenError},
+        },
     },
 };
 
+#[utoipa::path(
+    get,
+    path = "/api/refresh",
+    tag = "auth",
+    summary = "Refreshes the access token with the `refresh_token` cookie",
+    responses(
+        (status = 200, description = "A new access token, also set as the `access_token` cookie", body = ApiResponse<RefreshResponse>),
+        (status = 401, description = "Missing, invalid or revoked refresh token", body = ApiErrorResponse),
+    ),
+    security(("refresh_token_cookie" = []))
+)]
 pub async fn refresh_access_token_handler<AS: AuthService>(
     cookie_jar: CookieJar,
     State(state): State<Arc<AppState<AS>>>,
+    context: RequestContext,
 ) -> Result<impl IntoResponse, ApiError> {
     let refresh_token =
         extract_refresh_token(cookie_jar).map_err(|_| RefreshTokenError::MissingCredentials)?;
 
-    let domain_request = RefreshRequest::new(refresh_token);
+    let domain_request = RefreshRequest::new(refresh_token).with_context(context);
 
     let refresh_response = state.auth_service.refresh(&domain_request).await?;
 
@@ -1,17 +1,37 @@
 use crate::{
-    api::model::{api_error::ApiError, api_response::ApiResponse},
+    api::model::{
+        api_error::{ApiError, ApiErrorResponse},
+        api_response::ApiResponse,
+    },
     api::schemas::register_user::RegisterUserSchema,
+    api::utils::extractors::ApiJson,
     application::AppState,
-    domain::{auth_service::AuthService, model::user::FilteredUser},
+    domain::{
+        auth_service::AuthService,
+        model::{audit::RequestContext, user::FilteredUser},
+    },
 };
-use axum::{extract::State, Json};
+use axum::extract::State;
 use std::sync::Arc;
 
+#[utoipa::path(
+    post,
+    path = "/api/register",
+    tag = "auth",
+    summary = "Registers a user with an email and password",
+    request_body = RegisterUserSchema,
+    responses(
+        (status = 200, description = "The registered user", body = ApiResponse<FilteredUser>),
+        (status = 403, description = "Registration is not open to this email", body = ApiErrorResponse),
+        (status = 422, description = "Invalid email, password or invitation, or the email is already registered", body = ApiErrorResponse),
+    )
+)]
 pub async fn register_handler<AS: AuthService>(
     State(state): State<Arc<AppState<AS>>>,
-    Json(body): Json<RegisterUserSchema>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<RegisterUserSchema>,
 ) -> Result<ApiResponse<FilteredUser>, ApiError> {
-    let domain_request = body.try_into_domain()?;
+    let domain_request = body.try_into_domain()?.with_context(context);
 
     state
         .auth_service
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    api::{
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::registration_invitation::CreateRegistrationInvitationSchema,
+        utils::extractors::{ApiJson, ApiPath},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{
+            auth_middleware::AuthMiddleware,
+            registration::{CreateRegistrationInvitationResponse, RegistrationInvitation},
+        },
+        registration_invitation_service::RegistrationInvitationService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/invitations",
+    tag = "admin",
+    summary = "Creates a registration invitation",
+    request_body = CreateRegistrationInvitationSchema,
+    responses(
+        (status = 200, description = "The invitation, whose token is only ever returned here", body = ApiResponse<CreateRegistrationInvitationResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 422, description = "Invalid email or expiry", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn create_registration_invitation_handler<
+    AS: AuthService + RegistrationInvitationService,
+>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiJson(body): ApiJson<CreateRegistrationInvitationSchema>,
+) -> Result<ApiResponse<CreateRegistrationInvitationResponse>, ApiError> {
+    let admin = auth_guard.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+    let domain_request = body.try_into_domain(admin.id)?;
+
+    state
+        .auth_service
+        .create_registration_invitation(&domain_request)
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/invitations",
+    tag = "admin",
+    summary = "Lists registration invitations",
+    responses(
+        (status = 200, description = "The registration invitations", body = ApiResponse<Vec<RegistrationInvitation>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_registration_invitations_handler<
+    AS: AuthService + RegistrationInvitationService,
+>(
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<Vec<RegistrationInvitation>>, ApiError> {
+    state
+        .auth_service
+        .list_registration_invitations()
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/invitations/{invitation_id}",
+    tag = "admin",
+    summary = "Revokes a registration invitation",
+    params(
+        ("invitation_id" = uuid::Uuid, Path, description = "The id of the invitation"),
+    ),
+    responses(
+        (status = 200, description = "The invitation is revoked", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Invitation not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn revoke_registration_invitation_handler<
+    AS: AuthService + RegistrationInvitationService,
+>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(invitation_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    state
+        .auth_service
+        .revoke_registration_invitation(&invitation_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message(
+        "Registration invitation revoked",
+    ))
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Redirect},
+    Form,
+};
+use axum_extra::extract::{cookie::SameSite, CookieJar};
+
+use crate::{
+    api::{
+        endpoints::federation::{login_response, login_state_cookie, LOGIN_STATE_COOKIE},
+        model::{
+            api_error::{ApiError, ApiErrorResponse},
+            api_response::ApiResponse,
+        },
+        schemas::{federation::FederatedLoginSchema, saml::SamlAssertionSchema},
+        utils::extractors::{ApiPath, ApiQuery},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService, model::login_response::LoginResponse, saml_service::SamlService,
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/saml/{provider}/metadata",
+    tag = "federation",
+    summary = "Returns the SAML service provider metadata",
+    params(
+        ("provider" = String, Path, description = "The name of the identity provider"),
+    ),
+    responses(
+        (status = 200, description = "The service provider metadata", body = String, content_type = "application/samlmetadata+xml"),
+        (status = 404, description = "Unknown identity provider", body = ApiErrorResponse),
+    )
+)]
+pub async fn saml_metadata_handler<AS: AuthService + SamlService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(provider): ApiPath<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let metadata = state.auth_service.saml_metadata(&provider).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/samlmetadata+xml")],
+        metadata,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/saml/{provider}/login",
+    tag = "federation",
+    summary = "Starts a SAML login",
+    params(
+        ("provider" = String, Path, description = "The name of the identity provider"),
+        FederatedLoginSchema,
+    ),
+    responses(
+        (status = 303, description = "Redirects to the identity provider with an `AuthnRequest`, setting the `login_state` cookie"),
+        (status = 404, description = "Unknown identity provider", body = ApiErrorResponse),
+    )
+)]
+pub async fn saml_login_handler<AS: AuthService + SamlService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(provider): ApiPath<String>,
+    cookie_jar: CookieJar,
+    ApiQuery(params): ApiQuery<FederatedLoginSchema>,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = state
+        .auth_service
+        .saml_login(&params.into_domain(provider))
+        .await?;
+
+    let mut cookie = login_state_cookie(SAML_COOKIE_PATH, SameSite::None, response.state_digest);
+    cookie.set_max_age(time::Duration::seconds(response.state_max_age));
+
+    Ok((
+        cookie_jar.add(cookie),
+        Redirect::to(&response.authorization_url),
+    ))
+}
+
+/// Assertion consumer service for the HTTP-POST binding. Completes the login like the
+/// federated callback does.
+#[utoipa::path(
+    post,
+    path = "/api/saml/{provider}/acs",
+    tag = "federation",
+    summary = "Completes a SAML login with the identity provider's response",
+    params(
+        ("provider" = String, Path, description = "The name of the identity provider"),
+    ),
+    request_body(content = SamlAssertionSchema, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "The session's tokens, also set as cookies", body = ApiResponse<LoginResponse>),
+        (status = 401, description = "The assertion was rejected", body = ApiErrorResponse),
+        (status = 404, description = "Unknown identity provider", body = ApiErrorResponse),
+        (status = 422, description = "Invalid response, or the email belongs to another account", body = ApiErrorResponse),
+    )
+)]
+pub async fn saml_assertion_handler<AS: AuthService + SamlService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(provider): ApiPath<String>,
+    cookie_jar: CookieJar,
+    Form(body): Form<SamlAssertionSchema>,
+) -> impl IntoResponse {
+    let state_digest = cookie_jar
+        .get(LOGIN_STATE_COOKIE)
+        .map(|cookie| cookie.value().to_string());
+
+    let response = state
+        .auth_service
+        .saml_assertion(&body.into_domain(provider, state_digest))
+        .await
+        .map_err(ApiError::from)
+        .and_then(login_response);
+
+    let cookie = login_state_cookie(SAML_COOKIE_PATH, SameSite::None, String::new());
+    (cookie_jar.remove(cookie), response)
+}
+
+/// The identity provider posts its response across sites, which only sends the
+/// `LOGIN_STATE_COOKIE` along when it is `SameSite=None`.
+const SAML_COOKIE_PATH: &str = "/api/saml";
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use crate::{
+    api::{
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::{
+            personal_access_token::CreatePersonalAccessTokenSchema,
+            service_account::{CreateServiceAccountSchema, RotateServiceAccountKeySchema},
+        },
+        utils::extractors::{ApiJson, ApiPath},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{
+            auth_middleware::AuthMiddleware,
+            personal_access_token::{
+                CreatePersonalAccessTokenResponse, PersonalAccessToken,
+                RevokePersonalAccessTokenRequest,
+            },
+            user::FilteredUser,
+            user_id::UserId,
+        },
+        service_account_service::ServiceAccountService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/service-accounts",
+    tag = "admin",
+    summary = "Creates a service account",
+    request_body = CreateServiceAccountSchema,
+    responses(
+        (status = 200, description = "The service account", body = ApiResponse<FilteredUser>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 422, description = "Invalid or already taken name, or an invalid owner", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn create_service_account_handler<AS: AuthService + ServiceAccountService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiJson(body): ApiJson<CreateServiceAccountSchema>,
+) -> Result<ApiResponse<FilteredUser>, ApiError> {
+    let admin = auth_guard.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+
+    state
+        .auth_service
+        .create_service_account(&body.into_domain(admin.id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/service-accounts",
+    tag = "admin",
+    summary = "Lists service accounts",
+    responses(
+        (status = 200, description = "The service accounts", body = ApiResponse<Vec<FilteredUser>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_service_accounts_handler<AS: AuthService + ServiceAccountService>(
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<Vec<FilteredUser>>, ApiError> {
+    state
+        .auth_service
+        .list_service_accounts()
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/service-accounts/{account_id}",
+    tag = "admin",
+    summary = "Deletes a service account",
+    params(
+        ("account_id" = uuid::Uuid, Path, description = "The id of the service account"),
+    ),
+    responses(
+        (status = 200, description = "The service account and its keys are deleted", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Service account not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn delete_service_account_handler<AS: AuthService + ServiceAccountService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(account_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    state
+        .auth_service
+        .delete_service_account(&UserId::new(account_id))
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("Service account deleted"))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/service-accounts/{account_id}/keys",
+    tag = "admin",
+    summary = "Creates a service account key",
+    params(
+        ("account_id" = uuid::Uuid, Path, description = "The id of the service account"),
+    ),
+    request_body = CreatePersonalAccessTokenSchema,
+    responses(
+        (status = 200, description = "The key, whose secret is only ever returned here", body = ApiResponse<CreatePersonalAccessTokenResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Service account not found", body = ApiErrorResponse),
+        (status = 422, description = "Invalid name, scopes or expiry", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn create_service_account_key_handler<AS: AuthService + ServiceAccountService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(account_id): ApiPath<uuid::Uuid>,
+    ApiJson(body): ApiJson<CreatePersonalAccessTokenSchema>,
+) -> Result<ApiResponse<CreatePersonalAccessTokenResponse>, ApiError> {
+    let domain_request = body.try_into_domain(UserId::new(account_id))?;
+
+    state
+        .auth_service
+        .create_service_account_key(&domain_request)
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/service-accounts/{account_id}/keys",
+    tag = "admin",
+    summary = "Lists service account keys",
+    params(
+        ("account_id" = uuid::Uuid, Path, description = "The id of the service account"),
+    ),
+    responses(
+        (status = 200, description = "The keys of the service account", body = ApiResponse<Vec<PersonalAccessToken>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Service account not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_service_account_keys_handler<AS: AuthService + ServiceAccountService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(account_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<Vec<PersonalAccessToken>>, ApiError> {
+    state
+        .auth_service
+        .list_service_account_keys(&UserId::new(account_id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/service-accounts/{account_id}/keys/{key_id}",
+    tag = "admin",
+    summary = "Revokes a service account key",
+    params(
+        ("account_id" = uuid::Uuid, Path, description = "The id of the service account"),
+        ("key_id" = uuid::Uuid, Path, description = "The id of the key"),
+    ),
+    responses(
+        (status = 200, description = "The key is revoked", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Service account or key not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn revoke_service_account_key_handler<AS: AuthService + ServiceAccountService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath((account_id, key_id)): ApiPath<(uuid::Uuid, uuid::Uuid)>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    let domain_request = RevokePersonalAccessTokenRequest {
+        user_id: UserId::new(account_id),
+        token_id: key_id,
+    };
+
+    state
+        .auth_service
+        .revoke_service_account_key(&domain_request)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("Service account key revoked"))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/service-accounts/{account_id}/keys/{key_id}/rotate",
+    tag = "admin",
+    summary = "Rotates a service account key",
+    params(
+        ("account_id" = uuid::Uuid, Path, description = "The id of the service account"),
+        ("key_id" = uuid::Uuid, Path, description = "The id of the key"),
+    ),
+    request_body(content = Option<RotateServiceAccountKeySchema>),
+    responses(
+        (status = 200, description = "The replacement key, whose secret is only ever returned here", body = ApiResponse<CreatePersonalAccessTokenResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Service account or key not found", body = ApiErrorResponse),
+        (status = 422, description = "Invalid grace period", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn rotate_service_account_key_handler<AS: AuthService + ServiceAccountService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath((account_id, key_id)): ApiPath<(uuid::Uuid, uuid::Uuid)>,
+    body: Option<Json<RotateServiceAccountKeySchema>>,
+) -> Result<ApiResponse<CreatePersonalAccessTokenResponse>, ApiError> {
+    let Json(body) = body.unwrap_or_default();
+
+    state
+        .auth_service
+        .rotate_service_account_key(&body.into_domain(account_id, key_id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    api::{
+        model::{
+            api_error::{ApiError, ApiErrorBody, ApiErrorResponse, ErrorCode},
+            api_response::ApiResponse,
+        },
+        schemas::webhook::{CreateWebhookSchema, ListWebhookDeliveriesSchema},
+        utils::extractors::{ApiJson, ApiPath, ApiQuery},
+    },
+    application::AppState,
+    domain::{
+        auth_service::AuthService,
+        model::{
+            auth_middleware::AuthMiddleware,
+            webhook::{CreateWebhookResponse, WebhookDelivery, WebhookSubscription},
+        },
+        webhook_service::WebhookService,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhooks",
+    tag = "admin",
+    summary = "Subscribes a webhook to events",
+    request_body = CreateWebhookSchema,
+    responses(
+        (status = 200, description = "The webhook, whose signing secret is only ever returned here", body = ApiResponse<CreateWebhookResponse>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 422, description = "Invalid URL or event types", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn create_webhook_handler<AS: AuthService + WebhookService>(
+    Extension(auth_guard): Extension<AuthMiddleware>,
+    State(state): State<Arc<AppState<AS>>>,
+    ApiJson(body): ApiJson<CreateWebhookSchema>,
+) -> Result<ApiResponse<CreateWebhookResponse>, ApiError> {
+    let admin = auth_guard.user().ok_or_else(|| {
+        ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthUsersOnly,
+            "Only available to users",
+        ))
+    })?;
+
+    state
+        .auth_service
+        .create_webhook(&body.into_domain(admin.id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhooks",
+    tag = "admin",
+    summary = "Lists webhooks",
+    responses(
+        (status = 200, description = "The webhooks", body = ApiResponse<Vec<WebhookSubscription>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_webhooks_handler<AS: AuthService + WebhookService>(
+    State(state): State<Arc<AppState<AS>>>,
+) -> Result<ApiResponse<Vec<WebhookSubscription>>, ApiError> {
+    state
+        .auth_service
+        .list_webhooks()
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/webhooks/{webhook_id}",
+    tag = "admin",
+    summary = "Deletes a webhook",
+    params(
+        ("webhook_id" = uuid::Uuid, Path, description = "The id of the webhook"),
+    ),
+    responses(
+        (status = 200, description = "The webhook is deleted", body = ApiResponse<String>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Webhook not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn delete_webhook_handler<AS: AuthService + WebhookService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(webhook_id): ApiPath<uuid::Uuid>,
+) -> Result<ApiResponse<&'static str>, ApiError> {
+    state
+        .auth_service
+        .delete_webhook(&webhook_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(ApiResponse::success_message("Webhook deleted"))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhooks/{webhook_id}/deliveries",
+    tag = "admin",
+    summary = "Lists webhook deliveries",
+    params(
+        ("webhook_id" = uuid::Uuid, Path, description = "The id of the webhook"),
+        ListWebhookDeliveriesSchema,
+    ),
+    responses(
+        (status = 200, description = "The deliveries of the webhook, newest first", body = ApiResponse<Vec<WebhookDelivery>>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Webhook not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn list_webhook_deliveries_handler<AS: AuthService + WebhookService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath(webhook_id): ApiPath<uuid::Uuid>,
+    ApiQuery(query): ApiQuery<ListWebhookDeliveriesSchema>,
+) -> Result<ApiResponse<Vec<WebhookDelivery>>, ApiError> {
+    state
+        .auth_service
+        .list_webhook_deliveries(&query.into_domain(webhook_id))
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhooks/{webhook_id}/deliveries/{delivery_id}/retry",
+    tag = "admin",
+    summary = "Retries a webhook delivery",
+    params(
+        ("webhook_id" = uuid::Uuid, Path, description = "The id of the webhook"),
+        ("delivery_id" = uuid::Uuid, Path, description = "The id of the delivery"),
+    ),
+    responses(
+        (status = 200, description = "The delivery, queued for another attempt", body = ApiResponse<WebhookDelivery>),
+        (status = 401, description = "Missing, invalid or revoked access token", body = ApiErrorResponse),
+        (status = 403, description = "The access token does not belong to an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Webhook or delivery not found", body = ApiErrorResponse),
+    ),
+    security(("bearer_token" = []), ("access_token_cookie" = []))
+)]
+pub async fn retry_webhook_delivery_handler<AS: AuthService + WebhookService>(
+    State(state): State<Arc<AppState<AS>>>,
+    ApiPath((webhook_id, delivery_id)): ApiPath<(uuid::Uuid, uuid::Uuid)>,
+) -> Result<ApiResponse<WebhookDelivery>, ApiError> {
+    state
+        .auth_service
+        .retry_webhook_delivery(&webhook_id, &delivery_id)
+        .await
+        .map_err(ApiError::from)
+        .map(ApiResponse::success)
+}
@@ -0,0 +1,75 @@
+use crate::{
+    api::utils::request_context::request_context,
+    application::AppState,
+    domain::{
+        audit_service::AuditService,
+        auth_service::AuthService,
+        model::{admin_audit::AdminAuditEntry, auth_middleware::AuthMiddleware},
+    },
+};
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+/// Middleware function recording requests made by administrators in the audit log.
+///
+/// Must run after `auth`, and after `admin` on admin routes. Requests are recorded as
+/// `admin_action` events with the administrator, the method, the matched route and path, the
+/// status of the response, so rejected requests tell what was attempted, and where the request
+/// came from. Requests made with an impersonation token
+/// are attributed to the administrator behind it, with the impersonated user as subject.
+/// Requests from anyone else are forwarded without being recorded.
+///
+/// The response is returned even when the entry cannot be written, which is logged instead.
+pub async fn audit_admin_action<AS: AuthService + AuditService>(
+    State(state): State<Arc<AppState<AS>>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (actor_id, subject_id) = match req.extensions().get::<AuthMiddleware>() {
+        Some(auth_middleware) => match auth_middleware.actor() {
+            Some(actor_id) => (Some(actor_id), Some(auth_middleware.subject())),
+            None => (
+                auth_middleware
+                    .user()
+                    .filter(|user| user.is_admin())
+                    .map(|user| user.id),
+                None,
+            ),
+        },
+        None => (None, None),
+    };
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_default();
+    let path = req.uri().path().to_string();
+    let context = request_context(req.headers(), req.extensions());
+
+    let response = next.run(req).await;
+
+    if let Some(actor_id) = actor_id {
+        let entry = AdminAuditEntry {
+            actor_id,
+            subject_id,
+            method,
+            route,
+            path,
+            status: response.status().as_u16(),
+            context,
+        };
+        if let Err(e) = state.auth_service.record_admin_action(&entry).await {
+            tracing::error!("Failed to record admin action {:?}: {:?}", entry, e);
+        }
+    }
+
+    response
+}
@@ -3,7 +3,10 @@ use crate::{
     application::AppState,
     domain::{
         auth_service::AuthService,
-        model::auth::{AuthRequest, AuthorizationError},
+        model::{
+            auth::{AuthRequest, AuthorizationError},
+            personal_access_token::PersonalAccessTokenSecret,
+        },
     },
 };
 
@@ -24,8 +27,8 @@ use std::sync::Arc;
 /// with the request if authentication is successful. If the token is missing or invalid, it returns an error.
 ///
 /// The process includes:
-/// 1. **Extracting Access Token:** Retrieves the access token from cookies or headers using the `extract_access_token`
-///    helper function.
+/// 1. **Extracting Access Token:** Retrieves the access token or personal access token from cookies or headers using
+///    the `extract_access_token` helper function.
 /// 2. **Authentication:** Uses the extracted token to create an `AuthRequest` and calls the `auth` method on the
 ///    provided `AuthService` to authenticate the request.
 /// 3. **Request Extension:** Inserts the result of the authentication middleware into the request's extensions to
@@ -63,7 +66,48 @@ pub async fn auth<AS: AuthService>(
     Ok(next.run(req).await)
 }
 
-fn extract_access_token(
+/// Middleware function that authenticates the request when it carries an access token, without requiring one.
+///
+/// Works like `auth`, but a missing or invalid token is not an error: the request is forwarded without an
+/// `AuthMiddleware` extension, and handlers take `Option<Extension<AuthMiddleware>>` to decide what to do. This
+/// is used by the OpenID Connect authorization endpoint, which must answer `prompt=none` requests from users
+/// who are not logged in.
+///
+/// # Errors
+///
+/// This function only returns an `ApiError` if the authentication service fails unexpectedly.
+pub async fn optional_auth<AS: AuthService>(
+    cookie_jar: CookieJar,
+    State(state): State<Arc<AppState<AS>>>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Ok(access_token) = extract_access_token(cookie_jar, &req) {
+        match state
+            .auth_service
+            .auth(&AuthRequest::new(access_token))
+            .await
+        {
+            Ok(auth_middleware) => {
+                req.extensions_mut().insert(auth_middleware);
+            }
+            Err(
+                AuthorizationError::InvalidCredentials { .. } | AuthorizationError::TokenExpired,
+            ) => {}
+            Err(e) => return Err(ApiError::from(e)),
+        }
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// Header personal access tokens can be sent in, for clients that cannot set `Authorization`.
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Reads the access token from the `access_token` cookie, the `Authorization: Bearer` header or the
+/// `X-API-Key` header, in that order. Personal access tokens (`pat_...`) are accepted in either header
+/// and are told apart from JWTs by `AuthService::auth`.
+pub(crate) fn extract_access_token(
     cookie_jar: CookieJar,
     req: &Request<Body>,
 ) -> Result<String, AuthorizationError> {
@@ -79,9 +123,14 @@ fn extract_access_token(
                         .strip_prefix("Bearer ")
                         .map(|token| token.to_owned())
                 })
+        })
+        .or_else(|| {
+            req.headers()
+                .get(API_KEY_HEADER)
+                .and_then(|api_key| api_key.to_str().ok())
+                .filter(|api_key| PersonalAccessTokenSecret::is_personal_access_token(api_key))
+                .map(|api_key| api_key.to_owned())
         });
 
-    access_token.ok_or_else(|| AuthorizationError::InvalidCredentials {
-        reason: "You are not logged in".to_string(),
-    })
+    access_token.ok_or(AuthorizationError::MissingCredentials)
 }
@@ -0,0 +1,90 @@
+use crate::{
+    api::model::api_error::{ApiError, ApiErrorBody, ErrorCode},
+    domain::model::{auth::AuthorizationError, auth_middleware::AuthMiddleware},
+};
+
+use axum::{body::Body, extract::State, http::Request, middleware::Next, response::IntoResponse};
+
+/// Middleware function restricting a route to administrators.
+///
+/// Must run after `auth`, whose `AuthMiddleware` extension it inspects. The request is only
+/// forwarded when it comes from the login session of a user with the `admin` role, so scoped
+/// OAuth access tokens, personal access tokens and impersonation tokens cannot reach admin
+/// routes.
+///
+/// # Errors
+///
+/// Returns `ApiError::Unauthorized` if the request was not authenticated, and
+/// `ApiError::Forbidden` if it was not made by an administrator's login session.
+pub async fn admin(req: Request<Body>, next: Next) -> Result<impl IntoResponse, ApiError> {
+    let auth_middleware = req
+        .extensions()
+        .get::<AuthMiddleware>()
+        .ok_or_else(|| ApiError::from(AuthorizationError::MissingCredentials))?;
+
+    let is_admin = auth_middleware.is_login_session()
+        && !auth_middleware.is_impersonated()
+        && auth_middleware.user().is_some_and(|user| user.is_admin());
+    if !is_admin {
+        return Err(ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthAdminRequired,
+            "Only available to administrators",
+        )));
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// Middleware function refusing impersonation tokens on sensitive routes.
+///
+/// Must run after `auth`. Administrators impersonating a user see what the user sees, but
+/// cannot change the user's credentials or obtain tokens that outlive the impersonation.
+///
+/// # Errors
+///
+/// Returns `ApiError::Forbidden` if the request was made with an impersonation token.
+pub async fn not_impersonated(
+    req: Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, ApiError> {
+    let is_impersonated = req
+        .extensions()
+        .get::<AuthMiddleware>()
+        .is_some_and(|auth_middleware| auth_middleware.is_impersonated());
+    if is_impersonated {
+        return Err(ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthImpersonationNotAllowed,
+            "Not available while impersonating a user",
+        )));
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// Middleware function restricting a route to the requests that may act within the scope in
+/// its state, as `middleware::from_fn_with_state("profile", require_scope)`.
+///
+/// Must run after `auth`. Login sessions pass, while OAuth access tokens and personal access
+/// tokens must have been granted the scope.
+///
+/// # Errors
+///
+/// Returns `ApiError::Forbidden` if the token was not granted the scope.
+pub async fn require_scope(
+    State(scope): State<&'static str>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, ApiError> {
+    let has_scope = req
+        .extensions()
+        .get::<AuthMiddleware>()
+        .is_some_and(|auth_middleware| auth_middleware.has_scope(scope));
+    if !has_scope {
+        return Err(ApiError::Forbidden(ApiErrorBody::new(
+            ErrorCode::AuthInsufficientScope,
+            format!("Access token was not granted the {} scope", scope),
+        )));
+    }
+
+    Ok(next.run(req).await)
+}
@@ -1 +1,4 @@
+pub mod audit;
 pub mod authentication;
+pub mod authorization;
+pub mod request_id;
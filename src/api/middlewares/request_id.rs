@@ -0,0 +1,44 @@
+use crate::api::utils::request_context::{REQUEST_ID, REQUEST_ID_HEADER};
+
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+
+/// Longest request id accepted from clients, matching the `request_id` column of the audit log.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Middleware function giving every request an `x-request-id`.
+///
+/// The id sent by the client, or by a proxy in front of the service, is kept when it is a
+/// short printable string. Otherwise a new one is generated. The id is echoed in the response,
+/// so a client can quote it and it can be looked up in the audit log, and is in the body of
+/// every `ApiError` response.
+pub async fn request_id(mut req: Request<Body>, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LENGTH
+                && id.as_bytes().iter().all(|c| c.is_ascii_graphic())
+        })
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&uuid::Uuid::new_v4().to_string())
+                .expect("A UUID is a valid header value")
+        });
+    req.headers_mut()
+        .insert(REQUEST_ID_HEADER, request_id.clone());
+
+    let id = request_id
+        .to_str()
+        .expect("A request id is printable ASCII")
+        .to_string();
+    let mut response = REQUEST_ID.scope(id, next.run(req)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+
+    response
+}
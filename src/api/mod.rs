@@ -1,5 +1,6 @@
 pub mod endpoints;
 pub mod middlewares;
 pub mod model;
+pub mod openapi;
 pub mod schemas;
 pub mod utils;
@@ -1,39 +1,343 @@
-use crate::domain::model::{
-    auth::AuthorizationError,
-    login_user::LoginUserError,
-    refresh_token::RefreshTokenError,
-    register_user::{PasswordHashingError, RegisterUserError},
-    user_email::UserEmailEmptyError,
-    user_password::UserPasswordEmptyError,
+use crate::{
+    api::utils::{request_context::REQUEST_ID, status::Status},
+    domain::model::{
+        admin_user::AdminUserError,
+        audit::AuditError,
+        auth::AuthorizationError,
+        change_password::ChangePasswordError,
+        federation::FederationError,
+        impersonation::ImpersonationError,
+        login_user::LoginUserError,
+        organization::OrganizationError,
+        password_reset::ResetPasswordError,
+        personal_access_token::PersonalAccessTokenError,
+        refresh_token::RefreshTokenError,
+        register_user::{PasswordHashingError, RegisterUserError},
+        registration::RegistrationInvitationError,
+        service_account::ServiceAccountError,
+        user_email::UserEmailEmptyError,
+        user_password::UserPasswordEmptyError,
+        webhook::WebhookError,
+    },
 };
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
 
 #[derive(Debug)]
 pub enum ApiError {
-    InternalServerError(String),
-    UnprocessableEntity(String),
-    Unauthorized(String),
+    InternalServerError(ApiErrorBody),
+    BadRequest(ApiErrorBody),
+    UnprocessableEntity(ApiErrorBody),
+    UnsupportedMediaType(ApiErrorBody),
+    PayloadTooLarge(ApiErrorBody),
+    Unauthorized(ApiErrorBody),
+    Forbidden(ApiErrorBody),
+    NotFound(ApiErrorBody),
 }
 
-impl std::fmt::Display for ApiError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// The stable `code` of an `ApiErrorBody`, which clients branch on.
+///
+/// Codes are part of the public API: add new ones freely, but never rename or remove one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    AuditInvalidRequest,
+    AuthAdminRequired,
+    AuthImpersonationNotAllowed,
+    AuthInsufficientScope,
+    AuthInvalidAudience,
+    AuthInvalidToken,
+    AuthLoginSessionRequired,
+    AuthMissingCredentials,
+    AuthTokenExpired,
+    AuthUsersOnly,
+    ChangePasswordInvalidCredentials,
+    FederationAccountConflict,
+    FederationInvalidRequest,
+    FederationRejected,
+    FederationUnknownProvider,
+    ImpersonationForbidden,
+    ImpersonationInvalidRequest,
+    ImpersonationNotFound,
+    ImpersonationNotImpersonating,
+    LoginInvalidCredentials,
+    LoginLockedOut,
+    LoginPasswordResetRequired,
+    LoginSuspended,
+    OrganizationDuplicateSlug,
+    OrganizationForbidden,
+    OrganizationInvalidRequest,
+    OrganizationNotFound,
+    PasswordResetInvalidToken,
+    PersonalAccessTokenInvalidRequest,
+    PersonalAccessTokenNotFound,
+    RefreshInvalidToken,
+    RefreshMissingToken,
+    RefreshTokenExpired,
+    RegisterDuplicateEmail,
+    RegisterInvalidInvitation,
+    RegisterNotAllowed,
+    RegistrationInvitationInvalidRequest,
+    RegistrationInvitationNotFound,
+    RequestBodyTooLarge,
+    RequestInvalidBody,
+    RequestInvalidField,
+    RequestInvalidPath,
+    RequestInvalidQuery,
+    RequestMalformedJson,
+    RequestUnreadableBody,
+    RequestUnsupportedMediaType,
+    ServerInternalError,
+    ServiceAccountDuplicateName,
+    ServiceAccountInvalidRequest,
+    ServiceAccountNotFound,
+    UserDuplicateEmail,
+    UserInvalidRequest,
+    UserNotFound,
+    WebhookInvalidRequest,
+    WebhookNotFound,
+}
+
+impl ErrorCode {
+    /// Every code, which together make up the public set.
+    pub const ALL: [ErrorCode; 55] = [
+        ErrorCode::AuditInvalidRequest,
+        ErrorCode::AuthAdminRequired,
+        ErrorCode::AuthImpersonationNotAllowed,
+        ErrorCode::AuthInsufficientScope,
+        ErrorCode::AuthInvalidAudience,
+        ErrorCode::AuthInvalidToken,
+        ErrorCode::AuthLoginSessionRequired,
+        ErrorCode::AuthMissingCredentials,
+        ErrorCode::AuthTokenExpired,
+        ErrorCode::AuthUsersOnly,
+        ErrorCode::ChangePasswordInvalidCredentials,
+        ErrorCode::FederationAccountConflict,
+        ErrorCode::FederationInvalidRequest,
+        ErrorCode::FederationRejected,
+        ErrorCode::FederationUnknownProvider,
+        ErrorCode::ImpersonationForbidden,
+        ErrorCode::ImpersonationInvalidRequest,
+        ErrorCode::ImpersonationNotFound,
+        ErrorCode::ImpersonationNotImpersonating,
+        ErrorCode::LoginInvalidCredentials,
+        ErrorCode::LoginLockedOut,
+        ErrorCode::LoginPasswordResetRequired,
+        ErrorCode::LoginSuspended,
+        ErrorCode::OrganizationDuplicateSlug,
+        ErrorCode::OrganizationForbidden,
+        ErrorCode::OrganizationInvalidRequest,
+        ErrorCode::OrganizationNotFound,
+        ErrorCode::PasswordResetInvalidToken,
+        ErrorCode::PersonalAccessTokenInvalidRequest,
+        ErrorCode::PersonalAccessTokenNotFound,
+        ErrorCode::RefreshInvalidToken,
+        ErrorCode::RefreshMissingToken,
+        ErrorCode::RefreshTokenExpired,
+        ErrorCode::RegisterDuplicateEmail,
+        ErrorCode::RegisterInvalidInvitation,
+        ErrorCode::RegisterNotAllowed,
+        ErrorCode::RegistrationInvitationInvalidRequest,
+        ErrorCode::RegistrationInvitationNotFound,
+        ErrorCode::RequestBodyTooLarge,
+        ErrorCode::RequestInvalidBody,
+        ErrorCode::RequestInvalidField,
+        ErrorCode::RequestInvalidPath,
+        ErrorCode::RequestInvalidQuery,
+        ErrorCode::RequestMalformedJson,
+        ErrorCode::RequestUnreadableBody,
+        ErrorCode::RequestUnsupportedMediaType,
+        ErrorCode::ServerInternalError,
+        ErrorCode::ServiceAccountDuplicateName,
+        ErrorCode::ServiceAccountInvalidRequest,
+        ErrorCode::ServiceAccountNotFound,
+        ErrorCode::UserDuplicateEmail,
+        ErrorCode::UserInvalidRequest,
+        ErrorCode::UserNotFound,
+        ErrorCode::WebhookInvalidRequest,
+        ErrorCode::WebhookNotFound,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
         match self {
-            ApiError::InternalServerError(msg) => write!(f, "{}", msg),
-            ApiError::UnprocessableEntity(msg) => write!(f, "{}", msg),
-            ApiError::Unauthorized(msg) => write!(f, "{}", msg),
+            ErrorCode::AuditInvalidRequest => "audit.invalid_request",
+            ErrorCode::AuthAdminRequired => "auth.admin_required",
+            ErrorCode::AuthImpersonationNotAllowed => "auth.impersonation_not_allowed",
+            ErrorCode::AuthInsufficientScope => "auth.insufficient_scope",
+            ErrorCode::AuthInvalidAudience => "auth.invalid_audience",
+            ErrorCode::AuthInvalidToken => "auth.invalid_token",
+            ErrorCode::AuthLoginSessionRequired => "auth.login_session_required",
+            ErrorCode::AuthMissingCredentials => "auth.missing_credentials",
+            ErrorCode::AuthTokenExpired => "auth.token_expired",
+            ErrorCode::AuthUsersOnly => "auth.users_only",
+            ErrorCode::ChangePasswordInvalidCredentials => "change_password.invalid_credentials",
+            ErrorCode::FederationAccountConflict => "federation.account_conflict",
+            ErrorCode::FederationInvalidRequest => "federation.invalid_request",
+            ErrorCode::FederationRejected => "federation.rejected",
+            ErrorCode::FederationUnknownProvider => "federation.unknown_provider",
+            ErrorCode::ImpersonationForbidden => "impersonation.forbidden",
+            ErrorCode::ImpersonationInvalidRequest => "impersonation.invalid_request",
+            ErrorCode::ImpersonationNotFound => "impersonation.not_found",
+            ErrorCode::ImpersonationNotImpersonating => "impersonation.not_impersonating",
+            ErrorCode::LoginInvalidCredentials => "login.invalid_credentials",
+            ErrorCode::LoginLockedOut => "login.locked_out",
+            ErrorCode::LoginPasswordResetRequired => "login.password_reset_required",
+            ErrorCode::LoginSuspended => "login.suspended",
+            ErrorCode::OrganizationDuplicateSlug => "organization.duplicate_slug",
+            ErrorCode::OrganizationForbidden => "organization.forbidden",
+            ErrorCode::OrganizationInvalidRequest => "organization.invalid_request",
+            ErrorCode::OrganizationNotFound => "organization.not_found",
+            ErrorCode::PasswordResetInvalidToken => "password_reset.invalid_token",
+            ErrorCode::PersonalAccessTokenInvalidRequest => "personal_access_token.invalid_request",
+            ErrorCode::PersonalAccessTokenNotFound => "personal_access_token.not_found",
+            ErrorCode::RefreshInvalidToken => "refresh.invalid_token",
+            ErrorCode::RefreshMissingToken => "refresh.missing_token",
+            ErrorCode::RefreshTokenExpired => "refresh.token_expired",
+            ErrorCode::RegisterDuplicateEmail => "register.duplicate_email",
+            ErrorCode::RegisterInvalidInvitation => "register.invalid_invitation",
+            ErrorCode::RegisterNotAllowed => "register.not_allowed",
+            ErrorCode::RegistrationInvitationInvalidRequest => {
+                "registration_invitation.invalid_request"
+            }
+            ErrorCode::RegistrationInvitationNotFound => "registration_invitation.not_found",
+            ErrorCode::RequestBodyTooLarge => "request.body_too_large",
+            ErrorCode::RequestInvalidBody => "request.invalid_body",
+            ErrorCode::RequestInvalidField => "request.invalid_field",
+            ErrorCode::RequestInvalidPath => "request.invalid_path",
+            ErrorCode::RequestInvalidQuery => "request.invalid_query",
+            ErrorCode::RequestMalformedJson => "request.malformed_json",
+            ErrorCode::RequestUnreadableBody => "request.unreadable_body",
+            ErrorCode::RequestUnsupportedMediaType => "request.unsupported_media_type",
+            ErrorCode::ServerInternalError => "server.internal_error",
+            ErrorCode::ServiceAccountDuplicateName => "service_account.duplicate_name",
+            ErrorCode::ServiceAccountInvalidRequest => "service_account.invalid_request",
+            ErrorCode::ServiceAccountNotFound => "service_account.not_found",
+            ErrorCode::UserDuplicateEmail => "user.duplicate_email",
+            ErrorCode::UserInvalidRequest => "user.invalid_request",
+            ErrorCode::UserNotFound => "user.not_found",
+            ErrorCode::WebhookInvalidRequest => "webhook.invalid_request",
+            ErrorCode::WebhookNotFound => "webhook.not_found",
         }
     }
 }
 
+impl Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// The error of a failed `/api` request.
+///
+/// `code` is stable and meant for clients to branch on, like `auth.token_expired` or
+/// `register.duplicate_email`, while `message` is for humans and may change.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiErrorBody {
+    #[schema(value_type = String)]
+    pub code: ErrorCode,
+    pub message: String,
+    pub field_errors: Vec<FieldError>,
+    /// The `x-request-id` of the request, set when the response is built.
+    pub request_id: Option<String>,
+}
+
+/// A problem with one field of the request body.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// The body of every `ApiError` response: the `ApiResponse` envelope with a `Failure` status.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiErrorResponse {
+    status: Status,
+    error: ApiErrorBody,
+}
+
+impl ApiErrorBody {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiErrorBody {
+            code,
+            message: message.into(),
+            field_errors: vec![],
+            request_id: None,
+        }
+    }
+
+    /// The body of unexpected failures, whose cause is logged rather than returned.
+    pub fn internal() -> Self {
+        ApiErrorBody::new(ErrorCode::ServerInternalError, "Internal Server Error")
+    }
+
+    pub fn with_field_error(mut self, field: &str, message: impl Into<String>) -> Self {
+        self.field_errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+        self
+    }
+}
+
+impl ApiError {
+    pub fn body(&self) -> &ApiErrorBody {
+        match self {
+            ApiError::InternalServerError(body)
+            | ApiError::BadRequest(body)
+            | ApiError::UnprocessableEntity(body)
+            | ApiError::UnsupportedMediaType(body)
+            | ApiError::PayloadTooLarge(body)
+            | ApiError::Unauthorized(body)
+            | ApiError::Forbidden(body)
+            | ApiError::NotFound(body) => body,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.body().message)
+    }
+}
+
 impl From<RegisterUserError> for ApiError {
     fn from(value: RegisterUserError) -> Self {
         match &value {
-            RegisterUserError::Duplicate { email } => {
-                Self::UnprocessableEntity(format!("User with email {} already exists", email))
+            RegisterUserError::Duplicate { email } => Self::UnprocessableEntity(
+                ApiErrorBody::new(
+                    ErrorCode::RegisterDuplicateEmail,
+                    format!("User with email {} already exists", email),
+                )
+                .with_field_error("email", "already registered"),
+            ),
+            RegisterUserError::NotAllowed { reason } => {
+                Self::Forbidden(ApiErrorBody::new(ErrorCode::RegisterNotAllowed, reason))
             }
+            RegisterUserError::InvalidInvitation { reason } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::RegisterInvalidInvitation, reason)
+                    .with_field_error("invitation_token", reason),
+            ),
             RegisterUserError::Unknown(cause) => {
                 tracing::error!("{:?}\n{}", cause, cause.backtrace());
-                Self::InternalServerError("Internal Server Error".to_string())
+                Self::InternalServerError(ApiErrorBody::internal())
             }
         }
     }
@@ -42,12 +346,20 @@ impl From<RegisterUserError> for ApiError {
 impl From<AuthorizationError> for ApiError {
     fn from(value: AuthorizationError) -> Self {
         match &value {
+            AuthorizationError::MissingCredentials => Self::Unauthorized(ApiErrorBody::new(
+                ErrorCode::AuthMissingCredentials,
+                value.to_string(),
+            )),
             AuthorizationError::InvalidCredentials { reason } => {
-                Self::Unauthorized(reason.to_string())
+                Self::Unauthorized(ApiErrorBody::new(ErrorCode::AuthInvalidToken, reason))
             }
+            AuthorizationError::TokenExpired => Self::Unauthorized(ApiErrorBody::new(
+                ErrorCode::AuthTokenExpired,
+                value.to_string(),
+            )),
             AuthorizationError::Unknown(cause) => {
                 tracing::error!("{:?}\n{}", cause, cause.backtrace());
-                Self::InternalServerError("Internal Server Error".to_string())
+                Self::InternalServerError(ApiErrorBody::internal())
             }
         }
     }
@@ -56,54 +368,521 @@ impl From<AuthorizationError> for ApiError {
 impl From<RefreshTokenError> for ApiError {
     fn from(value: RefreshTokenError) -> ApiError {
         match value {
-            RefreshTokenError::InvalidCredentials { reason } => ApiError::Unauthorized(reason),
-            RefreshTokenError::MissingCredentials => {
-                ApiError::Unauthorized("Missing credentials".to_string())
+            RefreshTokenError::InvalidCredentials { reason } => {
+                ApiError::Unauthorized(ApiErrorBody::new(ErrorCode::RefreshInvalidToken, reason))
             }
-            _ => ApiError::InternalServerError("Internal Server Error".to_string()),
+            RefreshTokenError::MissingCredentials => ApiError::Unauthorized(ApiErrorBody::new(
+                ErrorCode::RefreshMissingToken,
+                "Missing credentials",
+            )),
+            RefreshTokenError::TokenExpired => ApiError::Unauthorized(ApiErrorBody::new(
+                ErrorCode::RefreshTokenExpired,
+                value.to_string(),
+            )),
+            _ => ApiError::InternalServerError(ApiErrorBody::internal()),
         }
     }
 }
 
 impl From<UserEmailEmptyError> for ApiError {
     fn from(_: UserEmailEmptyError) -> Self {
-        Self::UnprocessableEntity("Email cannot be empty".to_string())
+        Self::UnprocessableEntity(
+            ApiErrorBody::new(ErrorCode::RequestInvalidField, "Email cannot be empty")
+                .with_field_error("email", "cannot be empty"),
+        )
     }
 }
 
 impl From<UserPasswordEmptyError> for ApiError {
     fn from(_: UserPasswordEmptyError) -> Self {
-        Self::UnprocessableEntity("Password cannot be empty".to_string())
+        Self::UnprocessableEntity(
+            ApiErrorBody::new(ErrorCode::RequestInvalidField, "Password cannot be empty")
+                .with_field_error("password", "cannot be empty"),
+        )
     }
 }
 
 impl From<PasswordHashingError> for ApiError {
     fn from(_: PasswordHashingError) -> Self {
-        Self::InternalServerError("Something went wrong".to_string())
+        Self::InternalServerError(ApiErrorBody::new(
+            ErrorCode::ServerInternalError,
+            "Something went wrong",
+        ))
     }
 }
 
 impl From<LoginUserError> for ApiError {
     fn from(value: LoginUserError) -> Self {
         match &value {
-            LoginUserError::InvalidCredentials => {
-                Self::Unauthorized("Invalid credentials".to_string())
-            }
+            LoginUserError::InvalidCredentials => Self::Unauthorized(ApiErrorBody::new(
+                ErrorCode::LoginInvalidCredentials,
+                "Invalid credentials",
+            )),
+            LoginUserError::Suspended => Self::Forbidden(ApiErrorBody::new(
+                ErrorCode::LoginSuspended,
+                value.to_string(),
+            )),
+            LoginUserError::PasswordResetRequired => Self::Forbidden(ApiErrorBody::new(
+                ErrorCode::LoginPasswordResetRequired,
+                value.to_string(),
+            )),
+            LoginUserError::LockedOut => Self::Forbidden(ApiErrorBody::new(
+                ErrorCode::LoginLockedOut,
+                value.to_string(),
+            )),
             LoginUserError::Unknown(cause) => {
                 tracing::error!("{:?}\n{}", cause, cause.backtrace());
-                Self::InternalServerError("Internal Server Error".to_string())
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<FederationError> for ApiError {
+    fn from(value: FederationError) -> Self {
+        match &value {
+            FederationError::UnknownProvider { .. } => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::FederationUnknownProvider,
+                value.to_string(),
+            )),
+            FederationError::InvalidRequest { .. } => Self::UnprocessableEntity(ApiErrorBody::new(
+                ErrorCode::FederationInvalidRequest,
+                value.to_string(),
+            )),
+            FederationError::AccountConflict { .. } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::FederationAccountConflict, value.to_string()),
+            ),
+            FederationError::Rejected { reason } => {
+                tracing::warn!("Federated login rejected: {}", reason);
+                Self::Unauthorized(ApiErrorBody::new(
+                    ErrorCode::FederationRejected,
+                    "Federated login failed",
+                ))
+            }
+            FederationError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
             }
         }
     }
 }
 
+impl From<PersonalAccessTokenError> for ApiError {
+    fn from(value: PersonalAccessTokenError) -> Self {
+        match &value {
+            PersonalAccessTokenError::InvalidRequest { reason } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::PersonalAccessTokenInvalidRequest, reason),
+            ),
+            PersonalAccessTokenError::NotFound => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::PersonalAccessTokenNotFound,
+                value.to_string(),
+            )),
+            PersonalAccessTokenError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<ServiceAccountError> for ApiError {
+    fn from(value: ServiceAccountError) -> Self {
+        match &value {
+            ServiceAccountError::InvalidRequest { reason } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::ServiceAccountInvalidRequest, reason),
+            ),
+            ServiceAccountError::Duplicate { .. } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::ServiceAccountDuplicateName, value.to_string())
+                    .with_field_error("name", "already taken"),
+            ),
+            ServiceAccountError::NotFound => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::ServiceAccountNotFound,
+                value.to_string(),
+            )),
+            ServiceAccountError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<OrganizationError> for ApiError {
+    fn from(value: OrganizationError) -> Self {
+        match &value {
+            OrganizationError::InvalidRequest { reason } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::OrganizationInvalidRequest, reason),
+            ),
+            OrganizationError::Duplicate { .. } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::OrganizationDuplicateSlug, value.to_string())
+                    .with_field_error("slug", "already taken"),
+            ),
+            OrganizationError::NotFound => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::OrganizationNotFound,
+                value.to_string(),
+            )),
+            OrganizationError::Forbidden { reason } => {
+                Self::Forbidden(ApiErrorBody::new(ErrorCode::OrganizationForbidden, reason))
+            }
+            OrganizationError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<RegistrationInvitationError> for ApiError {
+    fn from(value: RegistrationInvitationError) -> Self {
+        match &value {
+            RegistrationInvitationError::InvalidRequest { reason } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::RegistrationInvitationInvalidRequest, reason),
+            ),
+            RegistrationInvitationError::NotFound => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::RegistrationInvitationNotFound,
+                value.to_string(),
+            )),
+            RegistrationInvitationError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<AdminUserError> for ApiError {
+    fn from(value: AdminUserError) -> Self {
+        match &value {
+            AdminUserError::InvalidRequest { reason } => {
+                Self::UnprocessableEntity(ApiErrorBody::new(ErrorCode::UserInvalidRequest, reason))
+            }
+            AdminUserError::Duplicate { .. } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::UserDuplicateEmail, value.to_string())
+                    .with_field_error("email", "already registered"),
+            ),
+            AdminUserError::NotFound => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::UserNotFound,
+                value.to_string(),
+            )),
+            AdminUserError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<AuditError> for ApiError {
+    fn from(value: AuditError) -> Self {
+        match &value {
+            AuditError::InvalidRequest { reason } => {
+                Self::UnprocessableEntity(ApiErrorBody::new(ErrorCode::AuditInvalidRequest, reason))
+            }
+            AuditError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<ResetPasswordError> for ApiError {
+    fn from(value: ResetPasswordError) -> Self {
+        match &value {
+            ResetPasswordError::InvalidToken => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::PasswordResetInvalidToken, value.to_string())
+                    .with_field_error("token", "invalid or expired"),
+            ),
+            ResetPasswordError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<ChangePasswordError> for ApiError {
+    fn from(value: ChangePasswordError) -> Self {
+        match &value {
+            ChangePasswordError::InvalidCredentials => Self::UnprocessableEntity(
+                ApiErrorBody::new(
+                    ErrorCode::ChangePasswordInvalidCredentials,
+                    value.to_string(),
+                )
+                .with_field_error("current_password", "incorrect"),
+            ),
+            ChangePasswordError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<ImpersonationError> for ApiError {
+    fn from(value: ImpersonationError) -> Self {
+        match &value {
+            ImpersonationError::InvalidRequest { reason } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::ImpersonationInvalidRequest, reason),
+            ),
+            ImpersonationError::NotFound => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::ImpersonationNotFound,
+                value.to_string(),
+            )),
+            ImpersonationError::Forbidden { reason } => {
+                Self::Forbidden(ApiErrorBody::new(ErrorCode::ImpersonationForbidden, reason))
+            }
+            ImpersonationError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<WebhookError> for ApiError {
+    fn from(value: WebhookError) -> Self {
+        match &value {
+            WebhookError::InvalidRequest { reason } => Self::UnprocessableEntity(
+                ApiErrorBody::new(ErrorCode::WebhookInvalidRequest, reason),
+            ),
+            WebhookError::NotFound => Self::NotFound(ApiErrorBody::new(
+                ErrorCode::WebhookNotFound,
+                value.to_string(),
+            )),
+            WebhookError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "verifier")]
+impl From<crate::verifier::verify_error::VerifyError> for ApiError {
+    fn from(value: crate::verifier::verify_error::VerifyError) -> ApiError {
+        use crate::verifier::verify_error::VerifyError;
+
+        match value {
+            VerifyError::MissingToken => ApiError::Unauthorized(ApiErrorBody::new(
+                ErrorCode::AuthMissingCredentials,
+                value.to_string(),
+            )),
+            VerifyError::InvalidToken { .. } => ApiError::Unauthorized(ApiErrorBody::new(
+                ErrorCode::AuthInvalidToken,
+                value.to_string(),
+            )),
+            VerifyError::InvalidAudience { .. } => ApiError::Unauthorized(ApiErrorBody::new(
+                ErrorCode::AuthInvalidAudience,
+                value.to_string(),
+            )),
+            VerifyError::InsufficientScope { .. } => ApiError::Forbidden(ApiErrorBody::new(
+                ErrorCode::AuthInsufficientScope,
+                value.to_string(),
+            )),
+            VerifyError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                ApiError::InternalServerError(ApiErrorBody::internal())
+            }
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(value: JsonRejection) -> Self {
+        match &value {
+            JsonRejection::JsonDataError(_) => {
+                let body = ApiErrorBody::new(ErrorCode::RequestInvalidBody, value.body_text());
+                Self::UnprocessableEntity(match missing_field(&value.body_text()) {
+                    Some(field) => body.with_field_error(&field, "is required"),
+                    None => body,
+                })
+            }
+            JsonRejection::JsonSyntaxError(_) => Self::BadRequest(ApiErrorBody::new(
+                ErrorCode::RequestMalformedJson,
+                value.body_text(),
+            )),
+            JsonRejection::MissingJsonContentType(_) => Self::UnsupportedMediaType(
+                ApiErrorBody::new(ErrorCode::RequestUnsupportedMediaType, value.body_text()),
+            ),
+            _ if value.status() == StatusCode::PAYLOAD_TOO_LARGE => Self::PayloadTooLarge(
+                ApiErrorBody::new(ErrorCode::RequestBodyTooLarge, value.body_text()),
+            ),
+            _ => Self::BadRequest(ApiErrorBody::new(
+                ErrorCode::RequestUnreadableBody,
+                value.body_text(),
+            )),
+        }
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(value: PathRejection) -> Self {
+        Self::BadRequest(ApiErrorBody::new(
+            ErrorCode::RequestInvalidPath,
+            value.body_text(),
+        ))
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(value: QueryRejection) -> Self {
+        Self::BadRequest(ApiErrorBody::new(
+            ErrorCode::RequestInvalidQuery,
+            value.body_text(),
+        ))
+    }
+}
+
+/// The field named by serde's "missing field `name`" error, the only deserialization error
+/// whose message says which field it is about.
+fn missing_field(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once("missing field `")?;
+    let (field, _) = rest.split_once('`')?;
+    Some(field.to_string())
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
-        match self {
-            ApiError::InternalServerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            ApiError::UnprocessableEntity(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
-            ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
-        }
-        .into_response()
+        let status = self.status();
+        let mut error = match self {
+            ApiError::InternalServerError(body)
+            | ApiError::BadRequest(body)
+            | ApiError::UnprocessableEntity(body)
+            | ApiError::UnsupportedMediaType(body)
+            | ApiError::PayloadTooLarge(body)
+            | ApiError::Unauthorized(body)
+            | ApiError::Forbidden(body)
+            | ApiError::NotFound(body) => body,
+        };
+        error.request_id = REQUEST_ID.try_with(|id| id.clone()).ok();
+
+        let response = ApiErrorResponse {
+            status: Status::Failure,
+            error,
+        };
+        (status, Json(response)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::to_bytes;
+
+    use super::*;
+    use crate::domain::model::user_email::UserEmail;
+
+    async fn response_json(error: ApiError) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_error_response_is_json_with_code_and_request_id() {
+        let error = ApiError::from(RegisterUserError::Duplicate {
+            email: UserEmail::new("test@example.com").unwrap(),
+        });
+
+        let (status, body) = REQUEST_ID
+            .scope("request-1".to_string(), response_json(error))
+            .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["status"], "Failure");
+        assert_eq!(body["error"]["code"], "register.duplicate_email");
+        assert_eq!(
+            body["error"]["message"],
+            "User with email test@example.com already exists"
+        );
+        assert_eq!(body["error"]["field_errors"][0]["field"], "email");
+        assert_eq!(body["error"]["request_id"], "request-1");
+    }
+
+    #[tokio::test]
+    async fn test_error_response_without_request_id() {
+        let (status, body) = response_json(ApiError::from(AuthorizationError::TokenExpired)).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], "auth.token_expired");
+        assert!(body["error"]["request_id"].is_null());
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        let codes: Vec<_> = ErrorCode::ALL.iter().map(ErrorCode::as_str).collect();
+
+        assert_eq!(
+            codes,
+            [
+                "audit.invalid_request",
+                "auth.admin_required",
+                "auth.impersonation_not_allowed",
+                "auth.insufficient_scope",
+                "auth.invalid_audience",
+                "auth.invalid_token",
+                "auth.login_session_required",
+                "auth.missing_credentials",
+                "auth.token_expired",
+                "auth.users_only",
+                "change_password.invalid_credentials",
+                "federation.account_conflict",
+                "federation.invalid_request",
+                "federation.rejected",
+                "federation.unknown_provider",
+                "impersonation.forbidden",
+                "impersonation.invalid_request",
+                "impersonation.not_found",
+                "impersonation.not_impersonating",
+                "login.invalid_credentials",
+                "login.locked_out",
+                "login.password_reset_required",
+                "login.suspended",
+                "organization.duplicate_slug",
+                "organization.forbidden",
+                "organization.invalid_request",
+                "organization.not_found",
+                "password_reset.invalid_token",
+                "personal_access_token.invalid_request",
+                "personal_access_token.not_found",
+                "refresh.invalid_token",
+                "refresh.missing_token",
+                "refresh.token_expired",
+                "register.duplicate_email",
+                "register.invalid_invitation",
+                "register.not_allowed",
+                "registration_invitation.invalid_request",
+                "registration_invitation.not_found",
+                "request.body_too_large",
+                "request.invalid_body",
+                "request.invalid_field",
+                "request.invalid_path",
+                "request.invalid_query",
+                "request.malformed_json",
+                "request.unreadable_body",
+                "request.unsupported_media_type",
+                "server.internal_error",
+                "service_account.duplicate_name",
+                "service_account.invalid_request",
+                "service_account.not_found",
+                "user.duplicate_email",
+                "user.invalid_request",
+                "user.not_found",
+                "webhook.invalid_request",
+                "webhook.not_found",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_field() {
+        assert_eq!(
+            missing_field(
+                "Failed to deserialize the JSON body into the target type: missing field `email` at line 1 column 2"
+            ),
+            Some("email".to_string())
+        );
+        assert_eq!(missing_field("invalid type: integer `1`"), None);
     }
 }
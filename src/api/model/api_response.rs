@@ -7,8 +7,11 @@ use axum::{
     Json,
 };
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize)]
+/// The envelope of every response of the `/api` endpoints: `data` on success, or a `message`
+/// for requests that only need acknowledging.
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ApiResponse<T> {
     status: Status,
     data: Option<T>,
@@ -1,2 +1,3 @@
 pub mod api_error;
 pub mod api_response;
+pub mod oauth_error;
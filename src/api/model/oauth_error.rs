@@ -0,0 +1,56 @@
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::model::oauth_errors::OAuthError;
+
+/// An error response from the OAuth endpoints, formatted as defined by RFC 6749 section 5.2.
+#[derive(Debug)]
+pub struct OAuthApiError(OAuthError);
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OAuthErrorBody {
+    error: &'static str,
+    error_description: String,
+}
+
+impl From<OAuthError> for OAuthApiError {
+    fn from(value: OAuthError) -> Self {
+        if let OAuthError::Unknown(cause) = &value {
+            tracing::error!("{:?}\n{}", cause, cause.backtrace());
+        }
+        OAuthApiError(value)
+    }
+}
+
+impl IntoResponse for OAuthApiError {
+    fn into_response(self) -> axum::response::Response {
+        let status = match &self.0 {
+            OAuthError::InvalidClient { .. } => StatusCode::UNAUTHORIZED,
+            OAuthError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            OAuthError::LoginRequired { .. } => StatusCode::UNAUTHORIZED,
+            OAuthError::InsufficientScope { .. } => StatusCode::FORBIDDEN,
+            OAuthError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+
+        let body = OAuthErrorBody {
+            error: self.0.code(),
+            error_description: self.0.description(),
+        };
+
+        (
+            status,
+            [
+                (header::CACHE_CONTROL, "no-store"),
+                (header::PRAGMA, "no-cache"),
+            ],
+            Json(body),
+        )
+            .into_response()
+    }
+}
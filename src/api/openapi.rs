@@ -0,0 +1,159 @@
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::api::endpoints::{
+    admin_users, audit_events, change_password, federation, forward_auth, get_me, healthcheck,
+    impersonation, login, logout, oauth_authorize, oauth_device, oauth_introspect, oauth_revoke,
+    oauth_token, oidc_discovery, oidc_userinfo, organizations, password_reset,
+    personal_access_tokens, refresh, register, registration_invitations, saml, service_accounts,
+    webhooks,
+};
+
+/// The OpenAPI document of every HTTP endpoint, served at `/api/openapi.json` and browsable at
+/// `/api/docs`.
+///
+/// Every route of `app` must be listed here, which the tests below check against the router.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Authentication Service",
+        description = "User authentication, sessions, OAuth 2.0 and OpenID Connect."
+    ),
+    paths(
+        healthcheck::healthcheck,
+        register::register_handler,
+        login::login_handler,
+        refresh::refresh_access_token_handler,
+        logout::logout_handler,
+        password_reset::reset_password_handler,
+        forward_auth::verify_handler,
+        get_me::get_me_handler,
+        audit_events::recent_activity_handler,
+        change_password::change_password_handler,
+        personal_access_tokens::create_personal_access_token_handler,
+        personal_access_tokens::list_personal_access_tokens_handler,
+        personal_access_tokens::revoke_personal_access_token_handler,
+        impersonation::end_impersonation_handler,
+        organizations::create_organization_handler,
+        organizations::list_organizations_handler,
+        organizations::list_members_handler,
+        organizations::invite_member_handler,
+        organizations::accept_invitation_handler,
+        organizations::remove_member_handler,
+        organizations::switch_organization_handler,
+        federation::federated_login_handler,
+        federation::federated_callback_handler,
+        saml::saml_metadata_handler,
+        saml::saml_login_handler,
+        saml::saml_assertion_handler,
+        oauth_authorize::authorize_handler,
+        oauth_token::token_handler,
+        oauth_introspect::introspect_handler,
+        oauth_revoke::revoke_handler,
+        oauth_device::device_authorization_handler,
+        oauth_device::device_verification_handler,
+        oidc_userinfo::userinfo_handler,
+        oidc_discovery::openid_configuration_handler,
+        oidc_discovery::jwks_handler,
+        audit_events::list_audit_events_handler,
+        audit_events::verify_audit_chain_handler,
+        admin_users::list_users_handler,
+        admin_users::get_user_handler,
+        admin_users::update_user_handler,
+        admin_users::delete_user_handler,
+        admin_users::force_password_reset_handler,
+        admin_users::revoke_user_sessions_handler,
+        impersonation::start_impersonation_handler,
+        registration_invitations::create_registration_invitation_handler,
+        registration_invitations::list_registration_invitations_handler,
+        registration_invitations::revoke_registration_invitation_handler,
+        service_accounts::create_service_account_handler,
+        service_accounts::list_service_accounts_handler,
+        service_accounts::delete_service_account_handler,
+        service_accounts::create_service_account_key_handler,
+        service_accounts::list_service_account_keys_handler,
+        service_accounts::revoke_service_account_key_handler,
+        service_accounts::rotate_service_account_key_handler,
+        webhooks::create_webhook_handler,
+        webhooks::list_webhooks_handler,
+        webhooks::delete_webhook_handler,
+        webhooks::list_webhook_deliveries_handler,
+        webhooks::retry_webhook_delivery_handler,
+    ),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "health", description = "Liveness of the service"),
+        (name = "auth", description = "Registration, login and sessions"),
+        (name = "forward-auth", description = "Authentication of requests forwarded by a reverse proxy"),
+        (name = "users", description = "The authenticated user"),
+        (name = "organizations", description = "Organizations and their members"),
+        (name = "federation", description = "Login with external OpenID Connect and SAML identity providers"),
+        (name = "oauth", description = "The OAuth 2.0 authorization server"),
+        (name = "oidc", description = "OpenID Connect discovery and userinfo"),
+        (name = "admin", description = "Administration, restricted to the `admin` role"),
+    )
+)]
+pub struct ApiDoc;
+
+/// The credentials referenced by the `security` of each operation. Access tokens are accepted
+/// as a bearer token or in the cookie set by login, and OAuth clients may authenticate with
+/// HTTP basic instead of form parameters.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "access_token_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("access_token"))),
+        );
+        components.add_security_scheme(
+            "refresh_token_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("refresh_token"))),
+        );
+        components.add_security_scheme(
+            "client_basic",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Basic).build()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utoipa::OpenApi;
+
+    use super::ApiDoc;
+
+    #[test]
+    fn test_openapi_documents_request_and_response_schemas() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = &spec["components"]["schemas"];
+
+        for schema in [
+            "RegisterUserSchema",
+            "LoginUserSchema",
+            "FilteredUser",
+            "LoginResponse",
+            "RefreshResponse",
+            "ApiErrorResponse",
+            "OAuthErrorBody",
+        ] {
+            assert!(
+                schemas.get(schema).is_some(),
+                "{} is not documented",
+                schema
+            );
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::model::api_error::ApiError,
+    domain::model::{
+        admin_user::{ListUsersRequest, UpdateUserRequest, DEFAULT_PAGE_SIZE},
+        user::{UserKind, UserStatus},
+        user_email::UserEmail,
+        user_id::UserId,
+    },
+};
+
+/// Query parameters of the admin user listing.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListUsersSchema {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub email: Option<String>,
+    pub status: Option<UserStatus>,
+    pub kind: Option<UserKind>,
+    pub role: Option<String>,
+}
+
+impl ListUsersSchema {
+    pub fn into_domain(self) -> ListUsersRequest {
+        ListUsersRequest {
+            page: self.page.unwrap_or(1),
+            per_page: self.per_page.unwrap_or(DEFAULT_PAGE_SIZE),
+            email: self.email.filter(|email| !email.is_empty()),
+            status: self.status,
+            kind: self.kind,
+            role: self.role.filter(|role| !role.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateUserSchema {
+    pub email: Option<String>,
+    pub status: Option<UserStatus>,
+}
+
+impl UpdateUserSchema {
+    pub fn try_into_domain(
+        self,
+        admin_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> Result<UpdateUserRequest, ApiError> {
+        let email = match self.email {
+            Some(email) => Some(UserEmail::new(&email)?),
+            None => None,
+        };
+
+        Ok(UpdateUserRequest {
+            actor_id: UserId::new(admin_id),
+            user_id: UserId::new(user_id),
+            email,
+            status: self.status,
+        })
+    }
+}
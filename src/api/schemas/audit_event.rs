@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::domain::model::audit::{
+    AuditEventType, AuditOutcome, ListAuditEventsRequest, DEFAULT_AUDIT_PAGE_SIZE,
+};
+
+/// Query parameters of the admin audit log search. `since` and `until` are RFC 3339
+/// timestamps.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListAuditEventsSchema {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub user_id: Option<uuid::Uuid>,
+    pub actor_id: Option<uuid::Uuid>,
+    pub event_type: Option<AuditEventType>,
+    pub outcome: Option<AuditOutcome>,
+    pub ip_address: Option<String>,
+    pub request_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ListAuditEventsSchema {
+    pub fn into_domain(self) -> ListAuditEventsRequest {
+        ListAuditEventsRequest {
+            page: self.page.unwrap_or(1),
+            per_page: self.per_page.unwrap_or(DEFAULT_AUDIT_PAGE_SIZE),
+            user_id: self.user_id,
+            actor_id: self.actor_id,
+            event_type: self.event_type,
+            outcome: self.outcome,
+            ip_address: self.ip_address.filter(|ip_address| !ip_address.is_empty()),
+            request_id: self.request_id.filter(|request_id| !request_id.is_empty()),
+            since: self.since,
+            until: self.until,
+        }
+    }
+}
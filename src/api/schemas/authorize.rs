@@ -0,0 +1,42 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::domain::model::{
+    authorize::{AuthorizeRequest, AuthorizeSession},
+    scope::Scopes,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuthorizeSchema {
+    #[serde(default)]
+    pub response_type: String,
+    #[serde(default)]
+    pub client_id: String,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>,
+    pub prompt: Option<String>,
+    pub max_age: Option<i64>,
+}
+
+impl AuthorizeSchema {
+    pub fn into_domain(self, session: Option<AuthorizeSession>) -> AuthorizeRequest {
+        AuthorizeRequest {
+            session,
+            response_type: self.response_type,
+            client_id: self.client_id,
+            redirect_uri: self.redirect_uri,
+            scope: Scopes::parse(self.scope.as_deref().unwrap_or_default()),
+            state: self.state,
+            code_challenge: self.code_challenge,
+            code_challenge_method: self.code_challenge_method,
+            nonce: self.nonce,
+            prompt: self.prompt,
+            max_age: self.max_age,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::model::api_error::ApiError,
+    domain::model::{
+        audit::RequestContext, change_password::ChangePasswordRequest,
+        register_user::HashedUserPassword, user_id::UserId, user_password::UserPassword,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangePasswordSchema {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+impl ChangePasswordSchema {
+    pub fn try_into_domain(
+        self,
+        user_id: uuid::Uuid,
+        context: RequestContext,
+    ) -> Result<ChangePasswordRequest, ApiError> {
+        let current_password = UserPassword::new(&self.current_password)?;
+        let new_password = HashedUserPassword::new(UserPassword::new(&self.new_password)?)?;
+        Ok(ChangePasswordRequest {
+            user_id: UserId::new(user_id),
+            current_password,
+            new_password,
+            context,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::utils::client_auth::client_authentication,
+    domain::model::{
+        auth_middleware::AuthMiddleware,
+        authentication_context::AuthenticationContext,
+        device_authorization::{DeviceAuthorizationRequest, DeviceVerificationRequest},
+        oauth_errors::OAuthError,
+        scope::Scopes,
+    },
+};
+
+/// Form body of the device authorization endpoint, RFC 8628 section 3.1.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeviceAuthorizationSchema {
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub scope: Option<String>,
+}
+
+impl DeviceAuthorizationSchema {
+    pub fn try_into_domain(
+        self,
+        headers: &HeaderMap,
+    ) -> Result<DeviceAuthorizationRequest, OAuthError> {
+        Ok(DeviceAuthorizationRequest {
+            client: client_authentication(headers, self.client_id, self.client_secret)?,
+            scope: Scopes::parse(self.scope.as_deref().unwrap_or_default()),
+        })
+    }
+}
+
+/// Body sent by a signed in user to approve or deny the device showing `user_code`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeviceVerificationSchema {
+    pub user_code: String,
+    pub approve: bool,
+}
+
+impl DeviceVerificationSchema {
+    pub fn try_into_domain(
+        self,
+        auth: &AuthMiddleware,
+    ) -> Result<DeviceVerificationRequest, OAuthError> {
+        let user = auth.user().ok_or_else(|| OAuthError::AccessDenied {
+            description: "Only users can approve devices".to_string(),
+        })?;
+        // A scoped token must not grant a device more than it was granted itself.
+        if !auth.is_login_session() {
+            return Err(OAuthError::AccessDenied {
+                description: "Devices can only be approved from a login session".to_string(),
+            });
+        }
+
+        Ok(DeviceVerificationRequest {
+            user_id: user.id,
+            context: AuthenticationContext::from_claims(&auth.claims),
+            user_code: self.user_code,
+            approve: self.approve,
+        })
+    }
+}
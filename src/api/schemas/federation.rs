@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::domain::model::federation::{FederatedCallbackRequest, FederatedLoginRequest};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FederatedLoginSchema {
+    pub return_to: Option<String>,
+}
+
+impl FederatedLoginSchema {
+    pub fn into_domain(self, provider: String) -> FederatedLoginRequest {
+        FederatedLoginRequest {
+            provider,
+            return_to: self.return_to,
+        }
+    }
+}
+
+/// Query of the provider's redirect back to us, a success or error authorization response.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FederatedCallbackSchema {
+    #[serde(default)]
+    pub state: String,
+    pub code: Option<String>,
+    pub error: Option<String>,
+}
+
+impl FederatedCallbackSchema {
+    pub fn into_domain(
+        self,
+        provider: String,
+        state_digest: Option<String>,
+    ) -> FederatedCallbackRequest {
+        FederatedCallbackRequest {
+            provider,
+            state: self.state,
+            state_digest,
+            code: self.code,
+            error: self.error,
+        }
+    }
+}
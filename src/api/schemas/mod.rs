@@ -1,2 +1,17 @@
+pub mod admin_user;
+pub mod audit_event;
+pub mod authorize;
+pub mod change_password;
+pub mod device_authorization;
+pub mod federation;
 pub mod login_user;
+pub mod organization;
+pub mod password_reset;
+pub mod personal_access_token;
 pub mod register_user;
+pub mod registration_invitation;
+pub mod saml;
+pub mod service_account;
+pub mod token_introspection;
+pub mod token_request;
+pub mod webhook;
@@ -0,0 +1,61 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::domain::model::{
+    organization::{
+        AcceptInvitationRequest, CreateOrganizationRequest, InviteMemberRequest, OrganizationRole,
+    },
+    user::User,
+    user_id::UserId,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateOrganizationSchema {
+    pub name: String,
+    pub slug: String,
+}
+
+impl CreateOrganizationSchema {
+    pub fn into_domain(self, user_id: UserId) -> CreateOrganizationRequest {
+        CreateOrganizationRequest {
+            user_id,
+            name: self.name,
+            slug: self.slug,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InviteMemberSchema {
+    pub email: String,
+    pub role: Option<OrganizationRole>,
+}
+
+impl InviteMemberSchema {
+    /// Invitees join as plain members unless `role` says otherwise.
+    pub fn into_domain(self, organization_id: uuid::Uuid, actor_id: UserId) -> InviteMemberRequest {
+        InviteMemberRequest {
+            organization_id,
+            actor_id,
+            email: self.email,
+            role: self.role.unwrap_or(OrganizationRole::Member),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AcceptInvitationSchema {
+    pub token: String,
+}
+
+impl AcceptInvitationSchema {
+    /// Invitations are accepted by the user they were sent to, so the email comes from the
+    /// session rather than the request.
+    pub fn into_domain(self, user: &User) -> AcceptInvitationRequest {
+        AcceptInvitationRequest {
+            user_id: UserId::new(user.id),
+            email: user.email.to_string(),
+            token: self.token,
+        }
+    }
+}
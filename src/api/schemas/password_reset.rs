@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::model::api_error::ApiError,
+    domain::model::{
+        audit::RequestContext, password_reset::ResetPasswordRequest,
+        register_user::HashedUserPassword, user_password::UserPassword,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResetPasswordSchema {
+    pub token: String,
+    pub password: String,
+}
+
+impl ResetPasswordSchema {
+    pub fn try_into_domain(
+        self,
+        context: RequestContext,
+    ) -> Result<ResetPasswordRequest, ApiError> {
+        let password = UserPassword::new(&self.password)?;
+        let hashed_password = HashedUserPassword::new(password)?;
+        Ok(ResetPasswordRequest {
+            token: self.token,
+            hashed_password,
+            context,
+        })
+    }
+}
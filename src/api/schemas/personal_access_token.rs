@@ -0,0 +1,57 @@
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::model::api_error::{ApiError, ApiErrorBody, ErrorCode},
+    domain::model::{
+        personal_access_token::CreatePersonalAccessTokenRequest, scope::Scopes, user_id::UserId,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreatePersonalAccessTokenSchema {
+    pub name: String,
+    pub scope: Option<String>,
+    pub expires_in_days: Option<i64>,
+}
+
+impl CreatePersonalAccessTokenSchema {
+    pub fn try_into_domain(
+        self,
+        user_id: UserId,
+    ) -> Result<CreatePersonalAccessTokenRequest, ApiError> {
+        let expires_at = match self.expires_in_days {
+            Some(days) if days < 1 => {
+                return Err(ApiError::UnprocessableEntity(
+                    ApiErrorBody::new(
+                        ErrorCode::RequestInvalidField,
+                        "expires_in_days must be at least 1",
+                    )
+                    .with_field_error("expires_in_days", "must be at least 1"),
+                ))
+            }
+            Some(days) => Some(
+                Duration::try_days(days)
+                    .and_then(|duration| Utc::now().checked_add_signed(duration))
+                    .ok_or_else(|| {
+                        ApiError::UnprocessableEntity(
+                            ApiErrorBody::new(
+                                ErrorCode::RequestInvalidField,
+                                "expires_in_days is too large",
+                            )
+                            .with_field_error("expires_in_days", "is too large"),
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+
+        Ok(CreatePersonalAccessTokenRequest {
+            user_id,
+            name: self.name,
+            scopes: Scopes::parse(self.scope.as_deref().unwrap_or_default()),
+            expires_at,
+        })
+    }
+}
@@ -2,16 +2,19 @@ use crate::{
     api::model::api_error::ApiError,
     domain::model::{
         register_user::{HashedUserPassword, RegisterUserRequest},
+        registration::RegistrationInvitationToken,
         user_email::UserEmail,
         user_password::UserPassword,
     },
 };
 use serde::Deserialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RegisterUserSchema {
     pub email: String,
     pub password: String,
+    pub invitation_token: Option<String>,
 }
 
 impl RegisterUserSchema {
@@ -19,6 +22,10 @@ impl RegisterUserSchema {
         let email = UserEmail::new(&self.email)?;
         let password = UserPassword::new(&self.password)?;
         let hashed_password = HashedUserPassword::new(password)?;
-        Ok(RegisterUserRequest::new(email, hashed_password))
+        let request = RegisterUserRequest::new(email, hashed_password);
+        Ok(match self.invitation_token {
+            Some(token) => request.with_invitation(RegistrationInvitationToken::new(&token)),
+            None => request,
+        })
     }
 }
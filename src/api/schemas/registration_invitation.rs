@@ -0,0 +1,42 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::model::api_error::{ApiError, ApiErrorBody, ErrorCode},
+    domain::model::{
+        organization::OrganizationRole, registration::CreateRegistrationInvitationRequest,
+        user_id::UserId,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateRegistrationInvitationSchema {
+    pub email: String,
+    pub organization_id: Option<uuid::Uuid>,
+    pub role: Option<OrganizationRole>,
+}
+
+impl CreateRegistrationInvitationSchema {
+    /// Invitees joining an organization do so as plain members unless `role` says otherwise.
+    pub fn try_into_domain(
+        self,
+        admin_id: uuid::Uuid,
+    ) -> Result<CreateRegistrationInvitationRequest, ApiError> {
+        if self.organization_id.is_none() && self.role.is_some() {
+            return Err(ApiError::UnprocessableEntity(
+                ApiErrorBody::new(
+                    ErrorCode::RequestInvalidField,
+                    "role requires an organization_id",
+                )
+                .with_field_error("organization_id", "is required with a role"),
+            ));
+        }
+
+        Ok(CreateRegistrationInvitationRequest {
+            email: self.email,
+            organization_id: self.organization_id,
+            role: self.role.unwrap_or(OrganizationRole::Member),
+            created_by: UserId::new(admin_id),
+        })
+    }
+}
@@ -0,0 +1,28 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::domain::model::saml::SamlAssertionRequest;
+
+/// Form the identity provider's page posts to the assertion consumer service.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SamlAssertionSchema {
+    #[serde(rename = "SAMLResponse")]
+    pub saml_response: String,
+    #[serde(rename = "RelayState")]
+    pub relay_state: Option<String>,
+}
+
+impl SamlAssertionSchema {
+    pub fn into_domain(
+        self,
+        provider: String,
+        state_digest: Option<String>,
+    ) -> SamlAssertionRequest {
+        SamlAssertionRequest {
+            provider,
+            saml_response: self.saml_response,
+            relay_state: self.relay_state,
+            state_digest,
+        }
+    }
+}
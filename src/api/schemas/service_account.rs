@@ -0,0 +1,42 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::domain::model::{
+    service_account::{CreateServiceAccountRequest, RotateServiceAccountKeyRequest},
+    user_id::UserId,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateServiceAccountSchema {
+    pub name: String,
+    pub owner_id: Option<uuid::Uuid>,
+}
+
+impl CreateServiceAccountSchema {
+    /// Accounts are owned by the administrator creating them unless `owner_id` says otherwise.
+    pub fn into_domain(self, admin_id: uuid::Uuid) -> CreateServiceAccountRequest {
+        CreateServiceAccountRequest {
+            name: self.name,
+            owner_id: UserId::new(self.owner_id.unwrap_or(admin_id)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RotateServiceAccountKeySchema {
+    pub grace_period_seconds: Option<i64>,
+}
+
+impl RotateServiceAccountKeySchema {
+    pub fn into_domain(
+        self,
+        service_account_id: uuid::Uuid,
+        key_id: uuid::Uuid,
+    ) -> RotateServiceAccountKeyRequest {
+        RotateServiceAccountKeyRequest {
+            service_account_id: UserId::new(service_account_id),
+            key_id,
+            grace_period_seconds: self.grace_period_seconds.unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::utils::client_auth::client_authentication,
+    domain::model::{
+        introspection::IntrospectionRequest, oauth_errors::OAuthError,
+        revocation::RevocationRequest,
+    },
+};
+
+/// Form body shared by the introspection (RFC 7662) and revocation (RFC 7009) endpoints.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TokenIntrospectionSchema {
+    #[serde(default)]
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+impl TokenIntrospectionSchema {
+    pub fn try_into_introspection(
+        self,
+        headers: &HeaderMap,
+    ) -> Result<IntrospectionRequest, OAuthError> {
+        Ok(IntrospectionRequest {
+            token: required_token(&self.token)?,
+            client: client_authentication(headers, self.client_id, self.client_secret)?,
+            token_type_hint: self.token_type_hint,
+        })
+    }
+
+    pub fn try_into_revocation(self, headers: &HeaderMap) -> Result<RevocationRequest, OAuthError> {
+        Ok(RevocationRequest {
+            token: required_token(&self.token)?,
+            client: client_authentication(headers, self.client_id, self.client_secret)?,
+            token_type_hint: self.token_type_hint,
+        })
+    }
+}
+
+fn required_token(token: &str) -> Result<String, OAuthError> {
+    if token.trim().is_empty() {
+        return Err(OAuthError::InvalidRequest {
+            description: "Missing token parameter".to_string(),
+        });
+    }
+    Ok(token.to_string())
+}
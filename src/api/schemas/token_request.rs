@@ -0,0 +1,73 @@
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::utils::client_auth::client_authentication,
+    domain::model::{
+        device_authorization::DEVICE_CODE_GRANT_TYPE,
+        oauth_errors::OAuthError,
+        oauth_token::{TokenGrant, TokenRequest},
+        scope::Scopes,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TokenRequestSchema {
+    #[serde(default)]
+    pub grant_type: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    pub device_code: Option<String>,
+    pub scope: Option<String>,
+}
+
+impl TokenRequestSchema {
+    /// Converts the form into a domain `TokenRequest`, authenticating the client from
+    /// either the `Authorization` header or the form body.
+    pub fn try_into_domain(self, headers: &HeaderMap) -> Result<TokenRequest, OAuthError> {
+        let grant = match self.grant_type.as_str() {
+            "authorization_code" => TokenGrant::AuthorizationCode {
+                code: required(self.code, "code")?,
+                redirect_uri: self.redirect_uri,
+                code_verifier: required(self.code_verifier, "code_verifier")?,
+            },
+            "refresh_token" => TokenGrant::RefreshToken {
+                refresh_token: required(self.refresh_token, "refresh_token")?,
+            },
+            "client_credentials" => TokenGrant::ClientCredentials {
+                scope: Scopes::parse(self.scope.as_deref().unwrap_or_default()),
+            },
+            DEVICE_CODE_GRANT_TYPE => TokenGrant::DeviceCode {
+                device_code: required(self.device_code, "device_code")?,
+            },
+            "" => {
+                return Err(OAuthError::InvalidRequest {
+                    description: "Missing grant_type parameter".to_string(),
+                })
+            }
+            grant_type => {
+                return Err(OAuthError::UnsupportedGrantType {
+                    description: format!("Grant type {} is not supported", grant_type),
+                })
+            }
+        };
+
+        Ok(TokenRequest {
+            client: client_authentication(headers, self.client_id, self.client_secret)?,
+            grant,
+        })
+    }
+}
+
+fn required(value: Option<String>, name: &str) -> Result<String, OAuthError> {
+    value
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| OAuthError::InvalidRequest {
+            description: format!("Missing {} parameter", name),
+        })
+}
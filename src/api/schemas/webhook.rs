@@ -0,0 +1,39 @@
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::model::{
+    user_id::UserId,
+    webhook::{CreateWebhookRequest, ListWebhookDeliveriesRequest, WebhookDeliveryStatus},
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookSchema {
+    pub url: String,
+    pub events: Vec<String>,
+}
+
+impl CreateWebhookSchema {
+    pub fn into_domain(self, admin_id: uuid::Uuid) -> CreateWebhookRequest {
+        CreateWebhookRequest {
+            url: self.url,
+            events: self.events,
+            created_by: UserId::new(admin_id),
+        }
+    }
+}
+
+/// Query parameters of the deliveries of a subscription.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListWebhookDeliveriesSchema {
+    pub status: Option<WebhookDeliveryStatus>,
+}
+
+impl ListWebhookDeliveriesSchema {
+    pub fn into_domain(self, subscription_id: uuid::Uuid) -> ListWebhookDeliveriesRequest {
+        ListWebhookDeliveriesRequest {
+            subscription_id,
+            status: self.status,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use axum::http::{header, HeaderMap};
+use base64::{engine::general_purpose, Engine};
+use percent_encoding::percent_decode_str;
+
+use crate::domain::model::{oauth_client::ClientAuthentication, oauth_errors::OAuthError};
+
+/// Resolves how a client authenticates at the token endpoint.
+///
+/// Supports `client_secret_basic`, where the credentials travel in an HTTP Basic
+/// `Authorization` header, and `client_secret_post`, where they travel in the form body.
+/// Public clients send only `client_id` in the body. As required by RFC 6749 section 2.3,
+/// a client may not use more than one authentication method in the same request.
+///
+/// # Arguments
+///
+/// * `headers` - The request headers, checked for a Basic `Authorization` header.
+/// * `client_id` - The `client_id` form parameter, if present.
+/// * `client_secret` - The `client_secret` form parameter, if present.
+///
+/// # Errors
+///
+/// Returns `OAuthError::InvalidClient` if the Basic credentials are malformed, and
+/// `OAuthError::InvalidRequest` if the client is not identified or uses both methods.
+///
+/// # Examples
+///
+/// ```rust
+/// use authentication_service::api::utils::client_auth::client_authentication;
+/// use axum::http::{header, HeaderMap, HeaderValue};
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert(
+///     header::AUTHORIZATION,
+///     HeaderValue::from_static("Basic YmFja2VuZC1qb2I6czNjcjN0"),
+/// );
+///
+/// let client = client_authentication(&headers, None, None).unwrap();
+/// assert_eq!(client.client_id, "backend-job");
+/// assert_eq!(client.client_secret.as_deref(), Some("s3cr3t"));
+/// ```
+pub fn client_authentication(
+    headers: &HeaderMap,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+) -> Result<ClientAuthentication, OAuthError> {
+    let basic = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "));
+
+    match basic {
+        Some(_) if client_secret.is_some() => Err(OAuthError::InvalidRequest {
+            description: "Only one client authentication method may be used".to_string(),
+        }),
+        Some(credentials) => {
+            let (basic_id, basic_secret) = decode_basic_credentials(credentials)?;
+
+            if client_id.is_some_and(|id| id != basic_id) {
+                return Err(OAuthError::InvalidRequest {
+                    description: "client_id does not match the authenticated client".to_string(),
+                });
+            }
+
+            Ok(ClientAuthentication::new(&basic_id, Some(&basic_secret)))
+        }
+        None => {
+            let client_id = client_id
+                .filter(|id| !id.trim().is_empty())
+                .ok_or_else(|| OAuthError::InvalidRequest {
+                    description: "Missing client_id parameter".to_string(),
+                })?;
+
+            Ok(ClientAuthentication::new(
+                &client_id,
+                client_secret.as_deref(),
+            ))
+        }
+    }
+}
+
+/// Decodes `client_secret_basic` credentials: base64 of the form-urlencoded
+/// client id and secret joined by a colon.
+fn decode_basic_credentials(credentials: &str) -> Result<(String, String), OAuthError> {
+    let invalid = || OAuthError::InvalidClient {
+        description: "Malformed Basic client credentials".to_string(),
+    };
+
+    let decoded = general_purpose::STANDARD
+        .decode(credentials.trim())
+        .map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let (client_id, client_secret) = decoded.split_once(':').ok_or_else(invalid)?;
+
+    Ok((
+        form_urldecode(client_id).ok_or_else(invalid)?,
+        form_urldecode(client_secret).ok_or_else(invalid)?,
+    ))
+}
+
+fn form_urldecode(value: &str) -> Option<String> {
+    percent_decode_str(&value.replace('+', " "))
+        .decode_utf8()
+        .ok()
+        .map(|decoded| decoded.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn basic(credentials: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!(
+                "Basic {}",
+                general_purpose::STANDARD.encode(credentials)
+            ))
+            .unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_client_secret_basic_is_form_urldecoded() {
+        let client = client_authentication(&basic("my%20client:p%3Ass+word"), None, None).unwrap();
+
+        assert_eq!(client.client_id, "my client");
+        assert_eq!(client.client_secret.as_deref(), Some("p:ss word"));
+    }
+
+    #[test]
+    fn test_client_secret_post() {
+        let client = client_authentication(
+            &HeaderMap::new(),
+            Some("backend-job".to_string()),
+            Some("s3cr3t".to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(client.client_id, "backend-job");
+        assert_eq!(client.client_secret.as_deref(), Some("s3cr3t"));
+    }
+
+    #[test]
+    fn test_multiple_authentication_methods_failure() {
+        let result = client_authentication(
+            &basic("backend-job:s3cr3t"),
+            Some("backend-job".to_string()),
+            Some("s3cr3t".to_string()),
+        );
+
+        assert!(matches!(result, Err(OAuthError::InvalidRequest { .. })));
+    }
+}
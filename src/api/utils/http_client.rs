@@ -0,0 +1,23 @@
+use std::{sync::OnceLock, time::Duration};
+
+/// How long to wait for the TCP and TLS handshakes with a remote server.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a whole request may take, from connecting to reading the last byte of the body.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The HTTP client shared by every outbound request: identity provider discovery, token and
+/// JWKS requests, webhook deliveries and the verifier's JWKS and introspection requests.
+///
+/// Redirects are not followed, so requests only ever reach the URL that was configured, be it a
+/// provider's endpoint or a webhook subscription.
+pub fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to build HTTP client")
+    })
+}
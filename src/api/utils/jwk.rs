@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose, Engine};
+use rsa::{
+    pkcs1::DecodeRsaPrivateKey, pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPrivateKey,
+    RsaPublicKey,
+};
+use sha2::{Digest, Sha256};
+
+use crate::domain::model::jwks::Jwk;
+
+/// Converts a base64 encoded PEM RSA public key, as stored in the configuration, into a
+/// signing `Jwk` for RS256.
+///
+/// The key id is the RFC 7638 thumbprint of the key, so it is stable for a given key and
+/// changes whenever the key is rotated.
+///
+/// # Errors
+///
+/// Returns an error if the key is not valid base64 or not a PEM encoded RSA public key.
+///
+/// # Examples
+///
+/// ```rust
+/// use authentication_service::{api::utils::jwk::public_jwk, helper::config::Config};
+/// use dotenv::dotenv;
+///
+/// dotenv().ok();
+/// let config = Config::init();
+///
+/// let jwk = public_jwk(&config.access_token_public_key).unwrap();
+/// assert_eq!(jwk.kty, "RSA");
+/// assert_eq!(jwk.alg, "RS256");
+/// ```
+pub fn public_jwk(public_key: &str) -> Result<Jwk> {
+    let bytes_public_key = general_purpose::STANDARD.decode(public_key)?;
+    let decoded_public_key = String::from_utf8(bytes_public_key)?;
+
+    let key = RsaPublicKey::from_public_key_pem(&decoded_public_key)
+        .map_err(|e| anyhow!(e).context("Failed to parse RSA public key"))?;
+
+    let n = general_purpose::URL_SAFE_NO_PAD.encode(key.n().to_bytes_be());
+    let e = general_purpose::URL_SAFE_NO_PAD.encode(key.e().to_bytes_be());
+
+    Ok(Jwk {
+        kty: "RSA".to_string(),
+        key_use: "sig".to_string(),
+        alg: "RS256".to_string(),
+        kid: thumbprint(&n, &e),
+        n,
+        e,
+    })
+}
+
+/// Derives the key id of a PEM RSA private key, which is the `kid` of its public key in the JWKS.
+///
+/// # Errors
+///
+/// Returns an error if the key is not a PEM encoded PKCS#1 RSA private key.
+pub fn private_key_id(private_key_pem: &str) -> Result<String> {
+    let key = RsaPrivateKey::from_pkcs1_pem(private_key_pem)
+        .map_err(|e| anyhow!(e).context("Failed to parse RSA private key"))?;
+
+    let n = general_purpose::URL_SAFE_NO_PAD.encode(key.n().to_bytes_be());
+    let e = general_purpose::URL_SAFE_NO_PAD.encode(key.e().to_bytes_be());
+
+    Ok(thumbprint(&n, &e))
+}
+
+/// Builds the key verifying the signatures of a published `Jwk`.
+///
+/// # Errors
+///
+/// Returns an error if the key is not an RSA key or its components are not valid base64url.
+pub fn decoding_key(jwk: &Jwk) -> Result<jsonwebtoken::DecodingKey> {
+    if jwk.kty != "RSA" {
+        return Err(anyhow!("Unsupported key type {}", jwk.kty));
+    }
+
+    Ok(jsonwebtoken::DecodingKey::from_rsa_components(
+        &jwk.n, &jwk.e,
+    )?)
+}
+
+/// Computes the RFC 7638 JWK thumbprint of an RSA key from its base64url encoded components.
+fn thumbprint(n: &str, e: &str) -> String {
+    let canonical = format!(r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#, e, n);
+    general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbprint_matches_rfc_7638_example() {
+        let n = "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw";
+
+        assert_eq!(
+            thumbprint(n, "AQAB"),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+    }
+}
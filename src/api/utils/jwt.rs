@@ -1,4 +1,12 @@
-use crate::domain::model::token::{TokenClaims, TokenDetails};
+use crate::{
+    api::utils::jwk::private_key_id,
+    domain::model::{
+        audit::AuditCheckpointClaims,
+        custom_claims::CustomClaims,
+        id_token::IdTokenClaims,
+        token::{TokenClaims, TokenDetails},
+    },
+};
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine};
 
@@ -6,13 +14,14 @@ use base64::{engine::general_purpose, Engine};
 ///
 /// This function decodes and verifies a JWT using a public RSA key. The JWT is decoded to extract its claims,
 /// which are then parsed to obtain the user ID and token UUID. If the token is valid and the claims can be parsed
-/// successfully, a `TokenDetails` struct is returned, containing the user ID and token UUID.
+/// successfully, a `TokenDetails` struct is returned, containing the user ID, token UUID and any custom claims
+/// that were embedded when the token was generated.
 ///
 /// The process includes:
 /// 1. **Decoding the Public Key:** Converts the base64-encoded public key string into bytes and then into a UTF-8
 ///    string representation.
 /// 2. **JWT Validation:** Uses the RSA public key to validate the token's signature and decode its claims.
-/// 3. **Parsing Claims:** Extracts the user ID and token UUID from the token claims.
+/// 3. **Parsing Claims:** Extracts the user ID, token UUID and custom claims from the token claims.
 ///
 /// # Arguments
 ///
@@ -31,13 +40,34 @@ pub fn verify_jwt(public_key: &str, token: &str) -> Result<TokenDetails> {
     let bytes_public_key = general_purpose::STANDARD.decode(public_key)?;
     let decoded_public_key = String::from_utf8(bytes_public_key)?;
 
-    let validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256);
-
-    let decoded = jsonwebtoken::decode::<TokenClaims>(
-        token,
+    verify_jwt_with_key(
         &jsonwebtoken::DecodingKey::from_rsa_pem(decoded_public_key.as_bytes())?,
-        &validation,
-    )?;
+        token,
+        &token_validation(),
+    )
+}
+
+/// The validation `verify_jwt` applies to access and refresh tokens: an RS256 signature and an
+/// `exp` in the future. The `aud` of tokens issued to OAuth clients is not checked, as the
+/// service accepts its own tokens whichever client they were issued to.
+pub fn token_validation() -> jsonwebtoken::Validation {
+    let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256);
+    validation.validate_aud = false;
+    validation
+}
+
+/// Verifies a JSON Web Token (JWT) like `verify_jwt`, with a decoding key that was already built, for
+/// instance from a key published at the JWKS endpoint.
+///
+/// # Errors
+///
+/// This function returns an error if the token fails `validation` or its claims cannot be parsed.
+pub fn verify_jwt_with_key(
+    key: &jsonwebtoken::DecodingKey,
+    token: &str,
+    validation: &jsonwebtoken::Validation,
+) -> Result<TokenDetails> {
+    let decoded = jsonwebtoken::decode::<TokenClaims>(token, key, validation)?;
 
     let user_id = uuid::Uuid::parse_str(decoded.claims.sub.as_str())?;
     let token_uuid = uuid::Uuid::parse_str(decoded.claims.token_uuid.as_str())?;
@@ -46,10 +76,20 @@ pub fn verify_jwt(public_key: &str, token: &str) -> Result<TokenDetails> {
         token: None,
         token_uuid,
         user_id,
-        expires_in: None,
+        issued_at: decoded.claims.iat,
+        expires_in: Some(decoded.claims.exp),
+        claims: decoded.claims.custom,
     })
 }
 
+/// Whether `verify_jwt` failed because the token's `exp` has passed, rather than because the token
+/// is not valid at all.
+pub fn is_expired(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<jsonwebtoken::errors::Error>()
+        .is_some_and(|e| *e.kind() == jsonwebtoken::errors::ErrorKind::ExpiredSignature)
+}
+
 /// Generates a JSON Web Token (JWT) for a user with the given time-to-live (TTL) and private key.
 ///
 /// This function creates a JWT for a user, including a unique token UUID and an expiration timestamp. The JWT is
@@ -77,6 +117,31 @@ pub fn verify_jwt(public_key: &str, token: &str) -> Result<TokenDetails> {
 ///
 /// This function returns an error if the private key decoding, JWT encoding, or token details creation fails.
 pub fn generate_jwt(user_id: uuid::Uuid, ttl: i64, private_key: &str) -> Result<TokenDetails> {
+    generate_jwt_with_claims(user_id, ttl, private_key, CustomClaims::default())
+}
+
+/// Generates a JSON Web Token (JWT) that carries additional custom claims.
+///
+/// Behaves exactly like `generate_jwt`, but flattens `claims` into the token payload next to the registered
+/// claims. Callers are expected to have filtered out reserved claim names beforehand, which the claims
+/// pipeline in `Service` takes care of.
+///
+/// # Arguments
+///
+/// * `user_id` - The UUID of the user for whom the token is being generated.
+/// * `ttl` - The time-to-live (TTL) in minutes for the token.
+/// * `private_key` - A base64-encoded string representation of the RSA private key used for signing the token.
+/// * `claims` - The custom claims to embed in the token.
+///
+/// # Errors
+///
+/// This function returns an error if the private key decoding, JWT encoding, or token details creation fails.
+pub fn generate_jwt_with_claims(
+    user_id: uuid::Uuid,
+    ttl: i64,
+    private_key: &str,
+    claims: CustomClaims,
+) -> Result<TokenDetails> {
     let bytes_private_key = general_purpose::STANDARD.decode(private_key)?;
     let decoded_private_key = String::from_utf8(bytes_private_key)?;
 
@@ -86,8 +151,10 @@ pub fn generate_jwt(user_id: uuid::Uuid, ttl: i64, private_key: &str) -> Result<
     let mut token_details = TokenDetails {
         user_id,
         token_uuid: uuid::Uuid::new_v4(),
+        issued_at: now.timestamp(),
         expires_in: Some(exp),
         token: None,
+        claims: claims.clone(),
     };
 
     let claims = TokenClaims {
@@ -96,6 +163,7 @@ pub fn generate_jwt(user_id: uuid::Uuid, ttl: i64, private_key: &str) -> Result<
         exp,
         iat: now.timestamp(),
         nbf: now.timestamp(),
+        custom: claims,
     };
 
     let token = encode_jwt(&claims, &decoded_private_key)?;
@@ -104,13 +172,100 @@ pub fn generate_jwt(user_id: uuid::Uuid, ttl: i64, private_key: &str) -> Result<
     Ok(token_details)
 }
 
+/// Generates an OpenID Connect ID token.
+///
+/// ID tokens are signed with RS256 like access tokens, but carry the `IdTokenClaims` payload instead of
+/// `TokenClaims`, and their header names the signing key with `kid` so relying parties can pick the right key
+/// from the JWKS endpoint.
+///
+/// # Arguments
+///
+/// * `claims` - The ID token claims, including the issuer, audience and authentication context.
+/// * `private_key` - A base64-encoded string representation of the RSA private key used for signing the token.
+/// * `kid` - The key id of the matching public key, as published in the JWKS.
+///
+/// # Errors
+///
+/// This function returns an error if the private key decoding or JWT encoding fails.
+pub fn generate_id_token(claims: &IdTokenClaims, private_key: &str, kid: &str) -> Result<String> {
+    let bytes_private_key = general_purpose::STANDARD.decode(private_key)?;
+    let decoded_private_key = String::from_utf8(bytes_private_key)?;
+
+    let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+    header.kid = Some(kid.to_string());
+
+    let token = jsonwebtoken::encode(
+        &header,
+        claims,
+        &jsonwebtoken::EncodingKey::from_rsa_pem(decoded_private_key.as_bytes())?,
+    )?;
+
+    Ok(token)
+}
+
+/// Signs a checkpoint of the audit log chain with the access token key.
+///
+/// # Arguments
+///
+/// * `claims` - The sequence number and hash of the checkpointed event.
+/// * `private_key` - A base64-encoded string representation of the RSA private key used for signing the checkpoint.
+/// * `kid` - The key id of the matching public key, as published in the JWKS.
+///
+/// # Errors
+///
+/// This function returns an error if the private key decoding or JWT encoding fails.
+pub fn sign_audit_checkpoint(
+    claims: &AuditCheckpointClaims,
+    private_key: &str,
+    kid: &str,
+) -> Result<String> {
+    let bytes_private_key = general_purpose::STANDARD.decode(private_key)?;
+    let decoded_private_key = String::from_utf8(bytes_private_key)?;
+
+    let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+    header.kid = Some(kid.to_string());
+
+    let token = jsonwebtoken::encode(
+        &header,
+        claims,
+        &jsonwebtoken::EncodingKey::from_rsa_pem(decoded_private_key.as_bytes())?,
+    )?;
+
+    Ok(token)
+}
+
+/// Verifies the signature of an audit log checkpoint and returns its claims.
+///
+/// Checkpoints never expire, so only the signature is validated.
+///
+/// # Errors
+///
+/// This function returns an error if the public key decoding fails or the signature is invalid.
+pub fn verify_audit_checkpoint(public_key: &str, token: &str) -> Result<AuditCheckpointClaims> {
+    let bytes_public_key = general_purpose::STANDARD.decode(public_key)?;
+    let decoded_public_key = String::from_utf8(bytes_public_key)?;
+
+    let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256);
+    validation.validate_exp = false;
+    validation.required_spec_claims.clear();
+
+    let decoded = jsonwebtoken::decode::<AuditCheckpointClaims>(
+        token,
+        &jsonwebtoken::DecodingKey::from_rsa_pem(decoded_public_key.as_bytes())?,
+        &validation,
+    )?;
+
+    Ok(decoded.claims)
+}
+
 /// Encodes claims into a JSON Web Token (JWT) using the provided private key.
 ///
 /// This helper function creates a JWT by encoding the given claims with an RSA private key. The resulting token
 /// is a string representation that can be used for authentication and authorization.
 ///
 /// The process includes:
-/// 1. **Creating Header:** Sets up the JWT header with the RS256 algorithm.
+/// 1. **Creating Header:** Sets up the JWT header with the RS256 algorithm and the `kid` of the signing key, so
+///    verifiers can pick the right key from the JWKS endpoint.
 /// 2. **Encoding:** Uses the private key to sign the token and attach the claims.
 ///
 /// # Arguments
@@ -126,7 +281,8 @@ pub fn generate_jwt(user_id: uuid::Uuid, ttl: i64, private_key: &str) -> Result<
 ///
 /// This function returns an error if the JWT encoding process fails.
 fn encode_jwt(claims: &TokenClaims, private_key: &str) -> Result<String> {
-    let header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+    let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+    header.kid = Some(private_key_id(private_key)?);
     let token = jsonwebtoken::encode(
         &header,
         &claims,
@@ -177,4 +333,86 @@ mod tests {
 
         assert_eq!(verified_details.unwrap().user_id, user_id);
     }
+
+    #[test]
+    fn test_token_header_names_published_key() {
+        dotenv().ok();
+        let config = Config::init();
+        let jwk = crate::api::utils::jwk::public_jwk(&config.access_token_public_key).unwrap();
+
+        let user_id = uuid::Uuid::new_v4();
+        let token = generate_jwt(
+            user_id,
+            config.access_token_max_age,
+            &config.access_token_private_key,
+        )
+        .unwrap()
+        .token
+        .unwrap();
+
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        let verified_details = verify_jwt_with_key(
+            &crate::api::utils::jwk::decoding_key(&jwk).unwrap(),
+            &token,
+            &token_validation(),
+        );
+
+        assert_eq!(header.kid, Some(jwk.kid));
+        assert_eq!(verified_details.unwrap().user_id, user_id);
+    }
+
+    #[test]
+    fn test_decoding_jwt_with_custom_claims() {
+        dotenv().ok();
+        let config = Config::init();
+        let user_id = uuid::Uuid::new_v4();
+
+        let mut claims = CustomClaims::default();
+        claims.insert("email", serde_json::json!("adrian@email.com"));
+        claims.insert("roles", serde_json::json!(["admin"]));
+
+        let token_details = generate_jwt_with_claims(
+            user_id,
+            config.access_token_max_age,
+            &config.access_token_private_key,
+            claims.clone(),
+        )
+        .unwrap();
+
+        let verified_details = verify_jwt(
+            &config.access_token_public_key,
+            &token_details.token.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(verified_details.claims, claims);
+    }
+
+    #[test]
+    fn test_audit_checkpoint_signature() {
+        dotenv().ok();
+        let config = Config::init();
+        let claims = AuditCheckpointClaims {
+            sequence: 100,
+            hash: "hash".to_string(),
+            iat: 0,
+        };
+
+        let signature =
+            sign_audit_checkpoint(&claims, &config.access_token_private_key, "kid").unwrap();
+        let forged = generate_jwt(
+            uuid::Uuid::new_v4(),
+            config.access_token_max_age,
+            &config.refresh_token_private_key,
+        )
+        .unwrap()
+        .token
+        .unwrap();
+
+        assert_eq!(
+            verify_audit_checkpoint(&config.access_token_public_key, &signature).unwrap(),
+            claims
+        );
+        assert!(verify_audit_checkpoint(&config.access_token_public_key, &forged).is_err());
+    }
 }
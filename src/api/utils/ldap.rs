@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use ldap3::{ldap_escape, Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+
+use crate::domain::model::ldap::{DirectoryUser, LdapConfig};
+
+/// Result code of a bind rejected because of a wrong password, from RFC 4511 section 4.1.9.
+const INVALID_CREDENTIALS: u32 = 49;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of checking a login against the directory.
+#[derive(Debug, PartialEq)]
+pub enum DirectoryLogin {
+    Authenticated(DirectoryUser),
+    /// No directory entry matches the login.
+    UnknownUser,
+    /// The entry exists but the password is wrong.
+    InvalidPassword,
+}
+
+/// Builds the search filter for `login` from the configured template, escaping the login so it
+/// cannot change the filter's meaning.
+pub fn user_filter(template: &str, login: &str) -> String {
+    template.replace("{login}", &ldap_escape(login))
+}
+
+/// Checks `login` and `password` against the directory by searching for the user's entry and
+/// binding as it.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be reached, the service account bind fails, the
+/// login matches more than one entry, or the entry lacks the email attribute.
+pub async fn authenticate(
+    config: &LdapConfig,
+    login: &str,
+    password: &str,
+) -> Result<DirectoryLogin> {
+    let (conn, mut ldap) = LdapConnAsync::with_settings(
+        LdapConnSettings::new().set_conn_timeout(TIMEOUT),
+        &config.url,
+    )
+    .await
+    .with_context(|| format!("Failed to connect to LDAP server {}", config.url))?;
+    ldap3::drive!(conn);
+
+    let result = search_and_bind(&mut ldap, config, login, password).await;
+    let _ = ldap.unbind().await;
+    result
+}
+
+async fn search_and_bind(
+    ldap: &mut Ldap,
+    config: &LdapConfig,
+    login: &str,
+    password: &str,
+) -> Result<DirectoryLogin> {
+    if let Some(bind_dn) = &config.bind_dn {
+        ldap.with_timeout(TIMEOUT)
+            .simple_bind(bind_dn, &config.bind_password)
+            .await?
+            .success()
+            .context("LDAP service account bind failed")?;
+    }
+
+    let (entries, _) = ldap
+        .with_timeout(TIMEOUT)
+        .search(
+            &config.base_dn,
+            Scope::Subtree,
+            &user_filter(&config.user_filter, login),
+            vec![
+                config.email_attribute.as_str(),
+                config.group_attribute.as_str(),
+            ],
+        )
+        .await?
+        .success()
+        .context("LDAP user search failed")?;
+
+    let entry = match entries.len() {
+        0 => return Ok(DirectoryLogin::UnknownUser),
+        1 => SearchEntry::construct(entries.into_iter().next().expect("one entry")),
+        n => bail!("LDAP user filter matched {} entries", n),
+    };
+
+    // A simple bind with an empty password is an unauthenticated bind, which servers accept
+    // without checking anything.
+    if password.is_empty() {
+        return Ok(DirectoryLogin::InvalidPassword);
+    }
+
+    let bind = ldap
+        .with_timeout(TIMEOUT)
+        .simple_bind(&entry.dn, password)
+        .await?;
+    match bind.rc {
+        0 => {}
+        INVALID_CREDENTIALS => return Ok(DirectoryLogin::InvalidPassword),
+        _ => {
+            bind.success().context("LDAP user bind failed")?;
+        }
+    }
+
+    let attribute = |name: &str| {
+        entry
+            .attrs
+            .iter()
+            .find(|(attr, _)| attr.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or_default()
+    };
+
+    let email = attribute(&config.email_attribute)
+        .first()
+        .with_context(|| format!("LDAP entry {} has no {}", entry.dn, config.email_attribute))?
+        .clone();
+
+    Ok(DirectoryLogin::Authenticated(DirectoryUser {
+        email,
+        roles: config.roles_for_groups(attribute(&config.group_attribute)),
+        grants_admin: config.grant_admin,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_filter_escapes_login() {
+        assert_eq!(
+            user_filter("(mail={login})", "adrian@email.com"),
+            "(mail=adrian@email.com)"
+        );
+        assert_eq!(
+            user_filter("(&(objectClass=person)(uid={login}))", "*)(uid=*"),
+            "(&(objectClass=person)(uid=\\2a\\29\\28uid=\\2a))"
+        );
+    }
+}
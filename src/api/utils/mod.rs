@@ -1,3 +1,13 @@
+pub mod client_auth;
+pub mod extractors;
+pub mod http_client;
+pub mod jwk;
 pub mod jwt;
+pub mod ldap;
+pub mod oidc_client;
+pub mod pkce;
+pub mod request_context;
+pub mod saml;
 pub mod security;
 pub mod status;
+pub mod webhook_client;
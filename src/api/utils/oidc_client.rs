@@ -0,0 +1,274 @@
+use anyhow::{anyhow, bail, Context, Result};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+use crate::{
+    api::utils::http_client::http_client,
+    domain::model::federation::{FederatedIdentity, FederatedProvider},
+};
+
+/// The parts of an external provider's discovery document needed to sign users in.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProviderDiscovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenEndpointResponse {
+    id_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// A key from a provider's JWKS. Only RSA signing keys are used, and every member but `kty`
+/// is optional in RFC 7517, so unsupported keys are skipped rather than rejected.
+#[derive(Debug, Deserialize)]
+struct ProviderJwk {
+    kty: String,
+    kid: Option<String>,
+    #[serde(rename = "use")]
+    key_use: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderJwkSet {
+    keys: Vec<ProviderJwk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderIdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: Option<EmailVerified>,
+    #[serde(default)]
+    amr: Vec<String>,
+}
+
+/// Some providers send `email_verified` as the string `"true"` instead of a boolean.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EmailVerified {
+    Bool(bool),
+    String(String),
+}
+
+impl EmailVerified {
+    fn is_verified(&self) -> bool {
+        match self {
+            EmailVerified::Bool(verified) => *verified,
+            EmailVerified::String(verified) => verified == "true",
+        }
+    }
+}
+
+/// Fetches a provider's OpenID Connect discovery document.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be fetched, or if its `issuer` differs from the
+/// configured one, as required by OpenID Connect Discovery section 4.3.
+pub async fn discover(provider: &FederatedProvider) -> Result<ProviderDiscovery> {
+    let issuer = provider.issuer.trim_end_matches('/');
+
+    let discovery: ProviderDiscovery = http_client()
+        .get(format!("{}/.well-known/openid-configuration", issuer))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch discovery document of {}", provider.name))?
+        .json()
+        .await
+        .with_context(|| format!("Invalid discovery document from {}", provider.name))?;
+
+    if discovery.issuer.trim_end_matches('/') != issuer {
+        bail!(
+            "Discovery document of {} names issuer {}",
+            provider.name,
+            discovery.issuer
+        );
+    }
+
+    Ok(discovery)
+}
+
+/// Builds the URL that starts an authorization code flow with PKCE at the provider.
+pub fn authorization_url(
+    discovery: &ProviderDiscovery,
+    provider: &FederatedProvider,
+    redirect_uri: &str,
+    state: &str,
+    nonce: &str,
+    code_challenge: &str,
+) -> Result<String> {
+    let mut url = url::Url::parse(&discovery.authorization_endpoint)
+        .context("Invalid authorization endpoint in discovery document")?;
+
+    url.query_pairs_mut().extend_pairs([
+        ("response_type", "code"),
+        ("client_id", provider.client_id.as_str()),
+        ("redirect_uri", redirect_uri),
+        ("scope", provider.scope.as_str()),
+        ("state", state),
+        ("nonce", nonce),
+        ("code_challenge", code_challenge),
+        ("code_challenge_method", "S256"),
+    ]);
+
+    Ok(url.to_string())
+}
+
+/// Exchanges an authorization code at the provider's token endpoint, authenticating with
+/// `client_secret_post`, and returns the ID token.
+pub async fn exchange_code(
+    discovery: &ProviderDiscovery,
+    provider: &FederatedProvider,
+    code: &str,
+    redirect_uri: &str,
+    code_verifier: &str,
+) -> Result<String> {
+    let response: TokenEndpointResponse = http_client()
+        .post(&discovery.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", code_verifier),
+            ("client_id", &provider.client_id),
+            ("client_secret", &provider.client_secret),
+        ])
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach token endpoint of {}", provider.name))?
+        .json()
+        .await
+        .with_context(|| format!("Invalid token response from {}", provider.name))?;
+
+    if let Some(error) = response.error {
+        bail!(
+            "{} rejected the authorization code: {} {}",
+            provider.name,
+            error,
+            response.error_description.unwrap_or_default()
+        );
+    }
+
+    response
+        .id_token
+        .ok_or_else(|| anyhow!("{} did not return an ID token", provider.name))
+}
+
+/// Validates an ID token from the provider and returns the identity it asserts.
+///
+/// The token must be signed with RS256 by a key from the provider's JWKS, be issued by the
+/// provider to our client, be unexpired, and carry the `nonce` sent with the login request.
+pub async fn verify_id_token(
+    discovery: &ProviderDiscovery,
+    provider: &FederatedProvider,
+    id_token: &str,
+    nonce: &str,
+) -> Result<FederatedIdentity> {
+    let header = jsonwebtoken::decode_header(id_token).context("Malformed ID token")?;
+    if header.alg != Algorithm::RS256 {
+        bail!("Unsupported ID token algorithm {:?}", header.alg);
+    }
+
+    let jwks: ProviderJwkSet = http_client()
+        .get(&discovery.jwks_uri)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch JWKS of {}", provider.name))?
+        .json()
+        .await
+        .with_context(|| format!("Invalid JWKS from {}", provider.name))?;
+
+    let (n, e) = jwks
+        .keys
+        .iter()
+        .filter(|key| key.kty == "RSA" && key.key_use.as_deref().unwrap_or("sig") == "sig")
+        .filter(|key| header.kid.is_none() || key.kid == header.kid)
+        .find_map(|key| Some((key.n.as_deref()?, key.e.as_deref()?)))
+        .ok_or_else(|| {
+            anyhow!(
+                "No key in the JWKS of {} matches the ID token",
+                provider.name
+            )
+        })?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_issuer(&[&discovery.issuer]);
+    validation.set_audience(&[&provider.client_id]);
+
+    let claims = jsonwebtoken::decode::<ProviderIdTokenClaims>(
+        id_token,
+        &DecodingKey::from_rsa_components(n, e)?,
+        &validation,
+    )
+    .context("ID token failed validation")?
+    .claims;
+
+    if claims.nonce.as_deref() != Some(nonce) {
+        bail!("ID token nonce does not match the login request");
+    }
+
+    Ok(FederatedIdentity {
+        provider: provider.name.clone(),
+        subject: claims.sub,
+        email: claims.email,
+        email_verified: claims
+            .email_verified
+            .is_some_and(|verified| verified.is_verified()),
+        amr: claims.amr,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorization_url_includes_pkce_and_nonce() {
+        let discovery = ProviderDiscovery {
+            issuer: "https://idp.example.com".to_string(),
+            authorization_endpoint: "https://idp.example.com/authorize?prompt=select_account"
+                .to_string(),
+            token_endpoint: "https://idp.example.com/token".to_string(),
+            jwks_uri: "https://idp.example.com/jwks".to_string(),
+        };
+        let provider = FederatedProvider {
+            name: "example".to_string(),
+            issuer: discovery.issuer.clone(),
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            scope: "openid email".to_string(),
+        };
+
+        let url = authorization_url(
+            &discovery,
+            &provider,
+            "http://localhost:8000/api/federation/example/callback",
+            "state",
+            "nonce",
+            "challenge",
+        )
+        .unwrap();
+
+        let url = url::Url::parse(&url).unwrap();
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(n, _)| n == name)
+                .map(|(_, value)| value.to_string())
+        };
+        assert_eq!(param("prompt").as_deref(), Some("select_account"));
+        assert_eq!(param("scope").as_deref(), Some("openid email"));
+        assert_eq!(param("nonce").as_deref(), Some("nonce"));
+        assert_eq!(param("code_challenge_method").as_deref(), Some("S256"));
+    }
+}
@@ -0,0 +1,60 @@
+use base64::{engine::general_purpose, Engine};
+use sha2::{Digest, Sha256};
+
+/// Computes the `S256` code challenge for a PKCE code verifier.
+///
+/// The challenge is the unpadded base64url encoding of the SHA-256 digest of the verifier,
+/// as defined in RFC 7636 section 4.2.
+///
+/// # Examples
+///
+/// ```rust
+/// use authentication_service::api::utils::pkce::code_challenge;
+///
+/// let challenge = code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+/// assert_eq!(challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+/// ```
+pub fn code_challenge(code_verifier: &str) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Verifies a PKCE code verifier against the `S256` code challenge stored with an authorization code.
+///
+/// # Returns
+///
+/// * `true` if the verifier is well formed and hashes to the given challenge.
+/// * `false` otherwise.
+pub fn verify_code_challenge(code_verifier: &str, challenge: &str) -> bool {
+    is_valid_code_verifier(code_verifier) && code_challenge(code_verifier) == challenge
+}
+
+/// Checks that a code verifier has the length and character set required by RFC 7636 section 4.1.
+fn is_valid_code_verifier(code_verifier: &str) -> bool {
+    (43..=128).contains(&code_verifier.len())
+        && code_verifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_code_verifier() {
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+        assert!(verify_code_challenge(verifier, &code_challenge(verifier)));
+    }
+
+    #[test]
+    fn test_invalid_code_verifier() {
+        let challenge = code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+
+        assert!(!verify_code_challenge("short", &challenge));
+        assert!(!verify_code_challenge(
+            "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXX",
+            &challenge
+        ));
+    }
+}
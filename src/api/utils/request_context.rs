@@ -0,0 +1,51 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, Extensions, HeaderMap},
+};
+
+use crate::domain::model::audit::RequestContext;
+
+/// Header carrying the id of a request, as sent by the client or set by `request_id`.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    /// The id of the request being handled, set by `request_id` so `ApiError` responses can
+    /// quote it without being handed the request.
+    pub static REQUEST_ID: String;
+}
+
+/// Reads where a request came from out of its headers and the connection it was received on.
+///
+/// The IP address is the one of the peer, which is only known when the server was started
+/// with `into_make_service_with_connect_info`, as `run` does.
+pub fn request_context(headers: &HeaderMap, extensions: &Extensions) -> RequestContext {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+
+    RequestContext {
+        ip_address: extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip().to_string()),
+        user_agent: header(axum::http::header::USER_AGENT.as_str()),
+        request_id: header(REQUEST_ID_HEADER),
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(request_context(&parts.headers, &parts.extensions))
+    }
+}
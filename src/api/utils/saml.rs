@@ -0,0 +1,491 @@
+use std::{collections::HashMap, io::Write, ops::Range};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use base64::{engine::general_purpose, Engine};
+use bergshamra_dsig::{verify::verify_all_document_with_source, DsigContext, VerifyResult};
+use bergshamra_keys::{loader::load_x509_cert_pem, KeysManager};
+use flate2::{write::DeflateEncoder, Compression};
+use roxmltree::{Document, Node};
+
+use crate::domain::model::saml::{SamlAssertion, SamlIdentityProvider};
+
+const PROTOCOL_NS: &str = "urn:oasis:names:tc:SAML:2.0:protocol";
+const ASSERTION_NS: &str = "urn:oasis:names:tc:SAML:2.0:assertion";
+const METADATA_NS: &str = "urn:oasis:names:tc:SAML:2.0:metadata";
+const DSIG_NS: &str = "http://www.w3.org/2000/09/xmldsig#";
+
+const STATUS_SUCCESS: &str = "urn:oasis:names:tc:SAML:2.0:status:Success";
+const BEARER: &str = "urn:oasis:names:tc:SAML:2.0:cm:bearer";
+const POST_BINDING: &str = "urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST";
+const NAME_ID_UNSPECIFIED: &str = "urn:oasis:names:tc:SAML:1.1:nameid-format:unspecified";
+
+/// Tolerated difference, in seconds, between our clock and the identity provider's.
+const CLOCK_SKEW: i64 = 60;
+
+/// What a response must be addressed to, from the `AuthnRequest` it answers.
+#[derive(Debug)]
+pub struct ExpectedResponse<'a> {
+    pub entity_id: &'a str,
+    pub acs_url: &'a str,
+    pub request_id: &'a str,
+}
+
+/// Builds the service provider metadata to register with an identity provider. Assertions are
+/// accepted over the POST binding at `acs_url` and must be signed.
+pub fn sp_metadata(entity_id: &str, acs_url: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<md:EntityDescriptor xmlns:md="{}" entityID="{}">
+  <md:SPSSODescriptor AuthnRequestsSigned="false" WantAssertionsSigned="true" protocolSupportEnumeration="{}">
+    <md:NameIDFormat>{}</md:NameIDFormat>
+    <md:AssertionConsumerService Binding="{}" Location="{}" index="0" isDefault="true"/>
+  </md:SPSSODescriptor>
+</md:EntityDescriptor>
+"#,
+        METADATA_NS,
+        escape_attribute(entity_id),
+        PROTOCOL_NS,
+        NAME_ID_UNSPECIFIED,
+        POST_BINDING,
+        escape_attribute(acs_url),
+    )
+}
+
+/// Builds the URL sending the user to the identity provider with an `AuthnRequest`, using the
+/// HTTP-Redirect binding: the request is deflated, base64 encoded and sent in the query
+/// together with `relay_state`.
+pub fn authn_request_url(
+    provider: &SamlIdentityProvider,
+    expected: &ExpectedResponse,
+    relay_state: &str,
+) -> Result<String> {
+    let request = format!(
+        r#"<samlp:AuthnRequest xmlns:samlp="{}" xmlns:saml="{}" ID="{}" Version="2.0" IssueInstant="{}" Destination="{}" AssertionConsumerServiceURL="{}" ProtocolBinding="{}"><saml:Issuer>{}</saml:Issuer><samlp:NameIDPolicy AllowCreate="true" Format="{}"/></samlp:AuthnRequest>"#,
+        PROTOCOL_NS,
+        ASSERTION_NS,
+        escape_attribute(expected.request_id),
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+        escape_attribute(&provider.sso_url),
+        escape_attribute(expected.acs_url),
+        POST_BINDING,
+        escape_text(expected.entity_id),
+        NAME_ID_UNSPECIFIED,
+    );
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(request.as_bytes())?;
+    let request = general_purpose::STANDARD.encode(encoder.finish()?);
+
+    let mut url =
+        url::Url::parse(&provider.sso_url).context("Invalid SSO URL of identity provider")?;
+    url.query_pairs_mut()
+        .append_pair("SAMLRequest", &request)
+        .append_pair("RelayState", relay_state);
+
+    Ok(url.to_string())
+}
+
+/// Validates a base64 encoded `SAMLResponse` from `provider` and returns its assertion.
+///
+/// The response must be successful, answer the request in `expected`, and carry exactly one
+/// assertion issued by the provider. The assertion, or the whole response, must be signed by
+/// the provider's certificate. The assertion must be addressed to our entity ID and assertion
+/// consumer service, and be valid at `now`, a unix timestamp.
+///
+/// # Errors
+///
+/// Returns an error describing the first check that failed.
+pub fn validate_response(
+    provider: &SamlIdentityProvider,
+    saml_response: &str,
+    expected: &ExpectedResponse,
+    now: i64,
+) -> Result<SamlAssertion> {
+    let encoded: String = saml_response
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let xml = general_purpose::STANDARD
+        .decode(encoded)
+        .context("SAMLResponse is not valid base64")?;
+    let xml = String::from_utf8(xml).context("SAMLResponse is not valid UTF-8")?;
+    // DTDs are rejected by the parser, which rules out entity expansion attacks.
+    let document = Document::parse(&xml).context("SAMLResponse is not valid XML")?;
+
+    let response = document.root_element();
+    ensure!(
+        response.has_tag_name((PROTOCOL_NS, "Response")),
+        "SAMLResponse is not a samlp:Response"
+    );
+
+    if let Some(destination) = response.attribute("Destination") {
+        ensure!(
+            destination == expected.acs_url,
+            "Response is addressed to {}",
+            destination
+        );
+    }
+    ensure!(
+        response.attribute("InResponseTo") == Some(expected.request_id),
+        "Response does not answer the pending request"
+    );
+    if let Some(issuer) = child(response, ASSERTION_NS, "Issuer").map(text) {
+        ensure!(
+            issuer == provider.entity_id,
+            "Response was issued by {}",
+            issuer
+        );
+    }
+
+    let status = child(response, PROTOCOL_NS, "Status")
+        .and_then(|status| child(status, PROTOCOL_NS, "StatusCode"))
+        .and_then(|code| code.attribute("Value"))
+        .ok_or_else(|| anyhow!("Response has no status"))?;
+    ensure!(
+        status == STATUS_SUCCESS,
+        "Identity provider returned {}",
+        status
+    );
+
+    ensure!(
+        child(response, ASSERTION_NS, "EncryptedAssertion").is_none(),
+        "Encrypted assertions are not supported"
+    );
+    let mut assertions = children(response, ASSERTION_NS, "Assertion");
+    let assertion = assertions
+        .next()
+        .ok_or_else(|| anyhow!("Response has no assertion"))?;
+    ensure!(
+        assertions.next().is_none(),
+        "Response has more than one assertion"
+    );
+
+    ensure!(
+        child(assertion, DSIG_NS, "Signature").is_some()
+            || child(response, DSIG_NS, "Signature").is_some(),
+        "Neither the response nor the assertion is signed"
+    );
+    // Everything below is read from the assertion that was checked to be signed, not from an
+    // element with the same ID elsewhere in the document.
+    let signed = signed_ranges(&provider.certificate, &xml)?;
+    ensure!(
+        [assertion, response]
+            .iter()
+            .any(|element| signed.contains(&element.range())),
+        "The assertion is not signed by the identity provider"
+    );
+
+    let issuer = child(assertion, ASSERTION_NS, "Issuer")
+        .map(text)
+        .unwrap_or_default();
+    ensure!(
+        issuer == provider.entity_id,
+        "Assertion was issued by {}",
+        issuer
+    );
+
+    let subject = child(assertion, ASSERTION_NS, "Subject")
+        .ok_or_else(|| anyhow!("Assertion has no subject"))?;
+    let name_id = child(subject, ASSERTION_NS, "NameID")
+        .map(text)
+        .map(|name_id| name_id.trim().to_string())
+        .filter(|name_id| !name_id.is_empty())
+        .ok_or_else(|| anyhow!("Assertion has no NameID"))?;
+
+    let confirmation_expires_at = children(subject, ASSERTION_NS, "SubjectConfirmation")
+        .filter(|confirmation| confirmation.attribute("Method") == Some(BEARER))
+        .filter_map(|confirmation| child(confirmation, ASSERTION_NS, "SubjectConfirmationData"))
+        .filter(|data| data.attribute("Recipient") == Some(expected.acs_url))
+        .filter(|data| {
+            data.attribute("InResponseTo")
+                .is_none_or(|id| id == expected.request_id)
+        })
+        .filter_map(|data| timestamp(data.attribute("NotOnOrAfter")?).ok())
+        .find(|not_on_or_after| now < not_on_or_after + CLOCK_SKEW)
+        .ok_or_else(|| anyhow!("Assertion has no valid bearer confirmation for this service"))?;
+
+    let conditions = child(assertion, ASSERTION_NS, "Conditions")
+        .ok_or_else(|| anyhow!("Assertion has no conditions"))?;
+    if let Some(not_before) = conditions.attribute("NotBefore") {
+        ensure!(
+            now + CLOCK_SKEW >= timestamp(not_before)?,
+            "Assertion is not valid yet"
+        );
+    }
+    let conditions_expire_at = match conditions.attribute("NotOnOrAfter") {
+        Some(not_on_or_after) => {
+            let not_on_or_after = timestamp(not_on_or_after)?;
+            ensure!(now < not_on_or_after + CLOCK_SKEW, "Assertion has expired");
+            not_on_or_after
+        }
+        None => confirmation_expires_at,
+    };
+
+    let mut restrictions = children(conditions, ASSERTION_NS, "AudienceRestriction").peekable();
+    ensure!(
+        restrictions.peek().is_some(),
+        "Assertion has no audience restriction"
+    );
+    for restriction in restrictions {
+        ensure!(
+            children(restriction, ASSERTION_NS, "Audience")
+                .any(|audience| text(audience).trim() == expected.entity_id),
+            "Assertion is intended for another audience"
+        );
+    }
+
+    let mut attributes: HashMap<String, Vec<String>> = HashMap::new();
+    for statement in children(assertion, ASSERTION_NS, "AttributeStatement") {
+        for attribute in children(statement, ASSERTION_NS, "Attribute") {
+            let Some(name) = attribute.attribute("Name") else {
+                continue;
+            };
+            attributes.entry(name.to_string()).or_default().extend(
+                children(attribute, ASSERTION_NS, "AttributeValue")
+                    .map(|value| text(value).trim().to_string()),
+            );
+        }
+    }
+
+    Ok(SamlAssertion {
+        id: assertion
+            .attribute("ID")
+            .ok_or_else(|| anyhow!("Assertion has no ID"))?
+            .to_string(),
+        name_id,
+        attributes,
+        expires_at: conditions_expire_at.min(confirmation_expires_at),
+    })
+}
+
+/// Verifies every XML signature in `xml` with the identity provider's certificate and returns
+/// the source ranges of the elements they sign.
+///
+/// Verification is left to bergshamra's XML signature implementation, with the defaults it
+/// hardens for SAML: only the configured certificate is trusted, keys embedded in a signature
+/// are ignored, IDs must be unique, and a signed element must enclose or neighbour its
+/// signature, which rules out signature wrapping. Algorithms such as SHA-1 are not compiled in.
+fn signed_ranges(certificate: &str, xml: &str) -> Result<Vec<Range<usize>>> {
+    let key = load_x509_cert_pem(certificate.as_bytes())
+        .map_err(|e| anyhow!("Invalid identity provider certificate: {}", e))?;
+    let mut keys = KeysManager::new();
+    keys.add_key(key);
+    // `ID`, the attribute SAML identifies elements by, is one of the defaults.
+    let context = DsigContext::new(keys);
+
+    let document =
+        uppsala::parse(xml).map_err(|e| anyhow!("SAMLResponse is not valid XML: {}", e))?;
+    let results = verify_all_document_with_source(&context, &document, Some(xml))
+        .map_err(|e| anyhow!("Signature could not be verified: {}", e))?;
+
+    let mut ranges = Vec::new();
+    for result in results {
+        match result {
+            VerifyResult::Valid { .. } if !result.all_reference_digests_verified() => {
+                bail!("Signature does not cover the response")
+            }
+            VerifyResult::Valid { references, .. } => ranges.extend(
+                references
+                    .iter()
+                    .filter_map(|reference| reference.resolved_node)
+                    .filter_map(|node| document.node_range(node)),
+            ),
+            VerifyResult::Invalid { reason } => bail!("Signature is invalid: {}", reason),
+        }
+    }
+
+    Ok(ranges)
+}
+
+fn child<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    namespace: &'a str,
+    name: &'a str,
+) -> Option<Node<'a, 'input>> {
+    children(node, namespace, name).next()
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    namespace: &'a str,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.has_tag_name((namespace, name)))
+}
+
+/// The text of `node` and all its descendants. Comments are skipped, as canonicalization left
+/// them out of what was signed: `a<!---->b` must read as `ab`, not `a`.
+fn text(node: Node) -> String {
+    node.descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .collect()
+}
+
+fn timestamp(value: &str) -> Result<i64> {
+    Ok(chrono::DateTime::parse_from_rfc3339(value)
+        .with_context(|| format!("Invalid timestamp {}", value))?
+        .timestamp())
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\r', "&#xD;")
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+        .replace('\t', "&#x9;")
+        .replace('\n', "&#xA;")
+        .replace('\r', "&#xD;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNED_RESPONSE: &str = include_str!("../../../tests/fixtures/saml/signed_response.xml");
+    const CERTIFICATE: &str = include_str!("../../../tests/fixtures/saml/idp_certificate.pem");
+    /// 2026-10-18T12:01:00Z, within the validity period of the fixture.
+    const NOW: i64 = 1792324860;
+
+    fn provider() -> SamlIdentityProvider {
+        SamlIdentityProvider {
+            name: "test-idp".to_string(),
+            entity_id: "https://idp.example.com/saml".to_string(),
+            sso_url: "https://idp.example.com/saml/sso".to_string(),
+            certificate: CERTIFICATE.to_string(),
+            email_attribute: Some("email".to_string()),
+            trust_email: true,
+        }
+    }
+
+    fn expected() -> ExpectedResponse<'static> {
+        ExpectedResponse {
+            entity_id: "https://sp.example.com/api/saml/test-idp/metadata",
+            acs_url: "https://sp.example.com/api/saml/test-idp/acs",
+            request_id: "_request-1",
+        }
+    }
+
+    fn encode(xml: &str) -> String {
+        general_purpose::STANDARD.encode(xml)
+    }
+
+    #[test]
+    fn test_validate_signed_fixture_success() {
+        let assertion =
+            validate_response(&provider(), &encode(SIGNED_RESPONSE), &expected(), NOW).unwrap();
+
+        assert_eq!(assertion.id, "_assertion-1");
+        assert_eq!(assertion.name_id, "ada@example.com");
+        assert_eq!(
+            assertion.attributes["email"],
+            vec!["ada.lovelace@example.com".to_string()]
+        );
+        assert_eq!(
+            assertion.attributes["displayName"],
+            vec!["Ada & Co <Engines>".to_string()]
+        );
+        assert_eq!(assertion.expires_at, NOW + 240);
+    }
+
+    #[test]
+    fn test_validate_tampered_fixture_failure() {
+        let tampered = SIGNED_RESPONSE.replace("ada.lovelace@example.com", "mallory@example.com");
+
+        let result = validate_response(&provider(), &encode(&tampered), &expected(), NOW);
+
+        assert!(format!("{:#}", result.unwrap_err()).contains("digest"));
+    }
+
+    #[test]
+    fn test_validate_fixture_checks_conditions() {
+        let expired = validate_response(
+            &provider(),
+            &encode(SIGNED_RESPONSE),
+            &expected(),
+            NOW + 3600,
+        );
+        assert!(expired.is_err());
+
+        let other_audience = ExpectedResponse {
+            entity_id: "https://other.example.com/metadata",
+            ..expected()
+        };
+        let result = validate_response(&provider(), &encode(SIGNED_RESPONSE), &other_audience, NOW);
+        assert!(result.is_err());
+
+        let other_request = ExpectedResponse {
+            request_id: "_request-2",
+            ..expected()
+        };
+        let result = validate_response(&provider(), &encode(SIGNED_RESPONSE), &other_request, NOW);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_unsigned_assertion_failure() {
+        let start = SIGNED_RESPONSE.find("<ds:Signature").unwrap();
+        let end = SIGNED_RESPONSE.find("</ds:Signature>").unwrap() + "</ds:Signature>".len();
+        let unsigned = format!("{}{}", &SIGNED_RESPONSE[..start], &SIGNED_RESPONSE[end..]);
+
+        let result = validate_response(&provider(), &encode(&unsigned), &expected(), NOW);
+
+        assert!(format!("{:#}", result.unwrap_err()).contains("signed"));
+    }
+
+    #[test]
+    fn test_validate_comment_split_values_success() {
+        // Canonicalization leaves comments out, so adding them keeps the signature valid.
+        let split = SIGNED_RESPONSE
+            .replace(">ada@example.com<", ">ada@exam<!---->ple.com<")
+            .replace(
+                ">ada.lovelace@example.com<",
+                ">ada.lovelace<!---->@example.com<",
+            );
+
+        let assertion = validate_response(&provider(), &encode(&split), &expected(), NOW).unwrap();
+
+        assert_eq!(assertion.name_id, "ada@example.com");
+        assert_eq!(
+            assertion.attributes["email"],
+            vec!["ada.lovelace@example.com".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_validate_wrapped_assertion_failure() {
+        let start = SIGNED_RESPONSE.find("<saml:Assertion").unwrap();
+        let end = SIGNED_RESPONSE.find("</saml:Assertion>").unwrap() + "</saml:Assertion>".len();
+        let signed = &SIGNED_RESPONSE[start..end];
+        let signature_start = signed.find("<ds:Signature").unwrap();
+        let signature_end = signed.find("</ds:Signature>").unwrap() + "</ds:Signature>".len();
+        let forged = signed.replace("ada.lovelace@example.com", "mallory@example.com");
+        let unsigned_forged = format!("{}{}", &forged[..signature_start], &forged[signature_end..])
+            .replace("_assertion-1", "_assertion-2");
+
+        // The signed assertion is moved out of the way, next to a forged one with its own ID
+        // or a copy of the signed one's ID and signature.
+        for forged in [unsigned_forged, forged] {
+            let wrapped = format!(
+                "{}<samlp:Extensions>{}</samlp:Extensions>{}{}",
+                &SIGNED_RESPONSE[..start],
+                signed,
+                forged,
+                &SIGNED_RESPONSE[end..]
+            );
+
+            let result = validate_response(&provider(), &encode(&wrapped), &expected(), NOW);
+
+            assert!(result.is_err());
+        }
+    }
+}
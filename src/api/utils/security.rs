@@ -1,6 +1,8 @@
 use anyhow::anyhow;
 use argon2::{password_hash::SaltString, Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
-use rand_core::OsRng;
+use base64::{engine::general_purpose, Engine};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
 
 /// Hashes a plain text password using the Argon2 algorithm.
 ///
@@ -72,11 +74,92 @@ pub fn is_valid(password: &str, hashed_password: &str) -> bool {
     match PasswordHash::new(hashed_password) {
         Ok(parsed_hash) => Argon2::default()
             .verify_password(password.as_bytes(), &parsed_hash)
-            .map_or(false, |_| true),
+            .is_ok(),
         Err(_) => false,
     }
 }
 
+/// Generates a random, URL-safe token suitable for one-time codes and secrets.
+///
+/// The token is built from 32 bytes of operating system randomness and encoded as
+/// unpadded base64url, so it can be placed in query strings without further escaping.
+///
+/// # Examples
+///
+/// ```rust
+/// use authentication_service::api::utils::security::generate_random_token;
+///
+/// let token = generate_random_token();
+/// assert_eq!(token.len(), 43);
+/// assert_ne!(token, generate_random_token());
+/// ```
+pub fn generate_random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Hashes a random token for storage, as a lowercase hex SHA-256 digest.
+///
+/// Unlike passwords, tokens from `generate_random_token` have 256 bits of entropy, so a fast
+/// unsalted hash is enough, and it lets a presented token be looked up by its hash.
+///
+/// # Examples
+///
+/// ```rust
+/// use authentication_service::api::utils::security::hash_token;
+///
+/// assert_eq!(hash_token("token").len(), 64);
+/// assert_eq!(hash_token("token"), hash_token("token"));
+/// ```
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Characters used in user codes: upper case consonants without vowels, so codes never spell
+/// words, and without letters that are easily confused when typed on another device.
+const USER_CODE_ALPHABET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+const USER_CODE_LENGTH: usize = 8;
+
+/// Generates a short code for a user to type on a second device, as in RFC 8628 section 6.1.
+///
+/// The code has 8 characters from a 20 letter alphabet, about 34 bits of entropy, which is
+/// enough given that device codes expire quickly and verification attempts are rate limited.
+///
+/// # Examples
+///
+/// ```rust
+/// use authentication_service::api::utils::security::{generate_user_code, normalize_user_code};
+///
+/// let code = generate_user_code();
+/// assert_eq!(code.len(), 8);
+/// assert_eq!(normalize_user_code(&code), code);
+/// ```
+pub fn generate_user_code() -> String {
+    // Bytes at or above the largest multiple of the alphabet size are rejected to avoid bias.
+    let limit = 256 - 256 % USER_CODE_ALPHABET.len();
+    let mut code = String::with_capacity(USER_CODE_LENGTH);
+    while code.len() < USER_CODE_LENGTH {
+        let byte = (OsRng.next_u32() & 0xff) as usize;
+        if byte < limit {
+            code.push(USER_CODE_ALPHABET[byte % USER_CODE_ALPHABET.len()] as char);
+        }
+    }
+    code
+}
+
+/// Normalizes a user code as typed by a user, ignoring case, dashes and spaces.
+pub fn normalize_user_code(user_code: &str) -> String {
+    user_code
+        .chars()
+        .map(|c| c.to_ascii_uppercase())
+        .filter(|c| c.is_ascii() && USER_CODE_ALPHABET.contains(&(*c as u8)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +178,10 @@ mod tests {
 
         assert!(!is_valid("1234", &hashed_password));
     }
+
+    #[test]
+    fn test_normalize_user_code() {
+        assert_eq!(normalize_user_code("bcdf-ghjk"), "BCDFGHJK");
+        assert_eq!(normalize_user_code(" BCDF GHJK "), "BCDFGHJK");
+    }
 }
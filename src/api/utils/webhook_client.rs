@@ -0,0 +1,68 @@
+use anyhow::{bail, Context, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{api::utils::http_client::http_client, domain::model::webhook::PendingWebhookDelivery};
+
+/// Header carrying `sha256=` and the hex HMAC-SHA256 of `{timestamp}.{body}`.
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+/// Header carrying the Unix time the delivery was signed at, so receivers can reject replays.
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+pub const EVENT_HEADER: &str = "x-webhook-event";
+/// Header carrying the id of the delivery, which stays the same across its retries.
+pub const DELIVERY_HEADER: &str = "x-webhook-id";
+
+/// Signs a webhook body for the `X-Webhook-Signature` header, as the lowercase hex
+/// HMAC-SHA256 of `{timestamp}.{body}` keyed with the subscription's secret.
+///
+/// # Examples
+///
+/// ```
+/// use authentication_service::api::utils::webhook_client::sign_payload;
+///
+/// let signature = sign_payload("whsec_secret", 1700000000, br#"{"type":"user.registered"}"#);
+/// assert_eq!(signature.len(), 64);
+/// assert_ne!(signature, sign_payload("whsec_other", 1700000000, br#"{"type":"user.registered"}"#));
+/// ```
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// POSTs a delivery's payload to its subscription, signed with the subscription's secret.
+///
+/// # Errors
+///
+/// Fails when the receiver cannot be reached or does not answer with a 2xx status.
+pub async fn send_webhook(delivery: &PendingWebhookDelivery) -> Result<()> {
+    let body = serde_json::to_vec(&delivery.payload).context("Failed to serialize payload")?;
+    let timestamp = chrono::Utc::now().timestamp();
+    let signature = sign_payload(&delivery.secret, timestamp, &body);
+
+    let response = http_client()
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, format!("sha256={}", signature))
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(EVENT_HEADER, &delivery.event_type)
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .body(body)
+        .send()
+        .await
+        .context("Failed to reach webhook receiver")?;
+
+    if !response.status().is_success() {
+        bail!("Webhook receiver answered with HTTP {}", response.status());
+    }
+
+    Ok(())
+}
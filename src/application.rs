@@ -1,27 +1,98 @@
 use crate::{
     api::{
         endpoints::{
-            get_me::get_me_handler, healthcheck::healthcheck, login::login_handler,
-            logout::logout_handler, refresh::refresh_access_token_handler,
+            admin_users::{
+                delete_user_handler, force_password_reset_handler, get_user_handler,
+                list_users_handler, revoke_user_sessions_handler, update_user_handler,
+            },
+            audit_events::{
+                list_audit_events_handler, recent_activity_handler, verify_audit_chain_handler,
+            },
+            change_password::change_password_handler,
+            federation::{federated_callback_handler, federated_login_handler},
+            forward_auth::verify_handler,
+            get_me::get_me_handler,
+            healthcheck::healthcheck,
+            impersonation::{end_impersonation_handler, start_impersonation_handler},
+            login::login_handler,
+            logout::logout_handler,
+            oauth_authorize::authorize_handler,
+            oauth_device::{device_authorization_handler, device_verification_handler},
+            oauth_introspect::introspect_handler,
+            oauth_revoke::revoke_handler,
+            oauth_token::token_handler,
+            oidc_discovery::{jwks_handler, openid_configuration_handler},
+            oidc_userinfo::userinfo_handler,
+            organizations::{
+                accept_invitation_handler, create_organization_handler, invite_member_handler,
+                list_members_handler, list_organizations_handler, remove_member_handler,
+                switch_organization_handler,
+            },
+            password_reset::reset_password_handler,
+            personal_access_tokens::{
+                create_personal_access_token_handler, list_personal_access_tokens_handler,
+                revoke_personal_access_token_handler,
+            },
+            refresh::refresh_access_token_handler,
             register::register_handler,
+            registration_invitations::{
+                create_registration_invitation_handler, list_registration_invitations_handler,
+                revoke_registration_invitation_handler,
+            },
+            saml::{saml_assertion_handler, saml_login_handler, saml_metadata_handler},
+            service_accounts::{
+                create_service_account_handler, create_service_account_key_handler,
+                delete_service_account_handler, list_service_account_keys_handler,
+                list_service_accounts_handler, revoke_service_account_key_handler,
+                rotate_service_account_key_handler,
+            },
+            webhooks::{
+                create_webhook_handler, delete_webhook_handler, list_webhook_deliveries_handler,
+                list_webhooks_handler, retry_webhook_delivery_handler,
+            },
         },
-        middlewares::authentication::auth,
+        middlewares::{
+            audit::audit_admin_action,
+            authentication::{auth, optional_auth},
+            authorization::{admin, not_impersonated, require_scope},
+            request_id::request_id,
+        },
+        openapi::ApiDoc,
+    },
+    claims::pipeline::ClaimsPipeline,
+    domain::{
+        admin_user_service::AdminUserService, audit_service::AuditService,
+        auth_service::AuthService, federation_service::FederationService,
+        forward_auth_service::ForwardAuthService, impersonation_service::ImpersonationService,
+        oauth_service::OAuthService, oidc_service::OidcService,
+        organization_service::OrganizationService,
+        personal_access_token_service::PersonalAccessTokenService,
+        registration_invitation_service::RegistrationInvitationService, saml_service::SamlService,
+        service_account_service::ServiceAccountService, webhook_service::WebhookService,
+    },
+    grpc::{
+        authentication::GrpcAuthentication,
+        proto::{authentication_server::AuthenticationServer, FILE_DESCRIPTOR_SET},
     },
-    domain::auth_service::AuthService,
     helper::config::Config,
-    repositories::{auth_repository::PostgresDB, cache_repository::RedisCache},
+    repositories::{
+        auth_repository::PostgresDB, cache_repository::RedisCache,
+        event_publisher::RedisEventPublisher,
+    },
     service::auth_service::Service,
 };
 use anyhow::Result;
 use axum::{
     middleware,
-    routing::{get, post},
+    routing::{any, delete, get, post},
     Router,
 };
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::net::TcpListener;
 use tower_http::trace::{self, TraceLayer};
 use tracing::Level;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
 /// Holds the shared state for the application, including the authentication service.
 ///
@@ -40,8 +111,12 @@ use tracing::Level;
 /// use std::sync::Arc;
 /// use authentication_service::{
 ///     application::AppState,
+///     claims::pipeline::ClaimsPipeline,
 ///     domain::auth_service::AuthService,
-///     repositories::{auth_repository::PostgresDB, cache_repository::RedisCache},
+///     repositories::{
+///         auth_repository::PostgresDB, cache_repository::RedisCache,
+///         event_publisher::RedisEventPublisher,
+///     },
 ///     helper::config::Config,
 ///     service::auth_service::Service,
 /// };
@@ -51,9 +126,18 @@ use tracing::Level;
 ///     let config = Config::init();
 ///     let postgres = PostgresDB::new(&config.database_url).await?;
 ///     let redis = RedisCache::new(&config.redis_url);
+///     let events = RedisEventPublisher::new(
+///         &config.redis_url,
+///         &config.event_stream,
+///         config.event_stream_max_len,
+///         &config.revocation_channel,
+///     )?;
 ///     let auth_service = Service {
-///         repo: postgres,
+///         repo: postgres.clone(),
 ///         cache: redis,
+///         audit: postgres,
+///         events,
+///         claims: ClaimsPipeline::from_config(&config),
 ///         config,
 ///     };
 ///
@@ -72,8 +156,10 @@ pub struct AppState<AS: AuthService> {
 /// Asynchronously runs the application with the given TCP listener and configuration.
 ///
 /// This function sets up the necessary components for the application, including
-/// the PostgreSQL database connection and the Redis cache. It then initializes
-/// the application state and starts the server using the Axum framework.
+/// the PostgreSQL database connection, which also holds the security audit log, and the Redis
+/// cache. It then initializes
+/// the application state and starts the server using the Axum framework. When `GRPC_ADDRESS`
+/// is set, the gRPC API for internal services is served there alongside it.
 ///
 /// # Arguments
 ///
@@ -106,14 +192,28 @@ pub struct AppState<AS: AuthService> {
 /// This function will return an error if:
 /// - The PostgreSQL database connection cannot be established.
 /// - The Redis cache cannot be initialized.
-/// - The server fails to start.
+/// - The server or the gRPC API fails to start.
 pub async fn run(listener: TcpListener, config: Config) -> Result<()> {
     let postgres = PostgresDB::new(&config.database_url).await?;
     let redis = RedisCache::new(&config.redis_url);
+    let events = RedisEventPublisher::new(
+        &config.redis_url,
+        &config.event_stream,
+        config.event_stream_max_len,
+        &config.revocation_channel,
+    )?;
+
+    let grpc_address = config.grpc_address;
+    let webhook_worker = config
+        .webhook_worker_enabled
+        .then(|| Duration::from_secs(config.webhook_poll_interval_seconds));
 
     let service = Service {
-        repo: postgres,
+        repo: postgres.clone(),
         cache: redis,
+        audit: postgres,
+        events,
+        claims: ClaimsPipeline::from_config(&config),
         config,
     };
 
@@ -121,18 +221,89 @@ pub async fn run(listener: TcpListener, config: Config) -> Result<()> {
         auth_service: service,
     });
 
-    let app = app(app_state);
-    axum::serve(listener, app).await?;
+    if let Some(poll_interval) = webhook_worker {
+        tokio::spawn(deliver_webhooks(app_state.clone(), poll_interval));
+    }
+
+    let app = app(app_state.clone());
+    let http = async {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
+        Ok(())
+    };
+
+    match grpc_address {
+        Some(address) => {
+            tokio::try_join!(http, serve_grpc(address, app_state))?;
+        }
+        None => http.await?,
+    }
+
+    Ok(())
+}
+
+/// Serves the gRPC API for internal services on `address`, next to the standard gRPC health
+/// checking and reflection services, until the application stops.
+async fn serve_grpc<AS: AuthService + AdminUserService + OAuthService>(
+    address: SocketAddr,
+    app_state: Arc<AppState<AS>>,
+) -> Result<()> {
+    let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter
+        .set_serving::<AuthenticationServer<GrpcAuthentication<AS>>>()
+        .await;
+
+    let reflection_service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build_v1()?;
+
+    tonic::transport::Server::builder()
+        .add_service(health_service)
+        .add_service(reflection_service)
+        .add_service(AuthenticationServer::new(GrpcAuthentication::new(
+            app_state,
+        )))
+        .serve(address)
+        .await?;
 
     Ok(())
 }
 
+/// Delivers queued webhook events until the application stops.
+///
+/// Deliveries are sent in batches, one batch after the other while there are any due, then
+/// the outbox is polled again every `poll_interval`. Errors reading the outbox are logged and
+/// retried at the next poll.
+async fn deliver_webhooks<AS: AuthService + WebhookService>(
+    app_state: Arc<AppState<AS>>,
+    poll_interval: Duration,
+) {
+    loop {
+        match app_state.auth_service.deliver_pending_webhooks().await {
+            Ok(attempted) if attempted > 0 => continue,
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to deliver webhooks: {:?}", e),
+        }
+
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
 /// Creates a new Axum router with the given application state.
 ///
 /// This function sets up the routes for the application and applies the necessary
 /// middlewares and layers. It includes routes for health checks, authentication,
-/// and user management. Each route is associated with its corresponding handler
-/// function and middleware where required.
+/// user management, personal access tokens, organizations, password changes and resets, the
+/// administration and impersonation of users, the security audit log, service accounts,
+/// registration invitations and webhooks, forward authentication for reverse proxies,
+/// federated login with OpenID Connect and SAML, the OAuth 2.0 authorization server and the
+/// OpenID Connect provider. Each route is associated with its corresponding handler function
+/// and middleware where required. Every request is given an
+/// `x-request-id`, which is recorded with the audit events it causes.
 ///
 /// # Arguments
 ///
@@ -146,14 +317,49 @@ pub async fn run(listener: TcpListener, config: Config) -> Result<()> {
 ///
 /// # Type Parameters
 ///
-/// * `AS` - A type that implements the `AuthService` trait. This is used to abstract
-///   over the authentication service implementation.
-fn app<AS: AuthService>(app_state: Arc<AppState<AS>>) -> Router {
+/// * `AS` - A type that implements the `AuthService`, `OAuthService`, `OidcService`,
+///   `FederationService`, `SamlService`, `PersonalAccessTokenService`,
+///   `ServiceAccountService`, `OrganizationService`, `RegistrationInvitationService`,
+///   `AdminUserService`, `ImpersonationService`, `AuditService`, `WebhookService` and
+///   `ForwardAuthService` traits.
+///   This is used to abstract over the authentication service implementation.
+fn app<
+    AS: AuthService
+        + OAuthService
+        + OidcService
+        + FederationService
+        + SamlService
+        + PersonalAccessTokenService
+        + ServiceAccountService
+        + OrganizationService
+        + RegistrationInvitationService
+        + AdminUserService
+        + ImpersonationService
+        + AuditService
+        + WebhookService
+        + ForwardAuthService,
+>(
+    app_state: Arc<AppState<AS>>,
+) -> Router {
     Router::new()
         .route("/api/healthcheck", get(healthcheck))
         .route("/api/refresh", get(refresh_access_token_handler))
         .route("/api/register", post(register_handler))
         .route("/api/login", post(login_handler))
+        .route("/api/verify", any(verify_handler))
+        .route("/api/verify/*path", any(verify_handler))
+        .route("/api/password/reset", post(reset_password_handler))
+        .route(
+            "/api/federation/:provider/login",
+            get(federated_login_handler),
+        )
+        .route(
+            "/api/federation/:provider/callback",
+            get(federated_callback_handler),
+        )
+        .route("/api/saml/:provider/metadata", get(saml_metadata_handler))
+        .route("/api/saml/:provider/login", get(saml_login_handler))
+        .route("/api/saml/:provider/acs", post(saml_assertion_handler))
         .route(
             "/api/logout",
             get(logout_handler)
@@ -162,8 +368,78 @@ fn app<AS: AuthService>(app_state: Arc<AppState<AS>>) -> Router {
         .route(
             "/api/users/me",
             get(get_me_handler)
+                .route_layer(middleware::from_fn_with_state("profile", require_scope))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/users/me/activity",
+            get(recent_activity_handler)
+                .route_layer(middleware::from_fn_with_state("profile", require_scope))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
+        .route(
+            "/api/users/me/password",
+            post(change_password_handler)
+                .route_layer(middleware::from_fn(not_impersonated))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/users/me/tokens",
+            get(list_personal_access_tokens_handler)
+                .post(create_personal_access_token_handler)
+                .route_layer(middleware::from_fn(not_impersonated))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/users/me/tokens/:token_id",
+            delete(revoke_personal_access_token_handler)
+                .route_layer(middleware::from_fn(not_impersonated))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/impersonation",
+            delete(end_impersonation_handler)
+                .route_layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    audit_admin_action,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .merge(organization_routes(app_state.clone()))
+        .merge(admin_routes(app_state.clone()))
+        .route(
+            "/oauth/authorize",
+            get(authorize_handler).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                optional_auth,
+            )),
+        )
+        .route("/oauth/token", post(token_handler))
+        .route("/oauth/introspect", post(introspect_handler))
+        .route("/oauth/revoke", post(revoke_handler))
+        .route(
+            "/oauth/device_authorization",
+            post(device_authorization_handler),
+        )
+        .route(
+            "/oauth/device",
+            post(device_verification_handler)
+                .route_layer(middleware::from_fn(not_impersonated))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/userinfo",
+            get(userinfo_handler)
+                .post(userinfo_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/.well-known/openid-configuration",
+            get(openid_configuration_handler),
+        )
+        .route("/.well-known/jwks.json", get(jwks_handler))
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
+        .layer(middleware::from_fn(request_id))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(trace::DefaultMakeSpan::new().level(Level::INFO))
@@ -171,3 +447,127 @@ fn app<AS: AuthService>(app_state: Arc<AppState<AS>>) -> Router {
         )
         .with_state(app_state)
 }
+
+/// Routes acting on the organizations of the authenticated user.
+fn organization_routes<AS: AuthService + OrganizationService>(
+    app_state: Arc<AppState<AS>>,
+) -> Router<Arc<AppState<AS>>> {
+    Router::new()
+        .route("/api/organizations", get(list_organizations_handler))
+        .route(
+            "/api/organizations",
+            post(create_organization_handler).route_layer(middleware::from_fn(not_impersonated)),
+        )
+        .route(
+            "/api/organizations/invitations/accept",
+            post(accept_invitation_handler).route_layer(middleware::from_fn(not_impersonated)),
+        )
+        .route(
+            "/api/organizations/:org_id/members",
+            get(list_members_handler),
+        )
+        .route(
+            "/api/organizations/:org_id/members/:user_id",
+            delete(remove_member_handler).route_layer(middleware::from_fn(not_impersonated)),
+        )
+        .route(
+            "/api/organizations/:org_id/invitations",
+            post(invite_member_handler).route_layer(middleware::from_fn(not_impersonated)),
+        )
+        .route(
+            "/api/organizations/:org_id/switch",
+            post(switch_organization_handler).route_layer(middleware::from_fn(not_impersonated)),
+        )
+        .route_layer(middleware::from_fn_with_state(app_state, auth))
+}
+
+/// Routes restricted to administrators by the `admin` middleware, which runs after `auth`.
+///
+/// Every request that passes `admin` is then recorded in the audit log by `audit_admin_action`.
+fn admin_routes<
+    AS: AuthService
+        + ServiceAccountService
+        + RegistrationInvitationService
+        + AdminUserService
+        + ImpersonationService
+        + AuditService
+        + WebhookService,
+>(
+    app_state: Arc<AppState<AS>>,
+) -> Router<Arc<AppState<AS>>> {
+    Router::new()
+        .route("/api/admin/audit-events", get(list_audit_events_handler))
+        .route(
+            "/api/admin/audit-events/verify",
+            get(verify_audit_chain_handler),
+        )
+        .route("/api/admin/users", get(list_users_handler))
+        .route(
+            "/api/admin/users/:user_id",
+            get(get_user_handler)
+                .patch(update_user_handler)
+                .delete(delete_user_handler),
+        )
+        .route(
+            "/api/admin/users/:user_id/password-reset",
+            post(force_password_reset_handler),
+        )
+        .route(
+            "/api/admin/users/:user_id/sessions",
+            delete(revoke_user_sessions_handler),
+        )
+        .route(
+            "/api/admin/users/:user_id/impersonate",
+            post(start_impersonation_handler),
+        )
+        .route(
+            "/api/admin/invitations",
+            get(list_registration_invitations_handler).post(create_registration_invitation_handler),
+        )
+        .route(
+            "/api/admin/invitations/:invitation_id",
+            delete(revoke_registration_invitation_handler),
+        )
+        .route(
+            "/api/admin/service-accounts",
+            get(list_service_accounts_handler).post(create_service_account_handler),
+        )
+        .route(
+            "/api/admin/service-accounts/:account_id",
+            delete(delete_service_account_handler),
+        )
+        .route(
+            "/api/admin/service-accounts/:account_id/keys",
+            get(list_service_account_keys_handler).post(create_service_account_key_handler),
+        )
+        .route(
+            "/api/admin/service-accounts/:account_id/keys/:key_id",
+            delete(revoke_service_account_key_handler),
+        )
+        .route(
+            "/api/admin/service-accounts/:account_id/keys/:key_id/rotate",
+            post(rotate_service_account_key_handler),
+        )
+        .route(
+            "/api/admin/webhooks",
+            get(list_webhooks_handler).post(create_webhook_handler),
+        )
+        .route(
+            "/api/admin/webhooks/:webhook_id",
+            delete(delete_webhook_handler),
+        )
+        .route(
+            "/api/admin/webhooks/:webhook_id/deliveries",
+            get(list_webhook_deliveries_handler),
+        )
+        .route(
+            "/api/admin/webhooks/:webhook_id/deliveries/:delivery_id/retry",
+            post(retry_webhook_delivery_handler),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            audit_admin_action,
+        ))
+        .route_layer(middleware::from_fn(admin))
+        .route_layer(middleware::from_fn_with_state(app_state, auth))
+}
@@ -0,0 +1,4 @@
+pub mod pipeline;
+pub mod profile_claims;
+pub mod role_claims;
+pub mod static_claims;
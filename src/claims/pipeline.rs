@@ -0,0 +1,147 @@
+use anyhow::anyhow;
+
+use crate::{
+    claims::{
+        profile_claims::ProfileClaimsProvider, role_claims::RoleClaimsProvider,
+        static_claims::StaticClaimsProvider,
+    },
+    domain::{
+        claims_provider::ClaimsProvider,
+        model::{
+            custom_claims::{ClaimsError, CustomClaims, RESERVED_CLAIMS},
+            user::User,
+        },
+    },
+    helper::config::Config,
+};
+
+/// An ordered collection of `ClaimsProvider`s with a size budget.
+///
+/// The pipeline runs each provider in registration order and merges their claims, with
+/// later providers overriding earlier ones on key collisions. Providers may not set any
+/// of the `RESERVED_CLAIMS`, and the serialized result must fit within `max_bytes` so
+/// tokens stay small enough to travel in cookies and headers.
+pub struct ClaimsPipeline {
+    providers: Vec<Box<dyn ClaimsProvider>>,
+    max_bytes: usize,
+}
+
+impl std::fmt::Debug for ClaimsPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClaimsPipeline")
+            .field(
+                "providers",
+                &self.providers.iter().map(|p| p.name()).collect::<Vec<_>>(),
+            )
+            .field("max_bytes", &self.max_bytes)
+            .finish()
+    }
+}
+
+impl ClaimsPipeline {
+    pub fn new(max_bytes: usize) -> ClaimsPipeline {
+        ClaimsPipeline {
+            providers: vec![],
+            max_bytes,
+        }
+    }
+
+    /// Builds the default pipeline: profile fields, roles and the static claims from configuration.
+    pub fn from_config(config: &Config) -> ClaimsPipeline {
+        ClaimsPipeline::new(config.access_token_claims_max_bytes)
+            .with_provider(ProfileClaimsProvider)
+            .with_provider(RoleClaimsProvider)
+            .with_provider(StaticClaimsProvider::new(
+                config.access_token_static_claims.clone(),
+            ))
+    }
+
+    pub fn with_provider(mut self, provider: impl ClaimsProvider) -> ClaimsPipeline {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Runs every provider for `user` and returns the merged claims.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaimsError::Reserved` if a provider tries to set a reserved claim,
+    /// `ClaimsError::BudgetExceeded` if the merged claims do not fit the size budget,
+    /// or the provider's own error if one of them fails.
+    pub fn enrich(&self, user: &User) -> Result<CustomClaims, ClaimsError> {
+        let mut merged = CustomClaims::default();
+
+        for provider in &self.providers {
+            for (name, value) in provider.claims(user)?.into_inner() {
+                if RESERVED_CLAIMS.contains(&name.as_str()) {
+                    return Err(ClaimsError::Reserved { name });
+                }
+                merged.insert(&name, value);
+            }
+        }
+
+        let size = serde_json::to_vec(&merged)
+            .map_err(|e| anyhow!(e).context("Failed to serialize custom claims"))?
+            .len();
+        if size > self.max_bytes {
+            return Err(ClaimsError::BudgetExceeded {
+                size,
+                budget: self.max_bytes,
+            });
+        }
+
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_enrich_merges_built_in_providers() {
+        let mut user = User::new("adrian@email.com", "password");
+        user.roles = vec!["admin".to_string()];
+
+        let mut static_claims = serde_json::Map::new();
+        static_claims.insert("tenant_id".to_string(), json!("acme"));
+
+        let pipeline = ClaimsPipeline::new(1024)
+            .with_provider(ProfileClaimsProvider)
+            .with_provider(RoleClaimsProvider)
+            .with_provider(StaticClaimsProvider::new(static_claims));
+
+        let claims = pipeline.enrich(&user).unwrap();
+
+        assert_eq!(claims.get("email"), Some(&json!("adrian@email.com")));
+        assert_eq!(claims.get("roles"), Some(&json!(["admin"])));
+        assert_eq!(claims.get("tenant_id"), Some(&json!("acme")));
+    }
+
+    #[test]
+    fn test_enrich_rejects_reserved_claims() {
+        let user = User::new("adrian@email.com", "password");
+
+        let mut static_claims = serde_json::Map::new();
+        static_claims.insert("sub".to_string(), json!("someone else"));
+
+        let pipeline =
+            ClaimsPipeline::new(1024).with_provider(StaticClaimsProvider::new(static_claims));
+
+        let result = pipeline.enrich(&user);
+
+        assert!(matches!(result, Err(ClaimsError::Reserved { .. })));
+    }
+
+    #[test]
+    fn test_enrich_enforces_size_budget() {
+        let user = User::new("adrian@email.com", "password");
+
+        let pipeline = ClaimsPipeline::new(8).with_provider(ProfileClaimsProvider);
+
+        let result = pipeline.enrich(&user);
+
+        assert!(matches!(result, Err(ClaimsError::BudgetExceeded { .. })));
+    }
+}
@@ -0,0 +1,23 @@
+use crate::domain::{
+    claims_provider::ClaimsProvider,
+    model::{
+        custom_claims::{ClaimsError, CustomClaims},
+        user::User,
+    },
+};
+
+/// Embeds the user's profile fields (currently the email address) into access tokens.
+#[derive(Debug, Default)]
+pub struct ProfileClaimsProvider;
+
+impl ClaimsProvider for ProfileClaimsProvider {
+    fn name(&self) -> &'static str {
+        "profile"
+    }
+
+    fn claims(&self, user: &User) -> Result<CustomClaims, ClaimsError> {
+        let mut claims = CustomClaims::default();
+        claims.insert("email", serde_json::json!(user.email));
+        Ok(claims)
+    }
+}
@@ -0,0 +1,23 @@
+use crate::domain::{
+    claims_provider::ClaimsProvider,
+    model::{
+        custom_claims::{ClaimsError, CustomClaims},
+        user::User,
+    },
+};
+
+/// Embeds the roles assigned to the user into access tokens under the `roles` claim.
+#[derive(Debug, Default)]
+pub struct RoleClaimsProvider;
+
+impl ClaimsProvider for RoleClaimsProvider {
+    fn name(&self) -> &'static str {
+        "roles"
+    }
+
+    fn claims(&self, user: &User) -> Result<CustomClaims, ClaimsError> {
+        let mut claims = CustomClaims::default();
+        claims.insert("roles", serde_json::json!(user.roles));
+        Ok(claims)
+    }
+}
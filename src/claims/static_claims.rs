@@ -0,0 +1,34 @@
+use serde_json::{Map, Value};
+
+use crate::domain::{
+    claims_provider::ClaimsProvider,
+    model::{
+        custom_claims::{ClaimsError, CustomClaims},
+        user::User,
+    },
+};
+
+/// Embeds a fixed set of claims taken from configuration into every access token.
+///
+/// This is intended for values such as a tenant id or feature flags that are the same
+/// for every token issued by a given deployment.
+#[derive(Debug, Default)]
+pub struct StaticClaimsProvider {
+    claims: Map<String, Value>,
+}
+
+impl StaticClaimsProvider {
+    pub fn new(claims: Map<String, Value>) -> StaticClaimsProvider {
+        StaticClaimsProvider { claims }
+    }
+}
+
+impl ClaimsProvider for StaticClaimsProvider {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    fn claims(&self, _user: &User) -> Result<CustomClaims, ClaimsError> {
+        Ok(CustomClaims::new(self.claims.clone()))
+    }
+}
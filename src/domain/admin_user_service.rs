@@ -0,0 +1,52 @@
+use crate::domain::model::{
+    admin_user::{
+        AdminUser, AdminUserError, DeleteUserRequest, ListUsersRequest, UpdateUserRequest, UserPage,
+    },
+    password_reset::PasswordResetResponse,
+    user_id::UserId,
+};
+
+use std::future::Future;
+
+/// Trait representing the administration of users by support staff.
+///
+/// Administrators can look users up, change their email or suspend them, make them choose a
+/// new password, sign them out everywhere, and delete them. Suspending a user, forcing a
+/// password reset and revoking sessions all invalidate the tokens the user already holds.
+///
+/// # Implementors
+///
+/// Any struct that implements the `AdminUserService` trait must be `Send`, `Sync`, and
+/// `'static`.
+pub trait AdminUserService: Send + Sync + 'static {
+    fn list_users(
+        &self,
+        request: &ListUsersRequest,
+    ) -> impl Future<Output = Result<UserPage, AdminUserError>> + Send;
+
+    fn fetch_user(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<AdminUser, AdminUserError>> + Send;
+
+    fn update_user(
+        &self,
+        request: &UpdateUserRequest,
+    ) -> impl Future<Output = Result<AdminUser, AdminUserError>> + Send;
+
+    /// Issues a password reset token for the user, who cannot log in until they use it.
+    fn force_password_reset(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<PasswordResetResponse, AdminUserError>> + Send;
+
+    fn revoke_sessions(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<(), AdminUserError>> + Send;
+
+    fn delete_user(
+        &self,
+        request: &DeleteUserRequest,
+    ) -> impl Future<Output = Result<(), AdminUserError>> + Send;
+}
@@ -0,0 +1,44 @@
+use crate::domain::model::{
+    admin_audit::AdminAuditEntry,
+    audit::{AuditChainReport, AuditError, AuditEvent, AuditEventPage, ListAuditEventsRequest},
+    user_id::UserId,
+};
+
+use std::future::Future;
+
+/// Trait representing the security audit log, as read by administrators and users.
+///
+/// The log records registrations, logins and failed login attempts, refreshes, logouts,
+/// password changes and resets, lockouts and the requests made by administrators, each with
+/// the IP address, user agent and request id they came from. Administrators can search all
+/// of it, while users only see the recent events about their own account.
+///
+/// The log is a hash chain with signed checkpoints, which administrators can verify to prove
+/// that no event was edited or deleted.
+///
+/// # Implementors
+///
+/// Any struct that implements the `AuditService` trait must be `Send`, `Sync`, and `'static`.
+pub trait AuditService: Send + Sync + 'static {
+    fn list_audit_events(
+        &self,
+        request: &ListAuditEventsRequest,
+    ) -> impl Future<Output = Result<AuditEventPage, AuditError>> + Send;
+
+    /// Lists the most recent events about `user_id`, newest first.
+    fn list_recent_activity(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<Vec<AuditEvent>, AuditError>> + Send;
+
+    /// Adds a request made by an administrator to the audit log.
+    fn record_admin_action(
+        &self,
+        entry: &AdminAuditEntry,
+    ) -> impl Future<Output = Result<(), AuditError>> + Send;
+
+    /// Walks the audit log chain from its first event, reporting the first broken link.
+    fn verify_audit_chain(
+        &self,
+    ) -> impl Future<Output = Result<AuditChainReport, AuditError>> + Send;
+}
@@ -1,9 +1,11 @@
 use crate::domain::model::{
     auth::{AuthRequest, AuthorizationError},
     auth_middleware::AuthMiddleware,
+    change_password::{ChangePasswordError, ChangePasswordRequest},
     login_response::LoginResponse,
     login_user::{LoginUserError, LoginUserRequest},
     logout::{LogoutRequest, LogoutResponse},
+    password_reset::{ResetPasswordError, ResetPasswordRequest},
     refresh_token::{RefreshRequest, RefreshResponse, RefreshTokenError},
     register_user::{RegisterUserError, RegisterUserRequest},
     user::FilteredUser,
@@ -14,7 +16,8 @@ use std::future::Future;
 /// Trait representing authentication services in the application.
 ///
 /// The `AuthService` trait defines the necessary methods for user registration, login,
-/// authentication, logout, and token refreshing. Implementations of this trait
+/// authentication, logout, token refreshing, and changing a password or resetting it with a
+/// reset token. Implementations of this trait
 /// provide the actual logic for handling these operations, which can involve interactions
 /// with databases, caches, and other services.
 ///
@@ -48,4 +51,14 @@ pub trait AuthService: Send + Sync + 'static {
         &self,
         request: &RefreshRequest,
     ) -> impl Future<Output = Result<RefreshResponse, RefreshTokenError>> + Send;
+
+    fn change_password(
+        &self,
+        request: &ChangePasswordRequest,
+    ) -> impl Future<Output = Result<(), ChangePasswordError>> + Send;
+
+    fn reset_password(
+        &self,
+        request: &ResetPasswordRequest,
+    ) -> impl Future<Output = Result<(), ResetPasswordError>> + Send;
 }
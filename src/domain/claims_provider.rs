@@ -0,0 +1,22 @@
+use crate::domain::model::{
+    custom_claims::{ClaimsError, CustomClaims},
+    user::User,
+};
+
+/// Trait representing a source of custom claims for access tokens.
+///
+/// The `ClaimsProvider` trait lets downstream data (profile fields, roles, static
+/// configuration and so on) be embedded into access tokens so that consumers do not
+/// have to call back into the service. `Service` runs every registered provider before
+/// calling `generate_jwt` and merges their output into the token claims.
+///
+/// # Implementors
+///
+/// Any struct that implements the `ClaimsProvider` trait must be `Send`, `Sync`, and `'static`.
+/// Providers are stored as trait objects, so the trait is kept synchronous and receives
+/// the already fetched `User`.
+pub trait ClaimsProvider: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    fn claims(&self, user: &User) -> Result<CustomClaims, ClaimsError>;
+}
@@ -0,0 +1,27 @@
+use crate::domain::model::federation::{
+    FederatedCallbackRequest, FederatedCallbackResponse, FederatedLoginRequest,
+    FederatedLoginResponse, FederationError,
+};
+
+use std::future::Future;
+
+/// Trait representing sign in through external OpenID Connect identity providers.
+///
+/// `federated_login` sends the user to a configured provider, and `federated_callback`
+/// validates the provider's answer, links the provider account to a user and starts a login
+/// session exactly like `AuthService::login` does for passwords.
+///
+/// # Implementors
+///
+/// Any struct that implements the `FederationService` trait must be `Send`, `Sync`, and `'static`.
+pub trait FederationService: Send + Sync + 'static {
+    fn federated_login(
+        &self,
+        request: &FederatedLoginRequest,
+    ) -> impl Future<Output = Result<FederatedLoginResponse, FederationError>> + Send;
+
+    fn federated_callback(
+        &self,
+        request: &FederatedCallbackRequest,
+    ) -> impl Future<Output = Result<FederatedCallbackResponse, FederationError>> + Send;
+}
@@ -0,0 +1,24 @@
+use crate::domain::model::{
+    auth::{AuthRequest, AuthorizationError},
+    forward_auth::ForwardAuthResponse,
+};
+
+use std::future::Future;
+
+/// Trait representing forward authentication for reverse proxies, such as nginx
+/// `auth_request`, Traefik `forwardAuth` and Envoy `ext_authz`.
+///
+/// The proxy asks whether the access token of a request is valid, exactly as
+/// `AuthService::auth` decides it, and copies the identity headers of the answer onto the
+/// request it forwards to the application.
+///
+/// # Implementors
+///
+/// Any struct that implements the `ForwardAuthService` trait must be `Send`, `Sync`, and
+/// `'static`.
+pub trait ForwardAuthService: Send + Sync + 'static {
+    fn forward_auth(
+        &self,
+        request: &AuthRequest,
+    ) -> impl Future<Output = Result<ForwardAuthResponse, AuthorizationError>> + Send;
+}
@@ -0,0 +1,30 @@
+use crate::domain::model::{
+    impersonation::{ImpersonationError, ImpersonationResponse, StartImpersonationRequest},
+    token_uuid::TokenUuid,
+};
+
+use std::future::Future;
+
+/// Trait representing administrators acting as other users, to see what they see.
+///
+/// Impersonation tokens are short-lived access tokens for the impersonated user that name the
+/// administrator in their `act` claim, as in RFC 8693 token exchange, so every request made
+/// with them can be traced back. They come without a refresh token and cannot be used to
+/// change credentials or to obtain other tokens.
+///
+/// # Implementors
+///
+/// Any struct that implements the `ImpersonationService` trait must be `Send`, `Sync`, and
+/// `'static`.
+pub trait ImpersonationService: Send + Sync + 'static {
+    fn start_impersonation(
+        &self,
+        request: &StartImpersonationRequest,
+    ) -> impl Future<Output = Result<ImpersonationResponse, ImpersonationError>> + Send;
+
+    /// Revokes the impersonation token identified by `access_token_uuid`.
+    fn end_impersonation(
+        &self,
+        access_token_uuid: &TokenUuid,
+    ) -> impl Future<Output = Result<(), ImpersonationError>> + Send;
+}
@@ -1,3 +1,17 @@
+pub mod admin_user_service;
+pub mod audit_service;
 pub mod auth_service;
+pub mod claims_provider;
+pub mod federation_service;
+pub mod forward_auth_service;
+pub mod impersonation_service;
 pub mod model;
+pub mod oauth_service;
+pub mod oidc_service;
+pub mod organization_service;
+pub mod personal_access_token_service;
+pub mod registration_invitation_service;
 pub mod repositories;
+pub mod saml_service;
+pub mod service_account_service;
+pub mod webhook_service;
@@ -0,0 +1,17 @@
+use super::audit::RequestContext;
+
+/// A request made by an administrator, as recorded in the audit log.
+///
+/// `route` is the matched route template, such as `/api/admin/users/:user_id`, so entries
+/// can be grouped by action, and `path` the actual path, which names the target. Requests
+/// made while impersonating a user have that user as `subject_id`.
+#[derive(Debug)]
+pub struct AdminAuditEntry {
+    pub actor_id: uuid::Uuid,
+    pub subject_id: Option<uuid::Uuid>,
+    pub method: String,
+    pub route: String,
+    pub path: String,
+    pub status: u16,
+    pub context: RequestContext,
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::{
+    auth_repo_errors::AuthRepositoryError,
+    user::{User, UserKind, UserStatus},
+    user_email::UserEmail,
+    user_id::UserId,
+};
+
+/// Users listed per page unless the request asks for another page size.
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+
+/// Most users listed per page.
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// A user as shown to administrators, with the account state hidden from `FilteredUser`.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AdminUser {
+    pub id: uuid::Uuid,
+    pub email: String,
+    pub email_verified: bool,
+    pub kind: UserKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<uuid::Uuid>,
+    pub roles: Vec<String>,
+    pub status: UserStatus,
+    pub password_reset_required: bool,
+    pub sessions_revoked_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<&User> for AdminUser {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            email: user.email.to_string(),
+            email_verified: user.email_verified,
+            kind: user.kind,
+            owner_id: user.owner_id,
+            roles: user.roles.clone(),
+            status: user.status,
+            password_reset_required: user.password_reset_required,
+            sessions_revoked_at: user.sessions_revoked_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+/// A page of users, optionally narrowed down by the filters. `page` starts at 1 and `email`
+/// matches any part of the address, case insensitively.
+#[derive(Debug)]
+pub struct ListUsersRequest {
+    pub page: i64,
+    pub per_page: i64,
+    pub email: Option<String>,
+    pub status: Option<UserStatus>,
+    pub kind: Option<UserKind>,
+    pub role: Option<String>,
+}
+
+impl ListUsersRequest {
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserPage {
+    pub users: Vec<AdminUser>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+/// A request from the administrator `actor_id` to change the email or status of `user_id`.
+#[derive(Debug)]
+pub struct UpdateUserRequest {
+    pub actor_id: UserId,
+    pub user_id: UserId,
+    pub email: Option<UserEmail>,
+    pub status: Option<UserStatus>,
+}
+
+/// A request from the administrator `actor_id` to delete `user_id`.
+#[derive(Debug)]
+pub struct DeleteUserRequest {
+    pub actor_id: UserId,
+    pub user_id: UserId,
+}
+
+#[derive(Debug, Error)]
+pub enum AdminUserError {
+    #[error("Invalid user administration request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("user with email {email} already exists")]
+    Duplicate { email: String },
+    #[error("User not found")]
+    NotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for AdminUserError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => AdminUserError::NotFound,
+            AuthRepositoryError::Duplicate { email } => AdminUserError::Duplicate {
+                email: email.to_string(),
+            },
+            e => AdminUserError::Unknown(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_users_request_offset() {
+        let request = ListUsersRequest {
+            page: 3,
+            per_page: DEFAULT_PAGE_SIZE,
+            email: None,
+            status: None,
+            kind: None,
+            role: None,
+        };
+
+        assert_eq!(request.offset(), 100);
+    }
+}
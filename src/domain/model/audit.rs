@@ -0,0 +1,611 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::api::utils::security::hash_token;
+
+use super::auth_repo_errors::AuthRepositoryError;
+
+/// Events listed per page unless the request asks for another page size.
+pub const DEFAULT_AUDIT_PAGE_SIZE: i64 = 50;
+
+/// Most events listed per page.
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 200;
+
+/// Events shown to a user as their recent activity.
+pub const RECENT_ACTIVITY_LIMIT: i64 = 50;
+
+/// Longest user agent kept with an event, matching the `user_agent` column.
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Events read at a time while verifying the chain.
+pub const AUDIT_CHAIN_PAGE_SIZE: i64 = 1000;
+
+/// What happened in a security audit event.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventType {
+    Register,
+    Login,
+    Refresh,
+    Logout,
+    PasswordChange,
+    PasswordReset,
+    Lockout,
+    AdminAction,
+}
+
+impl AuditEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventType::Register => "register",
+            AuditEventType::Login => "login",
+            AuditEventType::Refresh => "refresh",
+            AuditEventType::Logout => "logout",
+            AuditEventType::PasswordChange => "password_change",
+            AuditEventType::PasswordReset => "password_reset",
+            AuditEventType::Lockout => "lockout",
+            AuditEventType::AdminAction => "admin_action",
+        }
+    }
+}
+
+/// The `event_type` column only holds the values written by `as_str`.
+impl From<String> for AuditEventType {
+    fn from(event_type: String) -> Self {
+        match event_type.as_str() {
+            "register" => AuditEventType::Register,
+            "login" => AuditEventType::Login,
+            "refresh" => AuditEventType::Refresh,
+            "logout" => AuditEventType::Logout,
+            "password_change" => AuditEventType::PasswordChange,
+            "password_reset" => AuditEventType::PasswordReset,
+            "lockout" => AuditEventType::Lockout,
+            _ => AuditEventType::AdminAction,
+        }
+    }
+}
+
+/// Whether the audited action succeeded.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+impl AuditOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+        }
+    }
+}
+
+/// The `outcome` column is constrained to `success` and `failure`.
+impl From<String> for AuditOutcome {
+    fn from(outcome: String) -> Self {
+        match outcome.as_str() {
+            "success" => AuditOutcome::Success,
+            _ => AuditOutcome::Failure,
+        }
+    }
+}
+
+/// Where a request came from, recorded with the events it causes.
+///
+/// `ip_address` is the address of the peer the request was received from, and `request_id`
+/// the `x-request-id` of the request, which is generated when the client did not send one.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RequestContext {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// An event to be added to the security audit log.
+///
+/// `user_id` is the user the event is about, when known, and `actor_id` the administrator who
+/// caused it, if it was not the user. `reason` says why a failed action failed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewAuditEvent {
+    pub event_type: AuditEventType,
+    pub outcome: AuditOutcome,
+    pub reason: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+    pub actor_id: Option<uuid::Uuid>,
+    pub context: RequestContext,
+    pub details: serde_json::Value,
+}
+
+impl NewAuditEvent {
+    pub fn success(event_type: AuditEventType, context: &RequestContext) -> NewAuditEvent {
+        NewAuditEvent {
+            event_type,
+            outcome: AuditOutcome::Success,
+            reason: None,
+            user_id: None,
+            actor_id: None,
+            context: context.clone(),
+            details: serde_json::json!({}),
+        }
+    }
+
+    pub fn failure(
+        event_type: AuditEventType,
+        reason: &str,
+        context: &RequestContext,
+    ) -> NewAuditEvent {
+        NewAuditEvent {
+            outcome: AuditOutcome::Failure,
+            reason: Some(reason.to_string()),
+            ..NewAuditEvent::success(event_type, context)
+        }
+    }
+
+    pub fn with_user(self, user_id: Option<uuid::Uuid>) -> NewAuditEvent {
+        NewAuditEvent { user_id, ..self }
+    }
+
+    pub fn with_actor(self, actor_id: Option<uuid::Uuid>) -> NewAuditEvent {
+        NewAuditEvent { actor_id, ..self }
+    }
+
+    pub fn with_details(self, details: serde_json::Value) -> NewAuditEvent {
+        NewAuditEvent { details, ..self }
+    }
+
+    /// The user agent as stored, cut down to the length of its column.
+    pub fn user_agent(&self) -> Option<String> {
+        self.context
+            .user_agent
+            .as_ref()
+            .map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect())
+    }
+}
+
+/// An event of the security audit log, as stored.
+///
+/// Events are numbered by `sequence` and chained: each one carries the `hash` of the event
+/// before it as `previous_hash`, so editing or deleting an event breaks the link to the next
+/// one. Events recorded before the chain existed have no hashes.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct AuditEvent {
+    pub id: uuid::Uuid,
+    pub sequence: i64,
+    pub previous_hash: Option<String>,
+    pub hash: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub event_type: AuditEventType,
+    #[sqlx(try_from = "String")]
+    pub outcome: AuditOutcome,
+    pub reason: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+    pub actor_id: Option<uuid::Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    /// Stores `event` as number `sequence` of the chain, after the event hashed to
+    /// `previous_hash`.
+    ///
+    /// `created_at` is cut down to the microseconds Postgres keeps, so the hash still matches
+    /// once the event is read back.
+    pub fn chain(
+        event: &NewAuditEvent,
+        sequence: i64,
+        previous_hash: Option<String>,
+    ) -> AuditEvent {
+        let mut chained = AuditEvent {
+            id: uuid::Uuid::new_v4(),
+            sequence,
+            previous_hash,
+            hash: None,
+            event_type: event.event_type,
+            outcome: event.outcome,
+            reason: event.reason.clone(),
+            user_id: event.user_id,
+            actor_id: event.actor_id,
+            ip_address: event.context.ip_address.clone(),
+            user_agent: event.user_agent(),
+            request_id: event.context.request_id.clone(),
+            details: event.details.clone(),
+            created_at: Utc::now().trunc_subsecs(6),
+        };
+        chained.hash = Some(chained.chain_hash());
+        chained
+    }
+
+    /// The SHA-256 of every column of the event but `hash` itself, in a fixed order.
+    ///
+    /// `details` serializes with its keys sorted, whatever order JSONB gives them back in.
+    pub fn chain_hash(&self) -> String {
+        let content = serde_json::json!([
+            self.id,
+            self.sequence,
+            self.previous_hash,
+            self.event_type,
+            self.outcome,
+            self.reason,
+            self.user_id,
+            self.actor_id,
+            self.ip_address,
+            self.user_agent,
+            self.request_id,
+            self.details,
+            self.created_at.timestamp_micros(),
+        ]);
+        hash_token(&content.to_string())
+    }
+}
+
+/// The hash of the chain as of event `sequence`, signed with the access token key so the
+/// chain cannot be recomputed after rows are edited.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, sqlx::FromRow)]
+pub struct AuditCheckpoint {
+    pub sequence: i64,
+    pub hash: String,
+    pub signature: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// The claims of a checkpoint signature, a JWT without expiry.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AuditCheckpointClaims {
+    pub sequence: i64,
+    pub hash: String,
+    pub iat: i64,
+}
+
+/// Where the chain first stops adding up, and why.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct BrokenAuditLink {
+    pub sequence: i64,
+    pub reason: String,
+}
+
+impl BrokenAuditLink {
+    pub fn new(sequence: i64, reason: &str) -> BrokenAuditLink {
+        BrokenAuditLink {
+            sequence,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// The outcome of walking the chain. Verification stops at the first broken link.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditChainReport {
+    pub intact: bool,
+    pub events_checked: i64,
+    pub checkpoints_checked: i64,
+    pub first_broken_link: Option<BrokenAuditLink>,
+}
+
+/// Walks the audit log in order of `sequence`, checking each event against the one before it
+/// and against the checkpoint taken at it.
+///
+/// Deleted events leave a gap in the sequence, and edited ones no longer match their hash or
+/// the `previous_hash` of the next event. Events deleted from the end of the log are only
+/// caught by a checkpoint taken after them, and a chain recomputed after an edit by the
+/// checkpoints that follow it, which is why the checkpoints are signed. Their signatures are
+/// verified before they are given to the verifier.
+#[derive(Debug, Default)]
+pub struct AuditChainVerifier {
+    checkpoints: BTreeMap<i64, String>,
+    previous: Option<(i64, Option<String>)>,
+    events_checked: i64,
+    checkpoints_checked: i64,
+}
+
+impl AuditChainVerifier {
+    pub fn new(checkpoints: &[AuditCheckpoint]) -> AuditChainVerifier {
+        AuditChainVerifier {
+            checkpoints: checkpoints
+                .iter()
+                .map(|checkpoint| (checkpoint.sequence, checkpoint.hash.clone()))
+                .collect(),
+            ..AuditChainVerifier::default()
+        }
+    }
+
+    pub fn check(&mut self, event: &AuditEvent) -> Result<(), BrokenAuditLink> {
+        self.events_checked += 1;
+
+        let (expected_sequence, previous_hash) = match &self.previous {
+            Some((sequence, hash)) => (sequence + 1, hash.clone()),
+            None => (1, None),
+        };
+        if event.sequence != expected_sequence {
+            return Err(BrokenAuditLink::new(
+                event.sequence,
+                &format!(
+                    "Events {} to {} are missing",
+                    expected_sequence,
+                    event.sequence - 1
+                ),
+            ));
+        }
+
+        match &event.hash {
+            None if previous_hash.is_some() => {
+                return Err(BrokenAuditLink::new(
+                    event.sequence,
+                    "Event is missing from the chain",
+                ))
+            }
+            None => {}
+            Some(hash) => {
+                if event.previous_hash != previous_hash {
+                    return Err(BrokenAuditLink::new(
+                        event.sequence,
+                        "Previous hash does not match the event before",
+                    ));
+                }
+                if &event.chain_hash() != hash {
+                    return Err(BrokenAuditLink::new(
+                        event.sequence,
+                        "Hash does not match the contents of the event",
+                    ));
+                }
+            }
+        }
+
+        if let Some(checkpoint_hash) = self.checkpoints.get(&event.sequence) {
+            self.checkpoints_checked += 1;
+            if event.hash.as_ref() != Some(checkpoint_hash) {
+                return Err(BrokenAuditLink::new(
+                    event.sequence,
+                    "Event does not match its signed checkpoint",
+                ));
+            }
+        }
+
+        self.previous = Some((event.sequence, event.hash.clone()));
+        Ok(())
+    }
+
+    /// Reports on the events checked so far, failing if a checkpoint was taken after the last
+    /// of them.
+    pub fn finish(self, broken_link: Option<BrokenAuditLink>) -> AuditChainReport {
+        let last_sequence = self.previous.as_ref().map_or(0, |(sequence, _)| *sequence);
+        let broken_link = broken_link.or_else(|| {
+            self.checkpoints
+                .range(last_sequence + 1..)
+                .next()
+                .map(|(sequence, _)| {
+                    BrokenAuditLink::new(*sequence, "Checkpointed event is missing")
+                })
+        });
+
+        AuditChainReport {
+            intact: broken_link.is_none(),
+            events_checked: self.events_checked,
+            checkpoints_checked: self.checkpoints_checked,
+            first_broken_link: broken_link,
+        }
+    }
+}
+
+/// A page of audit events, newest first, optionally narrowed down by the filters. `page`
+/// starts at 1, and `since` and `until` bound `created_at`, inclusively and exclusively.
+#[derive(Debug, Default)]
+pub struct ListAuditEventsRequest {
+    pub page: i64,
+    pub per_page: i64,
+    pub user_id: Option<uuid::Uuid>,
+    pub actor_id: Option<uuid::Uuid>,
+    pub event_type: Option<AuditEventType>,
+    pub outcome: Option<AuditOutcome>,
+    pub ip_address: Option<String>,
+    pub request_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ListAuditEventsRequest {
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditEventPage {
+    pub events: Vec<AuditEvent>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("Invalid audit log request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for AuditError {
+    fn from(value: AuthRepositoryError) -> Self {
+        AuditError::Unknown(value.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_event_type_round_trips_through_its_column() {
+        for event_type in [
+            AuditEventType::Register,
+            AuditEventType::Login,
+            AuditEventType::Refresh,
+            AuditEventType::Logout,
+            AuditEventType::PasswordChange,
+            AuditEventType::PasswordReset,
+            AuditEventType::Lockout,
+            AuditEventType::AdminAction,
+        ] {
+            assert_eq!(
+                AuditEventType::from(event_type.as_str().to_string()),
+                event_type
+            );
+            assert_eq!(
+                serde_json::json!(event_type),
+                serde_json::json!(event_type.as_str())
+            );
+        }
+    }
+
+    fn chain(length: i64) -> Vec<AuditEvent> {
+        let mut events: Vec<AuditEvent> = vec![];
+        for sequence in 1..=length {
+            let event = NewAuditEvent::success(AuditEventType::Login, &RequestContext::default())
+                .with_details(
+                    serde_json::json!({ "email": "adrian@email.com", "attempt": sequence }),
+                );
+            let previous_hash = events.last().and_then(|event| event.hash.clone());
+            events.push(AuditEvent::chain(&event, sequence, previous_hash));
+        }
+        events
+    }
+
+    fn verify(events: &[AuditEvent], checkpoints: &[AuditCheckpoint]) -> AuditChainReport {
+        let mut verifier = AuditChainVerifier::new(checkpoints);
+        let broken_link = events.iter().find_map(|event| verifier.check(event).err());
+        verifier.finish(broken_link)
+    }
+
+    fn checkpoint(event: &AuditEvent) -> AuditCheckpoint {
+        AuditCheckpoint {
+            sequence: event.sequence,
+            hash: event.hash.clone().unwrap(),
+            signature: String::new(),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_audit_chain_intact() {
+        let events = chain(5);
+
+        let report = verify(&events, &[checkpoint(&events[3])]);
+
+        assert!(report.intact);
+        assert_eq!(report.events_checked, 5);
+        assert_eq!(report.checkpoints_checked, 1);
+    }
+
+    #[test]
+    fn test_audit_chain_detects_deleted_event() {
+        let mut events = chain(5);
+        events.remove(2);
+
+        let report = verify(&events, &[]);
+
+        assert_eq!(
+            report.first_broken_link,
+            Some(BrokenAuditLink::new(4, "Events 3 to 3 are missing"))
+        );
+    }
+
+    #[test]
+    fn test_audit_chain_detects_modified_event() {
+        let mut events = chain(5);
+        events[1].details = serde_json::json!({ "email": "someone@else.com" });
+
+        let report = verify(&events, &[]);
+
+        assert!(!report.intact);
+        assert_eq!(
+            report.first_broken_link,
+            Some(BrokenAuditLink::new(
+                2,
+                "Hash does not match the contents of the event"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_audit_chain_detects_rehashed_event_at_next_link() {
+        let mut events = chain(5);
+        events[1].reason = Some("Edited".to_string());
+        events[1].hash = Some(events[1].chain_hash());
+
+        let report = verify(&events, &[]);
+
+        assert_eq!(report.first_broken_link.unwrap().sequence, 3);
+    }
+
+    #[test]
+    fn test_audit_chain_detects_recomputed_chain_at_checkpoint() {
+        let original = chain(5);
+        let checkpoints = [checkpoint(&original[3])];
+        let mut events = original.clone();
+        events.remove(1);
+        for (index, event) in events.iter_mut().enumerate() {
+            event.sequence = index as i64 + 1;
+        }
+        for index in 1..events.len() {
+            events[index].previous_hash = events[index - 1].hash.clone();
+            events[index].hash = Some(events[index].chain_hash());
+        }
+
+        let report = verify(&events, &checkpoints);
+
+        assert_eq!(
+            report.first_broken_link,
+            Some(BrokenAuditLink::new(
+                4,
+                "Event does not match its signed checkpoint"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_audit_chain_detects_deleted_tail_at_checkpoint() {
+        let events = chain(5);
+
+        let report = verify(&events[..3], &[checkpoint(&events[4])]);
+
+        assert_eq!(report.first_broken_link.unwrap().sequence, 5);
+    }
+
+    #[test]
+    fn test_audit_chain_accepts_unchained_events_before_the_chain() {
+        let mut events = chain(3);
+        events[0].hash = None;
+        events[1].previous_hash = None;
+        events[1].hash = Some(events[1].chain_hash());
+        events[2].previous_hash = events[1].hash.clone();
+        events[2].hash = Some(events[2].chain_hash());
+
+        assert!(verify(&events, &[]).intact);
+
+        events[2].hash = None;
+
+        assert_eq!(verify(&events, &[]).first_broken_link.unwrap().sequence, 3);
+    }
+
+    #[test]
+    fn test_new_audit_event_truncates_user_agent() {
+        let context = RequestContext {
+            user_agent: Some("a".repeat(600)),
+            ..RequestContext::default()
+        };
+
+        let event = NewAuditEvent::failure(AuditEventType::Login, "Invalid", &context);
+
+        assert_eq!(event.outcome, AuditOutcome::Failure);
+        assert_eq!(event.user_agent().unwrap().len(), MAX_USER_AGENT_LENGTH);
+    }
+}
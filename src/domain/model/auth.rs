@@ -27,8 +27,12 @@ impl AccessToken {
 
 #[derive(Debug, Error)]
 pub enum AuthorizationError {
+    #[error("You are not logged in")]
+    MissingCredentials,
     #[error("Authorization error: {reason}")]
     InvalidCredentials { reason: String },
+    #[error("Access token expired")]
+    TokenExpired,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
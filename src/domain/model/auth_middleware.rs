@@ -1,17 +1,77 @@
-use crate::domain::model::user::User;
+use crate::domain::model::{
+    custom_claims::CustomClaims,
+    impersonation::Actor,
+    principal::{Principal, PrincipalType},
+    scope::Scopes,
+    user::User,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AuthMiddleware {
-    pub user: User,
+    pub principal: Principal,
     pub access_token_uuid: uuid::Uuid,
+    pub claims: CustomClaims,
+    /// The `exp` of the access token, when the request was authenticated by one.
+    pub expires_at: Option<i64>,
 }
 
 impl AuthMiddleware {
-    pub fn new(user: User, access_token_uuid: uuid::Uuid) -> AuthMiddleware {
+    pub fn new(
+        principal: Principal,
+        access_token_uuid: uuid::Uuid,
+        claims: CustomClaims,
+    ) -> AuthMiddleware {
         AuthMiddleware {
-            user,
+            principal,
             access_token_uuid,
+            claims,
+            expires_at: None,
         }
     }
+
+    pub fn with_expiry(self, expires_at: Option<i64>) -> AuthMiddleware {
+        AuthMiddleware { expires_at, ..self }
+    }
+
+    pub fn principal_type(&self) -> PrincipalType {
+        self.principal.principal_type()
+    }
+
+    /// The authenticated user, or `None` when the token belongs to an OAuth client.
+    pub fn user(&self) -> Option<&User> {
+        self.principal.user()
+    }
+
+    /// Id of the user or client the token was issued for, its `sub`.
+    pub fn subject(&self) -> uuid::Uuid {
+        self.principal.id()
+    }
+
+    /// Id of the administrator acting as the subject, when the token was issued to
+    /// impersonate a user.
+    pub fn actor(&self) -> Option<uuid::Uuid> {
+        Actor::from_claims(&self.claims).map(|actor| actor.sub)
+    }
+
+    pub fn is_impersonated(&self) -> bool {
+        self.actor().is_some()
+    }
+
+    /// Whether the request was authenticated by a user's own login session, rather than by a
+    /// scoped OAuth access token or personal access token acting on the user's behalf.
+    pub fn is_login_session(&self) -> bool {
+        self.user().is_some() && self.claims.get("scope").is_none()
+    }
+
+    /// Whether the request may act within `scope`: always for a login session, otherwise only
+    /// when the token was granted it.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.is_login_session()
+            || self
+                .claims
+                .get("scope")
+                .and_then(|scopes| scopes.as_str())
+                .is_some_and(|scopes| Scopes::parse(scopes).contains(scope))
+    }
 }
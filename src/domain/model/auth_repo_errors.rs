@@ -10,6 +10,8 @@ pub enum AuthRepositoryError {
     Database { reason: String },
     #[error("Authorization error: {reason}")]
     InvalidCredentials { reason: String },
+    #[error("Conflict: {reason}")]
+    Conflict { reason: String },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::custom_claims::CustomClaims;
+
+/// `acr` value for sessions started with an email and password.
+pub const PASSWORD_ACR: &str = "urn:authentication_service:acr:password";
+
+/// `acr` value for sessions started by signing in with an external identity provider.
+pub const FEDERATED_ACR: &str = "urn:authentication_service:acr:federated";
+
+/// How and when the user behind a session authenticated.
+///
+/// The context is recorded at login and travels in the `auth_time`, `amr` and `acr` claims
+/// of the session's access and refresh tokens, so it survives token refreshes. OpenID Connect
+/// uses it to enforce `max_age` and to populate the same claims in ID tokens.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AuthenticationContext {
+    pub auth_time: i64,
+    pub amr: Vec<String>,
+    pub acr: String,
+}
+
+impl AuthenticationContext {
+    /// A context for a user that just authenticated with their password.
+    pub fn password() -> AuthenticationContext {
+        AuthenticationContext {
+            auth_time: chrono::Utc::now().timestamp(),
+            amr: vec!["pwd".to_string()],
+            acr: PASSWORD_ACR.to_string(),
+        }
+    }
+
+    /// A context for a user that just signed in with an external identity provider, keeping
+    /// the `amr` values the provider reported, if any.
+    pub fn federated(amr: &[String]) -> AuthenticationContext {
+        AuthenticationContext {
+            auth_time: chrono::Utc::now().timestamp(),
+            amr: amr.to_vec(),
+            acr: FEDERATED_ACR.to_string(),
+        }
+    }
+
+    /// Reads the context back from token claims. Tokens issued before the context was
+    /// recorded, and tokens issued to clients, have none.
+    pub fn from_claims(claims: &CustomClaims) -> Option<AuthenticationContext> {
+        Some(AuthenticationContext {
+            auth_time: claims.get("auth_time")?.as_i64()?,
+            amr: serde_json::from_value(claims.get("amr")?.clone()).ok()?,
+            acr: claims.get("acr")?.as_str()?.to_string(),
+        })
+    }
+
+    pub fn insert_into(&self, claims: &mut CustomClaims) {
+        claims.insert("auth_time", json!(self.auth_time));
+        claims.insert("amr", json!(self.amr));
+        claims.insert("acr", json!(self.acr));
+    }
+
+    /// Seconds elapsed since the user authenticated.
+    pub fn age(&self) -> i64 {
+        chrono::Utc::now().timestamp() - self.auth_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_round_trips_through_claims() {
+        let context = AuthenticationContext::password();
+        let mut claims = CustomClaims::default();
+
+        context.insert_into(&mut claims);
+
+        assert_eq!(AuthenticationContext::from_claims(&claims), Some(context));
+        assert_eq!(
+            AuthenticationContext::from_claims(&CustomClaims::default()),
+            None
+        );
+    }
+}
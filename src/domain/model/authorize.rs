@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    authentication_context::AuthenticationContext, oauth_errors::OAuthError, scope::Scopes,
+};
+
+/// The user's existing session, if the authorization request carried a valid access token.
+#[derive(Debug)]
+pub struct AuthorizeSession {
+    pub user_id: uuid::Uuid,
+    pub context: Option<AuthenticationContext>,
+}
+
+#[derive(Debug)]
+pub struct AuthorizeRequest {
+    pub session: Option<AuthorizeSession>,
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: Option<String>,
+    pub scope: Scopes,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>,
+    pub prompt: Option<String>,
+    pub max_age: Option<i64>,
+}
+
+/// A successful authorization, to be delivered to the client by redirecting the user agent.
+#[derive(Debug)]
+pub struct AuthorizeResponse {
+    pub redirect_uri: String,
+    pub code: String,
+    pub state: Option<String>,
+}
+
+impl AuthorizeResponse {
+    pub fn location(&self) -> String {
+        let mut params = vec![("code", self.code.as_str())];
+        if let Some(state) = &self.state {
+            params.push(("state", state));
+        }
+        append_query(&self.redirect_uri, &params)
+    }
+}
+
+/// A failed authorization.
+///
+/// When the client and redirect URI could not be verified `redirect_uri` is `None`, and the
+/// error must be shown to the user instead of being sent back to the client, as required by
+/// RFC 6749 section 4.1.2.1.
+///
+/// When the user has to log in first, `login_uri` points to the configured login page,
+/// which sends the user back to the authorization endpoint afterwards.
+#[derive(Debug)]
+pub struct AuthorizeError {
+    pub error: OAuthError,
+    pub redirect_uri: Option<String>,
+    pub state: Option<String>,
+    pub login_uri: Option<String>,
+}
+
+impl AuthorizeError {
+    pub fn new(error: OAuthError) -> AuthorizeError {
+        AuthorizeError {
+            error,
+            redirect_uri: None,
+            state: None,
+            login_uri: None,
+        }
+    }
+
+    pub fn login_required(login_uri: Option<&str>) -> AuthorizeError {
+        AuthorizeError {
+            login_uri: login_uri.map(|uri| uri.to_string()),
+            ..AuthorizeError::new(OAuthError::LoginRequired {
+                description: "User must log in to continue".to_string(),
+            })
+        }
+    }
+
+    pub fn redirect(error: OAuthError, redirect_uri: &str, state: Option<&str>) -> AuthorizeError {
+        AuthorizeError {
+            error,
+            redirect_uri: Some(redirect_uri.to_string()),
+            state: state.map(|s| s.to_string()),
+            login_uri: None,
+        }
+    }
+
+    pub fn location(&self) -> Option<String> {
+        let redirect_uri = self.redirect_uri.as_ref()?;
+        let description = self.error.description();
+        let mut params = vec![
+            ("error", self.error.code()),
+            ("error_description", description.as_str()),
+        ];
+        if let Some(state) = &self.state {
+            params.push(("state", state));
+        }
+        Some(append_query(redirect_uri, &params))
+    }
+
+    /// The login page location, carrying `return_to` so the user resumes the authorization
+    /// request once logged in.
+    pub fn login_location(&self, return_to: &str) -> Option<String> {
+        let login_uri = self.login_uri.as_ref()?;
+        Some(append_query(login_uri, &[("return_to", return_to)]))
+    }
+}
+
+/// The data stored in the cache for an issued authorization code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthorizationCode {
+    pub code: String,
+    pub client_id: String,
+    pub user_id: uuid::Uuid,
+    pub redirect_uri: String,
+    /// Whether the authorization request named the `redirect_uri`, in which case the token
+    /// request must repeat it, RFC 6749 section 4.1.3.
+    #[serde(default)]
+    pub redirect_uri_requested: bool,
+    pub scope: String,
+    pub code_challenge: String,
+    pub max_age: i64,
+    #[serde(default)]
+    pub nonce: Option<String>,
+    #[serde(default)]
+    pub context: Option<AuthenticationContext>,
+}
+
+pub(crate) fn append_query(uri: &str, params: &[(&str, &str)]) -> String {
+    match url::Url::parse(uri) {
+        Ok(mut url) => {
+            url.query_pairs_mut().extend_pairs(params);
+            url.to_string()
+        }
+        Err(_) => {
+            let query = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(params)
+                .finish();
+            let separator = if uri.contains('?') { '&' } else { '?' };
+            format!("{}{}{}", uri, separator, query)
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use super::{
+    audit::RequestContext, auth_repo_errors::AuthRepositoryError,
+    register_user::HashedUserPassword, user_id::UserId, user_password::UserPassword,
+};
+use thiserror::Error;
+
+/// A request from `user_id` to replace their password, which they must confirm.
+#[derive(Debug)]
+pub struct ChangePasswordRequest {
+    pub user_id: UserId,
+    pub current_password: UserPassword,
+    pub new_password: HashedUserPassword,
+    pub context: RequestContext,
+}
+
+#[derive(Debug, Error)]
+pub enum ChangePasswordError {
+    #[error("Current password is incorrect")]
+    InvalidCredentials,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for ChangePasswordError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => {
+                ChangePasswordError::InvalidCredentials
+            }
+            e => ChangePasswordError::Unknown(e.into()),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// Registered claims that are always set by `generate_jwt`, plus the issuer, the audience, the
+/// principal type, the authentication context, the active organization and the login session,
+/// and can never be overridden by a claims provider.
+pub const RESERVED_CLAIMS: [&str; 14] = [
+    ISSUER_CLAIM,
+    AUDIENCE_CLAIM,
+    "sub",
+    "token_uuid",
+    "exp",
+    "iat",
+    "nbf",
+    PRINCIPAL_TYPE_CLAIM,
+    "auth_time",
+    "amr",
+    "acr",
+    "org_id",
+    "org_role",
+    "sid",
+];
+
+/// Claim naming the issuer of access tokens, the `OIDC_ISSUER` URL of the service, which
+/// verifiers of downstream services check.
+pub const ISSUER_CLAIM: &str = "iss";
+
+/// Claim naming the OAuth client an access token was issued to, which downstream services can
+/// require with `TokenVerifier::with_audience`.
+pub const AUDIENCE_CLAIM: &str = "aud";
+
+/// Claim marking tokens issued to OAuth clients rather than users.
+pub const PRINCIPAL_TYPE_CLAIM: &str = "principal_type";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CustomClaims(Map<String, Value>);
+
+impl CustomClaims {
+    pub fn new(claims: Map<String, Value>) -> CustomClaims {
+        CustomClaims(claims)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    pub fn insert(&mut self, name: &str, value: Value) {
+        self.0.insert(name.to_string(), value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.0.iter()
+    }
+
+    pub fn into_inner(self) -> Map<String, Value> {
+        self.0
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ClaimsError {
+    #[error("Claim {name} is reserved and cannot be set by a claims provider")]
+    Reserved { name: String },
+    #[error("Custom claims are {size} bytes, exceeding the budget of {budget} bytes")]
+    BudgetExceeded { size: usize, budget: usize },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
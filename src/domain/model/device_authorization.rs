@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{
+    authentication_context::AuthenticationContext, oauth_client::ClientAuthentication,
+    scope::Scopes,
+};
+
+/// The `grant_type` a device uses to poll the token endpoint, RFC 8628 section 3.4.
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// A request to the device authorization endpoint, RFC 8628 section 3.1.
+#[derive(Debug)]
+pub struct DeviceAuthorizationRequest {
+    pub client: ClientAuthentication,
+    pub scope: Scopes,
+}
+
+/// The device authorization response, RFC 8628 section 3.2.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeviceAuthorizationResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: i64,
+    pub interval: i64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DeviceAuthorizationStatus {
+    #[default]
+    Pending,
+    Approved {
+        user_id: uuid::Uuid,
+        context: Option<AuthenticationContext>,
+    },
+    Denied,
+}
+
+/// The data stored in the cache for a pending device authorization.
+///
+/// `interval` starts at the configured polling interval and grows by five seconds each
+/// time the device polls too fast, as required by RFC 8628 section 3.5.
+///
+/// The `status` is stored apart from the rest, as the user's decision, so the user deciding
+/// and the device polling never overwrite each other's changes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub client_id: String,
+    pub scope: String,
+    #[serde(skip)]
+    pub status: DeviceAuthorizationStatus,
+    pub interval: i64,
+    pub last_polled_at: Option<i64>,
+    pub expires_at: i64,
+}
+
+impl DeviceAuthorization {
+    /// Seconds until the device code expires.
+    pub fn expires_in(&self) -> i64 {
+        self.expires_at - chrono::Utc::now().timestamp()
+    }
+}
+
+/// A user's decision on the verification endpoint for the device showing `user_code`.
+#[derive(Debug)]
+pub struct DeviceVerificationRequest {
+    pub user_id: uuid::Uuid,
+    pub context: Option<AuthenticationContext>,
+    pub user_code: String,
+    pub approve: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeviceVerificationResponse {
+    pub client_id: String,
+    pub scope: String,
+    pub approved: bool,
+}
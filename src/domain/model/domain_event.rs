@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Version of the JSON schema of published events. It only changes when a field is removed or
+/// changes meaning, so consumers can keep reading events that gained new fields.
+pub const DOMAIN_EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// A successful authentication operation, as published to other services.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum DomainEventType {
+    #[serde(rename = "user.registered")]
+    UserRegistered,
+    #[serde(rename = "user.logged_in")]
+    UserLoggedIn,
+    #[serde(rename = "user.logged_out")]
+    UserLoggedOut,
+    #[serde(rename = "session.refreshed")]
+    SessionRefreshed,
+    #[serde(rename = "token.revoked")]
+    TokenRevoked,
+    #[serde(rename = "password.changed")]
+    PasswordChanged,
+    #[serde(rename = "password.reset")]
+    PasswordReset,
+}
+
+impl DomainEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DomainEventType::UserRegistered => "user.registered",
+            DomainEventType::UserLoggedIn => "user.logged_in",
+            DomainEventType::UserLoggedOut => "user.logged_out",
+            DomainEventType::SessionRefreshed => "session.refreshed",
+            DomainEventType::TokenRevoked => "token.revoked",
+            DomainEventType::PasswordChanged => "password.changed",
+            DomainEventType::PasswordReset => "password.reset",
+        }
+    }
+}
+
+/// An event of the internal bus, serialized as version `DOMAIN_EVENT_SCHEMA_VERSION` of the
+/// schema:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "id": "6f1c…",
+///   "type": "user.logged_in",
+///   "occurred_at": "2026-10-18T21:00:00Z",
+///   "user_id": "0b8e…",
+///   "data": { "email": "adrian@email.com" }
+/// }
+/// ```
+///
+/// `user_id` is the user the event is about, and `data` holds the details of its type.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DomainEvent {
+    pub version: u32,
+    pub id: uuid::Uuid,
+    #[serde(rename = "type")]
+    pub event_type: DomainEventType,
+    pub occurred_at: DateTime<Utc>,
+    pub user_id: Option<uuid::Uuid>,
+    pub data: serde_json::Value,
+}
+
+impl DomainEvent {
+    pub fn new(event_type: DomainEventType, user_id: Option<uuid::Uuid>) -> DomainEvent {
+        DomainEvent {
+            version: DOMAIN_EVENT_SCHEMA_VERSION,
+            id: uuid::Uuid::new_v4(),
+            event_type,
+            occurred_at: Utc::now(),
+            user_id,
+            data: serde_json::json!({}),
+        }
+    }
+
+    pub fn with_data(self, data: serde_json::Value) -> DomainEvent {
+        DomainEvent { data, ..self }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EventPublishError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_event_schema() {
+        let user_id = uuid::Uuid::new_v4();
+        let event = DomainEvent::new(DomainEventType::UserLoggedIn, Some(user_id))
+            .with_data(serde_json::json!({ "email": "adrian@email.com" }));
+
+        let json = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["type"], "user.logged_in");
+        assert_eq!(json["user_id"], serde_json::json!(user_id));
+        assert_eq!(json["data"]["email"], "adrian@email.com");
+        assert_eq!(serde_json::from_value::<DomainEvent>(json).unwrap(), event);
+    }
+}
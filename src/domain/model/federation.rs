@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    auth_repo_errors::AuthRepositoryError, cache_errors::CacheOperationError,
+    login_response::LoginResponse, login_user::LoginUserError,
+};
+
+/// An external OpenID Connect provider users can sign in with, such as Google or Okta.
+///
+/// Providers are configured through `FEDERATED_PROVIDERS`, and `name` appears in the login
+/// and callback paths, e.g. `/api/federation/google/login`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FederatedProvider {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default = "default_provider_scope")]
+    pub scope: String,
+}
+
+fn default_provider_scope() -> String {
+    "openid email".to_string()
+}
+
+/// The data kept in the cache between the redirect to a provider and its callback.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FederationState {
+    pub state: String,
+    pub provider: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub return_to: Option<String>,
+    pub max_age: i64,
+}
+
+/// A request to start signing in with `provider`.
+///
+/// `return_to` must be a path on this service, such as a pending `/oauth/authorize` request.
+#[derive(Debug)]
+pub struct FederatedLoginRequest {
+    pub provider: String,
+    pub return_to: Option<String>,
+}
+
+/// Where to send the user to sign in.
+///
+/// `state_digest` is kept in a cookie for `state_max_age` seconds, so the provider's response
+/// is only accepted from the browser that started the login.
+#[derive(Debug)]
+pub struct FederatedLoginResponse {
+    pub authorization_url: String,
+    pub state_digest: String,
+    pub state_max_age: i64,
+}
+
+/// The provider's redirect back to the callback endpoint, with the `state_digest` cookie of the
+/// browser it arrived in.
+#[derive(Debug)]
+pub struct FederatedCallbackRequest {
+    pub provider: String,
+    pub state: String,
+    pub state_digest: Option<String>,
+    pub code: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct FederatedCallbackResponse {
+    pub login: LoginResponse,
+    pub return_to: Option<String>,
+}
+
+/// An account at an external provider, as asserted by a validated ID token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FederatedIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub amr: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum FederationError {
+    #[error("Unknown identity provider {provider}")]
+    UnknownProvider { provider: String },
+    #[error("Invalid federated login request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("Federated login failed: {reason}")]
+    Rejected { reason: String },
+    #[error("Cannot link account: {reason}")]
+    AccountConflict { reason: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<CacheOperationError> for FederationError {
+    fn from(value: CacheOperationError) -> Self {
+        match value {
+            CacheOperationError::Invalid { reason } => FederationError::Rejected { reason },
+            e => FederationError::Unknown(anyhow::anyhow!(e)),
+        }
+    }
+}
+
+impl From<AuthRepositoryError> for FederationError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::Duplicate { email } => FederationError::AccountConflict {
+                reason: format!("A user with email {} already exists", email),
+            },
+            AuthRepositoryError::InvalidCredentials { reason } => {
+                FederationError::Rejected { reason }
+            }
+            e => FederationError::Unknown(anyhow::anyhow!(e)),
+        }
+    }
+}
+
+impl From<LoginUserError> for FederationError {
+    fn from(value: LoginUserError) -> Self {
+        FederationError::Unknown(anyhow::anyhow!(value))
+    }
+}
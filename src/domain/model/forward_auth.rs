@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use super::auth_middleware::AuthMiddleware;
+
+/// Who an access token authenticates, as passed on to the applications behind a reverse
+/// proxy. Tokens issued to OAuth clients have no email or roles.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ForwardAuthIdentity {
+    pub user_id: uuid::Uuid,
+    pub email: Option<String>,
+    pub roles: Vec<String>,
+}
+
+impl From<&AuthMiddleware> for ForwardAuthIdentity {
+    fn from(auth: &AuthMiddleware) -> Self {
+        ForwardAuthIdentity {
+            user_id: auth.subject(),
+            email: auth.user().map(|user| user.email.clone()),
+            roles: auth
+                .user()
+                .map(|user| user.roles.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// The headers a reverse proxy copies onto the request it forwards, named as configured.
+#[derive(Debug, PartialEq)]
+pub struct ForwardAuthResponse {
+    pub headers: Vec<(String, String)>,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// Claims of an OpenID Connect ID token, as defined by OpenID Connect Core section 2.
+///
+/// The authentication context claims are omitted for sessions that predate their recording.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amr: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acr: Option<String>,
+    pub email: String,
+    pub email_verified: bool,
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::{auth_repo_errors::AuthRepositoryError, custom_claims::CustomClaims, user_id::UserId};
+
+/// Claim naming the party acting on behalf of the subject of a token, as in RFC 8693.
+pub const ACT_CLAIM: &str = "act";
+
+/// How long an impersonation token is valid for, at most.
+pub const IMPERSONATION_MAX_AGE_MINUTES: i64 = 15;
+
+/// The administrator behind an impersonated access token, whose `sub` is the impersonated
+/// user. Serialized as the `act` claim, `{"sub": "<administrator id>"}`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Actor {
+    pub sub: uuid::Uuid,
+}
+
+impl Actor {
+    pub fn from_claims(claims: &CustomClaims) -> Option<Actor> {
+        serde_json::from_value(claims.get(ACT_CLAIM)?.clone()).ok()
+    }
+
+    pub fn insert_into(&self, claims: &mut CustomClaims) {
+        claims.insert(ACT_CLAIM, serde_json::json!(self));
+    }
+}
+
+/// A request from the administrator `actor_id` to act as `user_id`.
+#[derive(Debug)]
+pub struct StartImpersonationRequest {
+    pub actor_id: UserId,
+    pub user_id: UserId,
+}
+
+/// An access token for the impersonated user. There is no refresh token, so impersonation
+/// ends when the access token expires, if not before.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonationResponse {
+    pub access_token: String,
+    pub access_token_max_age: i64,
+}
+
+#[derive(Debug, Error)]
+pub enum ImpersonationError {
+    #[error("Invalid impersonation request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("User not found")]
+    NotFound,
+    #[error("Not allowed: {reason}")]
+    Forbidden { reason: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for ImpersonationError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => ImpersonationError::NotFound,
+            e => ImpersonationError::Unknown(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor_round_trips_through_claims() {
+        let actor = Actor {
+            sub: uuid::Uuid::new_v4(),
+        };
+        let mut claims = CustomClaims::default();
+
+        actor.insert_into(&mut claims);
+
+        assert_eq!(Actor::from_claims(&claims), Some(actor.clone()));
+        assert_eq!(
+            claims.get(ACT_CLAIM),
+            Some(&serde_json::json!({ "sub": actor.sub }))
+        );
+        assert_eq!(Actor::from_claims(&CustomClaims::default()), None);
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{oauth_client::ClientAuthentication, principal::PrincipalType};
+
+#[derive(Debug)]
+pub struct IntrospectionRequest {
+    pub client: ClientAuthentication,
+    pub token: String,
+    pub token_type_hint: Option<String>,
+}
+
+/// An introspection response as defined by RFC 7662 section 2.2.
+///
+/// Inactive tokens only carry `active: false`, so nothing is disclosed about
+/// tokens that are expired, revoked or were never issued by this server.
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct IntrospectionResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub principal_type: Option<PrincipalType>,
+}
+
+impl IntrospectionResponse {
+    pub fn inactive() -> IntrospectionResponse {
+        IntrospectionResponse::default()
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use super::user::ADMIN_ROLE;
+
+/// Provider of the `user_identities` linking users to the directory, whose subject is the
+/// user's email.
+pub const DIRECTORY_PROVIDER: &str = "ldap";
+
+/// How password logins use the LDAP directory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LdapMode {
+    /// Only the directory is consulted; local passwords are never checked.
+    Exclusive,
+    /// The directory is consulted first, and local passwords are checked for logins the
+    /// directory does not know.
+    First,
+}
+
+/// Settings of the LDAP or Active Directory authentication backend.
+///
+/// Logins search for the user with `user_filter`, where `{login}` is replaced by the escaped
+/// login, binding as `bind_dn` if set and anonymously otherwise, then bind as the user found
+/// to check the password.
+#[derive(Clone, Debug)]
+pub struct LdapConfig {
+    pub mode: LdapMode,
+    pub url: String,
+    pub bind_dn: Option<String>,
+    pub bind_password: String,
+    pub base_dn: String,
+    pub user_filter: String,
+    pub email_attribute: String,
+    pub group_attribute: String,
+    /// Maps group DNs, compared case insensitively, to the roles granted to their members.
+    pub group_roles: HashMap<String, String>,
+    /// Whether groups may grant and revoke the `admin` role. When they may not, a user's
+    /// `admin` role is left as it was assigned locally.
+    pub grant_admin: bool,
+}
+
+impl LdapConfig {
+    /// The roles granted by membership of `groups`, sorted and without duplicates.
+    ///
+    /// Returns `None` when no group mapping is configured, so roles assigned locally are kept.
+    pub fn roles_for_groups(&self, groups: &[String]) -> Option<Vec<String>> {
+        if self.group_roles.is_empty() {
+            return None;
+        }
+
+        let mut roles: Vec<String> = self
+            .group_roles
+            .iter()
+            .filter(|(group, _)| groups.iter().any(|g| g.eq_ignore_ascii_case(group)))
+            .map(|(_, role)| role.clone())
+            .collect();
+        roles.sort();
+        roles.dedup();
+        Some(roles)
+    }
+}
+
+/// A user authenticated by the directory, to be provisioned into `users` on login.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirectoryUser {
+    pub email: String,
+    /// Roles synchronized from directory groups, or `None` to leave the user's roles as they are.
+    pub roles: Option<Vec<String>>,
+    /// Whether the directory decides if the user is an `admin`, see `LdapConfig::grant_admin`.
+    pub grants_admin: bool,
+}
+
+impl DirectoryUser {
+    /// The roles of a user holding `current` once synchronized with the directory, or `None`
+    /// when they are left as they are.
+    pub fn synchronized_roles(&self, current: &[String]) -> Option<Vec<String>> {
+        let mut roles = self.roles.clone()?;
+        if !self.grants_admin {
+            roles.retain(|role| role != ADMIN_ROLE);
+            if current.iter().any(|role| role == ADMIN_ROLE) {
+                roles.push(ADMIN_ROLE.to_string());
+            }
+        }
+        roles.sort();
+        roles.dedup();
+        Some(roles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_for_groups() {
+        let mut config = LdapConfig {
+            mode: LdapMode::Exclusive,
+            url: "ldap://localhost:389".to_string(),
+            bind_dn: None,
+            bind_password: String::new(),
+            base_dn: "dc=example,dc=com".to_string(),
+            user_filter: "(mail={login})".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            group_roles: HashMap::new(),
+            grant_admin: false,
+        };
+        let groups = vec!["CN=Admins,OU=Groups,DC=example,DC=com".to_string()];
+
+        assert_eq!(config.roles_for_groups(&groups), None);
+
+        config.group_roles.insert(
+            "cn=admins,ou=groups,dc=example,dc=com".to_string(),
+            "admin".to_string(),
+        );
+        config.group_roles.insert(
+            "cn=staff,ou=groups,dc=example,dc=com".to_string(),
+            "staff".to_string(),
+        );
+
+        assert_eq!(
+            config.roles_for_groups(&groups),
+            Some(vec!["admin".to_string()])
+        );
+        assert_eq!(config.roles_for_groups(&[]), Some(vec![]));
+    }
+
+    #[test]
+    fn test_synchronized_roles_only_grant_admin_when_allowed() {
+        let mut directory_user = DirectoryUser {
+            email: "adrian@email.com".to_string(),
+            roles: Some(vec!["admin".to_string(), "staff".to_string()]),
+            grants_admin: false,
+        };
+
+        assert_eq!(
+            directory_user.synchronized_roles(&[]),
+            Some(vec!["staff".to_string()])
+        );
+        assert_eq!(
+            directory_user.synchronized_roles(&["admin".to_string(), "billing".to_string()]),
+            Some(vec!["admin".to_string(), "staff".to_string()])
+        );
+
+        directory_user.grants_admin = true;
+        directory_user.roles = Some(vec![]);
+        assert_eq!(
+            directory_user.synchronized_roles(&["admin".to_string()]),
+            Some(vec![])
+        );
+
+        directory_user.roles = None;
+        assert_eq!(
+            directory_user.synchronized_roles(&["admin".to_string()]),
+            None
+        );
+    }
+}
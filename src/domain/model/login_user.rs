@@ -1,5 +1,6 @@
 use super::{
-    auth_repo_errors::AuthRepositoryError, user_email::UserEmail, user_password::UserPassword,
+    audit::RequestContext, auth_repo_errors::AuthRepositoryError, user_email::UserEmail,
+    user_password::UserPassword,
 };
 use anyhow::anyhow;
 use thiserror::Error;
@@ -8,11 +9,20 @@ use thiserror::Error;
 pub struct LoginUserRequest {
     pub email: UserEmail,
     pub password: UserPassword,
+    pub context: RequestContext,
 }
 
 impl LoginUserRequest {
     pub fn new(email: UserEmail, password: UserPassword) -> LoginUserRequest {
-        LoginUserRequest { email, password }
+        LoginUserRequest {
+            email,
+            password,
+            context: RequestContext::default(),
+        }
+    }
+
+    pub fn with_context(self, context: RequestContext) -> LoginUserRequest {
+        LoginUserRequest { context, ..self }
     }
 }
 
@@ -20,6 +30,12 @@ impl LoginUserRequest {
 pub enum LoginUserError {
     #[error("Invalid email or password")]
     InvalidCredentials,
+    #[error("This account is suspended")]
+    Suspended,
+    #[error("A password reset is required before logging in")]
+    PasswordResetRequired,
+    #[error("Too many failed login attempts, try again later")]
+    LockedOut,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
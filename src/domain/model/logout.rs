@@ -1,8 +1,9 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 
-use super::token_uuid::TokenUuid;
+use super::{audit::RequestContext, token_uuid::TokenUuid};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LogoutResponse(String);
 
 impl LogoutResponse {
@@ -11,18 +12,50 @@ impl LogoutResponse {
     }
 }
 
+/// A request to end the session of an access token, which belongs to `user_id` unless it was
+/// issued to an OAuth client.
+///
+/// `session_id` and `expires_at` are read from the token, and broadcast with its revocation.
 #[derive(Debug)]
 pub struct LogoutRequest {
     token_uuid: TokenUuid,
+    pub user_id: Option<uuid::Uuid>,
+    pub session_id: Option<uuid::Uuid>,
+    pub expires_at: Option<i64>,
+    pub context: RequestContext,
 }
 
 impl LogoutRequest {
     pub fn new(token_uuid: uuid::Uuid) -> LogoutRequest {
         LogoutRequest {
             token_uuid: TokenUuid::new(token_uuid),
+            user_id: None,
+            session_id: None,
+            expires_at: None,
+            context: RequestContext::default(),
         }
     }
 
+    pub fn with_user(self, user_id: Option<uuid::Uuid>) -> LogoutRequest {
+        LogoutRequest { user_id, ..self }
+    }
+
+    pub fn with_session(
+        self,
+        session_id: Option<uuid::Uuid>,
+        expires_at: Option<i64>,
+    ) -> LogoutRequest {
+        LogoutRequest {
+            session_id,
+            expires_at,
+            ..self
+        }
+    }
+
+    pub fn with_context(self, context: RequestContext) -> LogoutRequest {
+        LogoutRequest { context, ..self }
+    }
+
     pub fn get_uuid(&self) -> &TokenUuid {
         &self.token_uuid
     }
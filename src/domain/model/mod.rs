@@ -1,15 +1,47 @@
+pub mod admin_audit;
+pub mod admin_user;
+pub mod audit;
 pub mod auth;
 pub mod auth_middleware;
 pub mod auth_repo_errors;
+pub mod authentication_context;
+pub mod authorize;
 pub mod cache_errors;
+pub mod change_password;
+pub mod custom_claims;
+pub mod device_authorization;
+pub mod domain_event;
+pub mod federation;
+pub mod forward_auth;
+pub mod id_token;
+pub mod impersonation;
+pub mod introspection;
+pub mod jwks;
+pub mod ldap;
 pub mod login_response;
 pub mod login_user;
 pub mod logout;
+pub mod oauth_client;
+pub mod oauth_errors;
+pub mod oauth_token;
+pub mod organization;
+pub mod password_reset;
+pub mod personal_access_token;
+pub mod principal;
+pub mod provider_metadata;
 pub mod refresh_token;
 pub mod register_user;
+pub mod registration;
+pub mod revocation;
+pub mod saml;
+pub mod scope;
+pub mod service_account;
+pub mod session_revocation;
 pub mod token;
 pub mod token_uuid;
 pub mod user;
 pub mod user_email;
 pub mod user_id;
 pub mod user_password;
+pub mod userinfo;
+pub mod webhook;
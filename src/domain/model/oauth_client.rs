@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::scope::Scopes;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClientType {
+    Public,
+    Confidential,
+}
+
+#[derive(Clone, Debug, Deserialize, sqlx::FromRow, Serialize)]
+pub struct OAuthClient {
+    pub id: uuid::Uuid,
+    pub client_id: String,
+    pub name: String,
+    pub client_type: String,
+    pub redirect_uris: Vec<String>,
+    pub allowed_scopes: Vec<String>,
+    pub client_secret_hash: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthClient {
+    pub fn new(
+        client_id: &str,
+        client_type: ClientType,
+        redirect_uris: Vec<String>,
+        allowed_scopes: Vec<String>,
+    ) -> OAuthClient {
+        let now = Utc::now();
+        OAuthClient {
+            id: uuid::Uuid::new_v4(),
+            client_id: client_id.to_string(),
+            name: client_id.to_string(),
+            client_type: match client_type {
+                ClientType::Public => "public".to_string(),
+                ClientType::Confidential => "confidential".to_string(),
+            },
+            redirect_uris,
+            allowed_scopes,
+            client_secret_hash: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+
+    /// Sets the Argon2 hash of the client secret, as produced by `hash_password`.
+    pub fn with_client_secret_hash(mut self, client_secret_hash: &str) -> OAuthClient {
+        self.client_secret_hash = Some(client_secret_hash.to_string());
+        self
+    }
+
+    pub fn client_type(&self) -> ClientType {
+        match self.client_type.as_str() {
+            "confidential" => ClientType::Confidential,
+            _ => ClientType::Public,
+        }
+    }
+
+    /// Resolves the redirect URI for an authorization request.
+    ///
+    /// Redirect URIs are compared with simple string matching as recommended by RFC 6749.
+    /// When the request omits the redirect URI, the client must have exactly one registered.
+    pub fn resolve_redirect_uri(&self, requested: Option<&str>) -> Option<String> {
+        match requested {
+            Some(uri) => self
+                .redirect_uris
+                .iter()
+                .find(|registered| registered.as_str() == uri)
+                .cloned(),
+            None if self.redirect_uris.len() == 1 => self.redirect_uris.first().cloned(),
+            None => None,
+        }
+    }
+
+    pub fn allows_scopes(&self, scopes: &Scopes) -> bool {
+        scopes.is_subset_of(&self.allowed_scopes)
+    }
+}
+
+/// The credentials a client presented at the token endpoint.
+///
+/// `client_secret` is only set for confidential clients authenticating with
+/// `client_secret_basic` or `client_secret_post`.
+#[derive(Debug)]
+pub struct ClientAuthentication {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+impl ClientAuthentication {
+    pub fn new(client_id: &str, client_secret: Option<&str>) -> ClientAuthentication {
+        ClientAuthentication {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.map(|secret| secret.to_string()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClientId(String);
+
+impl ClientId {
+    pub fn new(id: &str) -> ClientId {
+        ClientId(id.to_string())
+    }
+
+    pub fn get(&self) -> &str {
+        &self.0
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::anyhow;
+use thiserror::Error;
+
+use super::{
+    auth_repo_errors::AuthRepositoryError, cache_errors::CacheOperationError,
+    custom_claims::ClaimsError,
+};
+
+/// Errors defined by RFC 6749, RFC 6750, RFC 8628 and OpenID Connect for the authorization server endpoints.
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("invalid_request: {description}")]
+    InvalidRequest { description: String },
+    #[error("invalid_client: {description}")]
+    InvalidClient { description: String },
+    #[error("invalid_grant: {description}")]
+    InvalidGrant { description: String },
+    #[error("unauthorized_client: {description}")]
+    UnauthorizedClient { description: String },
+    #[error("unsupported_grant_type: {description}")]
+    UnsupportedGrantType { description: String },
+    #[error("unsupported_response_type: {description}")]
+    UnsupportedResponseType { description: String },
+    #[error("invalid_scope: {description}")]
+    InvalidScope { description: String },
+    #[error("access_denied: {description}")]
+    AccessDenied { description: String },
+    #[error("too_many_requests: {description}")]
+    TooManyRequests { description: String },
+    #[error("login_required: {description}")]
+    LoginRequired { description: String },
+    #[error("insufficient_scope: {description}")]
+    InsufficientScope { description: String },
+    #[error("authorization_pending: {description}")]
+    AuthorizationPending { description: String },
+    #[error("slow_down: {description}")]
+    SlowDown { description: String },
+    #[error("expired_token: {description}")]
+    ExpiredToken { description: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl OAuthError {
+    /// The error code as registered in RFC 6749, used for the `error` response parameter.
+    pub fn code(&self) -> &'static str {
+        match self {
+            OAuthError::InvalidRequest { .. } => "invalid_request",
+            OAuthError::InvalidClient { .. } => "invalid_client",
+            OAuthError::InvalidGrant { .. } => "invalid_grant",
+            OAuthError::UnauthorizedClient { .. } => "unauthorized_client",
+            OAuthError::UnsupportedGrantType { .. } => "unsupported_grant_type",
+            OAuthError::UnsupportedResponseType { .. } => "unsupported_response_type",
+            OAuthError::InvalidScope { .. } => "invalid_scope",
+            OAuthError::AccessDenied { .. } => "access_denied",
+            OAuthError::TooManyRequests { .. } => "too_many_requests",
+            OAuthError::LoginRequired { .. } => "login_required",
+            OAuthError::InsufficientScope { .. } => "insufficient_scope",
+            OAuthError::AuthorizationPending { .. } => "authorization_pending",
+            OAuthError::SlowDown { .. } => "slow_down",
+            OAuthError::ExpiredToken { .. } => "expired_token",
+            OAuthError::Unknown(_) => "server_error",
+        }
+    }
+
+    /// A human readable description, used for the `error_description` response parameter.
+    pub fn description(&self) -> String {
+        match self {
+            OAuthError::InvalidRequest { description }
+            | OAuthError::InvalidClient { description }
+            | OAuthError::InvalidGrant { description }
+            | OAuthError::UnauthorizedClient { description }
+            | OAuthError::UnsupportedGrantType { description }
+            | OAuthError::UnsupportedResponseType { description }
+            | OAuthError::InvalidScope { description }
+            | OAuthError::AccessDenied { description }
+            | OAuthError::TooManyRequests { description }
+            | OAuthError::LoginRequired { description }
+            | OAuthError::InsufficientScope { description }
+            | OAuthError::AuthorizationPending { description }
+            | OAuthError::SlowDown { description }
+            | OAuthError::ExpiredToken { description } => description.to_string(),
+            OAuthError::Unknown(_) => "Internal Server Error".to_string(),
+        }
+    }
+}
+
+impl From<AuthRepositoryError> for OAuthError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { reason } => OAuthError::InvalidGrant {
+                description: reason,
+            },
+            _ => OAuthError::Unknown(anyhow!("Internal Server Error")),
+        }
+    }
+}
+
+impl From<CacheOperationError> for OAuthError {
+    fn from(value: CacheOperationError) -> Self {
+        match value {
+            CacheOperationError::Invalid { reason } => OAuthError::InvalidGrant {
+                description: reason,
+            },
+            _ => OAuthError::Unknown(anyhow!("Internal Server Error")),
+        }
+    }
+}
+
+impl From<ClaimsError> for OAuthError {
+    fn from(value: ClaimsError) -> Self {
+        OAuthError::Unknown(anyhow!(value).context("Failed to build access token claims"))
+    }
+}
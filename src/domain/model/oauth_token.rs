@@ -0,0 +1,42 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{oauth_client::ClientAuthentication, scope::Scopes};
+
+/// A request to the token endpoint: the authenticating client and the grant it presents.
+#[derive(Debug)]
+pub struct TokenRequest {
+    pub client: ClientAuthentication,
+    pub grant: TokenGrant,
+}
+
+#[derive(Debug)]
+pub enum TokenGrant {
+    AuthorizationCode {
+        code: String,
+        redirect_uri: Option<String>,
+        code_verifier: String,
+    },
+    RefreshToken {
+        refresh_token: String,
+    },
+    ClientCredentials {
+        scope: Scopes,
+    },
+    DeviceCode {
+        device_code: String,
+    },
+}
+
+/// A successful token response as defined by RFC 6749 section 5.1.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
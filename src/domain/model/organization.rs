@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::{
+    auth_repo_errors::AuthRepositoryError, custom_claims::CustomClaims, login_user::LoginUserError,
+    user_id::UserId,
+};
+
+/// Claim holding the organization a session is acting in.
+pub const ORG_ID_CLAIM: &str = "org_id";
+
+/// Claim holding the user's role in the active organization.
+pub const ORG_ROLE_CLAIM: &str = "org_role";
+
+/// How long an invitation can be accepted for.
+pub const INVITATION_MAX_AGE_DAYS: i64 = 7;
+
+/// A tenant. Users belong to any number of organizations, with a role in each.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct Organization {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub slug: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A user's role in an organization, from most to least privileged.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl OrganizationRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrganizationRole::Owner => "owner",
+            OrganizationRole::Admin => "admin",
+            OrganizationRole::Member => "member",
+        }
+    }
+
+    /// Owners and admins manage memberships and invitations.
+    pub fn can_manage_members(&self) -> bool {
+        matches!(self, OrganizationRole::Owner | OrganizationRole::Admin)
+    }
+
+    /// Only owners can grant or take away the owner role.
+    pub fn can_manage(&self, role: OrganizationRole) -> bool {
+        self.can_manage_members() && (role != OrganizationRole::Owner || self == &Self::Owner)
+    }
+}
+
+/// The `role` columns are constrained to `owner`, `admin` and `member`.
+impl From<String> for OrganizationRole {
+    fn from(role: String) -> Self {
+        match role.as_str() {
+            "owner" => OrganizationRole::Owner,
+            "admin" => OrganizationRole::Admin,
+            _ => OrganizationRole::Member,
+        }
+    }
+}
+
+/// A user's membership, with the organization it is in.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct OrganizationMembership {
+    pub organization_id: uuid::Uuid,
+    pub name: String,
+    pub slug: String,
+    pub role: OrganizationRole,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl OrganizationMembership {
+    /// Records the membership as the session's active organization.
+    pub fn insert_into(&self, claims: &mut CustomClaims) {
+        claims.insert(ORG_ID_CLAIM, serde_json::json!(self.organization_id));
+        claims.insert(ORG_ROLE_CLAIM, serde_json::json!(self.role));
+    }
+}
+
+/// A member of an organization, as listed to the other members.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct OrganizationMember {
+    pub user_id: uuid::Uuid,
+    pub email: String,
+    pub role: OrganizationRole,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A pending invitation for `email` to join an organization.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct OrganizationInvitation {
+    pub id: uuid::Uuid,
+    pub organization_id: uuid::Uuid,
+    pub email: String,
+    pub role: OrganizationRole,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Reads the active organization of a session back from its token claims.
+pub fn active_organization(claims: &CustomClaims) -> Option<uuid::Uuid> {
+    claims
+        .get(ORG_ID_CLAIM)
+        .and_then(|id| id.as_str())
+        .and_then(|id| uuid::Uuid::parse_str(id).ok())
+}
+
+/// A request from `user_id` to create an organization, which they will own.
+#[derive(Debug)]
+pub struct CreateOrganizationRequest {
+    pub user_id: UserId,
+    pub name: String,
+    pub slug: String,
+}
+
+impl CreateOrganizationRequest {
+    /// Slugs are lowercase DNS labels, so they can be used in URLs and subdomains.
+    pub fn is_valid_slug(&self) -> bool {
+        let slug = self.slug.as_bytes();
+        (3..=63).contains(&slug.len())
+            && slug[0].is_ascii_lowercase()
+            && slug[slug.len() - 1] != b'-'
+            && slug
+                .iter()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'-')
+    }
+}
+
+/// The rows inserted for a new organization and its owner.
+#[derive(Debug)]
+pub struct NewOrganization {
+    pub name: String,
+    pub slug: String,
+    pub owner_id: uuid::Uuid,
+}
+
+/// A request from `actor_id` concerning the members of `organization_id`.
+#[derive(Debug)]
+pub struct OrganizationMembersRequest {
+    pub organization_id: uuid::Uuid,
+    pub actor_id: UserId,
+}
+
+/// A request from `actor_id` to invite `email` into `organization_id` with `role`.
+#[derive(Debug)]
+pub struct InviteMemberRequest {
+    pub organization_id: uuid::Uuid,
+    pub actor_id: UserId,
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+/// The row inserted for a new invitation, replacing any pending one for the same email.
+#[derive(Debug)]
+pub struct NewInvitation {
+    pub organization_id: uuid::Uuid,
+    pub email: String,
+    pub role: OrganizationRole,
+    pub token_hash: String,
+    pub invited_by: uuid::Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A new invitation. `token` is the only time the secret is available, and delivering it to
+/// the invitee is up to the caller.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateInvitationResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub invitation: OrganizationInvitation,
+}
+
+/// A request from `user_id` to accept an invitation sent to `email`.
+#[derive(Debug)]
+pub struct AcceptInvitationRequest {
+    pub user_id: UserId,
+    pub email: String,
+    pub token: String,
+}
+
+/// A request from `actor_id` to remove `user_id` from `organization_id`.
+#[derive(Debug)]
+pub struct RemoveMemberRequest {
+    pub organization_id: uuid::Uuid,
+    pub actor_id: UserId,
+    pub user_id: UserId,
+}
+
+/// A request to reissue the tokens of a session so it acts in `organization_id`.
+///
+/// `claims` are those of the current access token, whose authentication context carries
+/// over, and `access_token_uuid` identifies it so it can be revoked.
+#[derive(Debug)]
+pub struct SwitchOrganizationRequest {
+    pub user_id: UserId,
+    pub organization_id: uuid::Uuid,
+    pub access_token_uuid: uuid::Uuid,
+    pub claims: CustomClaims,
+}
+
+#[derive(Debug, Error)]
+pub enum OrganizationError {
+    #[error("Invalid organization request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("Organization {slug} already exists")]
+    Duplicate { slug: String },
+    #[error("Organization, member or invitation not found")]
+    NotFound,
+    #[error("Not allowed: {reason}")]
+    Forbidden { reason: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for OrganizationError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => OrganizationError::NotFound,
+            e => OrganizationError::Unknown(e.into()),
+        }
+    }
+}
+
+impl From<LoginUserError> for OrganizationError {
+    fn from(value: LoginUserError) -> Self {
+        match value {
+            LoginUserError::InvalidCredentials
+            | LoginUserError::Suspended
+            | LoginUserError::PasswordResetRequired
+            | LoginUserError::LockedOut => OrganizationError::Forbidden {
+                reason: "This user cannot start a session".to_string(),
+            },
+            LoginUserError::Unknown(cause) => OrganizationError::Unknown(cause),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_organization_role_permissions() {
+        assert!(OrganizationRole::Owner.can_manage(OrganizationRole::Owner));
+        assert!(OrganizationRole::Admin.can_manage(OrganizationRole::Member));
+        assert!(OrganizationRole::Admin.can_manage(OrganizationRole::Admin));
+        assert!(!OrganizationRole::Admin.can_manage(OrganizationRole::Owner));
+        assert!(!OrganizationRole::Member.can_manage(OrganizationRole::Member));
+    }
+
+    #[test]
+    fn test_membership_round_trips_through_claims() {
+        let membership = OrganizationMembership {
+            organization_id: uuid::Uuid::new_v4(),
+            name: "Acme".to_string(),
+            slug: "acme".to_string(),
+            role: OrganizationRole::Admin,
+            created_at: None,
+        };
+        let mut claims = CustomClaims::default();
+
+        membership.insert_into(&mut claims);
+
+        assert_eq!(
+            active_organization(&claims),
+            Some(membership.organization_id)
+        );
+        assert_eq!(
+            claims.get(ORG_ROLE_CLAIM),
+            Some(&serde_json::json!("admin"))
+        );
+        assert_eq!(active_organization(&CustomClaims::default()), None);
+    }
+}
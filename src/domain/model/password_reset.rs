@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::{
+    audit::RequestContext, auth_repo_errors::AuthRepositoryError, register_user::HashedUserPassword,
+};
+
+/// How long a password reset token can be used for.
+pub const PASSWORD_RESET_MAX_AGE_HOURS: i64 = 24;
+
+/// The row inserted when an administrator forces a password reset.
+#[derive(Debug)]
+pub struct NewPasswordReset {
+    pub user_id: uuid::Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A forced password reset. `token` is the only time the secret is available, and delivering
+/// it to the user is up to the caller.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PasswordResetResponse {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A request to choose a new password with a password reset token.
+#[derive(Debug)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub hashed_password: HashedUserPassword,
+    pub context: RequestContext,
+}
+
+#[derive(Debug, Error)]
+pub enum ResetPasswordError {
+    #[error("Password reset token is invalid or has expired")]
+    InvalidToken,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for ResetPasswordError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => ResetPasswordError::InvalidToken,
+            e => ResetPasswordError::Unknown(e.into()),
+        }
+    }
+}
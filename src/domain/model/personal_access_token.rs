@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::api::utils::security::hash_token;
+
+use super::{auth_repo_errors::AuthRepositoryError, scope::Scopes, user_id::UserId};
+
+/// Prefix of every personal access token, which is how the `auth` middleware tells them apart
+/// from JWT access tokens.
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "pat_";
+
+/// Characters of the secret kept after the prefix, so users can recognise a token in listings.
+const VISIBLE_SECRET_LENGTH: usize = 8;
+
+/// A personal access token as stored, without its secret.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct PersonalAccessToken {
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl PersonalAccessToken {
+    pub fn new(user_id: uuid::Uuid, name: &str, scopes: &[&str]) -> PersonalAccessToken {
+        PersonalAccessToken {
+            id: uuid::Uuid::new_v4(),
+            user_id,
+            name: name.to_string(),
+            token_prefix: format!("{}abcdefgh", PERSONAL_ACCESS_TOKEN_PREFIX),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expires_at: None,
+            last_used_at: None,
+            created_at: Some(Utc::now()),
+        }
+    }
+}
+
+/// The secret of a personal access token, as presented by the client.
+#[derive(Debug)]
+pub struct PersonalAccessTokenSecret(String);
+
+impl PersonalAccessTokenSecret {
+    pub fn new(secret: &str) -> PersonalAccessTokenSecret {
+        PersonalAccessTokenSecret(secret.to_string())
+    }
+
+    pub fn is_personal_access_token(token: &str) -> bool {
+        token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX)
+    }
+
+    pub fn get(&self) -> &str {
+        &self.0
+    }
+
+    /// The start of the token shown in listings.
+    pub fn visible_prefix(&self) -> String {
+        self.0
+            .chars()
+            .take(PERSONAL_ACCESS_TOKEN_PREFIX.len() + VISIBLE_SECRET_LENGTH)
+            .collect()
+    }
+
+    /// The digest stored in place of the token, see `hash_token`.
+    pub fn hash(&self) -> String {
+        hash_token(&self.0)
+    }
+}
+
+/// A request from `user_id` for a new token named `name`.
+#[derive(Debug)]
+pub struct CreatePersonalAccessTokenRequest {
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Scopes,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The row inserted for a new token.
+#[derive(Debug)]
+pub struct NewPersonalAccessToken {
+    pub user_id: uuid::Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A newly created token. `token` is the only time the secret is available.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatePersonalAccessTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub details: PersonalAccessToken,
+}
+
+#[derive(Debug)]
+pub struct RevokePersonalAccessTokenRequest {
+    pub user_id: UserId,
+    pub token_id: uuid::Uuid,
+}
+
+#[derive(Debug, Error)]
+pub enum PersonalAccessTokenError {
+    #[error("Invalid personal access token request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("Personal access token not found")]
+    NotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for PersonalAccessTokenError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => PersonalAccessTokenError::NotFound,
+            e => PersonalAccessTokenError::Unknown(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_personal_access_token_secret_prefix_and_hash() {
+        let secret = PersonalAccessTokenSecret::new("pat_0123456789abcdef");
+
+        assert_eq!(secret.visible_prefix(), "pat_01234567");
+        assert_eq!(secret.hash().len(), 64);
+        assert_eq!(
+            secret.hash(),
+            PersonalAccessTokenSecret::new("pat_0123456789abcdef").hash()
+        );
+        assert_ne!(
+            secret.hash(),
+            PersonalAccessTokenSecret::new("pat_0123456789abcdeg").hash()
+        );
+    }
+}
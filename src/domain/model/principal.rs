@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{oauth_client::OAuthClient, user::User};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PrincipalType {
+    User,
+    Client,
+}
+
+/// The authenticated party behind an access token.
+///
+/// Tokens issued through login or the authorization code flow act on behalf of a `User`.
+/// Tokens issued through the client credentials grant belong to the `OAuthClient` itself,
+/// and their `sub` is the client's id.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Principal {
+    User(User),
+    Client(OAuthClient),
+}
+
+impl Principal {
+    pub fn principal_type(&self) -> PrincipalType {
+        match self {
+            Principal::User(_) => PrincipalType::User,
+            Principal::Client(_) => PrincipalType::Client,
+        }
+    }
+
+    pub fn id(&self) -> uuid::Uuid {
+        match self {
+            Principal::User(user) => user.id,
+            Principal::Client(client) => client.id,
+        }
+    }
+
+    pub fn user(&self) -> Option<&User> {
+        match self {
+            Principal::User(user) => Some(user),
+            Principal::Client(_) => None,
+        }
+    }
+
+    pub fn client(&self) -> Option<&OAuthClient> {
+        match self {
+            Principal::User(_) => None,
+            Principal::Client(client) => Some(client),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::device_authorization::DEVICE_CODE_GRANT_TYPE;
+
+/// OpenID Provider metadata, served at `/.well-known/openid-configuration` as defined by
+/// OpenID Connect Discovery section 3.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub introspection_endpoint: String,
+    pub revocation_endpoint: String,
+    pub device_authorization_endpoint: String,
+    pub scopes_supported: Vec<&'static str>,
+    pub response_types_supported: Vec<&'static str>,
+    pub grant_types_supported: Vec<&'static str>,
+    pub subject_types_supported: Vec<&'static str>,
+    pub id_token_signing_alg_values_supported: Vec<&'static str>,
+    pub token_endpoint_auth_methods_supported: Vec<&'static str>,
+    pub code_challenge_methods_supported: Vec<&'static str>,
+    pub claims_supported: Vec<&'static str>,
+    pub prompt_values_supported: Vec<&'static str>,
+}
+
+impl ProviderMetadata {
+    pub fn new(issuer: &str) -> ProviderMetadata {
+        let issuer = issuer.trim_end_matches('/');
+        let endpoint = |path: &str| format!("{}{}", issuer, path);
+
+        ProviderMetadata {
+            issuer: issuer.to_string(),
+            authorization_endpoint: endpoint("/oauth/authorize"),
+            token_endpoint: endpoint("/oauth/token"),
+            userinfo_endpoint: endpoint("/userinfo"),
+            jwks_uri: endpoint("/.well-known/jwks.json"),
+            introspection_endpoint: endpoint("/oauth/introspect"),
+            revocation_endpoint: endpoint("/oauth/revoke"),
+            device_authorization_endpoint: endpoint("/oauth/device_authorization"),
+            scopes_supported: vec!["openid", "profile", "email"],
+            response_types_supported: vec!["code"],
+            grant_types_supported: vec![
+                "authorization_code",
+                "refresh_token",
+                "client_credentials",
+                DEVICE_CODE_GRANT_TYPE,
+            ],
+            subject_types_supported: vec!["public"],
+            id_token_signing_alg_values_supported: vec!["RS256"],
+            token_endpoint_auth_methods_supported: vec![
+                "none",
+                "client_secret_basic",
+                "client_secret_post",
+            ],
+            code_challenge_methods_supported: vec!["S256"],
+            claims_supported: vec![
+                "sub",
+                "iss",
+                "aud",
+                "exp",
+                "iat",
+                "auth_time",
+                "nonce",
+                "amr",
+                "acr",
+                "email",
+                "email_verified",
+                "updated_at",
+            ],
+            prompt_values_supported: vec!["none", "login"],
+        }
+    }
+}
@@ -1,21 +1,30 @@
 use anyhow::anyhow;
 use serde::Serialize;
 use thiserror::Error;
+use utoipa::ToSchema;
 
-use super::{auth_repo_errors::AuthRepositoryError, cache_errors::CacheOperationError};
+use super::{
+    audit::RequestContext, auth_repo_errors::AuthRepositoryError, cache_errors::CacheOperationError,
+};
 
 #[derive(Debug)]
 pub struct RefreshRequest {
     token: Token,
+    pub context: RequestContext,
 }
 
 impl RefreshRequest {
     pub fn new(token: String) -> RefreshRequest {
         RefreshRequest {
             token: Token(token),
+            context: RequestContext::default(),
         }
     }
 
+    pub fn with_context(self, context: RequestContext) -> RefreshRequest {
+        RefreshRequest { context, ..self }
+    }
+
     pub fn get_token(&self) -> &str {
         self.token.0.as_str()
     }
@@ -24,7 +33,7 @@ impl RefreshRequest {
 #[derive(Debug)]
 struct Token(String);
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RefreshResponse {
     pub access_token: String,
     pub access_token_max_age: i64,
@@ -36,6 +45,8 @@ pub enum RefreshTokenError {
     InvalidCredentials { reason: String },
     #[error("Refresh token not found")]
     MissingCredentials,
+    #[error("Refresh token expired")]
+    TokenExpired,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -1,7 +1,8 @@
 use crate::api::utils::security;
 
 use super::{
-    auth_repo_errors::AuthRepositoryError, user_email::UserEmail, user_password::UserPassword,
+    audit::RequestContext, auth_repo_errors::AuthRepositoryError,
+    registration::RegistrationInvitationToken, user_email::UserEmail, user_password::UserPassword,
 };
 use anyhow::{anyhow, Result};
 use thiserror::Error;
@@ -10,6 +11,10 @@ use thiserror::Error;
 pub struct RegisterUserRequest {
     pub email: UserEmail,
     pub hashed_password: HashedUserPassword,
+    /// The invitation the user registers with, redeemed by the repository together with the
+    /// creation of the account.
+    pub invitation: Option<RegistrationInvitationToken>,
+    pub context: RequestContext,
 }
 
 impl RegisterUserRequest {
@@ -17,14 +22,31 @@ impl RegisterUserRequest {
         RegisterUserRequest {
             email,
             hashed_password,
+            invitation: None,
+            context: RequestContext::default(),
         }
     }
+
+    pub fn with_invitation(self, invitation: RegistrationInvitationToken) -> Self {
+        RegisterUserRequest {
+            invitation: Some(invitation),
+            ..self
+        }
+    }
+
+    pub fn with_context(self, context: RequestContext) -> Self {
+        RegisterUserRequest { context, ..self }
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum RegisterUserError {
     #[error("user with email {email} already exists")]
     Duplicate { email: UserEmail },
+    #[error("registration is not allowed: {reason}")]
+    NotAllowed { reason: String },
+    #[error("invalid registration invitation: {reason}")]
+    InvalidInvitation { reason: String },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -33,6 +55,9 @@ impl From<AuthRepositoryError> for RegisterUserError {
     fn from(value: AuthRepositoryError) -> Self {
         match value {
             AuthRepositoryError::Duplicate { email } => RegisterUserError::Duplicate { email },
+            AuthRepositoryError::InvalidCredentials { reason } => {
+                RegisterUserError::InvalidInvitation { reason }
+            }
             _ => RegisterUserError::Unknown(anyhow!("Internal server error")),
         }
     }
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::api::utils::security::hash_token;
+
+use super::{
+    auth_repo_errors::AuthRepositoryError, organization::OrganizationRole, user_id::UserId,
+};
+
+/// How long a registration invitation can be used for.
+pub const REGISTRATION_INVITATION_MAX_AGE_DAYS: i64 = 7;
+
+/// Who may create an account, with `/api/register` or on their first federated, SAML or LDAP
+/// sign in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistrationMode {
+    /// Anyone.
+    Open,
+    /// Only holders of a registration invitation.
+    InviteOnly,
+    /// Anyone with an email address in one of the listed domains, and holders of an
+    /// invitation.
+    DomainAllowlist(Vec<String>),
+    /// No one. Only users who already have an account can sign in.
+    Closed,
+}
+
+impl RegistrationMode {
+    /// Whether `email` may register, given whether it holds a valid invitation.
+    pub fn allows(&self, email: &str, invited: bool) -> bool {
+        match self {
+            RegistrationMode::Open => true,
+            RegistrationMode::InviteOnly => invited,
+            RegistrationMode::DomainAllowlist(domains) => {
+                invited
+                    || email.rsplit_once('@').is_some_and(|(_, domain)| {
+                        domains
+                            .iter()
+                            .any(|allowed| allowed.eq_ignore_ascii_case(domain))
+                    })
+            }
+            RegistrationMode::Closed => false,
+        }
+    }
+}
+
+/// A pending registration invitation, without its token.
+///
+/// Invitees with an `organization_id` join that organization with `role` when they register.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct RegistrationInvitation {
+    pub id: uuid::Uuid,
+    pub email: String,
+    pub organization_id: Option<uuid::Uuid>,
+    pub role: OrganizationRole,
+    pub created_by: Option<uuid::Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A registration invitation token, as presented by the invitee.
+///
+/// Tokens are random strings from `generate_random_token`, so they can never pass for an access
+/// token. Only their digest is stored, next to the invited email, and redeeming a token
+/// deletes the invitation.
+#[derive(Debug)]
+pub struct RegistrationInvitationToken(String);
+
+impl RegistrationInvitationToken {
+    pub fn new(token: &str) -> RegistrationInvitationToken {
+        RegistrationInvitationToken(token.to_string())
+    }
+
+    pub fn get(&self) -> &str {
+        &self.0
+    }
+
+    /// The digest stored in place of the token, see `hash_token`.
+    pub fn hash(&self) -> String {
+        hash_token(&self.0)
+    }
+}
+
+/// A request from the administrator `created_by` to invite `email` to register.
+#[derive(Debug)]
+pub struct CreateRegistrationInvitationRequest {
+    pub email: String,
+    pub organization_id: Option<uuid::Uuid>,
+    pub role: OrganizationRole,
+    pub created_by: UserId,
+}
+
+/// The row inserted for a new invitation.
+#[derive(Debug)]
+pub struct NewRegistrationInvitation {
+    pub id: uuid::Uuid,
+    pub email: String,
+    pub organization_id: Option<uuid::Uuid>,
+    pub role: OrganizationRole,
+    pub token_hash: String,
+    pub created_by: uuid::Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A new invitation. `token` is the only time the token is available, and delivering it to
+/// the invitee is up to the caller.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateRegistrationInvitationResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub invitation: RegistrationInvitation,
+}
+
+#[derive(Debug, Error)]
+pub enum RegistrationInvitationError {
+    #[error("Invalid registration invitation request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("Registration invitation not found")]
+    NotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for RegistrationInvitationError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => RegistrationInvitationError::NotFound,
+            e => RegistrationInvitationError::Unknown(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_mode_allows() {
+        let allowlist = RegistrationMode::DomainAllowlist(vec!["example.com".to_string()]);
+
+        assert!(RegistrationMode::Open.allows("adrian@email.com", false));
+        assert!(!RegistrationMode::InviteOnly.allows("adrian@email.com", false));
+        assert!(RegistrationMode::InviteOnly.allows("adrian@email.com", true));
+        assert!(allowlist.allows("adrian@EXAMPLE.com", false));
+        assert!(!allowlist.allows("adrian@example.com.evil", false));
+        assert!(!allowlist.allows("adrian@sub.example.com", false));
+        assert!(allowlist.allows("adrian@email.com", true));
+        assert!(!RegistrationMode::Closed.allows("adrian@email.com", true));
+    }
+}
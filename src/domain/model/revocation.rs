@@ -0,0 +1,8 @@
+use super::oauth_client::ClientAuthentication;
+
+#[derive(Debug)]
+pub struct RevocationRequest {
+    pub client: ClientAuthentication,
+    pub token: String,
+    pub token_type_hint: Option<String>,
+}
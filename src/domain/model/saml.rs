@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A SAML 2.0 identity provider users can sign in with, for enterprises without OpenID Connect.
+///
+/// Identity providers are configured through `SAML_IDENTITY_PROVIDERS`, and `name` appears in
+/// the service provider paths, e.g. `/api/saml/okta/acs`. `certificate` is the PEM encoded
+/// certificate the provider signs assertions with.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SamlIdentityProvider {
+    pub name: String,
+    pub entity_id: String,
+    pub sso_url: String,
+    pub certificate: String,
+    /// Attribute holding the user's email, the `NameID` is used when unset.
+    pub email_attribute: Option<String>,
+    /// Whether the provider verifies the emails it asserts, which marks the users it creates as
+    /// verified. An assertion is never linked to an existing account with the same email.
+    #[serde(default)]
+    pub trust_email: bool,
+}
+
+/// The data kept in the cache between the `AuthnRequest` and the provider's response, keyed by
+/// the `RelayState` sent along with the request.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SamlRequestState {
+    pub relay_state: String,
+    pub provider: String,
+    pub request_id: String,
+    pub return_to: Option<String>,
+    pub max_age: i64,
+}
+
+/// A `SAMLResponse` posted to the assertion consumer service.
+#[derive(Debug)]
+pub struct SamlAssertionRequest {
+    pub provider: String,
+    pub saml_response: String,
+    pub relay_state: Option<String>,
+    pub state_digest: Option<String>,
+}
+
+/// An assertion whose signature, issuer, audience, recipient and validity period were checked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SamlAssertion {
+    pub id: String,
+    pub name_id: String,
+    pub attributes: HashMap<String, Vec<String>>,
+    /// When the assertion stops being valid, as a unix timestamp.
+    pub expires_at: i64,
+}
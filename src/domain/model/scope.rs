@@ -0,0 +1,39 @@
+use core::fmt::Display;
+
+/// A set of OAuth 2.0 scopes, parsed from and rendered as a space-delimited string.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scopes(Vec<String>);
+
+impl Scopes {
+    pub fn parse(raw: &str) -> Scopes {
+        let mut scopes: Vec<String> = vec![];
+        for scope in raw.split_whitespace() {
+            if !scopes.iter().any(|s| s == scope) {
+                scopes.push(scope.to_string());
+            }
+        }
+        Scopes(scopes)
+    }
+
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.iter().any(|s| s == scope)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_subset_of(&self, allowed: &[String]) -> bool {
+        self.0.iter().all(|s| allowed.contains(s))
+    }
+
+    pub fn get(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl Display for Scopes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
@@ -0,0 +1,118 @@
+use thiserror::Error;
+
+use super::{
+    auth_repo_errors::AuthRepositoryError, personal_access_token::PersonalAccessTokenError,
+    user_id::UserId,
+};
+
+/// Domain of the placeholder email service accounts get in place of a real address. The
+/// `.invalid` TLD is reserved, so no identity provider or directory can ever assert it.
+pub const SERVICE_ACCOUNT_EMAIL_DOMAIN: &str = "serviceaccount.invalid";
+
+/// A request to create the service account `name`, owned by the human user `owner_id`.
+#[derive(Debug)]
+pub struct CreateServiceAccountRequest {
+    pub name: String,
+    pub owner_id: UserId,
+}
+
+impl CreateServiceAccountRequest {
+    /// Names are lowercase DNS labels, so the placeholder email is always valid.
+    pub fn is_valid_name(&self) -> bool {
+        let name = self.name.as_bytes();
+        (3..=63).contains(&name.len())
+            && name[0].is_ascii_lowercase()
+            && name[name.len() - 1] != b'-'
+            && name
+                .iter()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'-')
+    }
+
+    pub fn email(&self) -> String {
+        format!("{}@{}", self.name, SERVICE_ACCOUNT_EMAIL_DOMAIN)
+    }
+}
+
+/// The row inserted for a new service account.
+#[derive(Debug)]
+pub struct NewServiceAccount {
+    pub email: String,
+    pub owner_id: uuid::Uuid,
+}
+
+/// A request to replace key `key_id` of a service account with a new one.
+///
+/// The old key keeps working for `grace_period_seconds`, so the new key can be rolled out
+/// before the old one stops working. With no grace period the old key is revoked immediately.
+#[derive(Debug)]
+pub struct RotateServiceAccountKeyRequest {
+    pub service_account_id: UserId,
+    pub key_id: uuid::Uuid,
+    pub grace_period_seconds: i64,
+}
+
+#[derive(Debug, Error)]
+pub enum ServiceAccountError {
+    #[error("Invalid service account request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("Service account {name} already exists")]
+    Duplicate { name: String },
+    #[error("Service account or key not found")]
+    NotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for ServiceAccountError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => ServiceAccountError::NotFound,
+            AuthRepositoryError::Duplicate { email } => ServiceAccountError::Duplicate {
+                name: email
+                    .get()
+                    .split('@')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+            },
+            e => ServiceAccountError::Unknown(e.into()),
+        }
+    }
+}
+
+impl From<PersonalAccessTokenError> for ServiceAccountError {
+    fn from(value: PersonalAccessTokenError) -> Self {
+        match value {
+            PersonalAccessTokenError::InvalidRequest { reason } => {
+                ServiceAccountError::InvalidRequest { reason }
+            }
+            PersonalAccessTokenError::NotFound => ServiceAccountError::NotFound,
+            PersonalAccessTokenError::Unknown(cause) => ServiceAccountError::Unknown(cause),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_account_name_validation() {
+        let request = |name: &str| CreateServiceAccountRequest {
+            name: name.to_string(),
+            owner_id: UserId::new(uuid::Uuid::new_v4()),
+        };
+
+        assert!(request("ci-deployer").is_valid_name());
+        assert!(request("backup2").is_valid_name());
+        assert!(!request("ci").is_valid_name());
+        assert!(!request("2fa-sync").is_valid_name());
+        assert!(!request("deployer-").is_valid_name());
+        assert!(!request("CI-Deployer").is_valid_name());
+        assert!(!request("ci@deployer").is_valid_name());
+        assert_eq!(
+            request("ci-deployer").email(),
+            "ci-deployer@serviceaccount.invalid"
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use super::{custom_claims::CustomClaims, token::TokenDetails};
+
+/// Claim carrying the id of the login session, shared by its access and refresh tokens and by
+/// every access token refreshed from it.
+pub const SESSION_ID_CLAIM: &str = "sid";
+
+/// Reads the login session of a token back from its claims. Tokens issued to OAuth clients and
+/// personal access tokens belong to no session.
+pub fn session_id(claims: &CustomClaims) -> Option<uuid::Uuid> {
+    claims
+        .get(SESSION_ID_CLAIM)
+        .and_then(|id| id.as_str())
+        .and_then(|id| uuid::Uuid::parse_str(id).ok())
+}
+
+/// Access tokens revoked before their `exp`, as broadcast to the services verifying tokens
+/// locally:
+///
+/// ```json
+/// {
+///   "token_uuid": "6f1c…",
+///   "session_id": "2d0a…",
+///   "user_id": "0b8e…",
+///   "revoked_at": 1792356300,
+///   "expires_at": 1792357200
+/// }
+/// ```
+///
+/// A revocation revokes the access token `token_uuid` and every token of the login session
+/// `session_id` issued at or before `revoked_at`. One naming neither, as when an administrator
+/// revokes the sessions of a user, revokes every token of the user `user_id` issued at or
+/// before `revoked_at`.
+///
+/// `expires_at` is when the last of those tokens expires, after which they are rejected anyway
+/// and the revocation no longer needs remembering.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SessionRevocation {
+    pub token_uuid: Option<uuid::Uuid>,
+    pub session_id: Option<uuid::Uuid>,
+    pub user_id: Option<uuid::Uuid>,
+    pub revoked_at: i64,
+    pub expires_at: i64,
+}
+
+impl SessionRevocation {
+    /// Revokes the access token `token_uuid`, which expires at `expires_at`.
+    pub fn token(token_uuid: uuid::Uuid, expires_at: i64) -> SessionRevocation {
+        SessionRevocation {
+            token_uuid: Some(token_uuid),
+            session_id: None,
+            user_id: None,
+            revoked_at: Utc::now().timestamp(),
+            expires_at,
+        }
+    }
+
+    /// Revokes every token of `user_id` issued so far. `max_age` is the lifetime of access
+    /// tokens in seconds.
+    pub fn user(user_id: uuid::Uuid, max_age: i64) -> SessionRevocation {
+        let revoked_at = Utc::now().timestamp();
+
+        SessionRevocation {
+            token_uuid: None,
+            session_id: None,
+            user_id: Some(user_id),
+            revoked_at,
+            expires_at: revoked_at + max_age,
+        }
+    }
+
+    /// Also revokes every token of the login session `session_id` issued so far, which
+    /// outlive the revocation by at most `max_age` seconds.
+    pub fn with_session(self, session_id: Option<uuid::Uuid>, max_age: i64) -> SessionRevocation {
+        match session_id {
+            Some(_) => SessionRevocation {
+                session_id,
+                expires_at: self.expires_at.max(self.revoked_at + max_age),
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    pub fn with_user(self, user_id: Option<uuid::Uuid>) -> SessionRevocation {
+        SessionRevocation { user_id, ..self }
+    }
+}
+
+/// When the tokens revoked by a session or user wide revocation were revoked, and when the
+/// last of them expires.
+#[derive(Clone, Copy, Debug)]
+struct RevokedUntil {
+    revoked_at: i64,
+    expires_at: i64,
+}
+
+impl RevokedUntil {
+    /// Merges two revocations of the same session or user, the later one revoking more tokens.
+    fn merge(self, other: RevokedUntil) -> RevokedUntil {
+        RevokedUntil {
+            revoked_at: self.revoked_at.max(other.revoked_at),
+            expires_at: self.expires_at.max(other.expires_at),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    tokens: HashMap<uuid::Uuid, i64>,
+    sessions: HashMap<uuid::Uuid, RevokedUntil>,
+    users: HashMap<uuid::Uuid, RevokedUntil>,
+}
+
+impl Entries {
+    fn prune(&mut self, now: i64) {
+        self.tokens.retain(|_, expires_at| *expires_at > now);
+        self.sessions.retain(|_, revoked| revoked.expires_at > now);
+        self.users.retain(|_, revoked| revoked.expires_at > now);
+    }
+}
+
+/// Local denylist of revoked access tokens, fed by `RevocationSubscriber`, so that services
+/// verifying tokens themselves can reject a revoked one without asking Redis on every request.
+///
+/// Revocations are kept until the tokens they revoke expire, and expired ones are dropped
+/// whenever another is added, so the denylist only ever holds tokens that would otherwise still
+/// be accepted. Clones share the same entries.
+#[derive(Clone, Debug, Default)]
+pub struct RevocationDenylist {
+    entries: Arc<RwLock<Entries>>,
+}
+
+impl RevocationDenylist {
+    /// Adds `revocation`, dropping the revocations that expired in the meantime.
+    pub fn insert(&self, revocation: &SessionRevocation) {
+        let now = Utc::now().timestamp();
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+
+        entries.prune(now);
+        if revocation.expires_at <= now {
+            return;
+        }
+
+        let revoked = RevokedUntil {
+            revoked_at: revocation.revoked_at,
+            expires_at: revocation.expires_at,
+        };
+        if let Some(token_uuid) = revocation.token_uuid {
+            entries.tokens.insert(token_uuid, revocation.expires_at);
+        }
+        if let Some(session_id) = revocation.session_id {
+            let merged = entries
+                .sessions
+                .get(&session_id)
+                .map_or(revoked, |existing| existing.merge(revoked));
+            entries.sessions.insert(session_id, merged);
+        }
+        if let (None, None, Some(user_id)) = (
+            revocation.token_uuid,
+            revocation.session_id,
+            revocation.user_id,
+        ) {
+            let merged = entries
+                .users
+                .get(&user_id)
+                .map_or(revoked, |existing| existing.merge(revoked));
+            entries.users.insert(user_id, merged);
+        }
+    }
+
+    /// Whether the verified access token `token` was revoked, by its `token_uuid`, its `sid`
+    /// session or its subject.
+    pub fn is_revoked(&self, token: &TokenDetails) -> bool {
+        let now = Utc::now().timestamp();
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        let revokes = |revoked: &RevokedUntil| {
+            revoked.expires_at > now && token.issued_at <= revoked.revoked_at
+        };
+
+        entries
+            .tokens
+            .get(&token.token_uuid)
+            .is_some_and(|expires_at| *expires_at > now)
+            || session_id(&token.claims)
+                .and_then(|session_id| entries.sessions.get(&session_id))
+                .is_some_and(revokes)
+            || entries.users.get(&token.user_id).is_some_and(revokes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(user_id: uuid::Uuid, session: Option<uuid::Uuid>, issued_at: i64) -> TokenDetails {
+        let mut claims = CustomClaims::default();
+        if let Some(session_id) = session {
+            claims.insert(SESSION_ID_CLAIM, serde_json::json!(session_id));
+        }
+
+        TokenDetails {
+            token: None,
+            token_uuid: uuid::Uuid::new_v4(),
+            user_id,
+            issued_at,
+            expires_in: Some(issued_at + 900),
+            claims,
+        }
+    }
+
+    #[test]
+    fn test_denylist_rejects_revoked_tokens_until_expiry() {
+        let denylist = RevocationDenylist::default();
+        let now = Utc::now().timestamp();
+        let active = token(uuid::Uuid::new_v4(), None, now);
+        let expired = token(uuid::Uuid::new_v4(), None, now);
+
+        denylist
+            .clone()
+            .insert(&SessionRevocation::token(active.token_uuid, now + 60));
+        denylist.insert(&SessionRevocation::token(expired.token_uuid, now - 1));
+
+        assert!(denylist.is_revoked(&active));
+        assert!(!denylist.is_revoked(&expired));
+        assert!(!denylist.is_revoked(&token(uuid::Uuid::new_v4(), None, now)));
+    }
+
+    #[test]
+    fn test_denylist_rejects_tokens_of_revoked_session_issued_before() {
+        let denylist = RevocationDenylist::default();
+        let user_id = uuid::Uuid::new_v4();
+        let session_id = uuid::Uuid::new_v4();
+        let now = Utc::now().timestamp();
+        let logged_out = token(user_id, Some(session_id), now);
+
+        denylist.insert(
+            &SessionRevocation::token(logged_out.token_uuid, now + 60)
+                .with_session(Some(session_id), 900)
+                .with_user(Some(user_id)),
+        );
+
+        assert!(denylist.is_revoked(&logged_out));
+        assert!(denylist.is_revoked(&token(user_id, Some(session_id), now - 60)));
+        assert!(!denylist.is_revoked(&token(user_id, Some(session_id), now + 1)));
+        assert!(!denylist.is_revoked(&token(user_id, Some(uuid::Uuid::new_v4()), now)));
+        assert!(!denylist.is_revoked(&token(user_id, None, now)));
+    }
+
+    #[test]
+    fn test_denylist_rejects_tokens_of_revoked_user_issued_before() {
+        let denylist = RevocationDenylist::default();
+        let user_id = uuid::Uuid::new_v4();
+        let now = Utc::now().timestamp();
+
+        denylist.insert(&SessionRevocation::user(user_id, 900));
+
+        assert!(denylist.is_revoked(&token(user_id, None, now)));
+        assert!(denylist.is_revoked(&token(user_id, Some(uuid::Uuid::new_v4()), now - 60)));
+        assert!(!denylist.is_revoked(&token(user_id, None, now + 1)));
+        assert!(!denylist.is_revoked(&token(uuid::Uuid::new_v4(), None, now)));
+    }
+
+    #[test]
+    fn test_denylist_drops_expired_revocations() {
+        let denylist = RevocationDenylist::default();
+        let now = Utc::now().timestamp();
+        let expired = SessionRevocation {
+            token_uuid: Some(uuid::Uuid::new_v4()),
+            session_id: Some(uuid::Uuid::new_v4()),
+            user_id: None,
+            revoked_at: now - 120,
+            expires_at: now + 1,
+        };
+
+        denylist.insert(&expired);
+        denylist.insert(&SessionRevocation::user(uuid::Uuid::new_v4(), 900));
+        {
+            let entries = denylist.entries.read().unwrap();
+            assert_eq!(entries.tokens.len(), 1);
+            assert_eq!(entries.sessions.len(), 1);
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        denylist.insert(&SessionRevocation::user(uuid::Uuid::new_v4(), 900));
+
+        let entries = denylist.entries.read().unwrap();
+        assert!(entries.tokens.is_empty());
+        assert!(entries.sessions.is_empty());
+        assert_eq!(entries.users.len(), 2);
+    }
+}
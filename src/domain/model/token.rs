@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use super::{
+    custom_claims::{CustomClaims, PRINCIPAL_TYPE_CLAIM},
+    principal::PrincipalType,
+};
+
 //TODO: add getters and setters
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
@@ -8,6 +13,8 @@ pub struct TokenClaims {
     pub exp: i64,
     pub iat: i64,
     pub nbf: i64,
+    #[serde(flatten)]
+    pub custom: CustomClaims,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,7 +22,23 @@ pub struct TokenDetails {
     pub token: Option<String>,
     pub token_uuid: uuid::Uuid,
     pub user_id: uuid::Uuid,
+    pub issued_at: i64,
     pub expires_in: Option<i64>,
+    pub claims: CustomClaims,
+}
+
+impl TokenDetails {
+    /// Tokens without a `principal_type` claim were issued to users.
+    pub fn principal_type(&self) -> PrincipalType {
+        match self
+            .claims
+            .get(PRINCIPAL_TYPE_CLAIM)
+            .and_then(|t| t.as_str())
+        {
+            Some("client") => PrincipalType::Client,
+            _ => PrincipalType::User,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -6,6 +6,10 @@ impl TokenUuid {
         TokenUuid(value)
     }
 
+    pub fn get(&self) -> &uuid::Uuid {
+        &self.0
+    }
+
     pub fn get_string(&self) -> String {
         self.0.to_string()
     }
@@ -1,5 +1,68 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Role granting access to the admin endpoints.
+pub const ADMIN_ROLE: &str = "admin";
+
+/// Whether a user is a person or a service account.
+///
+/// Service accounts are non-human principals for other systems. They have no password, cannot
+/// log in interactively, authenticate only with their API keys, and are owned by a human user.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UserKind {
+    #[default]
+    Human,
+    Service,
+}
+
+impl UserKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserKind::Human => "human",
+            UserKind::Service => "service",
+        }
+    }
+}
+
+/// The `kind` column is constrained to `human` and `service`.
+impl From<String> for UserKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "service" => UserKind::Service,
+            _ => UserKind::Human,
+        }
+    }
+}
+
+/// Whether a user may sign in. Suspended users keep their data but cannot authenticate.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    Suspended,
+}
+
+impl UserStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserStatus::Active => "active",
+            UserStatus::Suspended => "suspended",
+        }
+    }
+}
+
+/// The `status` column is constrained to `active` and `suspended`.
+impl From<String> for UserStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "suspended" => UserStatus::Suspended,
+            _ => UserStatus::Active,
+        }
+    }
+}
 
 #[derive(Clone, Debug, Deserialize, sqlx::FromRow, Serialize)]
 pub struct User {
@@ -8,6 +71,15 @@ pub struct User {
     pub password: String,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub roles: Vec<String>,
+    pub email_verified: bool,
+    #[sqlx(try_from = "String")]
+    pub kind: UserKind,
+    pub owner_id: Option<uuid::Uuid>,
+    #[sqlx(try_from = "String")]
+    pub status: UserStatus,
+    pub password_reset_required: bool,
+    pub sessions_revoked_at: Option<DateTime<Utc>>,
 }
 
 impl User {
@@ -19,14 +91,47 @@ impl User {
             password: password.to_string(),
             created_at: Some(now),
             updated_at: Some(now),
+            roles: vec![],
+            email_verified: false,
+            kind: UserKind::Human,
+            owner_id: None,
+            status: UserStatus::Active,
+            password_reset_required: false,
+            sessions_revoked_at: None,
         }
     }
+
+    pub fn is_service_account(&self) -> bool {
+        self.kind == UserKind::Service
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.roles.iter().any(|role| role == ADMIN_ROLE)
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.status == UserStatus::Suspended
+    }
+
+    /// Whether a token issued at `issued_at`, in seconds since the epoch, belongs to a session
+    /// revoked by an administrator.
+    ///
+    /// `iat` only has a one second resolution, so tokens issued in the second of the
+    /// revocation are revoked too.
+    pub fn is_session_revoked(&self, issued_at: i64) -> bool {
+        self.sessions_revoked_at
+            .is_some_and(|revoked_at| issued_at <= revoked_at.timestamp())
+    }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 pub struct FilteredUser {
     pub id: uuid::Uuid,
     pub email: String,
+    pub email_verified: bool,
+    pub kind: UserKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<uuid::Uuid>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -36,6 +141,9 @@ impl From<&User> for FilteredUser {
         Self {
             id: user.id,
             email: user.email.to_string(),
+            email_verified: user.email_verified,
+            kind: user.kind,
+            owner_id: user.owner_id,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
@@ -0,0 +1,62 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{scope::Scopes, user::FilteredUser};
+
+/// Claims returned by the OpenID Connect userinfo endpoint.
+///
+/// `sub` is always present. The other claims are only released when the access token
+/// was granted the matching scope: `profile` for `updated_at`, `email` for `email` and
+/// `email_verified`.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct UserInfo {
+    pub sub: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+impl UserInfo {
+    pub fn new(user: &FilteredUser, scope: &Scopes) -> UserInfo {
+        let email = scope.contains("email");
+        let profile = scope.contains("profile");
+
+        UserInfo {
+            sub: user.id.to_string(),
+            email: email.then(|| user.email.to_string()),
+            email_verified: email.then_some(user.email_verified),
+            updated_at: profile
+                .then_some(user.updated_at)
+                .flatten()
+                .map(|updated_at| updated_at.timestamp()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::user::User;
+
+    #[test]
+    fn test_userinfo_filtered_by_scope() {
+        let user = FilteredUser::from(&User::new("adrian@email.com", "password"));
+
+        let info = UserInfo::new(&user, &Scopes::parse("openid"));
+        assert_eq!(info.sub, user.id.to_string());
+        assert!(info.email.is_none());
+        assert!(info.updated_at.is_none());
+
+        let info = UserInfo::new(&user, &Scopes::parse("openid email"));
+        assert_eq!(info.email.as_deref(), Some("adrian@email.com"));
+        assert_eq!(info.email_verified, Some(false));
+        assert!(info.updated_at.is_none());
+
+        let info = UserInfo::new(&user, &Scopes::parse("openid profile"));
+        assert!(info.email.is_none());
+        assert!(info.updated_at.is_some());
+    }
+}
@@ -0,0 +1,427 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::{auth_repo_errors::AuthRepositoryError, user_id::UserId};
+
+/// Prefix of webhook secrets, so they are recognizable when leaked.
+pub const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+/// Deliveries claimed by the worker at a time.
+pub const WEBHOOK_BATCH_SIZE: i64 = 10;
+
+/// How long claimed deliveries are left to the worker that claimed them. A batch is sent one
+/// delivery after the other, each allowed 10 seconds, so the lease outlasts the batch.
+pub const WEBHOOK_LEASE_SECONDS: i64 = 120;
+
+/// Deliveries listed for a subscription, newest first.
+pub const WEBHOOK_DELIVERIES_LIMIT: i64 = 100;
+
+/// An authentication event that subscribers can be notified of.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum WebhookEventType {
+    #[serde(rename = "user.registered")]
+    UserRegistered,
+    #[serde(rename = "user.login_failed")]
+    UserLoginFailed,
+    #[serde(rename = "session.revoked")]
+    SessionRevoked,
+    #[serde(rename = "password.changed")]
+    PasswordChanged,
+}
+
+impl WebhookEventType {
+    pub const ALL: [WebhookEventType; 4] = [
+        WebhookEventType::UserRegistered,
+        WebhookEventType::UserLoginFailed,
+        WebhookEventType::SessionRevoked,
+        WebhookEventType::PasswordChanged,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::UserRegistered => "user.registered",
+            WebhookEventType::UserLoginFailed => "user.login_failed",
+            WebhookEventType::SessionRevoked => "session.revoked",
+            WebhookEventType::PasswordChanged => "password.changed",
+        }
+    }
+
+    pub fn parse(event_type: &str) -> Option<WebhookEventType> {
+        WebhookEventType::ALL
+            .into_iter()
+            .find(|known| known.as_str() == event_type)
+    }
+}
+
+/// An event to be delivered to every subscription that asked for its type.
+///
+/// `id` is the same in every delivery of the event, so receivers can tell retries and
+/// duplicates apart from new events.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WebhookEvent {
+    pub id: uuid::Uuid,
+    pub event_type: WebhookEventType,
+    pub data: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookEvent {
+    pub fn new(event_type: WebhookEventType, data: serde_json::Value) -> WebhookEvent {
+        WebhookEvent {
+            id: uuid::Uuid::new_v4(),
+            event_type,
+            data,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn user_registered(user_id: uuid::Uuid, email: &str) -> WebhookEvent {
+        WebhookEvent::new(
+            WebhookEventType::UserRegistered,
+            serde_json::json!({ "user_id": user_id, "email": email }),
+        )
+    }
+
+    /// `user_id` is only known when the email belongs to a user.
+    pub fn user_login_failed(
+        user_id: Option<uuid::Uuid>,
+        email: &str,
+        reason: &str,
+    ) -> WebhookEvent {
+        WebhookEvent::new(
+            WebhookEventType::UserLoginFailed,
+            serde_json::json!({ "user_id": user_id, "email": email, "reason": reason }),
+        )
+    }
+
+    /// `all_sessions` is set when every session of the user was revoked at once, rather than
+    /// the one that logged out.
+    pub fn session_revoked(user_id: uuid::Uuid, all_sessions: bool, reason: &str) -> WebhookEvent {
+        WebhookEvent::new(
+            WebhookEventType::SessionRevoked,
+            serde_json::json!({
+                "user_id": user_id,
+                "all_sessions": all_sessions,
+                "reason": reason,
+            }),
+        )
+    }
+
+    pub fn password_changed(user_id: uuid::Uuid, reason: &str) -> WebhookEvent {
+        WebhookEvent::new(
+            WebhookEventType::PasswordChanged,
+            serde_json::json!({ "user_id": user_id, "reason": reason }),
+        )
+    }
+
+    /// The body POSTed to subscribers.
+    pub fn payload(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "type": self.event_type,
+            "created_at": self.created_at,
+            "data": self.data,
+        })
+    }
+}
+
+/// A subscription to the events listed in `events`, as shown to administrators. Its secret is
+/// only shown when it is created.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct WebhookSubscription {
+    pub id: uuid::Uuid,
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_by: Option<uuid::Uuid>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A request from the administrator `created_by` to deliver `events` to `url`.
+#[derive(Debug)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_by: UserId,
+}
+
+impl CreateWebhookRequest {
+    pub fn validate(&self) -> Result<(), WebhookError> {
+        let url = url::Url::parse(&self.url).map_err(|_| WebhookError::InvalidRequest {
+            reason: "url must be an absolute URL".to_string(),
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(WebhookError::InvalidRequest {
+                reason: "url must be an http or https URL".to_string(),
+            });
+        }
+
+        if self.events.is_empty() {
+            return Err(WebhookError::InvalidRequest {
+                reason: "events must list at least one event type".to_string(),
+            });
+        }
+        if let Some(unknown) = self
+            .events
+            .iter()
+            .find(|event| WebhookEventType::parse(event).is_none())
+        {
+            return Err(WebhookError::InvalidRequest {
+                reason: format!("Unknown event type {}", unknown),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The row inserted for a new subscription. The secret is kept as is, since every delivery is
+/// signed with it.
+#[derive(Debug)]
+pub struct NewWebhookSubscription {
+    pub url: String,
+    pub events: Vec<String>,
+    pub secret: String,
+    pub created_by: uuid::Uuid,
+}
+
+/// A new subscription. `secret` is the only time the secret is available.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateWebhookResponse {
+    pub secret: String,
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+}
+
+/// Where a delivery is at. Deliveries that ran out of attempts are `dead` until an
+/// administrator retries them.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Dead => "dead",
+        }
+    }
+}
+
+/// The `status` column is constrained to `pending`, `delivered` and `dead`.
+impl From<String> for WebhookDeliveryStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "delivered" => WebhookDeliveryStatus::Delivered,
+            "dead" => WebhookDeliveryStatus::Dead,
+            _ => WebhookDeliveryStatus::Pending,
+        }
+    }
+}
+
+/// An event queued for a subscription in the outbox, with the outcome of its attempts.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: uuid::Uuid,
+    pub subscription_id: uuid::Uuid,
+    pub event_id: uuid::Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    #[sqlx(try_from = "String")]
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// The deliveries of `subscription_id`, optionally only those with `status`.
+#[derive(Debug)]
+pub struct ListWebhookDeliveriesRequest {
+    pub subscription_id: uuid::Uuid,
+    pub status: Option<WebhookDeliveryStatus>,
+}
+
+/// A delivery claimed by the worker, with what it needs to send it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingWebhookDelivery {
+    pub id: uuid::Uuid,
+    pub url: String,
+    pub secret: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+}
+
+/// What becomes of a delivery after an attempt.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WebhookAttemptOutcome {
+    Delivered,
+    Retry {
+        next_attempt_at: DateTime<Utc>,
+        error: String,
+    },
+    Dead {
+        error: String,
+    },
+}
+
+/// The outcome of attempt number `attempts` of a delivery.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WebhookAttempt {
+    pub delivery_id: uuid::Uuid,
+    pub attempts: i32,
+    pub outcome: WebhookAttemptOutcome,
+}
+
+impl WebhookAttempt {
+    pub fn delivered(delivery: &PendingWebhookDelivery) -> WebhookAttempt {
+        WebhookAttempt {
+            delivery_id: delivery.id,
+            attempts: delivery.attempts + 1,
+            outcome: WebhookAttemptOutcome::Delivered,
+        }
+    }
+
+    /// A failed attempt is retried after `backoff_seconds`, doubling with every attempt, until
+    /// `max_attempts` were made and the delivery is dead.
+    pub fn failed(
+        delivery: &PendingWebhookDelivery,
+        error: &str,
+        max_attempts: i32,
+        backoff_seconds: i64,
+    ) -> WebhookAttempt {
+        let attempts = delivery.attempts + 1;
+        let error = error.to_string();
+
+        let outcome = if attempts >= max_attempts {
+            WebhookAttemptOutcome::Dead { error }
+        } else {
+            let backoff = backoff_seconds.saturating_mul(1 << (attempts - 1).min(30));
+            WebhookAttemptOutcome::Retry {
+                next_attempt_at: Utc::now() + Duration::seconds(backoff),
+                error,
+            }
+        };
+
+        WebhookAttempt {
+            delivery_id: delivery.id,
+            attempts,
+            outcome,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Invalid webhook request: {reason}")]
+    InvalidRequest { reason: String },
+    #[error("Webhook or delivery not found")]
+    NotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<AuthRepositoryError> for WebhookError {
+    fn from(value: AuthRepositoryError) -> Self {
+        match value {
+            AuthRepositoryError::InvalidCredentials { .. } => WebhookError::NotFound,
+            e => WebhookError::Unknown(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_delivery(attempts: i32) -> PendingWebhookDelivery {
+        PendingWebhookDelivery {
+            id: uuid::Uuid::new_v4(),
+            url: "http://localhost:9000/hooks".to_string(),
+            secret: "whsec_secret".to_string(),
+            event_type: "user.registered".to_string(),
+            payload: serde_json::json!({}),
+            attempts,
+        }
+    }
+
+    #[test]
+    fn test_webhook_event_type_names() {
+        for event_type in WebhookEventType::ALL {
+            assert_eq!(
+                WebhookEventType::parse(event_type.as_str()),
+                Some(event_type)
+            );
+            assert_eq!(
+                serde_json::json!(event_type),
+                serde_json::json!(event_type.as_str())
+            );
+        }
+        assert_eq!(WebhookEventType::parse("user.deleted"), None);
+    }
+
+    #[test]
+    fn test_create_webhook_request_validation() {
+        let request = |url: &str, events: &[&str]| CreateWebhookRequest {
+            url: url.to_string(),
+            events: events.iter().map(|event| event.to_string()).collect(),
+            created_by: UserId::new(uuid::Uuid::new_v4()),
+        };
+
+        assert!(
+            request("https://crm.example.com/hooks", &["user.registered"])
+                .validate()
+                .is_ok()
+        );
+        assert!(request("ftp://crm.example.com", &["user.registered"])
+            .validate()
+            .is_err());
+        assert!(request("/hooks", &["user.registered"]).validate().is_err());
+        assert!(request("https://crm.example.com", &[]).validate().is_err());
+        assert!(request("https://crm.example.com", &["user.deleted"])
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_failed_attempts_back_off_exponentially() {
+        let retry_delay = |attempts: i32| match WebhookAttempt::failed(
+            &pending_delivery(attempts),
+            "HTTP 500",
+            5,
+            30,
+        )
+        .outcome
+        {
+            WebhookAttemptOutcome::Retry {
+                next_attempt_at, ..
+            } => (next_attempt_at - Utc::now()).num_seconds() + 1,
+            outcome => panic!("Unexpected outcome {:?}", outcome),
+        };
+
+        assert_eq!(retry_delay(0), 30);
+        assert_eq!(retry_delay(1), 60);
+        assert_eq!(retry_delay(3), 240);
+    }
+
+    #[test]
+    fn test_failed_attempts_dead_letter_after_max_attempts() {
+        let attempt = WebhookAttempt::failed(&pending_delivery(4), "HTTP 500", 5, 30);
+
+        assert_eq!(attempt.attempts, 5);
+        assert_eq!(
+            attempt.outcome,
+            WebhookAttemptOutcome::Dead {
+                error: "HTTP 500".to_string()
+            }
+        );
+    }
+}
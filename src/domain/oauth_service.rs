@@ -0,0 +1,64 @@
+use crate::domain::model::{
+    authorize::{AuthorizeError, AuthorizeRequest, AuthorizeResponse},
+    device_authorization::{
+        DeviceAuthorizationRequest, DeviceAuthorizationResponse, DeviceVerificationRequest,
+        DeviceVerificationResponse,
+    },
+    introspection::{IntrospectionRequest, IntrospectionResponse},
+    oauth_errors::OAuthError,
+    oauth_token::{TokenRequest, TokenResponse},
+    revocation::RevocationRequest,
+};
+
+use std::future::Future;
+
+/// Trait representing the OAuth 2.0 authorization server.
+///
+/// The `OAuthService` trait defines the authorization endpoint, which issues authorization
+/// codes to already authenticated users, the token endpoint, which exchanges grants
+/// for access and refresh tokens, and the introspection and revocation endpoints used by
+/// resource servers and clients to inspect and invalidate tokens, and the device authorization
+/// grant for input constrained devices. Implementations reuse
+/// the same token machinery as `AuthService`, so tokens issued here are accepted everywhere
+/// a login token is.
+///
+/// # Implementors
+///
+/// Any struct that implements the `OAuthService` trait must be `Send`, `Sync`, and `'static`.
+pub trait OAuthService: Send + Sync + 'static {
+    fn authorize(
+        &self,
+        request: &AuthorizeRequest,
+    ) -> impl Future<Output = Result<AuthorizeResponse, AuthorizeError>> + Send;
+
+    fn token(
+        &self,
+        request: &TokenRequest,
+    ) -> impl Future<Output = Result<TokenResponse, OAuthError>> + Send;
+
+    /// Reports whether a token is active, per RFC 7662. Only confidential clients may introspect.
+    fn introspect(
+        &self,
+        request: &IntrospectionRequest,
+    ) -> impl Future<Output = Result<IntrospectionResponse, OAuthError>> + Send;
+
+    /// Revokes an access or refresh token issued to the requesting client, per RFC 7009.
+    ///
+    /// Succeeds for tokens that are already invalid, so clients cannot probe for valid tokens.
+    fn revoke(
+        &self,
+        request: &RevocationRequest,
+    ) -> impl Future<Output = Result<(), OAuthError>> + Send;
+
+    /// Starts a device authorization grant, per RFC 8628, returning the device and user codes.
+    fn device_authorization(
+        &self,
+        request: &DeviceAuthorizationRequest,
+    ) -> impl Future<Output = Result<DeviceAuthorizationResponse, OAuthError>> + Send;
+
+    /// Records an authenticated user's approval or denial of a pending device authorization.
+    fn verify_device(
+        &self,
+        request: &DeviceVerificationRequest,
+    ) -> impl Future<Output = Result<DeviceVerificationResponse, OAuthError>> + Send;
+}
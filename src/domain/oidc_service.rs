@@ -0,0 +1,27 @@
+use crate::domain::model::{
+    auth_middleware::AuthMiddleware, jwks::JwkSet, oauth_errors::OAuthError,
+    provider_metadata::ProviderMetadata, userinfo::UserInfo,
+};
+
+use std::future::Future;
+
+/// Trait representing the OpenID Connect provider endpoints.
+///
+/// ID tokens are issued by the `OAuthService` token endpoint whenever the `openid` scope
+/// was granted. The `OidcService` trait covers the endpoints relying parties use around
+/// them: the userinfo endpoint, provider discovery and the JWKS used to verify ID tokens.
+///
+/// # Implementors
+///
+/// Any struct that implements the `OidcService` trait must be `Send`, `Sync`, and `'static`.
+pub trait OidcService: Send + Sync + 'static {
+    /// Returns the claims about the authenticated user allowed by the access token's scope.
+    fn userinfo(
+        &self,
+        auth: &AuthMiddleware,
+    ) -> impl Future<Output = Result<UserInfo, OAuthError>> + Send;
+
+    fn provider_metadata(&self) -> impl Future<Output = ProviderMetadata> + Send;
+
+    fn jwks(&self) -> impl Future<Output = Result<JwkSet, OAuthError>> + Send;
+}
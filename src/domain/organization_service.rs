@@ -0,0 +1,65 @@
+use crate::domain::model::{
+    login_response::LoginResponse,
+    organization::{
+        AcceptInvitationRequest, CreateInvitationResponse, CreateOrganizationRequest,
+        InviteMemberRequest, Organization, OrganizationError, OrganizationMember,
+        OrganizationMembersRequest, OrganizationMembership, RemoveMemberRequest,
+        SwitchOrganizationRequest,
+    },
+    user_id::UserId,
+};
+
+use std::future::Future;
+
+/// Trait representing organizations, the tenants users belong to, and their memberships.
+///
+/// Every operation on an organization is made on behalf of a user and is isolated to the
+/// organizations that user belongs to: an organization the user is not a member of behaves
+/// as if it did not exist, with `OrganizationError::NotFound`. Within an organization, only
+/// owners and admins manage members, and only owners manage other owners.
+///
+/// # Implementors
+///
+/// Any struct that implements the `OrganizationService` trait must be `Send`, `Sync`, and
+/// `'static`.
+pub trait OrganizationService: Send + Sync + 'static {
+    /// Creates an organization owned by the requesting user.
+    fn create_organization(
+        &self,
+        request: &CreateOrganizationRequest,
+    ) -> impl Future<Output = Result<Organization, OrganizationError>> + Send;
+
+    /// Lists the organizations the user belongs to, with their role in each.
+    fn list_organizations(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<Vec<OrganizationMembership>, OrganizationError>> + Send;
+
+    fn list_members(
+        &self,
+        request: &OrganizationMembersRequest,
+    ) -> impl Future<Output = Result<Vec<OrganizationMember>, OrganizationError>> + Send;
+
+    fn invite_member(
+        &self,
+        request: &InviteMemberRequest,
+    ) -> impl Future<Output = Result<CreateInvitationResponse, OrganizationError>> + Send;
+
+    fn accept_invitation(
+        &self,
+        request: &AcceptInvitationRequest,
+    ) -> impl Future<Output = Result<OrganizationMembership, OrganizationError>> + Send;
+
+    /// Removes a member. Any member may leave, but an organization always keeps an owner.
+    fn remove_member(
+        &self,
+        request: &RemoveMemberRequest,
+    ) -> impl Future<Output = Result<(), OrganizationError>> + Send;
+
+    /// Reissues the session's tokens with another active organization, revoking the current
+    /// access token.
+    fn switch_organization(
+        &self,
+        request: &SwitchOrganizationRequest,
+    ) -> impl Future<Output = Result<LoginResponse, OrganizationError>> + Send;
+}
@@ -0,0 +1,37 @@
+use crate::domain::model::{
+    personal_access_token::{
+        CreatePersonalAccessTokenRequest, CreatePersonalAccessTokenResponse, PersonalAccessToken,
+        PersonalAccessTokenError, RevokePersonalAccessTokenRequest,
+    },
+    user_id::UserId,
+};
+
+use std::future::Future;
+
+/// Trait representing the management of users' personal access tokens.
+///
+/// Personal access tokens are long-lived, scoped credentials for scripts and other tools that
+/// cannot go through a login. Only a hash of each token is stored, so the secret is returned
+/// once by `create_personal_access_token` and never again. `AuthService::auth` resolves the
+/// tokens to their owner.
+///
+/// # Implementors
+///
+/// Any struct that implements the `PersonalAccessTokenService` trait must be `Send`, `Sync`,
+/// and `'static`.
+pub trait PersonalAccessTokenService: Send + Sync + 'static {
+    fn create_personal_access_token(
+        &self,
+        request: &CreatePersonalAccessTokenRequest,
+    ) -> impl Future<Output = Result<CreatePersonalAccessTokenResponse, PersonalAccessTokenError>> + Send;
+
+    fn list_personal_access_tokens(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<Vec<PersonalAccessToken>, PersonalAccessTokenError>> + Send;
+
+    fn revoke_personal_access_token(
+        &self,
+        request: &RevokePersonalAccessTokenRequest,
+    ) -> impl Future<Output = Result<(), PersonalAccessTokenError>> + Send;
+}
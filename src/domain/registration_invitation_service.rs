@@ -0,0 +1,34 @@
+use crate::domain::model::registration::{
+    CreateRegistrationInvitationRequest, CreateRegistrationInvitationResponse,
+    RegistrationInvitation, RegistrationInvitationError,
+};
+
+use std::future::Future;
+
+/// Trait representing the administration of registration invitations.
+///
+/// Invitations let a specific email register when the registration mode would otherwise
+/// refuse it, optionally joining an organization with a given role. Each invitation can be
+/// redeemed once, and revoking it makes its token worthless.
+///
+/// # Implementors
+///
+/// Any struct that implements the `RegistrationInvitationService` trait must be `Send`,
+/// `Sync`, and `'static`.
+pub trait RegistrationInvitationService: Send + Sync + 'static {
+    fn create_registration_invitation(
+        &self,
+        request: &CreateRegistrationInvitationRequest,
+    ) -> impl Future<
+        Output = Result<CreateRegistrationInvitationResponse, RegistrationInvitationError>,
+    > + Send;
+
+    fn list_registration_invitations(
+        &self,
+    ) -> impl Future<Output = Result<Vec<RegistrationInvitation>, RegistrationInvitationError>> + Send;
+
+    fn revoke_registration_invitation(
+        &self,
+        invitation_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<(), RegistrationInvitationError>> + Send;
+}
@@ -0,0 +1,33 @@
+use std::future::Future;
+
+use crate::domain::model::audit::{AuditCheckpoint, AuditError, AuditEvent, NewAuditEvent};
+
+/// Trait defining the contract for writing the security audit log.
+///
+/// The `AuditSink` trait is where the service records who registered, logged in or failed to,
+/// refreshed a session, logged out, changed or reset a password, got locked out, and what
+/// administrators did. Events carry the IP address, user agent and request id of the request
+/// that caused them.
+///
+/// Events are appended to a hash chain, one at a time, so that every event holds the hash of
+/// the one recorded before it. `record` returns the event as chained, and the service signs
+/// a checkpoint of the chain every few events with `save_checkpoint`.
+///
+/// Failing to record an event does not fail the action being audited. The service logs the
+/// error instead, so an unavailable audit store cannot lock users out.
+///
+/// # Requirements
+///
+/// Any struct that implements the `AuditSink` trait must be `Send`, `Sync`, and have a
+/// `'static` lifetime.
+pub trait AuditSink: Send + Sync + 'static {
+    fn record(
+        &self,
+        event: &NewAuditEvent,
+    ) -> impl Future<Output = Result<AuditEvent, AuditError>> + Send;
+
+    fn save_checkpoint(
+        &self,
+        checkpoint: &AuditCheckpoint,
+    ) -> impl Future<Output = Result<(), AuditError>> + Send;
+}
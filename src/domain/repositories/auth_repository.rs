@@ -1,18 +1,46 @@
 use crate::domain::model::{
+    admin_user::{ListUsersRequest, UpdateUserRequest},
+    audit::{AuditCheckpoint, AuditEvent, ListAuditEventsRequest},
     auth_repo_errors::AuthRepositoryError,
+    federation::FederatedIdentity,
+    ldap::DirectoryUser,
     login_user::LoginUserRequest,
-    register_user::RegisterUserRequest,
+    oauth_client::{ClientId, OAuthClient},
+    organization::{
+        NewInvitation, NewOrganization, Organization, OrganizationInvitation, OrganizationMember,
+        OrganizationMembership,
+    },
+    password_reset::NewPasswordReset,
+    personal_access_token::{NewPersonalAccessToken, PersonalAccessToken},
+    register_user::{HashedUserPassword, RegisterUserRequest},
+    registration::{NewRegistrationInvitation, RegistrationInvitation},
+    service_account::NewServiceAccount,
     user::{FilteredUser, User},
     user_id::UserId,
+    webhook::{
+        ListWebhookDeliveriesRequest, NewWebhookSubscription, PendingWebhookDelivery,
+        WebhookAttempt, WebhookDelivery, WebhookEvent, WebhookSubscription,
+    },
 };
+use chrono::{DateTime, Utc};
 use std::future::Future;
 
 /// Trait defining the contract for authentication-related database repository operations.
 ///
 /// The `AuthRepository` trait specifies the necessary methods for user registration,
-/// login, and fetching user details by ID. Implementing this trait allows for
+/// login, fetching user details by ID, looking up registered OAuth clients and linking
+/// accounts at external identity providers to users, provisioning users from an LDAP
+/// directory, managing personal access tokens and service accounts, organizations with
+/// their memberships and invitations, registration invitations, and the administration of
+/// users with the security audit log. Queries about an organization's members are always
+/// scoped to that organization. Implementing this trait allows for
 /// interaction with various data storage backends.
 ///
+/// Webhook events are queued in an outbox for every subscription to them. Registering,
+/// changing or resetting a password and revoking sessions queue their events in the same
+/// transaction as the change itself, so an event is never lost nor sent for a change that
+/// was rolled back.
+///
 /// # Requirements
 ///
 /// Any struct that implements the `AuthRepository` trait must be `Send`, `Sync`,
@@ -24,6 +52,9 @@ use std::future::Future;
 /// The methods in this trait return a `Result` with the associated data type on success
 /// or an `AuthRepositoryError` on failure.
 pub trait AuthRepository: Send + Sync + 'static {
+    /// Creates the user. When the request carries an invitation, it is redeemed in the same
+    /// transaction, adding the user to the invitation's organization, and
+    /// `AuthRepositoryError::InvalidCredentials` is returned if it is not pending for the email.
     fn register(
         &self,
         request: &RegisterUserRequest,
@@ -38,4 +69,280 @@ pub trait AuthRepository: Send + Sync + 'static {
         &self,
         request: &UserId,
     ) -> impl Future<Output = Result<User, AuthRepositoryError>> + Send;
+
+    fn fetch_oauth_client(
+        &self,
+        request: &ClientId,
+    ) -> impl Future<Output = Result<OAuthClient, AuthRepositoryError>> + Send;
+
+    /// Fetches the user linked to an external provider account.
+    fn fetch_user_by_identity(
+        &self,
+        identity: &FederatedIdentity,
+    ) -> impl Future<Output = Result<User, AuthRepositoryError>> + Send;
+
+    /// Creates a user for an external provider account and links the account to them, if
+    /// `may_register`, otherwise `AuthRepositoryError::InvalidCredentials` is returned.
+    ///
+    /// A user with the same email is never taken over, whether or not the provider verified
+    /// the email, and `AuthRepositoryError::Duplicate` is returned instead.
+    fn link_identity(
+        &self,
+        identity: &FederatedIdentity,
+        may_register: bool,
+    ) -> impl Future<Output = Result<User, AuthRepositoryError>> + Send;
+
+    /// Fetches the user authenticated by the LDAP directory, creating them if `may_register`,
+    /// and synchronizes their roles with `DirectoryUser::synchronized_roles`.
+    ///
+    /// Directory users are linked to the `ldap` identity of their email when created, and only
+    /// linked users are ever returned: a local account with the same email is not taken over,
+    /// and `AuthRepositoryError::Duplicate` is returned instead.
+    fn provision_directory_user(
+        &self,
+        user: &DirectoryUser,
+        may_register: bool,
+    ) -> impl Future<Output = Result<User, AuthRepositoryError>> + Send;
+
+    fn create_personal_access_token(
+        &self,
+        token: &NewPersonalAccessToken,
+    ) -> impl Future<Output = Result<PersonalAccessToken, AuthRepositoryError>> + Send;
+
+    fn list_personal_access_tokens(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<Vec<PersonalAccessToken>, AuthRepositoryError>> + Send;
+
+    /// Deletes a token of `user_id`, returning `AuthRepositoryError::InvalidCredentials` when
+    /// the user has no token with that id.
+    fn delete_personal_access_token(
+        &self,
+        user_id: &UserId,
+        token_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Looks up an unexpired token by the hash of its secret and records that it was used.
+    fn use_personal_access_token(
+        &self,
+        token_hash: &str,
+    ) -> impl Future<Output = Result<PersonalAccessToken, AuthRepositoryError>> + Send;
+
+    /// Sets the expiry of a token of `user_id` to `expires_at`, unless it already expires sooner.
+    fn expire_personal_access_token(
+        &self,
+        user_id: &UserId,
+        token_id: &uuid::Uuid,
+        expires_at: &DateTime<Utc>,
+    ) -> impl Future<Output = Result<PersonalAccessToken, AuthRepositoryError>> + Send;
+
+    /// Creates a service account, returning `AuthRepositoryError::Duplicate` when a user with
+    /// its email already exists.
+    fn create_service_account(
+        &self,
+        account: &NewServiceAccount,
+    ) -> impl Future<Output = Result<User, AuthRepositoryError>> + Send;
+
+    fn list_service_accounts(
+        &self,
+    ) -> impl Future<Output = Result<Vec<User>, AuthRepositoryError>> + Send;
+
+    /// Deletes a service account and its keys, returning
+    /// `AuthRepositoryError::InvalidCredentials` when there is no service account with that id.
+    fn delete_service_account(
+        &self,
+        account_id: &UserId,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Creates an organization with `owner_id` as its owner, returning
+    /// `AuthRepositoryError::Conflict` when the slug is taken.
+    fn create_organization(
+        &self,
+        organization: &NewOrganization,
+    ) -> impl Future<Output = Result<Organization, AuthRepositoryError>> + Send;
+
+    /// Lists the organizations of `user_id`, oldest membership first.
+    fn list_memberships(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<Vec<OrganizationMembership>, AuthRepositoryError>> + Send;
+
+    /// Fetches the membership of `user_id` in `organization_id`, returning
+    /// `AuthRepositoryError::InvalidCredentials` when the user is not a member.
+    fn fetch_membership(
+        &self,
+        user_id: &UserId,
+        organization_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<OrganizationMembership, AuthRepositoryError>> + Send;
+
+    fn list_organization_members(
+        &self,
+        organization_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<Vec<OrganizationMember>, AuthRepositoryError>> + Send;
+
+    fn create_invitation(
+        &self,
+        invitation: &NewInvitation,
+    ) -> impl Future<Output = Result<OrganizationInvitation, AuthRepositoryError>> + Send;
+
+    /// Consumes the unexpired invitation with `token_hash` sent to `email` and makes `user_id` a
+    /// member, keeping the role of an existing membership. Returns
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such invitation.
+    fn accept_invitation(
+        &self,
+        token_hash: &str,
+        user_id: &UserId,
+        email: &str,
+    ) -> impl Future<Output = Result<OrganizationMembership, AuthRepositoryError>> + Send;
+
+    /// Removes `user_id` from `organization_id`, returning
+    /// `AuthRepositoryError::InvalidCredentials` when the user is not a member.
+    fn delete_membership(
+        &self,
+        organization_id: &uuid::Uuid,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Stores an invitation, returning `AuthRepositoryError::InvalidCredentials` when its
+    /// organization does not exist.
+    fn create_registration_invitation(
+        &self,
+        invitation: &NewRegistrationInvitation,
+    ) -> impl Future<Output = Result<RegistrationInvitation, AuthRepositoryError>> + Send;
+
+    /// Lists the invitations that have not been redeemed or revoked.
+    fn list_registration_invitations(
+        &self,
+    ) -> impl Future<Output = Result<Vec<RegistrationInvitation>, AuthRepositoryError>> + Send;
+
+    /// Revokes an invitation, returning `AuthRepositoryError::InvalidCredentials` when there
+    /// is no such invitation.
+    fn delete_registration_invitation(
+        &self,
+        invitation_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Lists a page of users matching the filters of `request`, ordered by creation, with the
+    /// total number of matching users.
+    fn list_users(
+        &self,
+        request: &ListUsersRequest,
+    ) -> impl Future<Output = Result<(Vec<User>, i64), AuthRepositoryError>> + Send;
+
+    /// Changes the email or status of a user, returning `AuthRepositoryError::InvalidCredentials`
+    /// when there is no such user and `AuthRepositoryError::Duplicate` when the email is taken.
+    /// A changed email is no longer verified.
+    fn update_user(
+        &self,
+        request: &UpdateUserRequest,
+    ) -> impl Future<Output = Result<User, AuthRepositoryError>> + Send;
+
+    /// Revokes every token issued to a user so far, returning
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such user.
+    fn revoke_user_sessions(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Stores a password reset, replacing any previous one, and makes the user reset their
+    /// password before logging in again, revoking their sessions. Returns
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such user.
+    fn create_password_reset(
+        &self,
+        reset: &NewPasswordReset,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Consumes the unexpired password reset with `token_hash`, sets the user's password and
+    /// revokes their sessions, returning the user's id, or
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such reset.
+    fn reset_password(
+        &self,
+        token_hash: &str,
+        password: &HashedUserPassword,
+    ) -> impl Future<Output = Result<uuid::Uuid, AuthRepositoryError>> + Send;
+
+    /// Deletes a user along with everything they own, returning
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such user.
+    fn delete_user(
+        &self,
+        user_id: &UserId,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Replaces the password of a user and revokes their sessions, returning
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such user.
+    fn update_password(
+        &self,
+        user_id: &UserId,
+        password: &HashedUserPassword,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Lists a page of the security audit log matching the filters of `request`, newest
+    /// first, with the total number of matching events.
+    fn list_audit_events(
+        &self,
+        request: &ListAuditEventsRequest,
+    ) -> impl Future<Output = Result<(Vec<AuditEvent>, i64), AuthRepositoryError>> + Send;
+
+    /// Lists up to `limit` events of the audit log chained after `after_sequence`, in order.
+    fn list_audit_chain(
+        &self,
+        after_sequence: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<AuditEvent>, AuthRepositoryError>> + Send;
+
+    /// Lists the signed checkpoints of the audit log chain, in order.
+    fn list_audit_checkpoints(
+        &self,
+    ) -> impl Future<Output = Result<Vec<AuditCheckpoint>, AuthRepositoryError>> + Send;
+
+    /// Queues `event` in the outbox for every subscription to its type.
+    fn enqueue_webhook_event(
+        &self,
+        event: &WebhookEvent,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    fn create_webhook_subscription(
+        &self,
+        subscription: &NewWebhookSubscription,
+    ) -> impl Future<Output = Result<WebhookSubscription, AuthRepositoryError>> + Send;
+
+    fn list_webhook_subscriptions(
+        &self,
+    ) -> impl Future<Output = Result<Vec<WebhookSubscription>, AuthRepositoryError>> + Send;
+
+    /// Deletes a subscription with its deliveries, returning
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such subscription.
+    fn delete_webhook_subscription(
+        &self,
+        subscription_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
+
+    /// Lists the most recent deliveries of a subscription matching `request`, newest first,
+    /// returning `AuthRepositoryError::InvalidCredentials` when there is no such subscription.
+    fn list_webhook_deliveries(
+        &self,
+        request: &ListWebhookDeliveriesRequest,
+    ) -> impl Future<Output = Result<Vec<WebhookDelivery>, AuthRepositoryError>> + Send;
+
+    /// Makes a dead delivery pending again with a fresh set of attempts, returning
+    /// `AuthRepositoryError::InvalidCredentials` when there is no such dead delivery.
+    fn retry_webhook_delivery(
+        &self,
+        subscription_id: &uuid::Uuid,
+        delivery_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<WebhookDelivery, AuthRepositoryError>> + Send;
+
+    /// Claims up to `limit` pending deliveries that are due, oldest first. Claimed deliveries
+    /// are not due again for `lease_seconds`, so that concurrent workers skip them while they
+    /// are being sent.
+    fn claim_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> impl Future<Output = Result<Vec<PendingWebhookDelivery>, AuthRepositoryError>> + Send;
+
+    fn record_webhook_attempt(
+        &self,
+        attempt: &WebhookAttempt,
+    ) -> impl Future<Output = Result<(), AuthRepositoryError>> + Send;
 }
@@ -1,7 +1,13 @@
 use std::future::Future;
 
 use crate::domain::model::{
+    authorize::AuthorizationCode,
     cache_errors::CacheOperationError,
+    device_authorization::{DeviceAuthorization, DeviceAuthorizationStatus},
+    federation::FederationState,
+    forward_auth::ForwardAuthIdentity,
+    saml::SamlRequestState,
+    session_revocation::SessionRevocation,
     token::{CacheToken, TokenDetails},
     token_uuid::TokenUuid,
 };
@@ -10,7 +16,7 @@ use crate::domain::model::{
 ///
 /// The `CacheRepository` trait specifies the necessary methods for interacting with a cache
 /// storage system. Implementing this trait allows for operations such as saving token data,
-/// verifying active sessions, and deleting tokens.
+/// verifying active sessions, deleting tokens, and storing single-use OAuth authorization codes.
 ///
 /// # Requirements
 ///
@@ -20,7 +26,8 @@ use crate::domain::model::{
 ///
 /// # Errors
 ///
-/// The methods in this trait return a `Result` with `()` on success or a `CacheOperationError` on failure.
+/// The methods in this trait return a `Result` with the associated data type on success or a
+/// `CacheOperationError` on failure.
 pub trait CacheRepository: Send + Sync + 'static {
     fn save_token_data(
         &self,
@@ -42,4 +49,123 @@ pub trait CacheRepository: Send + Sync + 'static {
         &self,
         token_uuid: &TokenUuid,
     ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    fn save_authorization_code(
+        &self,
+        code: &AuthorizationCode,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    /// Atomically fetches and deletes an authorization code so it can only be redeemed once.
+    fn take_authorization_code(
+        &self,
+        code: &str,
+    ) -> impl Future<Output = Result<AuthorizationCode, CacheOperationError>> + Send;
+
+    /// Increments the request counter for `key` in the current fixed window of
+    /// `window_seconds` and returns the number of requests seen in that window.
+    fn count_request(
+        &self,
+        key: &str,
+        window_seconds: i64,
+    ) -> impl Future<Output = Result<i64, CacheOperationError>> + Send;
+
+    /// Returns the number of requests counted for `key` in its current window, without
+    /// counting one more.
+    fn fetch_request_count(
+        &self,
+        key: &str,
+    ) -> impl Future<Output = Result<i64, CacheOperationError>> + Send;
+
+    /// Stores a new device authorization under both its device code and its user code,
+    /// expiring when the device code does.
+    fn save_device_authorization(
+        &self,
+        authorization: &DeviceAuthorization,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    /// Stores the polling `interval` and `last_polled_at` of a device authorization, leaving
+    /// its status as it is.
+    fn update_device_polling(
+        &self,
+        authorization: &DeviceAuthorization,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    /// Atomically records the user's decision on a pending device authorization. Returns false
+    /// when the authorization was no longer pending, as the user code was already used.
+    fn decide_device_authorization(
+        &self,
+        authorization: &DeviceAuthorization,
+        status: &DeviceAuthorizationStatus,
+    ) -> impl Future<Output = Result<bool, CacheOperationError>> + Send;
+
+    fn fetch_device_authorization(
+        &self,
+        device_code: &str,
+    ) -> impl Future<Output = Result<DeviceAuthorization, CacheOperationError>> + Send;
+
+    fn fetch_device_authorization_by_user_code(
+        &self,
+        user_code: &str,
+    ) -> impl Future<Output = Result<DeviceAuthorization, CacheOperationError>> + Send;
+
+    /// Atomically fetches and deletes a device authorization so tokens are only issued once.
+    fn take_device_authorization(
+        &self,
+        device_code: &str,
+    ) -> impl Future<Output = Result<DeviceAuthorization, CacheOperationError>> + Send;
+
+    fn save_federation_state(
+        &self,
+        state: &FederationState,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    /// Atomically fetches and deletes the state of a federated login so each provider
+    /// callback can only complete once.
+    fn take_federation_state(
+        &self,
+        state: &str,
+    ) -> impl Future<Output = Result<FederationState, CacheOperationError>> + Send;
+
+    fn save_saml_request(
+        &self,
+        state: &SamlRequestState,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    /// Atomically fetches and deletes the state of a SAML `AuthnRequest` by its `RelayState`,
+    /// so each request can only be answered once.
+    fn take_saml_request(
+        &self,
+        relay_state: &str,
+    ) -> impl Future<Output = Result<SamlRequestState, CacheOperationError>> + Send;
+
+    /// Records that the SAML assertion `id` was consumed, keeping it until `expires_at`.
+    /// Returns `CacheOperationError::Invalid` if the assertion was already recorded.
+    fn record_saml_assertion(
+        &self,
+        id: &str,
+        expires_at: i64,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    /// Caches the identity a reverse proxy is told about for the access token `token_uuid`,
+    /// for `ttl_seconds`. `delete_token` drops it along with the token.
+    fn save_forward_auth(
+        &self,
+        token_uuid: &TokenUuid,
+        identity: &ForwardAuthIdentity,
+        ttl_seconds: u64,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
+
+    /// Fetches the identity cached for the access token `token`, unless a session or user wide
+    /// revocation recorded with `record_revocation` revokes it.
+    fn fetch_forward_auth(
+        &self,
+        token: &TokenDetails,
+    ) -> impl Future<Output = Result<Option<ForwardAuthIdentity>, CacheOperationError>> + Send;
+
+    /// Records the session and user wide parts of `revocation` until its `expires_at`, with the
+    /// same meaning as in `RevocationDenylist`. Single tokens are revoked with `delete_token`.
+    fn record_revocation(
+        &self,
+        revocation: &SessionRevocation,
+    ) -> impl Future<Output = Result<(), CacheOperationError>> + Send;
 }
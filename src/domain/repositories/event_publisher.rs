@@ -0,0 +1,38 @@
+use std::future::Future;
+
+use crate::domain::model::{
+    domain_event::{DomainEvent, EventPublishError},
+    session_revocation::SessionRevocation,
+};
+
+/// Trait defining the contract for publishing authentication events to other services.
+///
+/// The service publishes an event after every successful registration, login, logout,
+/// refresh, password change or reset and token revocation, in the versioned schema of
+/// `DomainEvent`. Validating a token with `auth` is not published, as it happens on every
+/// request.
+///
+/// Events are published after the operation succeeded, and failing to publish one does not
+/// fail the operation. The service logs the error instead, like it does for audit events.
+///
+/// Revoked access tokens are also broadcast with `publish_revocation`, for the services that
+/// verify tokens locally and keep a `RevocationDenylist`: single revoked tokens, the session
+/// ended by a logout, and every token of a user whose sessions were revoked, whose password
+/// was changed or reset, or who was deleted. Unlike events, a revocation only needs to reach
+/// the subscribers listening at the time.
+///
+/// # Requirements
+///
+/// Any struct that implements the `EventPublisher` trait must be `Send`, `Sync`, and have a
+/// `'static` lifetime.
+pub trait EventPublisher: Send + Sync + 'static {
+    fn publish(
+        &self,
+        event: &DomainEvent,
+    ) -> impl Future<Output = Result<(), EventPublishError>> + Send;
+
+    fn publish_revocation(
+        &self,
+        revocation: &SessionRevocation,
+    ) -> impl Future<Output = Result<(), EventPublishError>> + Send;
+}
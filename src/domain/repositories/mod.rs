@@ -1,2 +1,4 @@
+pub mod audit_sink;
 pub mod auth_repository;
 pub mod cache_repository;
+pub mod event_publisher;
@@ -0,0 +1,35 @@
+use crate::domain::model::{
+    federation::{
+        FederatedCallbackResponse, FederatedLoginRequest, FederatedLoginResponse, FederationError,
+    },
+    saml::SamlAssertionRequest,
+};
+
+use std::future::Future;
+
+/// Trait representing this service as a SAML 2.0 service provider.
+///
+/// Each configured identity provider gets its own service provider metadata. `saml_login`
+/// sends the user to the provider with an `AuthnRequest` over the redirect binding, and
+/// `saml_assertion` consumes the provider's POST binding response, links the `NameID` to a
+/// user and starts a login session exactly like `AuthService::login` does for passwords.
+///
+/// # Implementors
+///
+/// Any struct that implements the `SamlService` trait must be `Send`, `Sync`, and `'static`.
+pub trait SamlService: Send + Sync + 'static {
+    fn saml_metadata(
+        &self,
+        provider: &str,
+    ) -> impl Future<Output = Result<String, FederationError>> + Send;
+
+    fn saml_login(
+        &self,
+        request: &FederatedLoginRequest,
+    ) -> impl Future<Output = Result<FederatedLoginResponse, FederationError>> + Send;
+
+    fn saml_assertion(
+        &self,
+        request: &SamlAssertionRequest,
+    ) -> impl Future<Output = Result<FederatedCallbackResponse, FederationError>> + Send;
+}
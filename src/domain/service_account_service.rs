@@ -0,0 +1,59 @@
+use crate::domain::model::{
+    personal_access_token::{
+        CreatePersonalAccessTokenRequest, CreatePersonalAccessTokenResponse, PersonalAccessToken,
+        RevokePersonalAccessTokenRequest,
+    },
+    service_account::{
+        CreateServiceAccountRequest, RotateServiceAccountKeyRequest, ServiceAccountError,
+    },
+    user::FilteredUser,
+    user_id::UserId,
+};
+
+use std::future::Future;
+
+/// Trait representing the administration of service accounts and their keys.
+///
+/// Service accounts are users of kind `service`. Their keys are personal access tokens, so
+/// they authenticate exactly like a person using a token, and key operations for an id that
+/// is not a service account fail with `ServiceAccountError::NotFound`.
+///
+/// # Implementors
+///
+/// Any struct that implements the `ServiceAccountService` trait must be `Send`, `Sync`, and
+/// `'static`.
+pub trait ServiceAccountService: Send + Sync + 'static {
+    fn create_service_account(
+        &self,
+        request: &CreateServiceAccountRequest,
+    ) -> impl Future<Output = Result<FilteredUser, ServiceAccountError>> + Send;
+
+    fn list_service_accounts(
+        &self,
+    ) -> impl Future<Output = Result<Vec<FilteredUser>, ServiceAccountError>> + Send;
+
+    fn delete_service_account(
+        &self,
+        account_id: &UserId,
+    ) -> impl Future<Output = Result<(), ServiceAccountError>> + Send;
+
+    fn create_service_account_key(
+        &self,
+        request: &CreatePersonalAccessTokenRequest,
+    ) -> impl Future<Output = Result<CreatePersonalAccessTokenResponse, ServiceAccountError>> + Send;
+
+    fn list_service_account_keys(
+        &self,
+        account_id: &UserId,
+    ) -> impl Future<Output = Result<Vec<PersonalAccessToken>, ServiceAccountError>> + Send;
+
+    fn revoke_service_account_key(
+        &self,
+        request: &RevokePersonalAccessTokenRequest,
+    ) -> impl Future<Output = Result<(), ServiceAccountError>> + Send;
+
+    fn rotate_service_account_key(
+        &self,
+        request: &RotateServiceAccountKeyRequest,
+    ) -> impl Future<Output = Result<CreatePersonalAccessTokenResponse, ServiceAccountError>> + Send;
+}
@@ -0,0 +1,49 @@
+use crate::domain::model::webhook::{
+    CreateWebhookRequest, CreateWebhookResponse, ListWebhookDeliveriesRequest, WebhookDelivery,
+    WebhookError, WebhookSubscription,
+};
+
+use std::future::Future;
+
+/// Trait representing outbound webhooks for authentication events.
+///
+/// Administrators subscribe URLs to `user.registered`, `user.login_failed`,
+/// `session.revoked` and `password.changed`. Events are queued in an outbox and delivered by
+/// a background worker, signed with the subscription's secret. Failed deliveries are retried
+/// with exponential backoff until they run out of attempts and are dead, after which an
+/// administrator can retry them.
+///
+/// # Implementors
+///
+/// Any struct that implements the `WebhookService` trait must be `Send`, `Sync`, and
+/// `'static`.
+pub trait WebhookService: Send + Sync + 'static {
+    fn create_webhook(
+        &self,
+        request: &CreateWebhookRequest,
+    ) -> impl Future<Output = Result<CreateWebhookResponse, WebhookError>> + Send;
+
+    fn list_webhooks(
+        &self,
+    ) -> impl Future<Output = Result<Vec<WebhookSubscription>, WebhookError>> + Send;
+
+    fn delete_webhook(
+        &self,
+        subscription_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<(), WebhookError>> + Send;
+
+    fn list_webhook_deliveries(
+        &self,
+        request: &ListWebhookDeliveriesRequest,
+    ) -> impl Future<Output = Result<Vec<WebhookDelivery>, WebhookError>> + Send;
+
+    /// Makes a dead delivery pending again, with a fresh set of attempts.
+    fn retry_webhook_delivery(
+        &self,
+        subscription_id: &uuid::Uuid,
+        delivery_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<WebhookDelivery, WebhookError>> + Send;
+
+    /// Sends a batch of the deliveries that are due, returning how many were attempted.
+    fn deliver_pending_webhooks(&self) -> impl Future<Output = Result<usize, WebhookError>> + Send;
+}
@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+use tonic::{metadata::MetadataMap, Request, Response, Status};
+
+use crate::{
+    application::AppState,
+    domain::{
+        admin_user_service::AdminUserService,
+        auth_service::AuthService,
+        model::{
+            admin_user::AdminUser,
+            auth::AuthRequest,
+            auth_middleware::AuthMiddleware,
+            introspection::{IntrospectionRequest, IntrospectionResponse},
+            logout::LogoutRequest,
+            oauth_client::ClientAuthentication,
+            personal_access_token::PersonalAccessTokenSecret,
+            principal::PrincipalType,
+            session_revocation::session_id,
+            user::User,
+            user_id::UserId,
+        },
+        oauth_service::OAuthService,
+    },
+    grpc::proto::{
+        self, authentication_server::Authentication, GetUserRequest, IntrospectRequest,
+        IntrospectResponse, RevokeSessionRequest, RevokeSessionResponse, VerifyTokenRequest,
+        VerifyTokenResponse,
+    },
+};
+
+/// The `auth.v1.Authentication` gRPC service, answered by the same services as the HTTP API.
+pub struct GrpcAuthentication<AS: AuthService> {
+    app_state: Arc<AppState<AS>>,
+}
+
+impl<AS: AuthService> GrpcAuthentication<AS> {
+    pub fn new(app_state: Arc<AppState<AS>>) -> GrpcAuthentication<AS> {
+        GrpcAuthentication { app_state }
+    }
+
+    /// Authenticates the service calling an RPC that reads or changes the state of others,
+    /// with the API key or access token of a service account in its `authorization: Bearer`
+    /// or `x-api-key` metadata.
+    ///
+    /// Fails with UNAUTHENTICATED without valid credentials, and with PERMISSION_DENIED when
+    /// they belong to anything but a service account.
+    async fn authenticate_service(&self, metadata: &MetadataMap) -> Result<AuthMiddleware, Status> {
+        let credential = caller_credential(metadata)
+            .ok_or_else(|| Status::unauthenticated("Missing service account credentials"))?;
+
+        let caller = self
+            .app_state
+            .auth_service
+            .auth(&AuthRequest::new(credential))
+            .await?;
+
+        match caller.user() {
+            Some(user) if user.is_service_account() => Ok(caller),
+            _ => Err(Status::permission_denied(
+                "Only service accounts may call this method",
+            )),
+        }
+    }
+}
+
+/// Header-like metadata API keys can be sent in, as the HTTP API accepts them.
+const API_KEY_METADATA: &str = "x-api-key";
+
+/// Reads the credentials of the caller from the `authorization: Bearer` metadata, or an API
+/// key from the `x-api-key` metadata.
+fn caller_credential(metadata: &MetadataMap) -> Option<String> {
+    metadata
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| {
+            metadata
+                .get(API_KEY_METADATA)
+                .and_then(|value| value.to_str().ok())
+                .filter(|api_key| PersonalAccessTokenSecret::is_personal_access_token(api_key))
+        })
+        .map(str::to_string)
+}
+
+#[tonic::async_trait]
+impl<AS> Authentication for GrpcAuthentication<AS>
+where
+    AS: AuthService + AdminUserService + OAuthService,
+{
+    async fn verify_token(
+        &self,
+        request: Request<VerifyTokenRequest>,
+    ) -> Result<Response<VerifyTokenResponse>, Status> {
+        let request = request.into_inner();
+
+        let auth = self
+            .app_state
+            .auth_service
+            .auth(&AuthRequest::new(request.access_token))
+            .await?;
+
+        Ok(Response::new(VerifyTokenResponse::from(&auth)))
+    }
+
+    async fn get_user(
+        &self,
+        request: Request<GetUserRequest>,
+    ) -> Result<Response<proto::User>, Status> {
+        self.authenticate_service(request.metadata()).await?;
+
+        let user_id = uuid::Uuid::parse_str(&request.into_inner().user_id)
+            .map_err(|_| Status::invalid_argument("user_id must be a UUID"))?;
+
+        let user = self
+            .app_state
+            .auth_service
+            .fetch_user(&UserId::new(user_id))
+            .await?;
+
+        Ok(Response::new(proto::User::from(&user)))
+    }
+
+    async fn revoke_session(
+        &self,
+        request: Request<RevokeSessionRequest>,
+    ) -> Result<Response<RevokeSessionResponse>, Status> {
+        self.authenticate_service(request.metadata()).await?;
+
+        let request = request.into_inner();
+
+        let auth = self
+            .app_state
+            .auth_service
+            .auth(&AuthRequest::new(request.access_token))
+            .await?;
+
+        let domain_request = LogoutRequest::new(auth.access_token_uuid)
+            .with_user(auth.user().map(|user| user.id))
+            .with_session(session_id(&auth.claims), auth.expires_at);
+
+        self.app_state.auth_service.logout(&domain_request).await?;
+
+        Ok(Response::new(RevokeSessionResponse {}))
+    }
+
+    async fn introspect(
+        &self,
+        request: Request<IntrospectRequest>,
+    ) -> Result<Response<IntrospectResponse>, Status> {
+        self.authenticate_service(request.metadata()).await?;
+
+        let request = request.into_inner();
+
+        let domain_request = IntrospectionRequest {
+            client: ClientAuthentication::new(&request.client_id, Some(&request.client_secret)),
+            token: request.token,
+            token_type_hint: request.token_type_hint,
+        };
+
+        let response = self
+            .app_state
+            .auth_service
+            .introspect(&domain_request)
+            .await?;
+
+        Ok(Response::new(IntrospectResponse::from(response)))
+    }
+}
+
+impl From<&AuthMiddleware> for VerifyTokenResponse {
+    fn from(auth: &AuthMiddleware) -> Self {
+        VerifyTokenResponse {
+            subject: auth.subject().to_string(),
+            principal_type: proto::PrincipalType::from(auth.principal_type()).into(),
+            user: auth.user().map(proto::User::from),
+            token_id: auth.access_token_uuid.to_string(),
+            session_id: session_id(&auth.claims).map(|id| id.to_string()),
+            expires_at: auth.expires_at,
+            scopes: auth
+                .claims
+                .get("scope")
+                .and_then(Value::as_str)
+                .map(|scope| scope.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            actor: auth.actor().map(|actor| actor.to_string()),
+        }
+    }
+}
+
+impl From<&AdminUser> for proto::User {
+    fn from(user: &AdminUser) -> Self {
+        proto::User {
+            id: user.id.to_string(),
+            email: user.email.clone(),
+            email_verified: user.email_verified,
+            roles: user.roles.clone(),
+            kind: user.kind.as_str().to_string(),
+            status: user.status.as_str().to_string(),
+            owner_id: user.owner_id.map(|id| id.to_string()),
+            created_at: user.created_at.map(|at| at.timestamp()),
+            updated_at: user.updated_at.map(|at| at.timestamp()),
+        }
+    }
+}
+
+impl From<&User> for proto::User {
+    fn from(user: &User) -> Self {
+        proto::User::from(&AdminUser::from(user))
+    }
+}
+
+impl From<PrincipalType> for proto::PrincipalType {
+    fn from(principal_type: PrincipalType) -> Self {
+        match principal_type {
+            PrincipalType::User => proto::PrincipalType::User,
+            PrincipalType::Client => proto::PrincipalType::Client,
+        }
+    }
+}
+
+impl From<IntrospectionResponse> for IntrospectResponse {
+    fn from(response: IntrospectionResponse) -> Self {
+        IntrospectResponse {
+            active: response.active,
+            scope: response.scope,
+            client_id: response.client_id,
+            token_type: response.token_type,
+            exp: response.exp,
+            sub: response.sub,
+            // `aud` is either a single audience or an array of them.
+            aud: match response.aud {
+                Some(Value::String(aud)) => vec![aud],
+                Some(Value::Array(auds)) => auds
+                    .iter()
+                    .filter_map(|aud| aud.as_str().map(str::to_string))
+                    .collect(),
+                _ => vec![],
+            },
+            jti: response.jti,
+            principal_type: response
+                .principal_type
+                .map(proto::PrincipalType::from)
+                .unwrap_or(proto::PrincipalType::Unspecified)
+                .into(),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+pub mod authentication;
+pub mod status;
+
+/// Types and services generated from `proto/auth/v1/authentication.proto`.
+pub mod proto {
+    tonic::include_proto!("auth.v1");
+
+    /// The encoded descriptors of the proto files, served by gRPC reflection.
+    pub const FILE_DESCRIPTOR_SET: &[u8] =
+        tonic::include_file_descriptor_set!("authentication_descriptor");
+}
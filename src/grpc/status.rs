@@ -0,0 +1,55 @@
+use tonic::Status;
+
+use crate::domain::model::{
+    admin_user::AdminUserError, auth::AuthorizationError, oauth_errors::OAuthError,
+};
+
+impl From<AuthorizationError> for Status {
+    fn from(value: AuthorizationError) -> Self {
+        match value {
+            AuthorizationError::InvalidCredentials { reason } => Status::unauthenticated(reason),
+            AuthorizationError::MissingCredentials | AuthorizationError::TokenExpired => {
+                Status::unauthenticated(value.to_string())
+            }
+            AuthorizationError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Status::internal("Internal Server Error")
+            }
+        }
+    }
+}
+
+impl From<AdminUserError> for Status {
+    fn from(value: AdminUserError) -> Self {
+        match value {
+            AdminUserError::InvalidRequest { reason } => Status::invalid_argument(reason),
+            AdminUserError::Duplicate { .. } => Status::already_exists(value.to_string()),
+            AdminUserError::NotFound => Status::not_found(value.to_string()),
+            AdminUserError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Status::internal("Internal Server Error")
+            }
+        }
+    }
+}
+
+/// Keeps the RFC 6749 error code in the message, as the HTTP API does in `error`.
+impl From<OAuthError> for Status {
+    fn from(value: OAuthError) -> Self {
+        let message = format!("{}: {}", value.code(), value.description());
+        match value {
+            OAuthError::InvalidClient { .. } | OAuthError::LoginRequired { .. } => {
+                Status::unauthenticated(message)
+            }
+            OAuthError::InsufficientScope { .. } | OAuthError::UnauthorizedClient { .. } => {
+                Status::permission_denied(message)
+            }
+            OAuthError::TooManyRequests { .. } => Status::resource_exhausted(message),
+            OAuthError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Status::internal(message)
+            }
+            _ => Status::invalid_argument(message),
+        }
+    }
+}
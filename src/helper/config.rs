@@ -1,3 +1,12 @@
+use std::net::SocketAddr;
+
+use crate::domain::model::{
+    federation::FederatedProvider,
+    ldap::{LdapConfig, LdapMode},
+    registration::RegistrationMode,
+    saml::SamlIdentityProvider,
+};
+
 /// Configuration settings for the application.
 ///
 /// The `Config` struct holds various configuration parameters required by the application.
@@ -16,12 +25,46 @@ pub struct Config {
     pub refresh_token_expires_in: String,
     pub refresh_token_max_age: i64,
     pub redis_url: String,
+    pub access_token_static_claims: serde_json::Map<String, serde_json::Value>,
+    pub access_token_claims_max_bytes: usize,
+    pub oauth_code_max_age_seconds: i64,
+    pub oauth_rate_limit_per_minute: i64,
+    pub oauth_device_code_max_age_seconds: i64,
+    pub oauth_device_poll_interval_seconds: i64,
+    pub oauth_device_verification_url: Option<String>,
+    pub oidc_issuer: String,
+    pub oidc_login_url: Option<String>,
+    pub federated_providers: Vec<FederatedProvider>,
+    pub federation_state_max_age_seconds: i64,
+    pub saml_identity_providers: Vec<SamlIdentityProvider>,
+    pub ldap: Option<LdapConfig>,
+    pub personal_access_token_scopes: Vec<String>,
+    pub registration_mode: RegistrationMode,
+    pub login_lockout_threshold: i64,
+    pub login_lockout_seconds: i64,
+    pub audit_checkpoint_interval: i64,
+    pub webhook_worker_enabled: bool,
+    pub webhook_poll_interval_seconds: u64,
+    pub webhook_max_attempts: i32,
+    pub webhook_backoff_seconds: i64,
+    pub event_stream: String,
+    pub event_stream_max_len: usize,
+    pub revocation_channel: String,
+    pub forward_auth_user_id_header: String,
+    pub forward_auth_email_header: String,
+    pub forward_auth_roles_header: String,
+    pub forward_auth_cache_seconds: u64,
+    pub grpc_address: Option<SocketAddr>,
 }
 
 fn get_env(var_name: &str) -> String {
     std::env::var(var_name).unwrap_or_else(|_| panic!("{} must be set in .env", var_name))
 }
 
+fn get_env_or(var_name: &str, default: &str) -> String {
+    std::env::var(var_name).unwrap_or_else(|_| default.to_string())
+}
+
 impl Config {
     /// Initializes a new `Config` instance from environment variables.
     ///
@@ -38,7 +81,8 @@ impl Config {
     /// # Panics
     ///
     /// This method will panic if any required environment variable is not set or if integer
-    /// values cannot be parsed correctly.
+    /// values cannot be parsed correctly. Optional settings fall back to a default when unset,
+    /// but still panic if they are set to a value that cannot be parsed.
     pub fn init() -> Config {
         let database_url = get_env("DATABASE_URL");
         let access_token_private_key = get_env("ACCESS_TOKEN_PRIVATE_KEY");
@@ -54,6 +98,75 @@ impl Config {
             get_env("REDIS_HOST"),
             get_env("REDIS_PORT")
         );
+        let access_token_static_claims = get_env_or("ACCESS_TOKEN_STATIC_CLAIMS", "{}");
+        let access_token_claims_max_bytes = get_env_or("ACCESS_TOKEN_CLAIMS_MAX_BYTES", "2048");
+        let oauth_code_max_age_seconds = get_env_or("OAUTH_CODE_MAXAGE_SECONDS", "60");
+        let oauth_rate_limit_per_minute = get_env_or("OAUTH_RATE_LIMIT_PER_MINUTE", "60");
+        let oauth_device_code_max_age_seconds =
+            get_env_or("OAUTH_DEVICE_CODE_MAXAGE_SECONDS", "600");
+        let oauth_device_poll_interval_seconds =
+            get_env_or("OAUTH_DEVICE_POLL_INTERVAL_SECONDS", "5");
+        let oauth_device_verification_url = get_env_or("OAUTH_DEVICE_VERIFICATION_URL", "");
+        let oidc_issuer = get_env_or("OIDC_ISSUER", "http://localhost:8000");
+        let oidc_login_url = get_env_or("OIDC_LOGIN_URL", "");
+        let federated_providers = get_env_or("FEDERATED_PROVIDERS", "[]");
+        let federation_state_max_age_seconds = get_env_or("FEDERATION_STATE_MAXAGE_SECONDS", "600");
+        let saml_identity_providers = get_env_or("SAML_IDENTITY_PROVIDERS", "[]");
+        let personal_access_token_scopes =
+            get_env_or("PERSONAL_ACCESS_TOKEN_SCOPES", "openid profile email");
+        let login_lockout_threshold = get_env_or("LOGIN_LOCKOUT_THRESHOLD", "5");
+        let login_lockout_seconds = get_env_or("LOGIN_LOCKOUT_SECONDS", "900");
+        let audit_checkpoint_interval = get_env_or("AUDIT_CHECKPOINT_INTERVAL", "100");
+        let webhook_worker_enabled = get_env_or("WEBHOOK_WORKER_ENABLED", "true");
+        let webhook_poll_interval_seconds = get_env_or("WEBHOOK_POLL_INTERVAL_SECONDS", "5");
+        let webhook_max_attempts = get_env_or("WEBHOOK_MAX_ATTEMPTS", "8");
+        let webhook_backoff_seconds = get_env_or("WEBHOOK_BACKOFF_SECONDS", "30");
+        let event_stream = get_env_or("EVENT_STREAM", "auth:events");
+        let event_stream_max_len = get_env_or("EVENT_STREAM_MAX_LEN", "10000");
+        let revocation_channel = get_env_or("REVOCATION_CHANNEL", "auth:revocations");
+        let forward_auth_user_id_header = get_env_or("FORWARD_AUTH_USER_ID_HEADER", "x-user-id");
+        let forward_auth_email_header = get_env_or("FORWARD_AUTH_EMAIL_HEADER", "x-user-email");
+        let forward_auth_roles_header = get_env_or("FORWARD_AUTH_ROLES_HEADER", "x-user-roles");
+        let forward_auth_cache_seconds = get_env_or("FORWARD_AUTH_CACHE_SECONDS", "0");
+        let grpc_address = get_env_or("GRPC_ADDRESS", "");
+
+        let registration_mode = match get_env_or("REGISTRATION_MODE", "open").as_str() {
+            "open" => RegistrationMode::Open,
+            "invite_only" => RegistrationMode::InviteOnly,
+            "domain_allowlist" => RegistrationMode::DomainAllowlist(
+                get_env("REGISTRATION_ALLOWED_DOMAINS")
+                    .split_whitespace()
+                    .map(|domain| domain.to_string())
+                    .collect(),
+            ),
+            "closed" => RegistrationMode::Closed,
+            _ => panic!(
+                "Registration mode must be one of open, invite_only, domain_allowlist or closed in .env"
+            ),
+        };
+
+        let ldap = match get_env_or("LDAP_MODE", "disabled").as_str() {
+            "disabled" => None,
+            mode => Some(LdapConfig {
+                mode: match mode {
+                    "exclusive" => LdapMode::Exclusive,
+                    "first" => LdapMode::First,
+                    _ => panic!("LDAP mode must be one of disabled, exclusive or first in .env"),
+                },
+                url: get_env("LDAP_URL"),
+                bind_dn: Some(get_env_or("LDAP_BIND_DN", "")).filter(|dn| !dn.is_empty()),
+                bind_password: get_env_or("LDAP_BIND_PASSWORD", ""),
+                base_dn: get_env("LDAP_BASE_DN"),
+                user_filter: get_env_or("LDAP_USER_FILTER", "(mail={login})"),
+                email_attribute: get_env_or("LDAP_EMAIL_ATTRIBUTE", "mail"),
+                group_attribute: get_env_or("LDAP_GROUP_ATTRIBUTE", "memberOf"),
+                group_roles: serde_json::from_str(&get_env_or("LDAP_GROUP_ROLES", "{}"))
+                    .expect("LDAP group roles must be a JSON object in .env"),
+                grant_admin: get_env_or("LDAP_GRANT_ADMIN", "false")
+                    .parse::<bool>()
+                    .expect("LDAP grant admin failed to parse from .env"),
+            }),
+        };
 
         Config {
             database_url,
@@ -70,6 +183,85 @@ impl Config {
                 .parse::<i64>()
                 .expect("Refresh token max age failed to parse from .env"),
             redis_url,
+            access_token_static_claims: serde_json::from_str(&access_token_static_claims)
+                .expect("Access token static claims must be a JSON object in .env"),
+            access_token_claims_max_bytes: access_token_claims_max_bytes
+                .parse::<usize>()
+                .expect("Access token claims max bytes failed to parse from .env"),
+            oauth_code_max_age_seconds: oauth_code_max_age_seconds
+                .parse::<i64>()
+                .expect("OAuth code max age failed to parse from .env"),
+            oauth_rate_limit_per_minute: oauth_rate_limit_per_minute
+                .parse::<i64>()
+                .expect("OAuth rate limit failed to parse from .env"),
+            oauth_device_code_max_age_seconds: oauth_device_code_max_age_seconds
+                .parse::<i64>()
+                .expect("OAuth device code max age failed to parse from .env"),
+            oauth_device_poll_interval_seconds: oauth_device_poll_interval_seconds
+                .parse::<i64>()
+                .expect("OAuth device poll interval failed to parse from .env"),
+            oauth_device_verification_url: Some(oauth_device_verification_url)
+                .filter(|url| !url.is_empty()),
+            oidc_issuer,
+            oidc_login_url: Some(oidc_login_url).filter(|url| !url.is_empty()),
+            federated_providers: serde_json::from_str(&federated_providers)
+                .expect("Federated providers must be a JSON array in .env"),
+            federation_state_max_age_seconds: federation_state_max_age_seconds
+                .parse::<i64>()
+                .expect("Federation state max age failed to parse from .env"),
+            saml_identity_providers: serde_json::from_str(&saml_identity_providers)
+                .expect("SAML identity providers must be a JSON array in .env"),
+            ldap,
+            personal_access_token_scopes: personal_access_token_scopes
+                .split_whitespace()
+                .map(|scope| scope.to_string())
+                .collect(),
+            registration_mode,
+            login_lockout_threshold: login_lockout_threshold
+                .parse::<i64>()
+                .expect("Login lockout threshold failed to parse from .env"),
+            login_lockout_seconds: login_lockout_seconds
+                .parse::<i64>()
+                .expect("Login lockout seconds failed to parse from .env"),
+            audit_checkpoint_interval: audit_checkpoint_interval
+                .parse::<i64>()
+                .ok()
+                .filter(|interval| *interval > 0)
+                .expect("Audit checkpoint interval must be a positive integer in .env"),
+            webhook_worker_enabled: webhook_worker_enabled
+                .parse::<bool>()
+                .expect("Webhook worker enabled failed to parse from .env"),
+            webhook_poll_interval_seconds: webhook_poll_interval_seconds
+                .parse::<u64>()
+                .ok()
+                .filter(|interval| *interval > 0)
+                .expect("Webhook poll interval must be a positive integer in .env"),
+            webhook_max_attempts: webhook_max_attempts
+                .parse::<i32>()
+                .ok()
+                .filter(|attempts| *attempts > 0)
+                .expect("Webhook max attempts must be a positive integer in .env"),
+            webhook_backoff_seconds: webhook_backoff_seconds
+                .parse::<i64>()
+                .expect("Webhook backoff seconds failed to parse from .env"),
+            event_stream,
+            event_stream_max_len: event_stream_max_len
+                .parse::<usize>()
+                .expect("Event stream max len failed to parse from .env"),
+            revocation_channel,
+            forward_auth_user_id_header,
+            forward_auth_email_header,
+            forward_auth_roles_header,
+            forward_auth_cache_seconds: forward_auth_cache_seconds
+                .parse::<u64>()
+                .expect("Forward auth cache seconds failed to parse from .env"),
+            grpc_address: Some(grpc_address)
+                .filter(|address| !address.is_empty())
+                .map(|address| {
+                    address
+                        .parse::<SocketAddr>()
+                        .expect("gRPC address failed to parse from .env")
+                }),
         }
     }
 }
@@ -1,6 +1,10 @@
 pub mod api;
 pub mod application;
+pub mod claims;
 pub mod domain;
+pub mod grpc;
 pub mod helper;
 pub mod repositories;
 pub mod service;
+#[cfg(feature = "verifier")]
+pub mod verifier;
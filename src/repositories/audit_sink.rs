@@ -0,0 +1,84 @@
+use anyhow::anyhow;
+
+use crate::{
+    domain::{
+        model::audit::{AuditCheckpoint, AuditError, AuditEvent, NewAuditEvent},
+        repositories::audit_sink::AuditSink,
+    },
+    repositories::auth_repository::PostgresDB,
+};
+
+/// Key of the transaction level advisory lock taken to append to the audit chain.
+const AUDIT_CHAIN_LOCK: i64 = 0x6175_6469_745f_6c6f;
+
+/// Writes the security audit log to the `audit_events` table, next to the users it is about.
+///
+/// The table has no foreign keys, so events outlive the users and administrators they name.
+impl AuditSink for PostgresDB {
+    /// Chains the event after the last one recorded. Writers take an advisory lock until the
+    /// event is inserted, so no two events are chained after the same one. The table itself is
+    /// not locked, so reads and other writes carry on meanwhile.
+    async fn record(&self, event: &NewAuditEvent) -> Result<AuditEvent, AuditError> {
+        let database_error =
+            |e: sqlx::Error| anyhow!(e).context("Database error while recording audit event");
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", AUDIT_CHAIN_LOCK)
+            .execute(&mut *transaction)
+            .await
+            .map_err(database_error)?;
+
+        let last =
+            sqlx::query!("SELECT sequence, hash FROM audit_events ORDER BY sequence DESC LIMIT 1")
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(database_error)?;
+
+        let event = match last {
+            Some(last) => AuditEvent::chain(event, last.sequence + 1, last.hash),
+            None => AuditEvent::chain(event, 1, None),
+        };
+
+        sqlx::query!(
+            "INSERT INTO audit_events (id, sequence, previous_hash, hash, event_type, outcome, \
+             reason, user_id, actor_id, ip_address, user_agent, request_id, details, created_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+            event.id,
+            event.sequence,
+            event.previous_hash,
+            event.hash,
+            event.event_type.as_str(),
+            event.outcome.as_str(),
+            event.reason,
+            event.user_id,
+            event.actor_id,
+            event.ip_address,
+            event.user_agent,
+            event.request_id,
+            event.details,
+            event.created_at
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)?;
+
+        Ok(event)
+    }
+
+    async fn save_checkpoint(&self, checkpoint: &AuditCheckpoint) -> Result<(), AuditError> {
+        sqlx::query!(
+            "INSERT INTO audit_checkpoints (sequence, hash, signature) VALUES ($1, $2, $3)",
+            checkpoint.sequence,
+            checkpoint.hash,
+            checkpoint.signature
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow!(e).context("Database error while saving audit checkpoint"))?;
+
+        Ok(())
+    }
+}
@@ -1,20 +1,39 @@
 use crate::domain::{
     model::{
+        admin_user::{ListUsersRequest, UpdateUserRequest},
+        audit::{AuditCheckpoint, AuditEvent, ListAuditEventsRequest},
         auth_repo_errors::AuthRepositoryError,
+        federation::FederatedIdentity,
+        ldap::{DirectoryUser, DIRECTORY_PROVIDER},
         login_user::LoginUserRequest,
-        register_user::RegisterUserRequest,
+        oauth_client::{ClientId, OAuthClient},
+        organization::{
+            NewInvitation, NewOrganization, Organization, OrganizationInvitation,
+            OrganizationMember, OrganizationMembership,
+        },
+        password_reset::NewPasswordReset,
+        personal_access_token::{NewPersonalAccessToken, PersonalAccessToken},
+        register_user::{HashedUserPassword, RegisterUserRequest},
+        registration::{NewRegistrationInvitation, RegistrationInvitation},
+        service_account::NewServiceAccount,
         user::{FilteredUser, User},
         user_email::UserEmail,
         user_id::UserId,
+        webhook::{
+            ListWebhookDeliveriesRequest, NewWebhookSubscription, PendingWebhookDelivery,
+            WebhookAttempt, WebhookAttemptOutcome, WebhookDelivery, WebhookDeliveryStatus,
+            WebhookEvent, WebhookSubscription, WEBHOOK_DELIVERIES_LIMIT,
+        },
     },
     repositories::auth_repository::AuthRepository,
 };
 use anyhow::Context;
-use sqlx::{postgres::PgPoolOptions, Postgres};
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgPoolOptions, PgExecutor, Postgres};
 
 #[derive(Clone, Debug)]
 pub struct PostgresDB {
-    pool: sqlx::Pool<Postgres>,
+    pub(crate) pool: sqlx::Pool<Postgres>,
 }
 
 // A PostgreSQL-based implementation of the `AuthRepository` trait.
@@ -49,20 +68,63 @@ impl AuthRepository for PostgresDB {
     ) -> Result<FilteredUser, AuthRepositoryError> {
         self.is_unique_constrain_violation(request).await?;
 
+        let email = request.email.get().to_ascii_lowercase();
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!(
+                "Database error while registering user with email {}: {}",
+                request.email, e
+            ),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
         let user = sqlx::query_as!(
             User,
             "INSERT INTO users (email, password) VALUES ($1, $2) RETURNING *",
-            request.email.get().to_ascii_lowercase(),
+            email,
             request.hashed_password.get(),
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *transaction)
         .await
-        .map_err(|e| AuthRepositoryError::Database {
-            reason: format!(
-                "Database error while registering user with email {}: {}",
-                request.email, e
-            ),
-        })?;
+        .map_err(database_error)?;
+
+        if let Some(invitation) = &request.invitation {
+            let redeemed = sqlx::query!(
+                "DELETE FROM registration_invitations \
+                 WHERE token_hash = $1 AND email = $2 AND expires_at > NOW() \
+                 RETURNING organization_id, role",
+                invitation.hash(),
+                email
+            )
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(database_error)?
+            .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+                reason: "The invitation is not valid for this email or has expired".to_string(),
+            })?;
+
+            if let Some(organization_id) = redeemed.organization_id {
+                sqlx::query!(
+                    "INSERT INTO organization_memberships (organization_id, user_id, role) \
+                     VALUES ($1, $2, $3)",
+                    organization_id,
+                    user.id,
+                    redeemed.role
+                )
+                .execute(&mut *transaction)
+                .await
+                .map_err(database_error)?;
+            }
+        }
+
+        enqueue_webhook_event(
+            &mut *transaction,
+            &WebhookEvent::user_registered(user.id, &user.email),
+        )
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)?;
 
         Ok(FilteredUser::from(&user))
     }
@@ -74,6 +136,1209 @@ impl AuthRepository for PostgresDB {
     async fn fetch_user_by_id(&self, request: &UserId) -> Result<User, AuthRepositoryError> {
         self.fetch_user_by_id(request).await
     }
+
+    async fn fetch_oauth_client(
+        &self,
+        request: &ClientId,
+    ) -> Result<OAuthClient, AuthRepositoryError> {
+        sqlx::query_as!(
+            OAuthClient,
+            "SELECT * FROM oauth_clients WHERE client_id = $1",
+            request.get()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!(
+                "Database error while looking up client {}: {}",
+                request.get(),
+                e
+            ),
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "Unknown client".to_string(),
+        })
+    }
+
+    async fn fetch_user_by_identity(
+        &self,
+        identity: &FederatedIdentity,
+    ) -> Result<User, AuthRepositoryError> {
+        sqlx::query_as!(
+            User,
+            "SELECT users.* FROM users JOIN user_identities ON user_identities.user_id = users.id WHERE user_identities.provider = $1 AND user_identities.subject = $2",
+            identity.provider,
+            identity.subject
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!(
+                "Database error while looking up {} identity: {}",
+                identity.provider, e
+            ),
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "Identity is not linked to a user".to_string(),
+        })
+    }
+
+    async fn link_identity(
+        &self,
+        identity: &FederatedIdentity,
+        may_register: bool,
+    ) -> Result<User, AuthRepositoryError> {
+        let email = identity
+            .email
+            .as_deref()
+            .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+                reason: "Identity provider did not share an email address".to_string(),
+            })?
+            .to_ascii_lowercase();
+
+        if !may_register {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: format!(
+                    "{} identity has no account and registration is closed to it",
+                    identity.provider
+                ),
+            });
+        }
+
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!(
+                "Database error while linking {} identity: {}",
+                identity.provider, e
+            ),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        // Federated users have no password; an empty hash never verifies.
+        let user = sqlx::query_as!(
+            User,
+            "INSERT INTO users (email, password, email_verified) VALUES ($1, '', $2) \
+             ON CONFLICT (email) DO NOTHING RETURNING *",
+            email,
+            identity.email_verified
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+        let Some(user) = user else {
+            return Err(AuthRepositoryError::Duplicate {
+                email: UserEmail::new(&email).map_err(|e| anyhow::anyhow!(e))?,
+            });
+        };
+
+        sqlx::query!(
+            "INSERT INTO user_identities (user_id, provider, subject) VALUES ($1, $2, $3)",
+            user.id,
+            identity.provider,
+            identity.subject
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)?;
+
+        Ok(user)
+    }
+
+    async fn provision_directory_user(
+        &self,
+        user: &DirectoryUser,
+        may_register: bool,
+    ) -> Result<User, AuthRepositoryError> {
+        let email = user.email.to_ascii_lowercase();
+
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while provisioning directory user: {}", e),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let linked = sqlx::query_as!(
+            User,
+            "SELECT users.* FROM users JOIN user_identities ON user_identities.user_id = users.id \
+             WHERE user_identities.provider = $1 AND user_identities.subject = $2 \
+             FOR UPDATE OF users",
+            DIRECTORY_PROVIDER,
+            email
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        let provisioned = match linked {
+            Some(linked) => linked,
+            None if !may_register => {
+                return Err(AuthRepositoryError::InvalidCredentials {
+                    reason: "Directory user has no account and registration is closed".to_string(),
+                })
+            }
+            None => {
+                // Directory users have no local password; an empty hash never verifies.
+                let created = sqlx::query_as!(
+                    User,
+                    "INSERT INTO users (email, password, email_verified) VALUES ($1, '', TRUE) \
+                     ON CONFLICT (email) DO NOTHING RETURNING *",
+                    email
+                )
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(database_error)?;
+                let Some(created) = created else {
+                    return Err(AuthRepositoryError::Duplicate {
+                        email: UserEmail::new(&email).map_err(|e| anyhow::anyhow!(e))?,
+                    });
+                };
+
+                sqlx::query!(
+                    "INSERT INTO user_identities (user_id, provider, subject) VALUES ($1, $2, $3)",
+                    created.id,
+                    DIRECTORY_PROVIDER,
+                    email
+                )
+                .execute(&mut *transaction)
+                .await
+                .map_err(database_error)?;
+
+                created
+            }
+        };
+
+        let provisioned = match user.synchronized_roles(&provisioned.roles) {
+            Some(roles) if roles != provisioned.roles => sqlx::query_as!(
+                User,
+                "UPDATE users SET roles = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+                provisioned.id,
+                &roles
+            )
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(database_error)?,
+            _ => provisioned,
+        };
+
+        transaction.commit().await.map_err(database_error)?;
+
+        Ok(provisioned)
+    }
+
+    async fn create_personal_access_token(
+        &self,
+        token: &NewPersonalAccessToken,
+    ) -> Result<PersonalAccessToken, AuthRepositoryError> {
+        sqlx::query_as!(
+            PersonalAccessToken,
+            "INSERT INTO personal_access_tokens \
+             (user_id, name, token_prefix, token_hash, scopes, expires_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             RETURNING id, user_id, name, token_prefix, scopes, expires_at, last_used_at, created_at",
+            token.user_id,
+            token.name,
+            token.token_prefix,
+            token.token_hash,
+            &token.scopes,
+            token.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while creating personal access token: {}", e),
+        })
+    }
+
+    async fn list_personal_access_tokens(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<PersonalAccessToken>, AuthRepositoryError> {
+        sqlx::query_as!(
+            PersonalAccessToken,
+            "SELECT id, user_id, name, token_prefix, scopes, expires_at, last_used_at, created_at \
+             FROM personal_access_tokens WHERE user_id = $1 ORDER BY created_at",
+            user_id.get()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while listing personal access tokens: {}", e),
+        })
+    }
+
+    async fn delete_personal_access_token(
+        &self,
+        user_id: &UserId,
+        token_id: &uuid::Uuid,
+    ) -> Result<(), AuthRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2",
+            token_id,
+            user_id.get()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while deleting personal access token: {}", e),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "Personal access token does not exist".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn use_personal_access_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PersonalAccessToken, AuthRepositoryError> {
+        sqlx::query_as!(
+            PersonalAccessToken,
+            "UPDATE personal_access_tokens SET last_used_at = NOW() \
+             WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > NOW()) \
+             RETURNING id, user_id, name, token_prefix, scopes, expires_at, last_used_at, created_at",
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while looking up personal access token: {}", e),
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "Personal access token is invalid or expired".to_string(),
+        })
+    }
+
+    async fn expire_personal_access_token(
+        &self,
+        user_id: &UserId,
+        token_id: &uuid::Uuid,
+        expires_at: &DateTime<Utc>,
+    ) -> Result<PersonalAccessToken, AuthRepositoryError> {
+        sqlx::query_as!(
+            PersonalAccessToken,
+            "UPDATE personal_access_tokens SET expires_at = LEAST(COALESCE(expires_at, $3), $3) \
+             WHERE id = $1 AND user_id = $2 \
+             RETURNING id, user_id, name, token_prefix, scopes, expires_at, last_used_at, created_at",
+            token_id,
+            user_id.get(),
+            expires_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while expiring personal access token: {}", e),
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "Personal access token does not exist".to_string(),
+        })
+    }
+
+    async fn create_service_account(
+        &self,
+        account: &NewServiceAccount,
+    ) -> Result<User, AuthRepositoryError> {
+        // Service accounts have no password; an empty hash never verifies.
+        sqlx::query_as!(
+            User,
+            "INSERT INTO users (email, password, kind, owner_id) VALUES ($1, '', 'service', $2) \
+             ON CONFLICT (email) DO NOTHING RETURNING *",
+            account.email,
+            account.owner_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while creating service account: {}", e),
+        })?
+        .ok_or_else(|| match UserEmail::new(&account.email) {
+            Ok(email) => AuthRepositoryError::Duplicate { email },
+            Err(e) => AuthRepositoryError::Unknown(anyhow::anyhow!(e)),
+        })
+    }
+
+    async fn list_service_accounts(&self) -> Result<Vec<User>, AuthRepositoryError> {
+        sqlx::query_as!(
+            User,
+            "SELECT * FROM users WHERE kind = 'service' ORDER BY created_at"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while listing service accounts: {}", e),
+        })
+    }
+
+    async fn delete_service_account(&self, account_id: &UserId) -> Result<(), AuthRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM users WHERE id = $1 AND kind = 'service'",
+            account_id.get()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while deleting service account: {}", e),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "Service account does not exist".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn create_organization(
+        &self,
+        organization: &NewOrganization,
+    ) -> Result<Organization, AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while creating organization: {}", e),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let created = sqlx::query_as!(
+            Organization,
+            "INSERT INTO organizations (name, slug) VALUES ($1, $2) \
+             ON CONFLICT (slug) DO NOTHING RETURNING *",
+            organization.name,
+            organization.slug
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AuthRepositoryError::Conflict {
+            reason: format!("Organization slug {} is taken", organization.slug),
+        })?;
+
+        sqlx::query!(
+            "INSERT INTO organization_memberships (organization_id, user_id, role) \
+             VALUES ($1, $2, 'owner')",
+            created.id,
+            organization.owner_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)?;
+
+        Ok(created)
+    }
+
+    async fn list_memberships(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<OrganizationMembership>, AuthRepositoryError> {
+        sqlx::query_as!(
+            OrganizationMembership,
+            "SELECT m.organization_id, o.name, o.slug, m.role, m.created_at \
+             FROM organization_memberships m JOIN organizations o ON o.id = m.organization_id \
+             WHERE m.user_id = $1 ORDER BY m.created_at, o.slug",
+            user_id.get()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while listing memberships: {}", e),
+        })
+    }
+
+    async fn fetch_membership(
+        &self,
+        user_id: &UserId,
+        organization_id: &uuid::Uuid,
+    ) -> Result<OrganizationMembership, AuthRepositoryError> {
+        sqlx::query_as!(
+            OrganizationMembership,
+            "SELECT m.organization_id, o.name, o.slug, m.role, m.created_at \
+             FROM organization_memberships m JOIN organizations o ON o.id = m.organization_id \
+             WHERE m.user_id = $1 AND m.organization_id = $2",
+            user_id.get(),
+            organization_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while looking up membership: {}", e),
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "User is not a member of the organization".to_string(),
+        })
+    }
+
+    async fn list_organization_members(
+        &self,
+        organization_id: &uuid::Uuid,
+    ) -> Result<Vec<OrganizationMember>, AuthRepositoryError> {
+        sqlx::query_as!(
+            OrganizationMember,
+            "SELECT m.user_id, u.email, m.role, m.created_at \
+             FROM organization_memberships m JOIN users u ON u.id = m.user_id \
+             WHERE m.organization_id = $1 ORDER BY m.created_at, u.email",
+            organization_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while listing organization members: {}", e),
+        })
+    }
+
+    async fn create_invitation(
+        &self,
+        invitation: &NewInvitation,
+    ) -> Result<OrganizationInvitation, AuthRepositoryError> {
+        sqlx::query_as!(
+            OrganizationInvitation,
+            "INSERT INTO organization_invitations \
+             (organization_id, email, role, token_hash, invited_by, expires_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             ON CONFLICT (organization_id, email) DO UPDATE \
+             SET role = $3, token_hash = $4, invited_by = $5, expires_at = $6, created_at = NOW() \
+             RETURNING id, organization_id, email, role, expires_at, created_at",
+            invitation.organization_id,
+            invitation.email.to_ascii_lowercase(),
+            invitation.role.as_str(),
+            invitation.token_hash,
+            invitation.invited_by,
+            invitation.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while creating invitation: {}", e),
+        })
+    }
+
+    async fn accept_invitation(
+        &self,
+        token_hash: &str,
+        user_id: &UserId,
+        email: &str,
+    ) -> Result<OrganizationMembership, AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while accepting invitation: {}", e),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let invitation = sqlx::query!(
+            "DELETE FROM organization_invitations \
+             WHERE token_hash = $1 AND email = $2 AND expires_at > NOW() \
+             RETURNING organization_id, role",
+            token_hash,
+            email.to_ascii_lowercase()
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "Invitation is invalid, expired or for another email".to_string(),
+        })?;
+
+        sqlx::query!(
+            "INSERT INTO organization_memberships (organization_id, user_id, role) \
+             VALUES ($1, $2, $3) ON CONFLICT (organization_id, user_id) DO NOTHING",
+            invitation.organization_id,
+            user_id.get(),
+            invitation.role
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)?;
+
+        self.fetch_membership(user_id, &invitation.organization_id)
+            .await
+    }
+
+    async fn delete_membership(
+        &self,
+        organization_id: &uuid::Uuid,
+        user_id: &UserId,
+    ) -> Result<(), AuthRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM organization_memberships WHERE organization_id = $1 AND user_id = $2",
+            organization_id,
+            user_id.get()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while removing member: {}", e),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "User is not a member of the organization".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn create_registration_invitation(
+        &self,
+        invitation: &NewRegistrationInvitation,
+    ) -> Result<RegistrationInvitation, AuthRepositoryError> {
+        sqlx::query_as!(
+            RegistrationInvitation,
+            "INSERT INTO registration_invitations \
+             (id, email, organization_id, role, token_hash, created_by, expires_at) \
+             SELECT $1, $2, $3, $4, $5, $6, $7 \
+             WHERE $3::uuid IS NULL OR EXISTS (SELECT 1 FROM organizations WHERE id = $3) \
+             RETURNING id, email, organization_id, role, created_by, expires_at, created_at",
+            invitation.id,
+            invitation.email.to_ascii_lowercase(),
+            invitation.organization_id,
+            invitation.role.as_str(),
+            invitation.token_hash,
+            invitation.created_by,
+            invitation.expires_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!(
+                "Database error while creating registration invitation: {}",
+                e
+            ),
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "Organization not found".to_string(),
+        })
+    }
+
+    async fn list_registration_invitations(
+        &self,
+    ) -> Result<Vec<RegistrationInvitation>, AuthRepositoryError> {
+        sqlx::query_as!(
+            RegistrationInvitation,
+            "SELECT id, email, organization_id, role, created_by, expires_at, created_at \
+             FROM registration_invitations ORDER BY created_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!(
+                "Database error while listing registration invitations: {}",
+                e
+            ),
+        })
+    }
+
+    async fn delete_registration_invitation(
+        &self,
+        invitation_id: &uuid::Uuid,
+    ) -> Result<(), AuthRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM registration_invitations WHERE id = $1",
+            invitation_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!(
+                "Database error while revoking registration invitation: {}",
+                e
+            ),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "Registration invitation not found".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn list_users(
+        &self,
+        request: &ListUsersRequest,
+    ) -> Result<(Vec<User>, i64), AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while listing users: {}", e),
+        };
+        // The search is a substring match, so the LIKE wildcards in it are matched literally.
+        let email = request.email.as_ref().map(|email| {
+            format!(
+                "%{}%",
+                email
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            )
+        });
+        let status = request.status.map(|status| status.as_str());
+        let kind = request.kind.map(|kind| kind.as_str());
+
+        let users = sqlx::query_as!(
+            User,
+            "SELECT * FROM users \
+             WHERE ($1::text IS NULL OR email ILIKE $1) \
+             AND ($2::text IS NULL OR status = $2) \
+             AND ($3::text IS NULL OR kind = $3) \
+             AND ($4::text IS NULL OR $4 = ANY(roles)) \
+             ORDER BY created_at, id LIMIT $5 OFFSET $6",
+            email,
+            status,
+            kind,
+            request.role,
+            request.per_page,
+            request.offset()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        let total = sqlx::query_scalar!(
+            "SELECT COUNT(*) AS \"total!\" FROM users \
+             WHERE ($1::text IS NULL OR email ILIKE $1) \
+             AND ($2::text IS NULL OR status = $2) \
+             AND ($3::text IS NULL OR kind = $3) \
+             AND ($4::text IS NULL OR $4 = ANY(roles))",
+            email,
+            status,
+            kind,
+            request.role
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        Ok((users, total))
+    }
+
+    async fn update_user(&self, request: &UpdateUserRequest) -> Result<User, AuthRepositoryError> {
+        let email = request
+            .email
+            .as_ref()
+            .map(|email| email.get().to_ascii_lowercase());
+
+        sqlx::query_as!(
+            User,
+            "UPDATE users SET \
+             email_verified = email_verified AND ($2::text IS NULL OR email = $2), \
+             email = COALESCE($2, email), \
+             status = COALESCE($3, status), \
+             updated_at = NOW() \
+             WHERE id = $1 RETURNING *",
+            request.user_id.get(),
+            email,
+            request.status.map(|status| status.as_str())
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match (&request.email, e.as_database_error()) {
+            (Some(email), Some(database_error)) if database_error.is_unique_violation() => {
+                AuthRepositoryError::Duplicate {
+                    email: email.clone(),
+                }
+            }
+            _ => AuthRepositoryError::Database {
+                reason: format!("Database error while updating user: {}", e),
+            },
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "User does not exist".to_string(),
+        })
+    }
+
+    async fn revoke_user_sessions(&self, user_id: &UserId) -> Result<(), AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while revoking sessions: {}", e),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let result = sqlx::query!(
+            "UPDATE users SET sessions_revoked_at = NOW() WHERE id = $1",
+            user_id.get()
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "User does not exist".to_string(),
+            });
+        }
+
+        enqueue_webhook_event(
+            &mut *transaction,
+            &WebhookEvent::session_revoked(*user_id.get(), true, "revoked_by_admin"),
+        )
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)
+    }
+
+    async fn create_password_reset(
+        &self,
+        reset: &NewPasswordReset,
+    ) -> Result<(), AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while creating password reset: {}", e),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let result = sqlx::query!(
+            "UPDATE users SET password_reset_required = TRUE, sessions_revoked_at = NOW(), \
+             updated_at = NOW() WHERE id = $1",
+            reset.user_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "User does not exist".to_string(),
+            });
+        }
+
+        sqlx::query!(
+            "DELETE FROM password_resets WHERE user_id = $1",
+            reset.user_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        sqlx::query!(
+            "INSERT INTO password_resets (token_hash, user_id, expires_at) VALUES ($1, $2, $3)",
+            reset.token_hash,
+            reset.user_id,
+            reset.expires_at
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        enqueue_webhook_event(
+            &mut *transaction,
+            &WebhookEvent::session_revoked(reset.user_id, true, "password_reset_required"),
+        )
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)
+    }
+
+    async fn reset_password(
+        &self,
+        token_hash: &str,
+        password: &HashedUserPassword,
+    ) -> Result<uuid::Uuid, AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while resetting password: {}", e),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let user_id = sqlx::query_scalar!(
+            "DELETE FROM password_resets WHERE token_hash = $1 AND expires_at > NOW() \
+             RETURNING user_id",
+            token_hash
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "The password reset token is not valid or has expired".to_string(),
+        })?;
+
+        sqlx::query!(
+            "UPDATE users SET password = $2, password_reset_required = FALSE, \
+             sessions_revoked_at = NOW(), updated_at = NOW() WHERE id = $1",
+            user_id,
+            password.get()
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        enqueue_webhook_event(
+            &mut *transaction,
+            &WebhookEvent::password_changed(user_id, "reset"),
+        )
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)?;
+
+        Ok(user_id)
+    }
+
+    async fn delete_user(&self, user_id: &UserId) -> Result<(), AuthRepositoryError> {
+        let result = sqlx::query!("DELETE FROM users WHERE id = $1", user_id.get())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AuthRepositoryError::Database {
+                reason: format!("Database error while deleting user: {}", e),
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "User does not exist".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn update_password(
+        &self,
+        user_id: &UserId,
+        password: &HashedUserPassword,
+    ) -> Result<(), AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while updating password: {}", e),
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let result = sqlx::query!(
+            "UPDATE users SET password = $2, sessions_revoked_at = NOW(), updated_at = NOW() \
+             WHERE id = $1",
+            user_id.get(),
+            password.get()
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "User does not exist".to_string(),
+            });
+        }
+
+        enqueue_webhook_event(
+            &mut *transaction,
+            &WebhookEvent::password_changed(*user_id.get(), "changed"),
+        )
+        .await
+        .map_err(database_error)?;
+
+        transaction.commit().await.map_err(database_error)
+    }
+
+    async fn list_audit_events(
+        &self,
+        request: &ListAuditEventsRequest,
+    ) -> Result<(Vec<AuditEvent>, i64), AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while listing audit events: {}", e),
+        };
+        let event_type = request.event_type.map(|event_type| event_type.as_str());
+        let outcome = request.outcome.map(|outcome| outcome.as_str());
+
+        let events = sqlx::query_as!(
+            AuditEvent,
+            "SELECT id, sequence, previous_hash, hash, event_type, outcome, reason, user_id, \
+             actor_id, ip_address, user_agent, request_id, details, created_at FROM audit_events \
+             WHERE ($1::uuid IS NULL OR user_id = $1) \
+             AND ($2::uuid IS NULL OR actor_id = $2) \
+             AND ($3::text IS NULL OR event_type = $3) \
+             AND ($4::text IS NULL OR outcome = $4) \
+             AND ($5::text IS NULL OR ip_address = $5) \
+             AND ($6::text IS NULL OR request_id = $6) \
+             AND ($7::timestamptz IS NULL OR created_at >= $7) \
+             AND ($8::timestamptz IS NULL OR created_at < $8) \
+             ORDER BY sequence DESC LIMIT $9 OFFSET $10",
+            request.user_id,
+            request.actor_id,
+            event_type,
+            outcome,
+            request.ip_address,
+            request.request_id,
+            request.since,
+            request.until,
+            request.per_page,
+            request.offset()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        let total = sqlx::query_scalar!(
+            "SELECT COUNT(*) AS \"total!\" FROM audit_events \
+             WHERE ($1::uuid IS NULL OR user_id = $1) \
+             AND ($2::uuid IS NULL OR actor_id = $2) \
+             AND ($3::text IS NULL OR event_type = $3) \
+             AND ($4::text IS NULL OR outcome = $4) \
+             AND ($5::text IS NULL OR ip_address = $5) \
+             AND ($6::text IS NULL OR request_id = $6) \
+             AND ($7::timestamptz IS NULL OR created_at >= $7) \
+             AND ($8::timestamptz IS NULL OR created_at < $8)",
+            request.user_id,
+            request.actor_id,
+            event_type,
+            outcome,
+            request.ip_address,
+            request.request_id,
+            request.since,
+            request.until
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        Ok((events, total))
+    }
+
+    async fn list_audit_chain(
+        &self,
+        after_sequence: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, AuthRepositoryError> {
+        sqlx::query_as!(
+            AuditEvent,
+            "SELECT id, sequence, previous_hash, hash, event_type, outcome, reason, user_id, \
+             actor_id, ip_address, user_agent, request_id, details, created_at FROM audit_events \
+             WHERE sequence > $1 ORDER BY sequence LIMIT $2",
+            after_sequence,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while reading the audit chain: {}", e),
+        })
+    }
+
+    async fn list_audit_checkpoints(&self) -> Result<Vec<AuditCheckpoint>, AuthRepositoryError> {
+        sqlx::query_as!(
+            AuditCheckpoint,
+            "SELECT * FROM audit_checkpoints ORDER BY sequence"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while listing audit checkpoints: {}", e),
+        })
+    }
+
+    async fn enqueue_webhook_event(&self, event: &WebhookEvent) -> Result<(), AuthRepositoryError> {
+        enqueue_webhook_event(&self.pool, event)
+            .await
+            .map_err(|e| AuthRepositoryError::Database {
+                reason: format!("Database error while queueing webhook event: {}", e),
+            })
+    }
+
+    async fn create_webhook_subscription(
+        &self,
+        subscription: &NewWebhookSubscription,
+    ) -> Result<WebhookSubscription, AuthRepositoryError> {
+        sqlx::query_as!(
+            WebhookSubscription,
+            "INSERT INTO webhook_subscriptions (url, events, secret, created_by) \
+             VALUES ($1, $2, $3, $4) RETURNING id, url, events, created_by, created_at",
+            subscription.url,
+            &subscription.events,
+            subscription.secret,
+            subscription.created_by
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while creating webhook subscription: {}", e),
+        })
+    }
+
+    async fn list_webhook_subscriptions(
+        &self,
+    ) -> Result<Vec<WebhookSubscription>, AuthRepositoryError> {
+        sqlx::query_as!(
+            WebhookSubscription,
+            "SELECT id, url, events, created_by, created_at FROM webhook_subscriptions \
+             ORDER BY created_at"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while listing webhook subscriptions: {}", e),
+        })
+    }
+
+    async fn delete_webhook_subscription(
+        &self,
+        subscription_id: &uuid::Uuid,
+    ) -> Result<(), AuthRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM webhook_subscriptions WHERE id = $1",
+            subscription_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while deleting webhook subscription: {}", e),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "Webhook subscription does not exist".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        request: &ListWebhookDeliveriesRequest,
+    ) -> Result<Vec<WebhookDelivery>, AuthRepositoryError> {
+        let database_error = |e: sqlx::Error| AuthRepositoryError::Database {
+            reason: format!("Database error while listing webhook deliveries: {}", e),
+        };
+
+        let exists = sqlx::query_scalar!(
+            "SELECT EXISTS(SELECT 1 FROM webhook_subscriptions WHERE id = $1) AS \"exists!\"",
+            request.subscription_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        if !exists {
+            return Err(AuthRepositoryError::InvalidCredentials {
+                reason: "Webhook subscription does not exist".to_string(),
+            });
+        }
+
+        sqlx::query_as!(
+            WebhookDelivery,
+            "SELECT * FROM webhook_outbox \
+             WHERE subscription_id = $1 AND ($2::text IS NULL OR status = $2) \
+             ORDER BY created_at DESC LIMIT $3",
+            request.subscription_id,
+            request.status.map(|status| status.as_str()),
+            WEBHOOK_DELIVERIES_LIMIT
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error)
+    }
+
+    async fn retry_webhook_delivery(
+        &self,
+        subscription_id: &uuid::Uuid,
+        delivery_id: &uuid::Uuid,
+    ) -> Result<WebhookDelivery, AuthRepositoryError> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            "UPDATE webhook_outbox SET status = 'pending', attempts = 0, next_attempt_at = NOW() \
+             WHERE id = $1 AND subscription_id = $2 AND status = 'dead' RETURNING *",
+            delivery_id,
+            subscription_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while retrying webhook delivery: {}", e),
+        })?
+        .ok_or_else(|| AuthRepositoryError::InvalidCredentials {
+            reason: "Dead webhook delivery does not exist".to_string(),
+        })
+    }
+
+    async fn claim_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<PendingWebhookDelivery>, AuthRepositoryError> {
+        sqlx::query_as!(
+            PendingWebhookDelivery,
+            "UPDATE webhook_outbox AS delivery \
+             SET next_attempt_at = NOW() + make_interval(secs => $2::bigint) \
+             FROM webhook_subscriptions AS subscription \
+             WHERE subscription.id = delivery.subscription_id AND delivery.id IN ( \
+             SELECT id FROM webhook_outbox \
+             WHERE status = 'pending' AND next_attempt_at <= NOW() \
+             ORDER BY next_attempt_at LIMIT $1 FOR UPDATE SKIP LOCKED) \
+             RETURNING delivery.id, subscription.url, subscription.secret, delivery.event_type, \
+             delivery.payload, delivery.attempts",
+            limit,
+            lease_seconds
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while claiming webhook deliveries: {}", e),
+        })
+    }
+
+    async fn record_webhook_attempt(
+        &self,
+        attempt: &WebhookAttempt,
+    ) -> Result<(), AuthRepositoryError> {
+        let (status, next_attempt_at, error) = match &attempt.outcome {
+            WebhookAttemptOutcome::Delivered => (WebhookDeliveryStatus::Delivered, None, None),
+            WebhookAttemptOutcome::Retry {
+                next_attempt_at,
+                error,
+            } => (
+                WebhookDeliveryStatus::Pending,
+                Some(*next_attempt_at),
+                Some(error),
+            ),
+            WebhookAttemptOutcome::Dead { error } => {
+                (WebhookDeliveryStatus::Dead, None, Some(error))
+            }
+        };
+
+        sqlx::query!(
+            "UPDATE webhook_outbox SET status = $2::text, attempts = $3, \
+             next_attempt_at = COALESCE($4, next_attempt_at), last_error = $5, \
+             delivered_at = CASE WHEN $2::text = 'delivered' THEN NOW() END WHERE id = $1",
+            attempt.delivery_id,
+            status.as_str(),
+            attempt.attempts,
+            next_attempt_at,
+            error
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthRepositoryError::Database {
+            reason: format!("Database error while recording webhook attempt: {}", e),
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Queues `event` for every subscription to its type, on the pool or inside the transaction
+/// of the change that caused it.
+async fn enqueue_webhook_event<'e>(
+    executor: impl PgExecutor<'e>,
+    event: &WebhookEvent,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO webhook_outbox (subscription_id, event_id, event_type, payload) \
+         SELECT id, $1, $2::text, $3 FROM webhook_subscriptions WHERE $2::text = ANY(events)",
+        event.id,
+        event.event_type.as_str(),
+        event.payload()
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
 }
 
 impl PostgresDB {
@@ -1,9 +1,15 @@
 use anyhow::anyhow;
-use redis::{AsyncCommands, Client};
+use redis::{AsyncCommands, Client, ExistenceCheck, SetExpiry, SetOptions};
 
 use crate::domain::{
     model::{
+        authorize::AuthorizationCode,
         cache_errors::CacheOperationError,
+        device_authorization::{DeviceAuthorization, DeviceAuthorizationStatus},
+        federation::FederationState,
+        forward_auth::ForwardAuthIdentity,
+        saml::SamlRequestState,
+        session_revocation::{session_id, SessionRevocation},
         token::{CacheToken, TokenDetails},
         token_uuid::TokenUuid,
     },
@@ -14,7 +20,9 @@ use crate::domain::{
 ///
 /// The `RedisCache` struct provides methods for interacting with a Redis cache
 /// storage system. It allows for saving token data, verifying active sessions,
-/// and deleting tokens.
+/// deleting tokens, and storing single-use OAuth authorization codes, device authorizations,
+/// federated login states, the SAML requests and assertions seen and the identities given to
+/// reverse proxies.
 ///
 /// # Fields
 ///
@@ -55,7 +63,7 @@ impl CacheRepository for RedisCache {
             .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
 
         redis_client
-            .set_ex(
+            .set_ex::<_, _, ()>(
                 token.token_uuid.to_string(),
                 token.user_id.to_string(),
                 (token.max_age * 60) as u64,
@@ -78,7 +86,7 @@ impl CacheRepository for RedisCache {
             .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
 
         redis_client
-            .set_ex(
+            .set_ex::<_, _, ()>(
                 access_token.token_uuid.to_string(),
                 access_token.user_id.to_string(),
                 (access_token.max_age * 60) as u64,
@@ -87,7 +95,7 @@ impl CacheRepository for RedisCache {
             .map_err(|_| CacheOperationError::Save)?;
 
         redis_client
-            .set_ex(
+            .set_ex::<_, _, ()>(
                 refresh_token.token_uuid.to_string(),
                 refresh_token.user_id.to_string(),
                 (refresh_token.max_age * 60) as u64,
@@ -132,10 +140,589 @@ impl CacheRepository for RedisCache {
             .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
 
         redis_client
-            .del(token_uuid.get_string())
+            .del::<_, ()>(&[token_uuid.get_string(), forward_auth_key(token_uuid)])
             .await
             .map_err(|e| anyhow!(e).context("Failed to delete token from redis"))?;
 
         Ok(())
     }
+
+    async fn save_authorization_code(
+        &self,
+        code: &AuthorizationCode,
+    ) -> Result<(), CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value = serde_json::to_string(code)
+            .map_err(|e| anyhow!(e).context("Failed to serialize authorization code"))?;
+
+        redis_client
+            .set_ex::<_, _, ()>(
+                authorization_code_key(&code.code),
+                value,
+                code.max_age as u64,
+            )
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(())
+    }
+
+    async fn take_authorization_code(
+        &self,
+        code: &str,
+    ) -> Result<AuthorizationCode, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value: Option<String> = redis_client
+            .get_del(authorization_code_key(code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to take authorization code from redis"))?;
+
+        let value = value.ok_or_else(|| CacheOperationError::Invalid {
+            reason: "Authorization code is invalid, expired or already used".to_string(),
+        })?;
+
+        serde_json::from_str(&value).map_err(|e| {
+            anyhow!(e)
+                .context("Failed to deserialize authorization code")
+                .into()
+        })
+    }
+
+    async fn count_request(
+        &self,
+        key: &str,
+        window_seconds: i64,
+    ) -> Result<i64, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        // The window starts with the counter, so a counter never exists without an expiry,
+        // and INCR keeps the expiry it was created with.
+        let key = rate_limit_key(key);
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(window_seconds as usize));
+        redis_client
+            .set_options::<_, _, ()>(&key, 0, options)
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to start rate limit window"))?;
+
+        let count: i64 = redis_client
+            .incr(&key, 1)
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to increment rate limit counter"))?;
+
+        Ok(count)
+    }
+
+    async fn fetch_request_count(&self, key: &str) -> Result<i64, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let count: Option<i64> = redis_client
+            .get(rate_limit_key(key))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to read rate limit counter"))?;
+
+        Ok(count.unwrap_or(0))
+    }
+
+    async fn save_device_authorization(
+        &self,
+        authorization: &DeviceAuthorization,
+    ) -> Result<(), CacheOperationError> {
+        let expires_in = authorization.expires_in();
+        if expires_in <= 0 {
+            return Err(CacheOperationError::Invalid {
+                reason: "Device code has expired".to_string(),
+            });
+        }
+
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value = serde_json::to_string(authorization)
+            .map_err(|e| anyhow!(e).context("Failed to serialize device authorization"))?;
+
+        redis_client
+            .set_ex::<_, _, ()>(
+                device_code_key(&authorization.device_code),
+                value,
+                expires_in as u64,
+            )
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        redis_client
+            .set_ex::<_, _, ()>(
+                user_code_key(&authorization.user_code),
+                &authorization.device_code,
+                expires_in as u64,
+            )
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(())
+    }
+
+    async fn update_device_polling(
+        &self,
+        authorization: &DeviceAuthorization,
+    ) -> Result<(), CacheOperationError> {
+        let expires_in = authorization.expires_in();
+        if expires_in <= 0 {
+            return Err(CacheOperationError::Invalid {
+                reason: "Device code has expired".to_string(),
+            });
+        }
+
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value = serde_json::to_string(authorization)
+            .map_err(|e| anyhow!(e).context("Failed to serialize device authorization"))?;
+
+        // Only overwrites an authorization that was not taken in the meantime.
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::XX)
+            .with_expiration(SetExpiry::EX(expires_in as usize));
+        redis_client
+            .set_options::<_, _, ()>(device_code_key(&authorization.device_code), value, options)
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(())
+    }
+
+    async fn decide_device_authorization(
+        &self,
+        authorization: &DeviceAuthorization,
+        status: &DeviceAuthorizationStatus,
+    ) -> Result<bool, CacheOperationError> {
+        let expires_in = authorization.expires_in();
+        if expires_in <= 0 {
+            return Err(CacheOperationError::Invalid {
+                reason: "Device code has expired".to_string(),
+            });
+        }
+
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value = serde_json::to_string(status)
+            .map_err(|e| anyhow!(e).context("Failed to serialize device authorization status"))?;
+
+        // An authorization is pending as long as it has no decision, so only the first one is
+        // ever recorded.
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(expires_in as usize));
+        let decided: Option<String> = redis_client
+            .set_options(
+                device_decision_key(&authorization.device_code),
+                value,
+                options,
+            )
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(decided.is_some())
+    }
+
+    async fn fetch_device_authorization(
+        &self,
+        device_code: &str,
+    ) -> Result<DeviceAuthorization, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value: Option<String> = redis_client
+            .get(device_code_key(device_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to fetch device authorization from redis"))?;
+
+        let decision: Option<String> = redis_client
+            .get(device_decision_key(device_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to fetch device decision from redis"))?;
+
+        parse_device_authorization(value, decision)
+    }
+
+    async fn fetch_device_authorization_by_user_code(
+        &self,
+        user_code: &str,
+    ) -> Result<DeviceAuthorization, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let device_code: Option<String> = redis_client
+            .get(user_code_key(user_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to fetch user code from redis"))?;
+
+        let device_code = device_code.ok_or_else(|| CacheOperationError::Invalid {
+            reason: "User code is invalid or expired".to_string(),
+        })?;
+
+        let value: Option<String> = redis_client
+            .get(device_code_key(&device_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to fetch device authorization from redis"))?;
+
+        let decision: Option<String> = redis_client
+            .get(device_decision_key(&device_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to fetch device decision from redis"))?;
+
+        parse_device_authorization(value, decision)
+    }
+
+    async fn take_device_authorization(
+        &self,
+        device_code: &str,
+    ) -> Result<DeviceAuthorization, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value: Option<String> = redis_client
+            .get_del(device_code_key(device_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to take device authorization from redis"))?;
+
+        let decision: Option<String> = redis_client
+            .get_del(device_decision_key(device_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to take device decision from redis"))?;
+
+        let authorization = parse_device_authorization(value, decision)?;
+
+        redis_client
+            .del::<_, ()>(user_code_key(&authorization.user_code))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to delete user code from redis"))?;
+
+        Ok(authorization)
+    }
+
+    async fn save_federation_state(
+        &self,
+        state: &FederationState,
+    ) -> Result<(), CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value = serde_json::to_string(state)
+            .map_err(|e| anyhow!(e).context("Failed to serialize federation state"))?;
+
+        redis_client
+            .set_ex::<_, _, ()>(
+                federation_state_key(&state.state),
+                value,
+                state.max_age as u64,
+            )
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(())
+    }
+
+    async fn take_federation_state(
+        &self,
+        state: &str,
+    ) -> Result<FederationState, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value: Option<String> = redis_client
+            .get_del(federation_state_key(state))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to take federation state from redis"))?;
+
+        let value = value.ok_or_else(|| CacheOperationError::Invalid {
+            reason: "Login state is invalid, expired or already used".to_string(),
+        })?;
+
+        serde_json::from_str(&value).map_err(|e| {
+            anyhow!(e)
+                .context("Failed to deserialize federation state")
+                .into()
+        })
+    }
+
+    async fn save_saml_request(&self, state: &SamlRequestState) -> Result<(), CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value = serde_json::to_string(state)
+            .map_err(|e| anyhow!(e).context("Failed to serialize SAML request state"))?;
+
+        redis_client
+            .set_ex::<_, _, ()>(
+                saml_request_key(&state.relay_state),
+                value,
+                state.max_age as u64,
+            )
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(())
+    }
+
+    async fn take_saml_request(
+        &self,
+        relay_state: &str,
+    ) -> Result<SamlRequestState, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value: Option<String> = redis_client
+            .get_del(saml_request_key(relay_state))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to take SAML request state from redis"))?;
+
+        let value = value.ok_or_else(|| CacheOperationError::Invalid {
+            reason: "SAML request is invalid, expired or already answered".to_string(),
+        })?;
+
+        serde_json::from_str(&value).map_err(|e| {
+            anyhow!(e)
+                .context("Failed to deserialize SAML request state")
+                .into()
+        })
+    }
+
+    async fn record_saml_assertion(
+        &self,
+        id: &str,
+        expires_at: i64,
+    ) -> Result<(), CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        // Expired assertions are rejected on their own, so they only need keeping until then.
+        let ttl = (expires_at - chrono::Utc::now().timestamp()).max(1) as usize;
+        let recorded: Option<String> = redis_client
+            .set_options(
+                saml_assertion_key(id),
+                1,
+                SetOptions::default()
+                    .conditional_set(ExistenceCheck::NX)
+                    .with_expiration(SetExpiry::EX(ttl)),
+            )
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to record SAML assertion in redis"))?;
+
+        match recorded {
+            Some(_) => Ok(()),
+            None => Err(CacheOperationError::Invalid {
+                reason: "SAML assertion was already used".to_string(),
+            }),
+        }
+    }
+
+    async fn save_forward_auth(
+        &self,
+        token_uuid: &TokenUuid,
+        identity: &ForwardAuthIdentity,
+        ttl_seconds: u64,
+    ) -> Result<(), CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let value = serde_json::to_string(identity)
+            .map_err(|e| anyhow!(e).context("Failed to serialize forward auth identity"))?;
+
+        redis_client
+            .set_ex::<_, _, ()>(forward_auth_key(token_uuid), value, ttl_seconds)
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(())
+    }
+
+    async fn fetch_forward_auth(
+        &self,
+        token: &TokenDetails,
+    ) -> Result<Option<ForwardAuthIdentity>, CacheOperationError> {
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        let mut keys = vec![
+            forward_auth_key(&TokenUuid::new(token.token_uuid)),
+            revoked_user_key(&token.user_id),
+        ];
+        keys.extend(session_id(&token.claims).map(|id| revoked_session_key(&id)));
+
+        let values: Vec<Option<String>> = redis_client
+            .mget(keys)
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to fetch forward auth identity from redis"))?;
+
+        let revoked = values[1..].iter().flatten().any(|revoked_at| {
+            revoked_at
+                .parse()
+                .is_ok_and(|at: i64| token.issued_at <= at)
+        });
+        if revoked {
+            return Ok(None);
+        }
+
+        values[0]
+            .as_ref()
+            .map(|value| {
+                serde_json::from_str(value).map_err(|e| {
+                    anyhow!(e)
+                        .context("Failed to deserialize forward auth identity")
+                        .into()
+                })
+            })
+            .transpose()
+    }
+
+    async fn record_revocation(
+        &self,
+        revocation: &SessionRevocation,
+    ) -> Result<(), CacheOperationError> {
+        let key = match (revocation.session_id, revocation.user_id) {
+            (Some(session_id), _) => revoked_session_key(&session_id),
+            (None, Some(user_id)) if revocation.token_uuid.is_none() => revoked_user_key(&user_id),
+            _ => return Ok(()),
+        };
+        let ttl = revocation.expires_at - chrono::Utc::now().timestamp();
+        if ttl <= 0 {
+            return Ok(());
+        }
+
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        redis_client
+            .set_ex::<_, _, ()>(key, revocation.revoked_at, ttl as u64)
+            .await
+            .map_err(|_| CacheOperationError::Save)?;
+
+        Ok(())
+    }
+}
+
+/// Reads a device authorization and the user's decision on it, pending while there is none.
+fn parse_device_authorization(
+    value: Option<String>,
+    decision: Option<String>,
+) -> Result<DeviceAuthorization, CacheOperationError> {
+    let value = value.ok_or_else(|| CacheOperationError::Invalid {
+        reason: "Device code is invalid or expired".to_string(),
+    })?;
+
+    let mut authorization: DeviceAuthorization = serde_json::from_str(&value)
+        .map_err(|e| anyhow!(e).context("Failed to deserialize device authorization"))?;
+    if let Some(decision) = decision {
+        authorization.status = serde_json::from_str(&decision)
+            .map_err(|e| anyhow!(e).context("Failed to deserialize device decision"))?;
+    }
+
+    Ok(authorization)
+}
+
+fn authorization_code_key(code: &str) -> String {
+    format!("oauth_code:{}", code)
+}
+
+fn device_code_key(device_code: &str) -> String {
+    format!("oauth_device_code:{}", device_code)
+}
+
+fn device_decision_key(device_code: &str) -> String {
+    format!("oauth_device_decision:{}", device_code)
+}
+
+fn user_code_key(user_code: &str) -> String {
+    format!("oauth_user_code:{}", user_code)
+}
+
+fn federation_state_key(state: &str) -> String {
+    format!("federation_state:{}", state)
+}
+
+fn saml_request_key(relay_state: &str) -> String {
+    format!("saml_request:{}", relay_state)
+}
+
+fn saml_assertion_key(id: &str) -> String {
+    format!("saml_assertion:{}", id)
+}
+
+fn forward_auth_key(token_uuid: &TokenUuid) -> String {
+    format!("forward_auth:{}", token_uuid.get_string())
+}
+
+fn revoked_session_key(session_id: &uuid::Uuid) -> String {
+    format!("revoked_session:{}", session_id)
+}
+
+fn revoked_user_key(user_id: &uuid::Uuid) -> String {
+    format!("revoked_user:{}", user_id)
+}
+
+fn rate_limit_key(key: &str) -> String {
+    format!("rate_limit:{}", key)
 }
@@ -0,0 +1,172 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use anyhow::{anyhow, Context};
+use redis::{streams::StreamMaxlen, AsyncCommands, Client};
+use tokio::sync::Mutex;
+
+use crate::domain::{
+    model::{
+        domain_event::{DomainEvent, EventPublishError},
+        session_revocation::SessionRevocation,
+    },
+    repositories::event_publisher::EventPublisher,
+};
+
+/// A Redis Streams implementation of the `EventPublisher` trait.
+///
+/// Every event is appended to `stream` with `XADD`, as the fields `type`, `version` and
+/// `event`, the last one holding the event as JSON. The stream is trimmed to about `max_len`
+/// entries, so consumers that fall further behind than that miss events.
+///
+/// Revocations are sent with `PUBLISH` on `revocation_channel` instead, as JSON, for the
+/// `RevocationSubscriber`s listening on it.
+#[derive(Debug)]
+pub struct RedisEventPublisher {
+    client: Client,
+    stream: String,
+    max_len: usize,
+    revocation_channel: String,
+}
+
+impl RedisEventPublisher {
+    pub fn new(
+        url: &str,
+        stream: &str,
+        max_len: usize,
+        revocation_channel: &str,
+    ) -> anyhow::Result<RedisEventPublisher> {
+        let client = Client::open(url).context("Invalid redis url for events")?;
+
+        Ok(RedisEventPublisher {
+            client,
+            stream: stream.to_string(),
+            max_len,
+            revocation_channel: revocation_channel.to_string(),
+        })
+    }
+}
+
+impl EventPublisher for RedisEventPublisher {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), EventPublishError> {
+        let payload = serde_json::to_string(event).context("Failed to serialize event")?;
+
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        redis_client
+            .xadd_maxlen::<_, _, _, _, ()>(
+                &self.stream,
+                StreamMaxlen::Approx(self.max_len),
+                "*",
+                &[
+                    ("type", event.event_type.as_str().to_string()),
+                    ("version", event.version.to_string()),
+                    ("event", payload),
+                ],
+            )
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to add event to redis stream"))?;
+
+        Ok(())
+    }
+
+    async fn publish_revocation(
+        &self,
+        revocation: &SessionRevocation,
+    ) -> Result<(), EventPublishError> {
+        let payload =
+            serde_json::to_string(revocation).context("Failed to serialize revocation")?;
+
+        let mut redis_client = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to get redis connection"))?;
+
+        redis_client
+            .publish::<_, _, ()>(&self.revocation_channel, payload)
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to publish revocation"))?;
+
+        Ok(())
+    }
+}
+
+/// How many events, and how many revocations, an `InMemoryEventPublisher` keeps.
+pub const IN_MEMORY_EVENT_CAPACITY: usize = 1000;
+
+/// An `EventPublisher` that keeps published events in memory, for embedding the service
+/// without Redis and for tests.
+///
+/// Only the last `IN_MEMORY_EVENT_CAPACITY` events and revocations are kept, the oldest being
+/// dropped first, like a Redis stream trimmed to its `max_len`. Clones share the same events,
+/// so one can be kept to read what the service published.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryEventPublisher {
+    events: Arc<Mutex<VecDeque<DomainEvent>>>,
+    revocations: Arc<Mutex<VecDeque<SessionRevocation>>>,
+}
+
+impl InMemoryEventPublisher {
+    /// The events published so far, oldest first.
+    pub async fn published(&self) -> Vec<DomainEvent> {
+        self.events.lock().await.iter().cloned().collect()
+    }
+
+    /// The revocations published so far, oldest first.
+    pub async fn revocations(&self) -> Vec<SessionRevocation> {
+        self.revocations.lock().await.iter().cloned().collect()
+    }
+}
+
+/// Appends `item`, dropping the oldest items beyond `IN_MEMORY_EVENT_CAPACITY`.
+fn push_capped<T>(items: &mut VecDeque<T>, item: T) {
+    if items.len() == IN_MEMORY_EVENT_CAPACITY {
+        items.pop_front();
+    }
+    items.push_back(item);
+}
+
+impl EventPublisher for InMemoryEventPublisher {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), EventPublishError> {
+        push_capped(&mut *self.events.lock().await, event.clone());
+        Ok(())
+    }
+
+    async fn publish_revocation(
+        &self,
+        revocation: &SessionRevocation,
+    ) -> Result<(), EventPublishError> {
+        push_capped(&mut *self.revocations.lock().await, revocation.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::domain_event::DomainEventType;
+
+    #[tokio::test]
+    async fn test_in_memory_publisher_drops_oldest_events_beyond_capacity() {
+        let publisher = InMemoryEventPublisher::default();
+        let first = DomainEvent::new(DomainEventType::UserLoggedOut, None);
+
+        publisher.publish(&first).await.unwrap();
+        for _ in 0..IN_MEMORY_EVENT_CAPACITY {
+            publisher
+                .publish(&DomainEvent::new(DomainEventType::SessionRefreshed, None))
+                .await
+                .unwrap();
+        }
+
+        let published = publisher.published().await;
+        assert_eq!(published.len(), IN_MEMORY_EVENT_CAPACITY);
+        assert!(published
+            .iter()
+            .all(|event| event.event_type == DomainEventType::SessionRefreshed));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod audit_sink;
 pub mod auth_repository;
 pub mod cache_repository;
+pub mod event_publisher;
+pub mod revocation_subscriber;
 pub mod test_helpers;
@@ -0,0 +1,97 @@
+use std::{thread, time::Duration};
+
+use anyhow::Context;
+use redis::Client;
+
+use crate::domain::model::session_revocation::{RevocationDenylist, SessionRevocation};
+
+/// How long the subscriber waits before reconnecting after losing the channel.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Listens to the revocations broadcast by `RedisEventPublisher` on a Redis pub/sub channel,
+/// and keeps a `RevocationDenylist` up to date with them, for services that verify access
+/// tokens locally.
+///
+/// Pub/sub delivers no history, so revocations broadcast while the subscriber is disconnected
+/// are missed, and those tokens stay accepted until they expire, as they would without it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use authentication_service::{
+///     domain::model::session_revocation::RevocationDenylist,
+///     repositories::revocation_subscriber::RevocationSubscriber,
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let denylist = RevocationDenylist::default();
+/// RevocationSubscriber::new("redis://127.0.0.1:6379", "auth:revocations")?
+///     .spawn(denylist.clone());
+///
+/// // For every request, after verifying the signature and `exp` of its access token:
+/// # let token = authentication_service::domain::model::token::TokenDetails {
+/// #     token: None,
+/// #     token_uuid: uuid::Uuid::new_v4(),
+/// #     user_id: uuid::Uuid::new_v4(),
+/// #     issued_at: 0,
+/// #     expires_in: None,
+/// #     claims: Default::default(),
+/// # };
+/// if denylist.is_revoked(&token) {
+///     // reject the request
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RevocationSubscriber {
+    client: Client,
+    channel: String,
+}
+
+impl RevocationSubscriber {
+    pub fn new(url: &str, channel: &str) -> anyhow::Result<RevocationSubscriber> {
+        let client = Client::open(url).context("Invalid redis url for revocations")?;
+
+        Ok(RevocationSubscriber {
+            client,
+            channel: channel.to_string(),
+        })
+    }
+
+    /// Starts listening on a thread of its own, reconnecting whenever the connection is lost,
+    /// and adding every revocation received to `denylist`.
+    pub fn spawn(self, denylist: RevocationDenylist) -> thread::JoinHandle<()> {
+        thread::spawn(move || loop {
+            if let Err(e) = self.listen(&denylist) {
+                tracing::error!("Revocation subscriber disconnected: {:?}", e);
+            }
+            thread::sleep(RECONNECT_DELAY);
+        })
+    }
+
+    fn listen(&self, denylist: &RevocationDenylist) -> anyhow::Result<()> {
+        let mut connection = self
+            .client
+            .get_connection()
+            .context("Failed to get redis connection")?;
+        let mut pubsub = connection.as_pubsub();
+        pubsub
+            .subscribe(&self.channel)
+            .context("Failed to subscribe to the revocation channel")?;
+
+        loop {
+            let message = pubsub
+                .get_message()
+                .context("Failed to read from the revocation channel")?;
+            let payload = message
+                .get_payload::<String>()
+                .context("Failed to read revocation payload")?;
+
+            match serde_json::from_str::<SessionRevocation>(&payload) {
+                Ok(revocation) => denylist.insert(&revocation),
+                Err(e) => tracing::warn!("Ignoring malformed revocation {}: {:?}", payload, e),
+            }
+        }
+    }
+}
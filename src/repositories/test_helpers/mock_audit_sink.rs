@@ -0,0 +1,123 @@
+#[cfg(test)]
+pub mod test_helpers {
+    use std::sync::Arc;
+
+    use anyhow::anyhow;
+    use tokio::sync::Mutex;
+
+    use crate::domain::{
+        model::audit::{
+            AuditCheckpoint, AuditError, AuditEvent, AuditEventType, NewAuditEvent, RequestContext,
+        },
+        repositories::audit_sink::AuditSink,
+    };
+
+    /// Keeps the events recorded through it, so tests can check what was audited, chained
+    /// as they would be stored, along with the checkpoints saved.
+    ///
+    /// Clones share the same events, so a test can keep one while the service owns another.
+    /// A failing sink records nothing, and every call returns an error.
+    #[derive(Clone, Default)]
+    pub struct MockAuditSink {
+        pub events: Arc<Mutex<Vec<NewAuditEvent>>>,
+        pub chain: Arc<Mutex<Vec<AuditEvent>>>,
+        pub checkpoints: Arc<Mutex<Vec<AuditCheckpoint>>>,
+        pub fails: bool,
+    }
+
+    impl AuditSink for MockAuditSink {
+        async fn record(&self, event: &NewAuditEvent) -> Result<AuditEvent, AuditError> {
+            if self.fails {
+                return Err(AuditError::Unknown(anyhow!("record result error")));
+            }
+
+            let mut chain = self.chain.lock().await;
+            let chained = AuditEvent::chain(
+                event,
+                chain.len() as i64 + 1,
+                chain.last().and_then(|last| last.hash.clone()),
+            );
+            chain.push(chained.clone());
+            self.events.lock().await.push(event.clone());
+            Ok(chained)
+        }
+
+        async fn save_checkpoint(&self, checkpoint: &AuditCheckpoint) -> Result<(), AuditError> {
+            if self.fails {
+                return Err(AuditError::Unknown(anyhow!("save checkpoint result error")));
+            }
+
+            self.checkpoints.lock().await.push(checkpoint.clone());
+            Ok(())
+        }
+    }
+
+    impl MockAuditSink {
+        pub fn success() -> MockAuditSink {
+            MockAuditSink::default()
+        }
+
+        pub fn failure() -> MockAuditSink {
+            MockAuditSink {
+                fails: true,
+                ..MockAuditSink::default()
+            }
+        }
+
+        /// The events recorded so far, oldest first.
+        pub async fn recorded(&self) -> Vec<NewAuditEvent> {
+            self.events.lock().await.clone()
+        }
+
+        /// The events recorded so far as they were chained, oldest first.
+        pub async fn chained(&self) -> Vec<AuditEvent> {
+            self.chain.lock().await.clone()
+        }
+
+        pub async fn saved_checkpoints(&self) -> Vec<AuditCheckpoint> {
+            self.checkpoints.lock().await.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_success() {
+        let sink = MockAuditSink::success();
+        let event = NewAuditEvent::success(AuditEventType::Logout, &RequestContext::default());
+
+        let first = sink.clone().record(&event).await.unwrap();
+        let second = sink.clone().record(&event).await.unwrap();
+
+        assert_eq!(sink.recorded().await, vec![event.clone(), event]);
+        assert_eq!(second.sequence, 2);
+        assert_eq!(second.previous_hash, first.hash);
+        assert_eq!(sink.chained().await, vec![first, second]);
+    }
+
+    #[tokio::test]
+    async fn test_save_checkpoint_success() {
+        let sink = MockAuditSink::success();
+        let checkpoint = AuditCheckpoint {
+            sequence: 1,
+            hash: "hash".to_string(),
+            signature: "signature".to_string(),
+            created_at: None,
+        };
+
+        let result = sink.clone().save_checkpoint(&checkpoint).await;
+
+        assert!(result.is_ok());
+        assert_eq!(sink.saved_checkpoints().await, vec![checkpoint]);
+    }
+
+    #[tokio::test]
+    async fn test_record_failure() {
+        let sink = MockAuditSink::failure();
+        let event = NewAuditEvent::success(AuditEventType::Logout, &RequestContext::default());
+
+        let result = sink.record(&event).await;
+
+        assert!(result.is_err());
+        assert!(sink.recorded().await.is_empty());
+        assert!(sink.chained().await.is_empty());
+    }
+}
@@ -1,22 +1,50 @@
 #[cfg(test)]
 pub mod test_helpers {
     use anyhow::anyhow;
+    use chrono::{DateTime, Utc};
     use std::{mem, ops::DerefMut, sync::Arc};
     use tokio::sync::Mutex;
 
     use crate::domain::{
         model::{
+            admin_user::{ListUsersRequest, UpdateUserRequest},
+            audit::{
+                AuditCheckpoint, AuditEvent, AuditEventType, ListAuditEventsRequest, NewAuditEvent,
+                RequestContext,
+            },
             auth_repo_errors::AuthRepositoryError,
+            federation::FederatedIdentity,
+            ldap::DirectoryUser,
             login_user::LoginUserRequest,
+            oauth_client::{ClientId, ClientType, OAuthClient},
+            organization::{
+                NewInvitation, NewOrganization, Organization, OrganizationInvitation,
+                OrganizationMember, OrganizationMembership, OrganizationRole,
+            },
+            password_reset::NewPasswordReset,
+            personal_access_token::{NewPersonalAccessToken, PersonalAccessToken},
             register_user::{HashedUserPassword, RegisterUserRequest},
-            user::{FilteredUser, User},
+            registration::{NewRegistrationInvitation, RegistrationInvitation},
+            service_account::NewServiceAccount,
+            user::{FilteredUser, User, UserKind, UserStatus},
             user_email::UserEmail,
             user_id::UserId,
             user_password::UserPassword,
+            webhook::{
+                ListWebhookDeliveriesRequest, NewWebhookSubscription, PendingWebhookDelivery,
+                WebhookAttempt, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent,
+                WebhookSubscription,
+            },
         },
         repositories::auth_repository::AuthRepository,
     };
 
+    /// A page of users with the total number of matching users.
+    type ListUsersResult = Result<(Vec<User>, i64), AuthRepositoryError>;
+
+    /// A page of audit events with the total number of matching events.
+    type ListAuditEventsResult = Result<(Vec<AuditEvent>, i64), AuthRepositoryError>;
+
     pub struct MockAuthRepository {
         /// It would be great for result to just take a Result instead of the below, unfortunately
         /// it needs to conform to `Clone` but AuthRepositoryError` has an `Unknown` variant that
@@ -24,6 +52,64 @@ pub mod test_helpers {
         pub register_result: Arc<Mutex<Result<FilteredUser, AuthRepositoryError>>>,
         pub auth_result: Arc<Mutex<Result<User, AuthRepositoryError>>>,
         pub login_result: Arc<Mutex<Result<User, AuthRepositoryError>>>,
+        pub fetch_oauth_client_result: Arc<Mutex<Result<OAuthClient, AuthRepositoryError>>>,
+        pub fetch_user_by_identity_result: Arc<Mutex<Result<User, AuthRepositoryError>>>,
+        pub link_identity_result: Arc<Mutex<Result<User, AuthRepositoryError>>>,
+        pub provision_directory_user_result: Arc<Mutex<Result<User, AuthRepositoryError>>>,
+        pub create_personal_access_token_result:
+            Arc<Mutex<Result<PersonalAccessToken, AuthRepositoryError>>>,
+        pub list_personal_access_tokens_result:
+            Arc<Mutex<Result<Vec<PersonalAccessToken>, AuthRepositoryError>>>,
+        pub delete_personal_access_token_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub use_personal_access_token_result:
+            Arc<Mutex<Result<PersonalAccessToken, AuthRepositoryError>>>,
+        pub expire_personal_access_token_result:
+            Arc<Mutex<Result<PersonalAccessToken, AuthRepositoryError>>>,
+        pub create_service_account_result: Arc<Mutex<Result<User, AuthRepositoryError>>>,
+        pub list_service_accounts_result: Arc<Mutex<Result<Vec<User>, AuthRepositoryError>>>,
+        pub delete_service_account_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub create_organization_result: Arc<Mutex<Result<Organization, AuthRepositoryError>>>,
+        pub list_memberships_result:
+            Arc<Mutex<Result<Vec<OrganizationMembership>, AuthRepositoryError>>>,
+        pub fetch_membership_result:
+            Arc<Mutex<Result<OrganizationMembership, AuthRepositoryError>>>,
+        pub list_organization_members_result:
+            Arc<Mutex<Result<Vec<OrganizationMember>, AuthRepositoryError>>>,
+        pub create_invitation_result:
+            Arc<Mutex<Result<OrganizationInvitation, AuthRepositoryError>>>,
+        pub accept_invitation_result:
+            Arc<Mutex<Result<OrganizationMembership, AuthRepositoryError>>>,
+        pub delete_membership_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub create_registration_invitation_result:
+            Arc<Mutex<Result<RegistrationInvitation, AuthRepositoryError>>>,
+        pub list_registration_invitations_result:
+            Arc<Mutex<Result<Vec<RegistrationInvitation>, AuthRepositoryError>>>,
+        pub delete_registration_invitation_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub list_users_result: Arc<Mutex<ListUsersResult>>,
+        pub update_user_result: Arc<Mutex<Result<User, AuthRepositoryError>>>,
+        pub revoke_user_sessions_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub create_password_reset_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub reset_password_result: Arc<Mutex<Result<uuid::Uuid, AuthRepositoryError>>>,
+        pub delete_user_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub update_password_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub list_audit_events_result: Arc<Mutex<ListAuditEventsResult>>,
+        pub list_audit_chain_result: Arc<Mutex<Result<Vec<AuditEvent>, AuthRepositoryError>>>,
+        pub list_audit_checkpoints_result:
+            Arc<Mutex<Result<Vec<AuditCheckpoint>, AuthRepositoryError>>>,
+        pub create_webhook_subscription_result:
+            Arc<Mutex<Result<WebhookSubscription, AuthRepositoryError>>>,
+        pub list_webhook_subscriptions_result:
+            Arc<Mutex<Result<Vec<WebhookSubscription>, AuthRepositoryError>>>,
+        pub delete_webhook_subscription_result: Arc<Mutex<Result<(), AuthRepositoryError>>>,
+        pub list_webhook_deliveries_result:
+            Arc<Mutex<Result<Vec<WebhookDelivery>, AuthRepositoryError>>>,
+        pub retry_webhook_delivery_result: Arc<Mutex<Result<WebhookDelivery, AuthRepositoryError>>>,
+        pub claim_webhook_deliveries_result:
+            Arc<Mutex<Result<Vec<PendingWebhookDelivery>, AuthRepositoryError>>>,
+        /// Queued webhook events and recorded attempts are kept rather than given a result, as
+        /// the service calls these any number of times and never fails because of them.
+        pub enqueued_webhook_events: Arc<Mutex<Vec<WebhookEvent>>>,
+        pub recorded_webhook_attempts: Arc<Mutex<Vec<WebhookAttempt>>>,
     }
 
     impl AuthRepository for MockAuthRepository {
@@ -50,6 +136,410 @@ pub mod test_helpers {
             mem::swap(guard.deref_mut(), &mut result);
             result
         }
+
+        async fn fetch_oauth_client(
+            &self,
+            _request: &ClientId,
+        ) -> Result<OAuthClient, AuthRepositoryError> {
+            let mut guard = self.fetch_oauth_client_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn fetch_user_by_identity(
+            &self,
+            _identity: &FederatedIdentity,
+        ) -> Result<User, AuthRepositoryError> {
+            let mut guard = self.fetch_user_by_identity_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn link_identity(
+            &self,
+            _identity: &FederatedIdentity,
+            _may_register: bool,
+        ) -> Result<User, AuthRepositoryError> {
+            let mut guard = self.link_identity_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn provision_directory_user(
+            &self,
+            _user: &DirectoryUser,
+            _may_register: bool,
+        ) -> Result<User, AuthRepositoryError> {
+            let mut guard = self.provision_directory_user_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn create_personal_access_token(
+            &self,
+            _token: &NewPersonalAccessToken,
+        ) -> Result<PersonalAccessToken, AuthRepositoryError> {
+            let mut guard = self.create_personal_access_token_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_personal_access_tokens(
+            &self,
+            _user_id: &UserId,
+        ) -> Result<Vec<PersonalAccessToken>, AuthRepositoryError> {
+            let mut guard = self.list_personal_access_tokens_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn delete_personal_access_token(
+            &self,
+            _user_id: &UserId,
+            _token_id: &uuid::Uuid,
+        ) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.delete_personal_access_token_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn use_personal_access_token(
+            &self,
+            _token_hash: &str,
+        ) -> Result<PersonalAccessToken, AuthRepositoryError> {
+            let mut guard = self.use_personal_access_token_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn expire_personal_access_token(
+            &self,
+            _user_id: &UserId,
+            _token_id: &uuid::Uuid,
+            _expires_at: &DateTime<Utc>,
+        ) -> Result<PersonalAccessToken, AuthRepositoryError> {
+            let mut guard = self.expire_personal_access_token_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn create_service_account(
+            &self,
+            _account: &NewServiceAccount,
+        ) -> Result<User, AuthRepositoryError> {
+            let mut guard = self.create_service_account_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_service_accounts(&self) -> Result<Vec<User>, AuthRepositoryError> {
+            let mut guard = self.list_service_accounts_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn delete_service_account(
+            &self,
+            _account_id: &UserId,
+        ) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.delete_service_account_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn create_organization(
+            &self,
+            _organization: &NewOrganization,
+        ) -> Result<Organization, AuthRepositoryError> {
+            let mut guard = self.create_organization_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_memberships(
+            &self,
+            _user_id: &UserId,
+        ) -> Result<Vec<OrganizationMembership>, AuthRepositoryError> {
+            let mut guard = self.list_memberships_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn fetch_membership(
+            &self,
+            _user_id: &UserId,
+            _organization_id: &uuid::Uuid,
+        ) -> Result<OrganizationMembership, AuthRepositoryError> {
+            let mut guard = self.fetch_membership_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_organization_members(
+            &self,
+            _organization_id: &uuid::Uuid,
+        ) -> Result<Vec<OrganizationMember>, AuthRepositoryError> {
+            let mut guard = self.list_organization_members_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn create_invitation(
+            &self,
+            _invitation: &NewInvitation,
+        ) -> Result<OrganizationInvitation, AuthRepositoryError> {
+            let mut guard = self.create_invitation_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn accept_invitation(
+            &self,
+            _token_hash: &str,
+            _user_id: &UserId,
+            _email: &str,
+        ) -> Result<OrganizationMembership, AuthRepositoryError> {
+            let mut guard = self.accept_invitation_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn delete_membership(
+            &self,
+            _organization_id: &uuid::Uuid,
+            _user_id: &UserId,
+        ) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.delete_membership_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn create_registration_invitation(
+            &self,
+            _invitation: &NewRegistrationInvitation,
+        ) -> Result<RegistrationInvitation, AuthRepositoryError> {
+            let mut guard = self.create_registration_invitation_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_registration_invitations(
+            &self,
+        ) -> Result<Vec<RegistrationInvitation>, AuthRepositoryError> {
+            let mut guard = self.list_registration_invitations_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn delete_registration_invitation(
+            &self,
+            _invitation_id: &uuid::Uuid,
+        ) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.delete_registration_invitation_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_users(
+            &self,
+            _request: &ListUsersRequest,
+        ) -> Result<(Vec<User>, i64), AuthRepositoryError> {
+            let mut guard = self.list_users_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn update_user(
+            &self,
+            _request: &UpdateUserRequest,
+        ) -> Result<User, AuthRepositoryError> {
+            let mut guard = self.update_user_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn revoke_user_sessions(&self, _user_id: &UserId) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.revoke_user_sessions_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn create_password_reset(
+            &self,
+            _reset: &NewPasswordReset,
+        ) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.create_password_reset_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn reset_password(
+            &self,
+            _token_hash: &str,
+            _password: &HashedUserPassword,
+        ) -> Result<uuid::Uuid, AuthRepositoryError> {
+            let mut guard = self.reset_password_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn delete_user(&self, _user_id: &UserId) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.delete_user_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn update_password(
+            &self,
+            _user_id: &UserId,
+            _password: &HashedUserPassword,
+        ) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.update_password_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_audit_events(
+            &self,
+            _request: &ListAuditEventsRequest,
+        ) -> ListAuditEventsResult {
+            let mut guard = self.list_audit_events_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_audit_chain(
+            &self,
+            _after_sequence: i64,
+            _limit: i64,
+        ) -> Result<Vec<AuditEvent>, AuthRepositoryError> {
+            let mut guard = self.list_audit_chain_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_audit_checkpoints(
+            &self,
+        ) -> Result<Vec<AuditCheckpoint>, AuthRepositoryError> {
+            let mut guard = self.list_audit_checkpoints_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn enqueue_webhook_event(
+            &self,
+            event: &WebhookEvent,
+        ) -> Result<(), AuthRepositoryError> {
+            self.enqueued_webhook_events
+                .lock()
+                .await
+                .push(event.clone());
+            Ok(())
+        }
+
+        async fn create_webhook_subscription(
+            &self,
+            _subscription: &NewWebhookSubscription,
+        ) -> Result<WebhookSubscription, AuthRepositoryError> {
+            let mut guard = self.create_webhook_subscription_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_webhook_subscriptions(
+            &self,
+        ) -> Result<Vec<WebhookSubscription>, AuthRepositoryError> {
+            let mut guard = self.list_webhook_subscriptions_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn delete_webhook_subscription(
+            &self,
+            _subscription_id: &uuid::Uuid,
+        ) -> Result<(), AuthRepositoryError> {
+            let mut guard = self.delete_webhook_subscription_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn list_webhook_deliveries(
+            &self,
+            _request: &ListWebhookDeliveriesRequest,
+        ) -> Result<Vec<WebhookDelivery>, AuthRepositoryError> {
+            let mut guard = self.list_webhook_deliveries_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn retry_webhook_delivery(
+            &self,
+            _subscription_id: &uuid::Uuid,
+            _delivery_id: &uuid::Uuid,
+        ) -> Result<WebhookDelivery, AuthRepositoryError> {
+            let mut guard = self.retry_webhook_delivery_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn claim_webhook_deliveries(
+            &self,
+            _limit: i64,
+            _lease_seconds: i64,
+        ) -> Result<Vec<PendingWebhookDelivery>, AuthRepositoryError> {
+            let mut guard = self.claim_webhook_deliveries_result.lock().await;
+            let mut result = Err(AuthRepositoryError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn record_webhook_attempt(
+            &self,
+            attempt: &WebhookAttempt,
+        ) -> Result<(), AuthRepositoryError> {
+            self.recorded_webhook_attempts
+                .lock()
+                .await
+                .push(attempt.clone());
+            Ok(())
+        }
     }
 
     impl MockAuthRepository {
@@ -58,12 +548,163 @@ pub mod test_helpers {
             let filtered_user = FilteredUser::from(&user);
             let register_result = Arc::new(Mutex::new(Ok(filtered_user)));
             let auth_result = Arc::new(Mutex::new(Ok(user.clone())));
+            let fetch_user_by_identity_result = Arc::new(Mutex::new(Ok(user.clone())));
+            let link_identity_result = Arc::new(Mutex::new(Ok(user.clone())));
+            let provision_directory_user_result = Arc::new(Mutex::new(Ok(user.clone())));
+            let personal_access_token =
+                PersonalAccessToken::new(user.id, TEST_PERSONAL_ACCESS_TOKEN_NAME, &["profile"]);
+            let create_personal_access_token_result =
+                Arc::new(Mutex::new(Ok(personal_access_token.clone())));
+            let list_personal_access_tokens_result =
+                Arc::new(Mutex::new(Ok(vec![personal_access_token.clone()])));
+            let delete_personal_access_token_result = Arc::new(Mutex::new(Ok(())));
+            let use_personal_access_token_result =
+                Arc::new(Mutex::new(Ok(personal_access_token.clone())));
+            let expire_personal_access_token_result =
+                Arc::new(Mutex::new(Ok(personal_access_token)));
+            let service_account = User {
+                email: TEST_SERVICE_ACCOUNT_EMAIL.to_string(),
+                password: String::new(),
+                kind: UserKind::Service,
+                owner_id: Some(user.id),
+                ..User::new(TEST_SERVICE_ACCOUNT_EMAIL, "")
+            };
+            let create_service_account_result = Arc::new(Mutex::new(Ok(service_account.clone())));
+            let list_service_accounts_result = Arc::new(Mutex::new(Ok(vec![service_account])));
+            let delete_service_account_result = Arc::new(Mutex::new(Ok(())));
+            let organization = test_organization();
+            let membership = OrganizationMembership {
+                organization_id: organization.id,
+                name: organization.name.clone(),
+                slug: organization.slug.clone(),
+                role: OrganizationRole::Owner,
+                created_at: organization.created_at,
+            };
+            let create_organization_result = Arc::new(Mutex::new(Ok(organization.clone())));
+            let list_memberships_result = Arc::new(Mutex::new(Ok(vec![membership.clone()])));
+            let fetch_membership_result = Arc::new(Mutex::new(Ok(membership.clone())));
+            let list_organization_members_result =
+                Arc::new(Mutex::new(Ok(vec![OrganizationMember {
+                    user_id: user.id,
+                    email: user.email.clone(),
+                    role: OrganizationRole::Owner,
+                    created_at: organization.created_at,
+                }])));
+            let create_invitation_result = Arc::new(Mutex::new(Ok(OrganizationInvitation {
+                id: uuid::Uuid::new_v4(),
+                organization_id: organization.id,
+                email: "invitee@email.com".to_string(),
+                role: OrganizationRole::Member,
+                expires_at: Utc::now(),
+                created_at: organization.created_at,
+            })));
+            let accept_invitation_result = Arc::new(Mutex::new(Ok(membership)));
+            let delete_membership_result = Arc::new(Mutex::new(Ok(())));
+            let registration_invitation = RegistrationInvitation {
+                id: uuid::Uuid::new_v4(),
+                email: "invitee@email.com".to_string(),
+                organization_id: None,
+                role: OrganizationRole::Member,
+                created_by: Some(user.id),
+                expires_at: Utc::now(),
+                created_at: Some(Utc::now()),
+            };
+            let create_registration_invitation_result =
+                Arc::new(Mutex::new(Ok(registration_invitation.clone())));
+            let list_registration_invitations_result =
+                Arc::new(Mutex::new(Ok(vec![registration_invitation])));
+            let delete_registration_invitation_result = Arc::new(Mutex::new(Ok(())));
+            let list_users_result = Arc::new(Mutex::new(Ok((vec![user.clone()], 1))));
+            let update_user_result = Arc::new(Mutex::new(Ok(User {
+                status: UserStatus::Suspended,
+                ..user.clone()
+            })));
+            let revoke_user_sessions_result = Arc::new(Mutex::new(Ok(())));
+            let create_password_reset_result = Arc::new(Mutex::new(Ok(())));
+            let reset_password_result = Arc::new(Mutex::new(Ok(user.id)));
+            let delete_user_result = Arc::new(Mutex::new(Ok(())));
+            let update_password_result = Arc::new(Mutex::new(Ok(())));
+            let audit_event = AuditEvent::chain(
+                &NewAuditEvent::success(
+                    AuditEventType::Login,
+                    &RequestContext {
+                        ip_address: Some("127.0.0.1".to_string()),
+                        user_agent: Some("test-agent".to_string()),
+                        request_id: Some("test-request".to_string()),
+                    },
+                )
+                .with_user(Some(user.id))
+                .with_details(serde_json::json!({ "email": user.email })),
+                1,
+                None,
+            );
+            let list_audit_events_result = Arc::new(Mutex::new(Ok((vec![audit_event.clone()], 1))));
+            let list_audit_chain_result = Arc::new(Mutex::new(Ok(vec![audit_event])));
+            let list_audit_checkpoints_result = Arc::new(Mutex::new(Ok(vec![])));
+            let subscription = test_webhook_subscription(user.id);
+            let delivery = test_webhook_delivery(subscription.id);
+            let create_webhook_subscription_result = Arc::new(Mutex::new(Ok(subscription.clone())));
+            let list_webhook_subscriptions_result = Arc::new(Mutex::new(Ok(vec![subscription])));
+            let delete_webhook_subscription_result = Arc::new(Mutex::new(Ok(())));
+            let list_webhook_deliveries_result = Arc::new(Mutex::new(Ok(vec![delivery.clone()])));
+            let retry_webhook_delivery_result = Arc::new(Mutex::new(Ok(delivery)));
+            let claim_webhook_deliveries_result = Arc::new(Mutex::new(Ok(vec![])));
             let login_result = Arc::new(Mutex::new(Ok(user)));
+            let fetch_oauth_client_result = Arc::new(Mutex::new(Ok(OAuthClient::new(
+                TEST_CLIENT_ID,
+                ClientType::Public,
+                vec![TEST_REDIRECT_URI.to_string()],
+                vec![
+                    "openid".to_string(),
+                    "profile".to_string(),
+                    "email".to_string(),
+                ],
+            ))));
 
             MockAuthRepository {
                 register_result,
                 auth_result,
                 login_result,
+                fetch_oauth_client_result,
+                fetch_user_by_identity_result,
+                link_identity_result,
+                provision_directory_user_result,
+                create_personal_access_token_result,
+                list_personal_access_tokens_result,
+                delete_personal_access_token_result,
+                use_personal_access_token_result,
+                expire_personal_access_token_result,
+                create_service_account_result,
+                list_service_accounts_result,
+                delete_service_account_result,
+                create_organization_result,
+                list_memberships_result,
+                fetch_membership_result,
+                list_organization_members_result,
+                create_invitation_result,
+                accept_invitation_result,
+                delete_membership_result,
+                create_registration_invitation_result,
+                list_registration_invitations_result,
+                delete_registration_invitation_result,
+                list_users_result,
+                update_user_result,
+                revoke_user_sessions_result,
+                create_password_reset_result,
+                reset_password_result,
+                delete_user_result,
+                update_password_result,
+                list_audit_events_result,
+                list_audit_chain_result,
+                list_audit_checkpoints_result,
+                create_webhook_subscription_result,
+                list_webhook_subscriptions_result,
+                delete_webhook_subscription_result,
+                list_webhook_deliveries_result,
+                retry_webhook_delivery_result,
+                claim_webhook_deliveries_result,
+                enqueued_webhook_events: Arc::new(Mutex::new(vec![])),
+                recorded_webhook_attempts: Arc::new(Mutex::new(vec![])),
             }
         }
 
@@ -77,31 +718,315 @@ pub mod test_helpers {
             let login_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(anyhow!(
                 "login result error"
             )))));
+            let fetch_oauth_client_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("fetch oauth client result error")),
+            )));
+            let fetch_user_by_identity_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("fetch user by identity result error")),
+            )));
+            let link_identity_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("link identity result error"),
+            ))));
+            let provision_directory_user_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("provision directory user result error")),
+            )));
+            let create_personal_access_token_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("create personal access token result error")),
+            )));
+            let list_personal_access_tokens_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("list personal access tokens result error")),
+            )));
+            let delete_personal_access_token_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("delete personal access token result error")),
+            )));
+            let use_personal_access_token_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("use personal access token result error")),
+            )));
+            let expire_personal_access_token_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("expire personal access token result error")),
+            )));
+            let create_service_account_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("create service account result error")),
+            )));
+            let list_service_accounts_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("list service accounts result error")),
+            )));
+            let delete_service_account_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("delete service account result error")),
+            )));
+            let create_organization_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("create organization result error")),
+            )));
+            let list_memberships_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("list memberships result error"),
+            ))));
+            let fetch_membership_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("fetch membership result error"),
+            ))));
+            let list_organization_members_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("list organization members result error")),
+            )));
+            let create_invitation_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("create invitation result error"),
+            ))));
+            let accept_invitation_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("accept invitation result error"),
+            ))));
+            let delete_membership_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("delete membership result error"),
+            ))));
+            let create_registration_invitation_result =
+                Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(anyhow!(
+                    "create registration invitation result error"
+                )))));
+            let list_registration_invitations_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("list registration invitations result error")),
+            )));
+            let delete_registration_invitation_result =
+                Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(anyhow!(
+                    "delete registration invitation result error"
+                )))));
+            let list_users_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("list users result error"),
+            ))));
+            let update_user_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("update user result error"),
+            ))));
+            let revoke_user_sessions_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("revoke user sessions result error")),
+            )));
+            let create_password_reset_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("create password reset result error")),
+            )));
+            let reset_password_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("reset password result error"),
+            ))));
+            let delete_user_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("delete user result error"),
+            ))));
+            let update_password_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("update password result error"),
+            ))));
+            let list_audit_events_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("list audit events result error"),
+            ))));
+            let list_audit_chain_result = Arc::new(Mutex::new(Err(AuthRepositoryError::Unknown(
+                anyhow!("list audit chain result error"),
+            ))));
+            let list_audit_checkpoints_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("list audit checkpoints result error")),
+            )));
+            let create_webhook_subscription_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("create webhook subscription result error")),
+            )));
+            let list_webhook_subscriptions_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("list webhook subscriptions result error")),
+            )));
+            let delete_webhook_subscription_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("delete webhook subscription result error")),
+            )));
+            let list_webhook_deliveries_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("list webhook deliveries result error")),
+            )));
+            let retry_webhook_delivery_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("retry webhook delivery result error")),
+            )));
+            let claim_webhook_deliveries_result = Arc::new(Mutex::new(Err(
+                AuthRepositoryError::Unknown(anyhow!("claim webhook deliveries result error")),
+            )));
 
             MockAuthRepository {
                 register_result,
                 auth_result,
                 login_result,
+                fetch_oauth_client_result,
+                fetch_user_by_identity_result,
+                link_identity_result,
+                provision_directory_user_result,
+                create_personal_access_token_result,
+                list_personal_access_tokens_result,
+                delete_personal_access_token_result,
+                use_personal_access_token_result,
+                expire_personal_access_token_result,
+                create_service_account_result,
+                list_service_accounts_result,
+                delete_service_account_result,
+                create_organization_result,
+                list_memberships_result,
+                fetch_membership_result,
+                list_organization_members_result,
+                create_invitation_result,
+                accept_invitation_result,
+                delete_membership_result,
+                create_registration_invitation_result,
+                list_registration_invitations_result,
+                delete_registration_invitation_result,
+                list_users_result,
+                update_user_result,
+                revoke_user_sessions_result,
+                create_password_reset_result,
+                reset_password_result,
+                delete_user_result,
+                update_password_result,
+                list_audit_events_result,
+                list_audit_chain_result,
+                list_audit_checkpoints_result,
+                create_webhook_subscription_result,
+                list_webhook_subscriptions_result,
+                delete_webhook_subscription_result,
+                list_webhook_deliveries_result,
+                retry_webhook_delivery_result,
+                claim_webhook_deliveries_result,
+                enqueued_webhook_events: Arc::new(Mutex::new(vec![])),
+                recorded_webhook_attempts: Arc::new(Mutex::new(vec![])),
             }
         }
-    }
 
-    #[tokio::test]
-    async fn test_register_success() {
-        let email = "adrian@email.com";
-        let password = "password";
+        /// Makes the identity lookup fail as if the provider account was never linked.
+        pub fn with_unlinked_identity(self) -> MockAuthRepository {
+            MockAuthRepository {
+                fetch_user_by_identity_result: Arc::new(Mutex::new(Err(
+                    AuthRepositoryError::InvalidCredentials {
+                        reason: "Identity is not linked to a user".to_string(),
+                    },
+                ))),
+                ..self
+            }
+        }
 
-        let mock_repo = MockAuthRepository::success(email, password);
+        pub fn with_personal_access_token(self, token: PersonalAccessToken) -> MockAuthRepository {
+            MockAuthRepository {
+                use_personal_access_token_result: Arc::new(Mutex::new(Ok(token))),
+                ..self
+            }
+        }
 
-        let result = mock_repo
-            .register(&RegisterUserRequest::new(
-                UserEmail::new(email).unwrap(),
-                HashedUserPassword::new(UserPassword::new(password).unwrap()).unwrap(),
-            ))
-            .await;
+        /// Makes `fetch_user_by_id` and `login` return `user`, such as a service account.
+        pub fn with_user(self, user: User) -> MockAuthRepository {
+            MockAuthRepository {
+                auth_result: Arc::new(Mutex::new(Ok(user.clone()))),
+                login_result: Arc::new(Mutex::new(Ok(user))),
+                ..self
+            }
+        }
 
-        assert_eq!(email.to_string(), result.unwrap().email);
-    }
+        /// Makes the membership lookups fail as if the user had no organizations.
+        pub fn without_memberships(self) -> MockAuthRepository {
+            MockAuthRepository {
+                list_memberships_result: Arc::new(Mutex::new(Ok(vec![]))),
+                fetch_membership_result: Arc::new(Mutex::new(Err(
+                    AuthRepositoryError::InvalidCredentials {
+                        reason: "User is not a member of the organization".to_string(),
+                    },
+                ))),
+                ..self
+            }
+        }
+
+        pub fn with_membership(self, membership: OrganizationMembership) -> MockAuthRepository {
+            MockAuthRepository {
+                list_memberships_result: Arc::new(Mutex::new(Ok(vec![membership.clone()]))),
+                fetch_membership_result: Arc::new(Mutex::new(Ok(membership))),
+                ..self
+            }
+        }
+
+        pub fn with_oauth_client(self, client: OAuthClient) -> MockAuthRepository {
+            MockAuthRepository {
+                fetch_oauth_client_result: Arc::new(Mutex::new(Ok(client))),
+                ..self
+            }
+        }
+
+        pub fn with_audit_chain(
+            self,
+            events: Vec<AuditEvent>,
+            checkpoints: Vec<AuditCheckpoint>,
+        ) -> MockAuthRepository {
+            MockAuthRepository {
+                list_audit_chain_result: Arc::new(Mutex::new(Ok(events))),
+                list_audit_checkpoints_result: Arc::new(Mutex::new(Ok(checkpoints))),
+                ..self
+            }
+        }
+
+        /// Makes the worker claim `deliveries` the first time it looks for pending ones.
+        pub fn with_pending_webhook_deliveries(
+            self,
+            deliveries: Vec<PendingWebhookDelivery>,
+        ) -> MockAuthRepository {
+            MockAuthRepository {
+                claim_webhook_deliveries_result: Arc::new(Mutex::new(Ok(deliveries))),
+                ..self
+            }
+        }
+
+        pub async fn enqueued_webhook_events(&self) -> Vec<WebhookEvent> {
+            self.enqueued_webhook_events.lock().await.clone()
+        }
+
+        pub async fn recorded_webhook_attempts(&self) -> Vec<WebhookAttempt> {
+            self.recorded_webhook_attempts.lock().await.clone()
+        }
+    }
+
+    pub const TEST_CLIENT_ID: &str = "test-client";
+    pub const TEST_REDIRECT_URI: &str = "http://localhost:8080/callback";
+    pub const TEST_PERSONAL_ACCESS_TOKEN_NAME: &str = "test-token";
+    pub const TEST_SERVICE_ACCOUNT_EMAIL: &str = "test-service@serviceaccount.invalid";
+    pub const TEST_ORGANIZATION_SLUG: &str = "test-organization";
+
+    pub fn test_webhook_subscription(created_by: uuid::Uuid) -> WebhookSubscription {
+        WebhookSubscription {
+            id: uuid::Uuid::new_v4(),
+            url: "https://crm.example.com/hooks".to_string(),
+            events: vec!["user.registered".to_string()],
+            created_by: Some(created_by),
+            created_at: Some(Utc::now()),
+        }
+    }
+
+    pub fn test_webhook_delivery(subscription_id: uuid::Uuid) -> WebhookDelivery {
+        WebhookDelivery {
+            id: uuid::Uuid::new_v4(),
+            subscription_id,
+            event_id: uuid::Uuid::new_v4(),
+            event_type: "user.registered".to_string(),
+            payload: serde_json::json!({}),
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+            created_at: Utc::now(),
+            delivered_at: None,
+        }
+    }
+
+    pub fn test_organization() -> Organization {
+        Organization {
+            id: uuid::Uuid::new_v4(),
+            name: "Test Organization".to_string(),
+            slug: TEST_ORGANIZATION_SLUG.to_string(),
+            created_at: Some(Utc::now()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_success() {
+        let email = "adrian@email.com";
+        let password = "password";
+
+        let mock_repo = MockAuthRepository::success(email, password);
+
+        let result = mock_repo
+            .register(&RegisterUserRequest::new(
+                UserEmail::new(email).unwrap(),
+                HashedUserPassword::new(UserPassword::new(password).unwrap()).unwrap(),
+            ))
+            .await;
+
+        assert_eq!(email.to_string(), result.unwrap().email);
+    }
 
     #[tokio::test]
     async fn test_register_failure() {
@@ -179,4 +1104,540 @@ pub mod test_helpers {
 
         assert!(result.is_err())
     }
+
+    #[tokio::test]
+    async fn test_fetch_oauth_client_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+
+        let result = mock_repo
+            .fetch_oauth_client(&ClientId::new(TEST_CLIENT_ID))
+            .await;
+
+        assert_eq!(result.unwrap().client_id, TEST_CLIENT_ID);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_oauth_client_failure() {
+        let mock_repo = MockAuthRepository::failure();
+
+        let result = mock_repo
+            .fetch_oauth_client(&ClientId::new(TEST_CLIENT_ID))
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    fn federated_identity() -> FederatedIdentity {
+        FederatedIdentity {
+            provider: "test-provider".to_string(),
+            subject: "subject".to_string(),
+            email: Some("adrian@email.com".to_string()),
+            email_verified: true,
+            amr: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_identity_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+
+        let result = mock_repo
+            .fetch_user_by_identity(&federated_identity())
+            .await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.link_identity(&federated_identity(), true).await;
+        assert_eq!(result.unwrap().email, "adrian@email.com");
+    }
+
+    #[tokio::test]
+    async fn test_link_identity_failure() {
+        let mock_repo = MockAuthRepository::failure();
+
+        let result = mock_repo
+            .fetch_user_by_identity(&federated_identity())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.link_identity(&federated_identity(), true).await;
+        assert!(result.is_err());
+    }
+
+    fn directory_user() -> DirectoryUser {
+        DirectoryUser {
+            email: "adrian@email.com".to_string(),
+            roles: Some(vec!["admin".to_string()]),
+            grants_admin: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provision_directory_user_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+
+        let result = mock_repo
+            .provision_directory_user(&directory_user(), true)
+            .await;
+
+        assert_eq!(result.unwrap().email, "adrian@email.com");
+    }
+
+    #[tokio::test]
+    async fn test_provision_directory_user_failure() {
+        let mock_repo = MockAuthRepository::failure();
+
+        let result = mock_repo
+            .provision_directory_user(&directory_user(), true)
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    fn new_personal_access_token() -> NewPersonalAccessToken {
+        NewPersonalAccessToken {
+            user_id: uuid::Uuid::new_v4(),
+            name: TEST_PERSONAL_ACCESS_TOKEN_NAME.to_string(),
+            token_prefix: "pat_abcdefgh".to_string(),
+            token_hash: "hash".to_string(),
+            scopes: vec!["profile".to_string()],
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_personal_access_tokens_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+        let user_id = UserId::new(uuid::Uuid::new_v4());
+
+        let result = mock_repo
+            .create_personal_access_token(&new_personal_access_token())
+            .await;
+        assert_eq!(result.unwrap().name, TEST_PERSONAL_ACCESS_TOKEN_NAME);
+
+        let result = mock_repo.list_personal_access_tokens(&user_id).await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = mock_repo.use_personal_access_token("hash").await;
+        assert_eq!(result.unwrap().scopes, vec!["profile".to_string()]);
+
+        let result = mock_repo
+            .expire_personal_access_token(&user_id, &uuid::Uuid::new_v4(), &Utc::now())
+            .await;
+        assert!(result.is_ok());
+
+        let result = mock_repo
+            .delete_personal_access_token(&user_id, &uuid::Uuid::new_v4())
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_personal_access_tokens_failure() {
+        let mock_repo = MockAuthRepository::failure();
+        let user_id = UserId::new(uuid::Uuid::new_v4());
+
+        let result = mock_repo
+            .create_personal_access_token(&new_personal_access_token())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_personal_access_tokens(&user_id).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.use_personal_access_token("hash").await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .expire_personal_access_token(&user_id, &uuid::Uuid::new_v4(), &Utc::now())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .delete_personal_access_token(&user_id, &uuid::Uuid::new_v4())
+            .await;
+        assert!(result.is_err());
+    }
+
+    fn new_service_account() -> NewServiceAccount {
+        NewServiceAccount {
+            email: TEST_SERVICE_ACCOUNT_EMAIL.to_string(),
+            owner_id: uuid::Uuid::new_v4(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_accounts_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+
+        let result = mock_repo
+            .create_service_account(&new_service_account())
+            .await
+            .unwrap();
+        assert!(result.is_service_account());
+
+        let result = mock_repo.list_service_accounts().await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = mock_repo
+            .delete_service_account(&UserId::new(uuid::Uuid::new_v4()))
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_service_accounts_failure() {
+        let mock_repo = MockAuthRepository::failure();
+
+        let result = mock_repo
+            .create_service_account(&new_service_account())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_service_accounts().await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .delete_service_account(&UserId::new(uuid::Uuid::new_v4()))
+            .await;
+        assert!(result.is_err());
+    }
+
+    fn new_invitation(organization_id: uuid::Uuid) -> NewInvitation {
+        NewInvitation {
+            organization_id,
+            email: "invitee@email.com".to_string(),
+            role: OrganizationRole::Member,
+            token_hash: "hash".to_string(),
+            invited_by: uuid::Uuid::new_v4(),
+            expires_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_organizations_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+        let user_id = UserId::new(uuid::Uuid::new_v4());
+
+        let organization = mock_repo
+            .create_organization(&NewOrganization {
+                name: "Test Organization".to_string(),
+                slug: TEST_ORGANIZATION_SLUG.to_string(),
+                owner_id: *user_id.get(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(organization.slug, TEST_ORGANIZATION_SLUG);
+
+        let result = mock_repo.list_memberships(&user_id).await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = mock_repo.fetch_membership(&user_id, &organization.id).await;
+        assert_eq!(result.unwrap().role, OrganizationRole::Owner);
+
+        let result = mock_repo.list_organization_members(&organization.id).await;
+        assert_eq!(result.unwrap()[0].email, "adrian@email.com");
+
+        let result = mock_repo
+            .create_invitation(&new_invitation(organization.id))
+            .await;
+        assert_eq!(result.unwrap().email, "invitee@email.com");
+
+        let result = mock_repo
+            .accept_invitation("hash", &user_id, "invitee@email.com")
+            .await;
+        assert!(result.is_ok());
+
+        let result = mock_repo
+            .delete_membership(&organization.id, &user_id)
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_organizations_failure() {
+        let mock_repo = MockAuthRepository::failure();
+        let user_id = UserId::new(uuid::Uuid::new_v4());
+        let organization_id = uuid::Uuid::new_v4();
+
+        let result = mock_repo
+            .create_organization(&NewOrganization {
+                name: "Test Organization".to_string(),
+                slug: TEST_ORGANIZATION_SLUG.to_string(),
+                owner_id: *user_id.get(),
+            })
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_memberships(&user_id).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.fetch_membership(&user_id, &organization_id).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_organization_members(&organization_id).await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .create_invitation(&new_invitation(organization_id))
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .accept_invitation("hash", &user_id, "invitee@email.com")
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .delete_membership(&organization_id, &user_id)
+            .await;
+        assert!(result.is_err());
+    }
+
+    fn new_registration_invitation() -> NewRegistrationInvitation {
+        NewRegistrationInvitation {
+            id: uuid::Uuid::new_v4(),
+            email: "invitee@email.com".to_string(),
+            organization_id: None,
+            role: OrganizationRole::Member,
+            token_hash: "hash".to_string(),
+            created_by: uuid::Uuid::new_v4(),
+            expires_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registration_invitations_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+
+        let result = mock_repo
+            .create_registration_invitation(&new_registration_invitation())
+            .await;
+        assert_eq!(result.unwrap().email, "invitee@email.com");
+
+        let result = mock_repo.list_registration_invitations().await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = mock_repo
+            .delete_registration_invitation(&uuid::Uuid::new_v4())
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_registration_invitations_failure() {
+        let mock_repo = MockAuthRepository::failure();
+
+        let result = mock_repo
+            .create_registration_invitation(&new_registration_invitation())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_registration_invitations().await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .delete_registration_invitation(&uuid::Uuid::new_v4())
+            .await;
+        assert!(result.is_err());
+    }
+
+    fn update_user_request() -> UpdateUserRequest {
+        UpdateUserRequest {
+            actor_id: UserId::new(uuid::Uuid::new_v4()),
+            user_id: UserId::new(uuid::Uuid::new_v4()),
+            email: None,
+            status: Some(UserStatus::Suspended),
+        }
+    }
+
+    fn new_password_reset() -> NewPasswordReset {
+        NewPasswordReset {
+            user_id: uuid::Uuid::new_v4(),
+            token_hash: "hash".to_string(),
+            expires_at: Utc::now(),
+        }
+    }
+
+    fn list_audit_events_request() -> ListAuditEventsRequest {
+        ListAuditEventsRequest {
+            page: 1,
+            per_page: 50,
+            event_type: Some(AuditEventType::Login),
+            ..ListAuditEventsRequest::default()
+        }
+    }
+
+    fn list_users_request() -> ListUsersRequest {
+        ListUsersRequest {
+            page: 1,
+            per_page: 50,
+            email: Some("adrian".to_string()),
+            status: None,
+            kind: None,
+            role: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_administration_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+        let user_id = UserId::new(uuid::Uuid::new_v4());
+        let password = HashedUserPassword::new(UserPassword::new("password").unwrap()).unwrap();
+
+        let result = mock_repo.list_users(&list_users_request()).await;
+        assert_eq!(result.unwrap().1, 1);
+
+        let result = mock_repo.update_user(&update_user_request()).await;
+        assert_eq!(result.unwrap().status, UserStatus::Suspended);
+
+        let result = mock_repo.revoke_user_sessions(&user_id).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.create_password_reset(&new_password_reset()).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.reset_password("hash", &password).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.delete_user(&user_id).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.update_password(&user_id, &password).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo
+            .list_audit_events(&list_audit_events_request())
+            .await;
+        assert_eq!(result.unwrap().1, 1);
+
+        let result = mock_repo.list_audit_chain(0, 1000).await;
+        assert_eq!(result.unwrap()[0].sequence, 1);
+
+        let result = mock_repo.list_audit_checkpoints().await;
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_user_administration_failure() {
+        let mock_repo = MockAuthRepository::failure();
+        let user_id = UserId::new(uuid::Uuid::new_v4());
+        let password = HashedUserPassword::new(UserPassword::new("password").unwrap()).unwrap();
+
+        let result = mock_repo.list_users(&list_users_request()).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.update_user(&update_user_request()).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.revoke_user_sessions(&user_id).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.create_password_reset(&new_password_reset()).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.reset_password("hash", &password).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.delete_user(&user_id).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.update_password(&user_id, &password).await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .list_audit_events(&list_audit_events_request())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_audit_chain(0, 1000).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_audit_checkpoints().await;
+        assert!(result.is_err());
+    }
+
+    fn new_webhook_subscription() -> NewWebhookSubscription {
+        NewWebhookSubscription {
+            url: "https://crm.example.com/hooks".to_string(),
+            events: vec!["user.registered".to_string()],
+            secret: "whsec_secret".to_string(),
+            created_by: uuid::Uuid::new_v4(),
+        }
+    }
+
+    fn list_webhook_deliveries_request() -> ListWebhookDeliveriesRequest {
+        ListWebhookDeliveriesRequest {
+            subscription_id: uuid::Uuid::new_v4(),
+            status: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhooks_success() {
+        let mock_repo = MockAuthRepository::success("adrian@email.com", "password");
+        let subscription_id = uuid::Uuid::new_v4();
+
+        let result = mock_repo
+            .create_webhook_subscription(&new_webhook_subscription())
+            .await;
+        assert_eq!(result.unwrap().events, vec!["user.registered"]);
+
+        let result = mock_repo.list_webhook_subscriptions().await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = mock_repo
+            .delete_webhook_subscription(&subscription_id)
+            .await;
+        assert!(result.is_ok());
+
+        let result = mock_repo
+            .list_webhook_deliveries(&list_webhook_deliveries_request())
+            .await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = mock_repo
+            .retry_webhook_delivery(&subscription_id, &uuid::Uuid::new_v4())
+            .await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.claim_webhook_deliveries(50, 60).await;
+        assert!(result.unwrap().is_empty());
+
+        let event = WebhookEvent::user_registered(uuid::Uuid::new_v4(), "adrian@email.com");
+        let result = mock_repo.enqueue_webhook_event(&event).await;
+        assert!(result.is_ok());
+        assert_eq!(mock_repo.enqueued_webhook_events().await, vec![event]);
+    }
+
+    #[tokio::test]
+    async fn test_webhooks_failure() {
+        let mock_repo = MockAuthRepository::failure();
+        let subscription_id = uuid::Uuid::new_v4();
+
+        let result = mock_repo
+            .create_webhook_subscription(&new_webhook_subscription())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.list_webhook_subscriptions().await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .delete_webhook_subscription(&subscription_id)
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .list_webhook_deliveries(&list_webhook_deliveries_request())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .retry_webhook_delivery(&subscription_id, &uuid::Uuid::new_v4())
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.claim_webhook_deliveries(50, 60).await;
+        assert!(result.is_err());
+    }
 }
@@ -7,7 +7,14 @@ pub mod test_helpers {
 
     use crate::domain::{
         model::{
+            authorize::AuthorizationCode,
             cache_errors::CacheOperationError,
+            custom_claims::CustomClaims,
+            device_authorization::{DeviceAuthorization, DeviceAuthorizationStatus},
+            federation::FederationState,
+            forward_auth::ForwardAuthIdentity,
+            saml::SamlRequestState,
+            session_revocation::SessionRevocation,
             token::{CacheToken, TokenDetails},
             token_uuid::TokenUuid,
         },
@@ -19,6 +26,27 @@ pub mod test_helpers {
         pub save_tokens_data_result: Arc<Mutex<Result<(), CacheOperationError>>>,
         pub verify_active_session_result: Arc<Mutex<Result<(), CacheOperationError>>>,
         pub delete_token_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub save_authorization_code_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub take_authorization_code_result:
+            Arc<Mutex<Result<AuthorizationCode, CacheOperationError>>>,
+        pub count_request_result: Arc<Mutex<Result<i64, CacheOperationError>>>,
+        pub fetch_request_count_result: Arc<Mutex<Result<i64, CacheOperationError>>>,
+        pub save_device_authorization_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub update_device_polling_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub decide_device_authorization_result: Arc<Mutex<Result<bool, CacheOperationError>>>,
+        pub fetch_device_authorization_result:
+            Arc<Mutex<Result<DeviceAuthorization, CacheOperationError>>>,
+        pub take_device_authorization_result:
+            Arc<Mutex<Result<DeviceAuthorization, CacheOperationError>>>,
+        pub save_federation_state_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub take_federation_state_result: Arc<Mutex<Result<FederationState, CacheOperationError>>>,
+        pub save_saml_request_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub take_saml_request_result: Arc<Mutex<Result<SamlRequestState, CacheOperationError>>>,
+        pub record_saml_assertion_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub save_forward_auth_result: Arc<Mutex<Result<(), CacheOperationError>>>,
+        pub fetch_forward_auth_result:
+            Arc<Mutex<Result<Option<ForwardAuthIdentity>, CacheOperationError>>>,
+        pub record_revocation_result: Arc<Mutex<Result<(), CacheOperationError>>>,
     }
 
     impl CacheRepository for MockCacheRepository {
@@ -56,6 +84,188 @@ pub mod test_helpers {
             mem::swap(guard.deref_mut(), &mut result);
             result
         }
+
+        async fn save_authorization_code(
+            &self,
+            _code: &AuthorizationCode,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.save_authorization_code_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn take_authorization_code(
+            &self,
+            _code: &str,
+        ) -> Result<AuthorizationCode, CacheOperationError> {
+            let mut guard = self.take_authorization_code_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn count_request(
+            &self,
+            _key: &str,
+            _window_seconds: i64,
+        ) -> Result<i64, CacheOperationError> {
+            let mut guard = self.count_request_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn fetch_request_count(&self, _key: &str) -> Result<i64, CacheOperationError> {
+            let mut guard = self.fetch_request_count_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn save_device_authorization(
+            &self,
+            _authorization: &DeviceAuthorization,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.save_device_authorization_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn update_device_polling(
+            &self,
+            _authorization: &DeviceAuthorization,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.update_device_polling_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn decide_device_authorization(
+            &self,
+            _authorization: &DeviceAuthorization,
+            _status: &DeviceAuthorizationStatus,
+        ) -> Result<bool, CacheOperationError> {
+            let mut guard = self.decide_device_authorization_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn fetch_device_authorization(
+            &self,
+            _device_code: &str,
+        ) -> Result<DeviceAuthorization, CacheOperationError> {
+            let mut guard = self.fetch_device_authorization_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn fetch_device_authorization_by_user_code(
+            &self,
+            _user_code: &str,
+        ) -> Result<DeviceAuthorization, CacheOperationError> {
+            let mut guard = self.fetch_device_authorization_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn take_device_authorization(
+            &self,
+            _device_code: &str,
+        ) -> Result<DeviceAuthorization, CacheOperationError> {
+            let mut guard = self.take_device_authorization_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn save_federation_state(
+            &self,
+            _state: &FederationState,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.save_federation_state_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn take_federation_state(
+            &self,
+            _state: &str,
+        ) -> Result<FederationState, CacheOperationError> {
+            let mut guard = self.take_federation_state_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn save_saml_request(
+            &self,
+            _state: &SamlRequestState,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.save_saml_request_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn take_saml_request(
+            &self,
+            _relay_state: &str,
+        ) -> Result<SamlRequestState, CacheOperationError> {
+            let mut guard = self.take_saml_request_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn record_saml_assertion(
+            &self,
+            _id: &str,
+            _expires_at: i64,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.record_saml_assertion_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn save_forward_auth(
+            &self,
+            _token_uuid: &TokenUuid,
+            _identity: &ForwardAuthIdentity,
+            _ttl_seconds: u64,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.save_forward_auth_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn fetch_forward_auth(
+            &self,
+            _token: &TokenDetails,
+        ) -> Result<Option<ForwardAuthIdentity>, CacheOperationError> {
+            let mut guard = self.fetch_forward_auth_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
+
+        async fn record_revocation(
+            &self,
+            _revocation: &SessionRevocation,
+        ) -> Result<(), CacheOperationError> {
+            let mut guard = self.record_revocation_result.lock().await;
+            let mut result = Err(CacheOperationError::Unknown(anyhow!("substitute error")));
+            mem::swap(guard.deref_mut(), &mut result);
+            result
+        }
     }
 
     impl MockCacheRepository {
@@ -64,12 +274,81 @@ pub mod test_helpers {
             let save_tokens_data_result = Arc::new(Mutex::new(Ok(())));
             let verify_active_session_result = Arc::new(Mutex::new(Ok(())));
             let delete_token_result = Arc::new(Mutex::new(Ok(())));
+            let save_authorization_code_result = Arc::new(Mutex::new(Ok(())));
+            let take_authorization_code_result = Arc::new(Mutex::new(Ok(AuthorizationCode {
+                code: "code".to_string(),
+                client_id: "test-client".to_string(),
+                user_id: uuid::Uuid::new_v4(),
+                redirect_uri: "http://localhost:8080/callback".to_string(),
+                redirect_uri_requested: true,
+                scope: "openid".to_string(),
+                code_challenge: "".to_string(),
+                max_age: 60,
+                nonce: None,
+                context: None,
+            })));
+            let count_request_result = Arc::new(Mutex::new(Ok(1)));
+            let fetch_request_count_result = Arc::new(Mutex::new(Ok(0)));
+            let save_device_authorization_result = Arc::new(Mutex::new(Ok(())));
+            let update_device_polling_result = Arc::new(Mutex::new(Ok(())));
+            let decide_device_authorization_result = Arc::new(Mutex::new(Ok(true)));
+            let device_authorization = DeviceAuthorization {
+                device_code: "device-code".to_string(),
+                user_code: "BCDFGHJK".to_string(),
+                client_id: "test-client".to_string(),
+                scope: "openid".to_string(),
+                status: DeviceAuthorizationStatus::Pending,
+                interval: 5,
+                last_polled_at: None,
+                expires_at: chrono::Utc::now().timestamp() + 600,
+            };
+            let fetch_device_authorization_result =
+                Arc::new(Mutex::new(Ok(device_authorization.clone())));
+            let take_device_authorization_result = Arc::new(Mutex::new(Ok(device_authorization)));
+            let save_federation_state_result = Arc::new(Mutex::new(Ok(())));
+            let take_federation_state_result = Arc::new(Mutex::new(Ok(FederationState {
+                state: "state".to_string(),
+                provider: "test-provider".to_string(),
+                nonce: "nonce".to_string(),
+                code_verifier: "code-verifier".to_string(),
+                return_to: None,
+                max_age: 600,
+            })));
+            let save_saml_request_result = Arc::new(Mutex::new(Ok(())));
+            let take_saml_request_result = Arc::new(Mutex::new(Ok(SamlRequestState {
+                relay_state: "relay-state".to_string(),
+                provider: "test-idp".to_string(),
+                request_id: "_request-1".to_string(),
+                return_to: None,
+                max_age: 600,
+            })));
+            let record_saml_assertion_result = Arc::new(Mutex::new(Ok(())));
+            let save_forward_auth_result = Arc::new(Mutex::new(Ok(())));
+            let fetch_forward_auth_result = Arc::new(Mutex::new(Ok(None)));
+            let record_revocation_result = Arc::new(Mutex::new(Ok(())));
 
             MockCacheRepository {
                 save_token_data_result,
                 save_tokens_data_result,
                 verify_active_session_result,
                 delete_token_result,
+                save_authorization_code_result,
+                take_authorization_code_result,
+                count_request_result,
+                fetch_request_count_result,
+                save_device_authorization_result,
+                update_device_polling_result,
+                decide_device_authorization_result,
+                fetch_device_authorization_result,
+                take_device_authorization_result,
+                save_federation_state_result,
+                take_federation_state_result,
+                save_saml_request_result,
+                take_saml_request_result,
+                record_saml_assertion_result,
+                save_forward_auth_result,
+                fetch_forward_auth_result,
+                record_revocation_result,
             }
         }
 
@@ -86,12 +365,121 @@ pub mod test_helpers {
             let delete_token_result = Arc::new(Mutex::new(Err(CacheOperationError::Unknown(
                 anyhow!("delete token result error"),
             ))));
+            let save_authorization_code_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("save authorization code result error")),
+            )));
+            let take_authorization_code_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("take authorization code result error")),
+            )));
+            let count_request_result = Arc::new(Mutex::new(Err(CacheOperationError::Unknown(
+                anyhow!("count request result error"),
+            ))));
+            let fetch_request_count_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("fetch request count result error")),
+            )));
+            let save_device_authorization_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("save device authorization result error")),
+            )));
+            let update_device_polling_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("update device polling result error")),
+            )));
+            let decide_device_authorization_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("decide device authorization result error")),
+            )));
+            let fetch_device_authorization_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("fetch device authorization result error")),
+            )));
+            let take_device_authorization_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("take device authorization result error")),
+            )));
+            let save_federation_state_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("save federation state result error")),
+            )));
+            let take_federation_state_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("take federation state result error")),
+            )));
+            let save_saml_request_result = Arc::new(Mutex::new(Err(CacheOperationError::Unknown(
+                anyhow!("save saml request result error"),
+            ))));
+            let take_saml_request_result = Arc::new(Mutex::new(Err(CacheOperationError::Unknown(
+                anyhow!("take saml request result error"),
+            ))));
+            let record_saml_assertion_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("record saml assertion result error")),
+            )));
+            let save_forward_auth_result = Arc::new(Mutex::new(Err(CacheOperationError::Unknown(
+                anyhow!("save forward auth result error"),
+            ))));
+            let fetch_forward_auth_result = Arc::new(Mutex::new(Err(
+                CacheOperationError::Unknown(anyhow!("fetch forward auth result error")),
+            )));
+            let record_revocation_result = Arc::new(Mutex::new(Err(CacheOperationError::Unknown(
+                anyhow!("record revocation result error"),
+            ))));
 
             MockCacheRepository {
                 save_token_data_result,
                 save_tokens_data_result,
                 verify_active_session_result,
                 delete_token_result,
+                save_authorization_code_result,
+                take_authorization_code_result,
+                count_request_result,
+                fetch_request_count_result,
+                save_device_authorization_result,
+                update_device_polling_result,
+                decide_device_authorization_result,
+                fetch_device_authorization_result,
+                take_device_authorization_result,
+                save_federation_state_result,
+                take_federation_state_result,
+                save_saml_request_result,
+                take_saml_request_result,
+                record_saml_assertion_result,
+                save_forward_auth_result,
+                fetch_forward_auth_result,
+                record_revocation_result,
+            }
+        }
+
+        pub fn with_request_count(self, count: i64) -> MockCacheRepository {
+            MockCacheRepository {
+                count_request_result: Arc::new(Mutex::new(Ok(count))),
+                ..self
+            }
+        }
+
+        /// Makes `fetch_request_count` report `count` requests already made in the window.
+        pub fn with_counted_requests(self, count: i64) -> MockCacheRepository {
+            MockCacheRepository {
+                fetch_request_count_result: Arc::new(Mutex::new(Ok(count))),
+                ..self
+            }
+        }
+
+        pub fn with_device_authorization(
+            self,
+            authorization: DeviceAuthorization,
+        ) -> MockCacheRepository {
+            MockCacheRepository {
+                fetch_device_authorization_result: Arc::new(Mutex::new(Ok(authorization.clone()))),
+                take_device_authorization_result: Arc::new(Mutex::new(Ok(authorization))),
+                ..self
+            }
+        }
+
+        /// Makes `fetch_forward_auth` find `identity` cached for the access token.
+        pub fn with_forward_auth(self, identity: ForwardAuthIdentity) -> MockCacheRepository {
+            MockCacheRepository {
+                fetch_forward_auth_result: Arc::new(Mutex::new(Ok(Some(identity)))),
+                ..self
+            }
+        }
+
+        pub fn with_authorization_code(self, code: AuthorizationCode) -> MockCacheRepository {
+            MockCacheRepository {
+                take_authorization_code_result: Arc::new(Mutex::new(Ok(code))),
+                ..self
             }
         }
     }
@@ -103,7 +491,9 @@ pub mod test_helpers {
             token: None,
             token_uuid: uuid,
             user_id: uuid,
+            issued_at: 0,
             expires_in: None,
+            claims: CustomClaims::default(),
         };
 
         let mock_repo = MockCacheRepository::success();
@@ -126,6 +516,69 @@ pub mod test_helpers {
 
         let result = mock_repo.delete_token(&TokenUuid::new(uuid)).await;
         assert!(result.is_ok());
+
+        let code = mock_repo.take_authorization_code("code").await;
+        assert!(code.is_ok());
+
+        let result = mock_repo.save_authorization_code(&code.unwrap()).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.count_request("key", 60).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.fetch_request_count("key").await;
+        assert_eq!(result.unwrap(), 0);
+
+        let authorization = mock_repo.fetch_device_authorization("device-code").await;
+        assert!(authorization.is_ok());
+
+        let authorization = authorization.unwrap();
+        let result = mock_repo.save_device_authorization(&authorization).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.update_device_polling(&authorization).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo
+            .decide_device_authorization(&authorization, &DeviceAuthorizationStatus::Denied)
+            .await;
+        assert!(result.unwrap());
+
+        let result = mock_repo.take_device_authorization("device-code").await;
+        assert!(result.is_ok());
+
+        let state = mock_repo.take_federation_state("state").await;
+        assert!(state.is_ok());
+
+        let result = mock_repo.save_federation_state(&state.unwrap()).await;
+        assert!(result.is_ok());
+
+        let state = mock_repo.take_saml_request("relay-state").await;
+        assert!(state.is_ok());
+
+        let result = mock_repo.save_saml_request(&state.unwrap()).await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.record_saml_assertion("_assertion-1", 0).await;
+        assert!(result.is_ok());
+
+        let identity = ForwardAuthIdentity {
+            user_id: uuid,
+            email: Some("adrian@email.com".to_string()),
+            roles: vec!["user".to_string()],
+        };
+        let result = mock_repo
+            .save_forward_auth(&TokenUuid::new(uuid), &identity, 30)
+            .await;
+        assert!(result.is_ok());
+
+        let result = mock_repo.fetch_forward_auth(&token).await;
+        assert_eq!(result.unwrap(), None);
+
+        let result = mock_repo
+            .record_revocation(&SessionRevocation::user(uuid, 60))
+            .await;
+        assert!(result.is_ok());
     }
 
     #[tokio::test]
@@ -135,7 +588,9 @@ pub mod test_helpers {
             token: None,
             token_uuid: uuid,
             user_id: uuid,
+            issued_at: 0,
             expires_in: None,
+            claims: CustomClaims::default(),
         };
 
         let mock_repo = MockCacheRepository::failure();
@@ -158,5 +613,54 @@ pub mod test_helpers {
 
         let result = mock_repo.delete_token(&TokenUuid::new(uuid)).await;
         assert!(result.is_err());
+
+        let result = mock_repo.take_authorization_code("code").await;
+        assert!(result.is_err());
+
+        let result = mock_repo.count_request("key", 60).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.fetch_request_count("key").await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .fetch_device_authorization_by_user_code("BCDFGHJK")
+            .await;
+        assert!(result.is_err());
+
+        let authorization = DeviceAuthorization {
+            device_code: "device-code".to_string(),
+            user_code: "BCDFGHJK".to_string(),
+            client_id: "test-client".to_string(),
+            scope: "openid".to_string(),
+            status: DeviceAuthorizationStatus::Pending,
+            interval: 5,
+            last_polled_at: None,
+            expires_at: chrono::Utc::now().timestamp() + 600,
+        };
+        let result = mock_repo
+            .decide_device_authorization(&authorization, &DeviceAuthorizationStatus::Denied)
+            .await;
+        assert!(result.is_err());
+
+        let result = mock_repo.take_device_authorization("device-code").await;
+        assert!(result.is_err());
+
+        let result = mock_repo.take_federation_state("state").await;
+        assert!(result.is_err());
+
+        let result = mock_repo.take_saml_request("relay-state").await;
+        assert!(result.is_err());
+
+        let result = mock_repo.record_saml_assertion("_assertion-1", 0).await;
+        assert!(result.is_err());
+
+        let result = mock_repo.fetch_forward_auth(&token).await;
+        assert!(result.is_err());
+
+        let result = mock_repo
+            .record_revocation(&SessionRevocation::user(uuid, 60))
+            .await;
+        assert!(result.is_err());
     }
 }
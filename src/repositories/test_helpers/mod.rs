@@ -1,2 +1,3 @@
+pub mod mock_audit_sink;
 pub mod mock_auth_repository;
 pub mod mock_cache_repository;
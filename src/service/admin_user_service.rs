@@ -0,0 +1,119 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    api::utils::security::{generate_random_token, hash_token},
+    domain::{
+        admin_user_service::AdminUserService,
+        model::{
+            admin_user::{
+                AdminUser, AdminUserError, DeleteUserRequest, ListUsersRequest, UpdateUserRequest,
+                UserPage, MAX_PAGE_SIZE,
+            },
+            password_reset::{
+                NewPasswordReset, PasswordResetResponse, PASSWORD_RESET_MAX_AGE_HOURS,
+            },
+            user::UserStatus,
+            user_id::UserId,
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> AdminUserService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn list_users(&self, request: &ListUsersRequest) -> Result<UserPage, AdminUserError> {
+        if request.page < 1 || !(1..=MAX_PAGE_SIZE).contains(&request.per_page) {
+            return Err(AdminUserError::InvalidRequest {
+                reason: format!(
+                    "page must be at least 1 and per_page between 1 and {}",
+                    MAX_PAGE_SIZE
+                ),
+            });
+        }
+
+        let (users, total) = self.repo.list_users(request).await?;
+
+        Ok(UserPage {
+            users: users.iter().map(AdminUser::from).collect(),
+            page: request.page,
+            per_page: request.per_page,
+            total,
+        })
+    }
+
+    async fn fetch_user(&self, user_id: &UserId) -> Result<AdminUser, AdminUserError> {
+        let user = self.repo.fetch_user_by_id(user_id).await?;
+        Ok(AdminUser::from(&user))
+    }
+
+    async fn update_user(&self, request: &UpdateUserRequest) -> Result<AdminUser, AdminUserError> {
+        if request.email.is_none() && request.status.is_none() {
+            return Err(AdminUserError::InvalidRequest {
+                reason: "nothing to update".to_string(),
+            });
+        }
+
+        if request.status == Some(UserStatus::Suspended)
+            && request.actor_id.get() == request.user_id.get()
+        {
+            return Err(AdminUserError::InvalidRequest {
+                reason: "administrators cannot suspend their own account".to_string(),
+            });
+        }
+
+        let user = self.repo.update_user(request).await?;
+        if request.status == Some(UserStatus::Suspended) {
+            self.broadcast_user_revocation(*request.user_id.get()).await;
+        }
+
+        Ok(AdminUser::from(&user))
+    }
+
+    async fn force_password_reset(
+        &self,
+        user_id: &UserId,
+    ) -> Result<PasswordResetResponse, AdminUserError> {
+        let token = generate_random_token();
+        let expires_at = Utc::now() + Duration::hours(PASSWORD_RESET_MAX_AGE_HOURS);
+
+        self.repo
+            .create_password_reset(&NewPasswordReset {
+                user_id: *user_id.get(),
+                token_hash: hash_token(&token),
+                expires_at,
+            })
+            .await?;
+        self.broadcast_user_revocation(*user_id.get()).await;
+
+        Ok(PasswordResetResponse { token, expires_at })
+    }
+
+    async fn revoke_sessions(&self, user_id: &UserId) -> Result<(), AdminUserError> {
+        self.repo.revoke_user_sessions(user_id).await?;
+        self.broadcast_user_revocation(*user_id.get()).await;
+
+        Ok(())
+    }
+
+    async fn delete_user(&self, request: &DeleteUserRequest) -> Result<(), AdminUserError> {
+        if request.actor_id.get() == request.user_id.get() {
+            return Err(AdminUserError::InvalidRequest {
+                reason: "administrators cannot delete their own account".to_string(),
+            });
+        }
+
+        self.repo.delete_user(&request.user_id).await?;
+        self.broadcast_user_revocation(*request.user_id.get()).await;
+
+        Ok(())
+    }
+}
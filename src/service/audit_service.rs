@@ -0,0 +1,195 @@
+use anyhow::anyhow;
+
+use crate::{
+    api::utils::{
+        jwk::public_jwk,
+        jwt::{sign_audit_checkpoint, verify_audit_checkpoint},
+    },
+    domain::{
+        audit_service::AuditService,
+        model::{
+            admin_audit::AdminAuditEntry,
+            audit::{
+                AuditChainReport, AuditChainVerifier, AuditCheckpoint, AuditCheckpointClaims,
+                AuditError, AuditEvent, AuditEventPage, AuditEventType, BrokenAuditLink,
+                ListAuditEventsRequest, NewAuditEvent, AUDIT_CHAIN_PAGE_SIZE, MAX_AUDIT_PAGE_SIZE,
+                RECENT_ACTIVITY_LIMIT,
+            },
+            user_id::UserId,
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> AuditService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn list_audit_events(
+        &self,
+        request: &ListAuditEventsRequest,
+    ) -> Result<AuditEventPage, AuditError> {
+        if request.page < 1 || !(1..=MAX_AUDIT_PAGE_SIZE).contains(&request.per_page) {
+            return Err(AuditError::InvalidRequest {
+                reason: format!(
+                    "page must be at least 1 and per_page between 1 and {}",
+                    MAX_AUDIT_PAGE_SIZE
+                ),
+            });
+        }
+
+        if let (Some(since), Some(until)) = (request.since, request.until) {
+            if since >= until {
+                return Err(AuditError::InvalidRequest {
+                    reason: "since must be before until".to_string(),
+                });
+            }
+        }
+
+        let (events, total) = self.repo.list_audit_events(request).await?;
+
+        Ok(AuditEventPage {
+            events,
+            page: request.page,
+            per_page: request.per_page,
+            total,
+        })
+    }
+
+    async fn list_recent_activity(&self, user_id: &UserId) -> Result<Vec<AuditEvent>, AuditError> {
+        let (events, _) = self
+            .repo
+            .list_audit_events(&ListAuditEventsRequest {
+                page: 1,
+                per_page: RECENT_ACTIVITY_LIMIT,
+                user_id: Some(*user_id.get()),
+                ..ListAuditEventsRequest::default()
+            })
+            .await?;
+
+        Ok(events)
+    }
+
+    async fn record_admin_action(&self, entry: &AdminAuditEntry) -> Result<(), AuditError> {
+        let event = if entry.status < 400 {
+            NewAuditEvent::success(AuditEventType::AdminAction, &entry.context)
+        } else {
+            NewAuditEvent::failure(
+                AuditEventType::AdminAction,
+                &format!("HTTP {}", entry.status),
+                &entry.context,
+            )
+        };
+
+        self.append_audit_event(
+            &event
+                .with_user(entry.subject_id)
+                .with_actor(Some(entry.actor_id))
+                .with_details(serde_json::json!({
+                    "method": entry.method,
+                    "route": entry.route,
+                    "path": entry.path,
+                    "status": entry.status,
+                })),
+        )
+        .await
+    }
+
+    async fn verify_audit_chain(&self) -> Result<AuditChainReport, AuditError> {
+        let checkpoints = self.repo.list_audit_checkpoints().await?;
+        let (signed, forged): (Vec<_>, Vec<_>) = checkpoints
+            .into_iter()
+            .partition(|checkpoint| self.is_signed_checkpoint(checkpoint));
+        let forged = forged.first().map(|checkpoint| {
+            BrokenAuditLink::new(checkpoint.sequence, "Checkpoint signature is invalid")
+        });
+
+        let mut verifier = AuditChainVerifier::new(&signed);
+        let mut broken_link = None;
+        let mut after_sequence = 0;
+        while broken_link.is_none() {
+            let events = self
+                .repo
+                .list_audit_chain(after_sequence, AUDIT_CHAIN_PAGE_SIZE)
+                .await?;
+            broken_link = events.iter().find_map(|event| verifier.check(event).err());
+
+            match events.last() {
+                Some(last) if events.len() as i64 == AUDIT_CHAIN_PAGE_SIZE => {
+                    after_sequence = last.sequence
+                }
+                _ => break,
+            }
+        }
+
+        let mut report = verifier.finish(broken_link);
+        if let Some(forged) = forged {
+            if report
+                .first_broken_link
+                .as_ref()
+                .is_none_or(|link| forged.sequence <= link.sequence)
+            {
+                report.intact = false;
+                report.first_broken_link = Some(forged);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    /// Appends `event` to the audit log chain, and signs a checkpoint of the chain when the
+    /// event's sequence number is a multiple of the checkpoint interval.
+    pub(crate) async fn append_audit_event(&self, event: &NewAuditEvent) -> Result<(), AuditError> {
+        let event = self.audit.record(event).await?;
+
+        match &event.hash {
+            Some(hash) if event.sequence % self.config.audit_checkpoint_interval == 0 => {
+                let kid = public_jwk(&self.config.access_token_public_key)
+                    .map_err(|e| e.context("Failed to derive signing key id"))?
+                    .kid;
+                let claims = AuditCheckpointClaims {
+                    sequence: event.sequence,
+                    hash: hash.to_string(),
+                    iat: chrono::Utc::now().timestamp(),
+                };
+                let signature =
+                    sign_audit_checkpoint(&claims, &self.config.access_token_private_key, &kid)
+                        .map_err(|e| anyhow!(e).context("Failed to sign audit checkpoint"))?;
+
+                self.audit
+                    .save_checkpoint(&AuditCheckpoint {
+                        sequence: claims.sequence,
+                        hash: claims.hash,
+                        signature,
+                        created_at: None,
+                    })
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `checkpoint` was signed with the access token key for its own sequence number
+    /// and hash.
+    fn is_signed_checkpoint(&self, checkpoint: &AuditCheckpoint) -> bool {
+        verify_audit_checkpoint(&self.config.access_token_public_key, &checkpoint.signature)
+            .is_ok_and(|claims| {
+                claims.sequence == checkpoint.sequence && claims.hash == checkpoint.hash
+            })
+    }
+}
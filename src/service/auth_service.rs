@@ -1,25 +1,45 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 
 use crate::{
     api::utils::{
-        jwt::{generate_jwt, verify_jwt},
-        security::is_valid,
+        jwt::{generate_jwt_with_claims, is_expired, verify_jwt},
+        ldap::{authenticate, DirectoryLogin},
+        security::{hash_token, is_valid},
     },
+    claims::pipeline::ClaimsPipeline,
     domain::{
         auth_service::AuthService,
         model::{
+            audit::{AuditEventType, NewAuditEvent, RequestContext},
             auth::{AuthRequest, AuthorizationError},
             auth_middleware::AuthMiddleware,
+            auth_repo_errors::AuthRepositoryError,
+            authentication_context::AuthenticationContext,
+            change_password::{ChangePasswordError, ChangePasswordRequest},
+            custom_claims::{CustomClaims, ISSUER_CLAIM},
+            domain_event::{DomainEvent, DomainEventType},
+            ldap::LdapMode,
             login_response::LoginResponse,
             login_user::{LoginUserError, LoginUserRequest},
             logout::{LogoutRequest, LogoutResponse},
+            oauth_client::ClientId,
+            organization::{active_organization, OrganizationMembership},
+            password_reset::{ResetPasswordError, ResetPasswordRequest},
+            personal_access_token::PersonalAccessTokenSecret,
+            principal::{Principal, PrincipalType},
             refresh_token::{RefreshRequest, RefreshResponse, RefreshTokenError},
             register_user::{RegisterUserError, RegisterUserRequest},
-            token::CacheToken,
-            user::FilteredUser,
+            registration::RegistrationMode,
+            session_revocation::{session_id, SessionRevocation, SESSION_ID_CLAIM},
+            token::{CacheToken, TokenDetails},
+            user::{FilteredUser, User},
             user_id::UserId,
+            webhook::WebhookEvent,
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
         },
-        repositories::{auth_repository::AuthRepository, cache_repository::CacheRepository},
     },
     helper::config::Config,
 };
@@ -27,57 +47,388 @@ use crate::{
 /// A service struct that implements the `AuthService` trait, providing authentication-related functionality.
 ///
 /// The `Service` struct interacts with the authentication repository and cache repository to
-/// handle registration, login, token validation, logout, and token refreshing. It uses the configuration
-/// parameters provided by the `Config` struct to manage tokens and other settings.
+/// handle registration, login, token validation, logout, and token refreshing, and records what
+/// happened in the security audit log, publishing successful operations to other services. It
+/// uses the configuration
+/// parameters provided by the `Config` struct to manage tokens and other settings, and runs the
+/// `ClaimsPipeline` to enrich access tokens with custom claims before they are signed.
 ///
 /// # Type Parameters
 ///
 /// * `R` - A type that implements the `AuthRepository` trait, providing methods to interact with the authentication database.
 /// * `C` - A type that implements the `CacheRepository` trait, providing methods to interact with the cache storage.
+/// * `A` - A type that implements the `AuditSink` trait, where security audit events are written.
+/// * `P` - A type that implements the `EventPublisher` trait, where authentication events are
+///   published.
 #[derive(Debug)]
-pub struct Service<R, C>
+pub struct Service<R, C, A, P>
 where
     R: AuthRepository,
     C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
 {
     pub repo: R,
     pub cache: C,
+    pub audit: A,
+    pub events: P,
+    pub claims: ClaimsPipeline,
     pub config: Config,
 }
 
-impl<R, C> AuthService for Service<R, C>
+impl<R, C, A, P> AuthService for Service<R, C, A, P>
 where
     R: AuthRepository,
     C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
 {
     async fn register(
         &self,
         request: &RegisterUserRequest,
     ) -> Result<FilteredUser, RegisterUserError> {
-        self.repo
-            .register(request)
-            .await
-            .map_err(RegisterUserError::from)
+        let result = self.register_user(request).await;
+
+        let user_id = result.as_ref().ok().map(|user| user.id);
+        self.record_event(
+            audit_event(AuditEventType::Register, &result, &request.context)
+                .with_user(user_id)
+                .with_details(serde_json::json!({ "email": request.email.get() })),
+        )
+        .await;
+
+        if let Ok(user) = &result {
+            self.publish_event(
+                DomainEvent::new(DomainEventType::UserRegistered, Some(user.id))
+                    .with_data(serde_json::json!({ "email": user.email })),
+            )
+            .await;
+        }
+
+        result
     }
 
     async fn login(&self, request: &LoginUserRequest) -> Result<LoginResponse, LoginUserError> {
-        let user = self.repo.login(request).await?;
+        let failed_attempts = self
+            .cache
+            .fetch_request_count(&login_failures_key(request))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to read failed login attempts"))?;
+
+        let (user_id, result) = if failed_attempts >= self.config.login_lockout_threshold {
+            (None, Err(LoginUserError::LockedOut))
+        } else {
+            match self.find_login_user(request).await {
+                Ok((user, verified)) => (
+                    Some(user.id),
+                    self.start_login_session(&user, verified, request).await,
+                ),
+                Err(e) => (None, Err(e)),
+            }
+        };
+
+        self.record_event(
+            audit_event(AuditEventType::Login, &result, &request.context)
+                .with_user(user_id)
+                .with_details(serde_json::json!({ "email": request.email.get() })),
+        )
+        .await;
+
+        if let Err(LoginUserError::InvalidCredentials) = result {
+            self.count_login_failure(request, user_id).await;
+        }
+
+        if result.is_ok() {
+            self.publish_event(
+                DomainEvent::new(DomainEventType::UserLoggedIn, user_id)
+                    .with_data(serde_json::json!({ "email": request.email.get() })),
+            )
+            .await;
+        }
+
+        if let Err(e) = &result {
+            if !matches!(e, LoginUserError::Unknown(_)) {
+                self.emit_webhook_event(&WebhookEvent::user_login_failed(
+                    user_id,
+                    request.email.get(),
+                    &e.to_string(),
+                ))
+                .await;
+            }
+        }
+
+        result
+    }
+
+    async fn auth(&self, request: &AuthRequest) -> Result<AuthMiddleware, AuthorizationError> {
+        if PersonalAccessTokenSecret::is_personal_access_token(request.access_token.get()) {
+            return self
+                .personal_access_token_auth(&PersonalAccessTokenSecret::new(
+                    request.access_token.get(),
+                ))
+                .await;
+        }
+
+        let access_token_details = verify_jwt(
+            &self.config.access_token_public_key,
+            request.access_token.get(),
+        )
+        .map_err(|e| {
+            if is_expired(&e) {
+                AuthorizationError::TokenExpired
+            } else {
+                AuthorizationError::InvalidCredentials {
+                    reason: "Access token no longer valid".to_string(),
+                }
+            }
+        })?;
+
+        self.cache
+            .verify_active_session(&access_token_details)
+            .await
+            .map_err(AuthorizationError::from)?;
+
+        let principal_type = access_token_details.principal_type();
+        let mut claims = access_token_details.claims;
+        let principal = match principal_type {
+            PrincipalType::User => {
+                let user_id = UserId::new(access_token_details.user_id);
+                if let Some(organization_id) = active_organization(&claims) {
+                    self.repo
+                        .fetch_membership(&user_id, &organization_id)
+                        .await?
+                        .insert_into(&mut claims);
+                }
+
+                let user = self.repo.fetch_user_by_id(&user_id).await?;
+                if user.is_suspended() || user.is_session_revoked(access_token_details.issued_at) {
+                    return Err(AuthorizationError::InvalidCredentials {
+                        reason: "Access token no longer valid".to_string(),
+                    });
+                }
+
+                Principal::User(user)
+            }
+            PrincipalType::Client => {
+                let client_id = claims
+                    .get("client_id")
+                    .and_then(|id| id.as_str())
+                    .ok_or_else(|| AuthorizationError::InvalidCredentials {
+                        reason: "Access token is missing the client_id claim".to_string(),
+                    })?;
+
+                let client = self
+                    .repo
+                    .fetch_oauth_client(&ClientId::new(client_id))
+                    .await?;
+                if client.id != access_token_details.user_id {
+                    return Err(AuthorizationError::InvalidCredentials {
+                        reason: "Access token subject does not match its client".to_string(),
+                    });
+                }
+
+                Principal::Client(client)
+            }
+        };
+
+        Ok(
+            AuthMiddleware::new(principal, access_token_details.token_uuid, claims)
+                .with_expiry(access_token_details.expires_in),
+        )
+    }
+
+    async fn logout(&self, request: &LogoutRequest) -> Result<LogoutResponse, AuthorizationError> {
+        let result = self
+            .cache
+            .delete_token(request.get_uuid())
+            .await
+            .map(|_| LogoutResponse::new("User logged out"));
+
+        self.record_event(
+            audit_event(AuditEventType::Logout, &result, &request.context)
+                .with_user(request.user_id),
+        )
+        .await;
+
+        if result.is_ok() {
+            self.publish_event(
+                DomainEvent::new(DomainEventType::UserLoggedOut, request.user_id).with_data(
+                    serde_json::json!({ "token_uuid": request.get_uuid().get_string() }),
+                ),
+            )
+            .await;
+        }
+
+        if result.is_ok() {
+            // A logout ends the session, so the access tokens refreshed in it are revoked too.
+            self.broadcast_revocation(
+                SessionRevocation::token(
+                    *request.get_uuid().get(),
+                    self.access_token_expiry(request.expires_at),
+                )
+                .with_session(request.session_id, self.config.access_token_max_age * 60)
+                .with_user(request.user_id),
+            )
+            .await;
+        }
+
+        if let (Ok(_), Some(user_id)) = (&result, request.user_id) {
+            self.emit_webhook_event(&WebhookEvent::session_revoked(user_id, false, "logout"))
+                .await;
+        }
+
+        Ok(result?)
+    }
+
+    async fn refresh(
+        &self,
+        request: &RefreshRequest,
+    ) -> Result<RefreshResponse, RefreshTokenError> {
+        let (user_id, result) =
+            match verify_jwt(&self.config.refresh_token_public_key, request.get_token()) {
+                Ok(refresh_token_details) => (
+                    Some(refresh_token_details.user_id),
+                    self.refresh_session(&refresh_token_details).await,
+                ),
+                Err(e) if is_expired(&e) => (None, Err(RefreshTokenError::TokenExpired)),
+                Err(_) => (
+                    None,
+                    Err(RefreshTokenError::InvalidCredentials {
+                        reason: "Refresh token no longer valid".to_string(),
+                    }),
+                ),
+            };
+
+        self.record_event(
+            audit_event(AuditEventType::Refresh, &result, &request.context).with_user(user_id),
+        )
+        .await;
+
+        if result.is_ok() {
+            self.publish_event(DomainEvent::new(DomainEventType::SessionRefreshed, user_id))
+                .await;
+        }
+
+        result
+    }
+
+    async fn change_password(
+        &self,
+        request: &ChangePasswordRequest,
+    ) -> Result<(), ChangePasswordError> {
+        let result = self.update_user_password(request).await;
+
+        self.record_event(
+            audit_event(AuditEventType::PasswordChange, &result, &request.context)
+                .with_user(Some(*request.user_id.get())),
+        )
+        .await;
+
+        if result.is_ok() {
+            self.publish_event(DomainEvent::new(
+                DomainEventType::PasswordChanged,
+                Some(*request.user_id.get()),
+            ))
+            .await;
+            self.broadcast_user_revocation(*request.user_id.get()).await;
+        }
+
+        result
+    }
+
+    async fn reset_password(
+        &self,
+        request: &ResetPasswordRequest,
+    ) -> Result<(), ResetPasswordError> {
+        let result = self
+            .repo
+            .reset_password(&hash_token(&request.token), &request.hashed_password)
+            .await
+            .map_err(ResetPasswordError::from);
+
+        let user_id = result.as_ref().ok().copied();
+        self.record_event(
+            audit_event(AuditEventType::PasswordReset, &result, &request.context)
+                .with_user(user_id),
+        )
+        .await;
+
+        if let Some(user_id) = user_id {
+            self.publish_event(DomainEvent::new(
+                DomainEventType::PasswordReset,
+                Some(user_id),
+            ))
+            .await;
+            self.broadcast_user_revocation(user_id).await;
+        }
+
+        result.map(|_| ())
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    /// Issues the access and refresh tokens of a new login session for `user`.
+    ///
+    /// Password and federated logins share this path, so both get the same custom claims,
+    /// cache entries and cookies. The authentication `context` is recorded in both tokens.
+    /// Service accounts and suspended users never get a login session, whichever way they were
+    /// authenticated.
+    ///
+    /// The session starts in the user's oldest organization, if they belong to any.
+    pub(crate) async fn issue_login_tokens(
+        &self,
+        user: &User,
+        context: &AuthenticationContext,
+    ) -> Result<LoginResponse, LoginUserError> {
+        let memberships = self.repo.list_memberships(&UserId::new(user.id)).await?;
+        self.issue_session_tokens(user, context, memberships.first())
+            .await
+    }
 
-        let is_valid = is_valid(request.password.get(), &user.password);
-        if !is_valid {
+    /// Issues the tokens of a session for `user` acting in `organization`, which is recorded
+    /// in both tokens alongside the authentication `context`.
+    pub(crate) async fn issue_session_tokens(
+        &self,
+        user: &User,
+        context: &AuthenticationContext,
+        organization: Option<&OrganizationMembership>,
+    ) -> Result<LoginResponse, LoginUserError> {
+        if user.is_service_account() {
             return Err(LoginUserError::InvalidCredentials);
         }
+        if user.is_suspended() {
+            return Err(LoginUserError::Suspended);
+        }
 
-        let access_token_details = generate_jwt(
-            user.id,
-            self.config.access_token_max_age,
-            &self.config.access_token_private_key,
-        )?;
+        let mut session_claims = CustomClaims::default();
+        session_claims.insert(SESSION_ID_CLAIM, serde_json::json!(uuid::Uuid::new_v4()));
+        context.insert_into(&mut session_claims);
+        if let Some(membership) = organization {
+            membership.insert_into(&mut session_claims);
+        }
 
-        let refresh_token_details = generate_jwt(
+        let mut claims = self
+            .claims
+            .enrich(user)
+            .map_err(|e| anyhow!(e).context("Failed to build access token claims"))?;
+        for (name, value) in session_claims.iter() {
+            claims.insert(name, value.clone());
+        }
+
+        let access_token_details =
+            self.sign_access_token(user.id, self.config.access_token_max_age, claims)?;
+
+        let refresh_token_details = generate_jwt_with_claims(
             user.id,
             self.config.refresh_token_max_age,
             &self.config.refresh_token_private_key,
+            session_claims,
         )?;
 
         self.cache
@@ -112,46 +463,166 @@ where
         })
     }
 
-    async fn auth(&self, request: &AuthRequest) -> Result<AuthMiddleware, AuthorizationError> {
-        let access_token_details = verify_jwt(
-            &self.config.access_token_public_key,
-            request.access_token.get(),
-        )
-        .map_err(|_| AuthorizationError::InvalidCredentials {
-            reason: "Access token no longer valid".to_string(),
-        })?;
+    /// Creates the account of a new user, if the registration mode lets them register.
+    async fn register_user(
+        &self,
+        request: &RegisterUserRequest,
+    ) -> Result<FilteredUser, RegisterUserError> {
+        // The invitation is checked against the invited email when the repository redeems it,
+        // which rolls the registration back when it is not valid.
+        let invited = request.invitation.is_some();
 
-        self.cache
-            .verify_active_session(&access_token_details)
+        let mode = &self.config.registration_mode;
+        if !mode.allows(request.email.get(), invited) {
+            let reason = match mode {
+                RegistrationMode::DomainAllowlist(_) => {
+                    "an invitation or an email address in an allowed domain is required"
+                }
+                RegistrationMode::Closed => "registration is closed",
+                _ => "an invitation is required",
+            };
+            return Err(RegisterUserError::NotAllowed {
+                reason: reason.to_string(),
+            });
+        }
+
+        self.repo
+            .register(request)
             .await
-            .map_err(AuthorizationError::from)?;
+            .map_err(RegisterUserError::from)
+    }
 
-        let user = self
-            .repo
-            .fetch_user_by_id(&UserId::new(access_token_details.user_id))
-            .await?;
+    /// Finds the user logging in, provisioning them first when they are in the LDAP directory.
+    ///
+    /// Users authenticated by the directory come back as `verified`, as their password was
+    /// already checked there. Anyone else still has to have their password checked.
+    async fn find_login_user(
+        &self,
+        request: &LoginUserRequest,
+    ) -> Result<(User, bool), LoginUserError> {
+        if let Some(ldap) = &self.config.ldap {
+            let login = authenticate(ldap, request.email.get(), request.password.get())
+                .await
+                .context("LDAP authentication failed")?;
 
-        Ok(AuthMiddleware::new(user, access_token_details.token_uuid))
+            match login {
+                DirectoryLogin::Authenticated(directory_user) => {
+                    let may_register = self
+                        .config
+                        .registration_mode
+                        .allows(&directory_user.email, false);
+                    let user = self
+                        .repo
+                        .provision_directory_user(&directory_user, may_register)
+                        .await
+                        .map_err(|e| match e {
+                            AuthRepositoryError::Duplicate { email } => {
+                                tracing::warn!(
+                                    "Refused LDAP login of {}, which belongs to a local account",
+                                    email
+                                );
+                                LoginUserError::InvalidCredentials
+                            }
+                            e => LoginUserError::from(e),
+                        })?;
+                    return Ok((user, true));
+                }
+                DirectoryLogin::InvalidPassword => return Err(LoginUserError::InvalidCredentials),
+                DirectoryLogin::UnknownUser if ldap.mode == LdapMode::Exclusive => {
+                    return Err(LoginUserError::InvalidCredentials)
+                }
+                DirectoryLogin::UnknownUser => {}
+            }
+        }
+
+        Ok((self.repo.login(request).await?, false))
     }
 
-    async fn logout(&self, request: &LogoutRequest) -> Result<LogoutResponse, AuthorizationError> {
-        self.cache.delete_token(request.get_uuid()).await?;
-        Ok(LogoutResponse::new("User logged out"))
+    /// Checks the password of `user`, unless it was `verified` by the directory, and issues
+    /// the tokens of their new session.
+    async fn start_login_session(
+        &self,
+        user: &User,
+        verified: bool,
+        request: &LoginUserRequest,
+    ) -> Result<LoginResponse, LoginUserError> {
+        if !verified {
+            if user.is_service_account() {
+                return Err(LoginUserError::InvalidCredentials);
+            }
+
+            let is_valid = is_valid(request.password.get(), &user.password);
+            if !is_valid {
+                return Err(LoginUserError::InvalidCredentials);
+            }
+
+            if user.password_reset_required {
+                return Err(LoginUserError::PasswordResetRequired);
+            }
+        }
+
+        self.issue_login_tokens(user, &AuthenticationContext::password())
+            .await
     }
 
-    async fn refresh(
+    /// Counts a failed login for the email of `request`, and records a lockout event when it
+    /// reaches `LOGIN_LOCKOUT_THRESHOLD`, after which logins are refused until the
+    /// `LOGIN_LOCKOUT_SECONDS` window the first failure started has passed.
+    ///
+    /// Failures are counted by email rather than by user, so guessing at unknown addresses
+    /// is throttled the same way.
+    async fn count_login_failure(&self, request: &LoginUserRequest, user_id: Option<uuid::Uuid>) {
+        let failed_attempts = match self
+            .cache
+            .count_request(
+                &login_failures_key(request),
+                self.config.login_lockout_seconds,
+            )
+            .await
+        {
+            Ok(failed_attempts) => failed_attempts,
+            Err(e) => {
+                tracing::error!("Failed to count failed login attempt: {:?}", e);
+                return;
+            }
+        };
+
+        if failed_attempts == self.config.login_lockout_threshold {
+            self.record_event(
+                NewAuditEvent::failure(
+                    AuditEventType::Lockout,
+                    "Too many failed login attempts",
+                    &request.context,
+                )
+                .with_user(user_id)
+                .with_details(serde_json::json!({
+                    "email": request.email.get(),
+                    "failed_attempts": failed_attempts,
+                    "lockout_seconds": self.config.login_lockout_seconds,
+                })),
+            )
+            .await;
+        }
+    }
+
+    /// Issues a new access token for the session of a verified refresh token.
+    ///
+    /// Refresh tokens issued to OAuth clients are signed with the same key but carry the
+    /// `client_id` and `scope` of their grant, and are refused here: exchanging them would turn a
+    /// scoped grant into an unscoped login session.
+    async fn refresh_session(
         &self,
-        request: &RefreshRequest,
+        refresh_token_details: &TokenDetails,
     ) -> Result<RefreshResponse, RefreshTokenError> {
-        let refresh_token_details =
-            verify_jwt(&self.config.refresh_token_public_key, request.get_token()).map_err(
-                |_| RefreshTokenError::InvalidCredentials {
-                    reason: "Refresh token no longer valid".to_string(),
-                },
-            )?;
+        let claims = &refresh_token_details.claims;
+        if claims.get("client_id").is_some() || claims.get("scope").is_some() {
+            return Err(RefreshTokenError::InvalidCredentials {
+                reason: "Refresh token was not issued for a login session".to_string(),
+            });
+        }
 
         self.cache
-            .verify_active_session(&refresh_token_details)
+            .verify_active_session(refresh_token_details)
             .await
             .map_err(RefreshTokenError::from)?;
 
@@ -159,12 +630,31 @@ where
             .repo
             .fetch_user_by_id(&UserId::new(refresh_token_details.user_id))
             .await?;
+        if user.is_suspended() || user.is_session_revoked(refresh_token_details.issued_at) {
+            return Err(RefreshTokenError::InvalidCredentials {
+                reason: "Refresh token no longer valid".to_string(),
+            });
+        }
 
-        let access_token_details = generate_jwt(
-            user.id,
-            self.config.access_token_max_age,
-            &self.config.access_token_private_key,
-        )?;
+        let mut claims = self
+            .claims
+            .enrich(&user)
+            .map_err(|e| anyhow!(e).context("Failed to build access token claims"))?;
+        if let Some(context) = AuthenticationContext::from_claims(&refresh_token_details.claims) {
+            context.insert_into(&mut claims);
+        }
+        if let Some(session_id) = session_id(&refresh_token_details.claims) {
+            claims.insert(SESSION_ID_CLAIM, serde_json::json!(session_id));
+        }
+        if let Some(organization_id) = active_organization(&refresh_token_details.claims) {
+            self.repo
+                .fetch_membership(&UserId::new(user.id), &organization_id)
+                .await?
+                .insert_into(&mut claims);
+        }
+
+        let access_token_details =
+            self.sign_access_token(user.id, self.config.access_token_max_age, claims)?;
 
         self.cache
             .save_token_data(&CacheToken::new(
@@ -183,4 +673,112 @@ where
             access_token_max_age: self.config.access_token_max_age,
         })
     }
+
+    async fn update_user_password(
+        &self,
+        request: &ChangePasswordRequest,
+    ) -> Result<(), ChangePasswordError> {
+        let user = self.repo.fetch_user_by_id(&request.user_id).await?;
+        if !is_valid(request.current_password.get(), &user.password) {
+            return Err(ChangePasswordError::InvalidCredentials);
+        }
+
+        Ok(self
+            .repo
+            .update_password(&request.user_id, &request.new_password)
+            .await?)
+    }
+
+    /// Signs an access token for `subject` carrying `claims`, with the service's `OIDC_ISSUER`
+    /// as its `iss`, for as long as `max_age` minutes.
+    pub(crate) fn sign_access_token(
+        &self,
+        subject: uuid::Uuid,
+        max_age: i64,
+        mut claims: CustomClaims,
+    ) -> anyhow::Result<TokenDetails> {
+        claims.insert(
+            ISSUER_CLAIM,
+            serde_json::json!(self.config.oidc_issuer.trim_end_matches('/')),
+        );
+
+        generate_jwt_with_claims(
+            subject,
+            max_age,
+            &self.config.access_token_private_key,
+            claims,
+        )
+    }
+
+    /// Adds an event to the security audit log. Failing to do so is logged rather than
+    /// returned, so the action being audited goes ahead.
+    async fn record_event(&self, event: NewAuditEvent) {
+        if let Err(e) = self.append_audit_event(&event).await {
+            tracing::error!("Failed to record audit event {:?}: {:?}", event, e);
+        }
+    }
+
+    /// Publishes the event of a successful operation. Failing to do so is logged rather than
+    /// returned, so the operation still succeeds.
+    pub(crate) async fn publish_event(&self, event: DomainEvent) {
+        if let Err(e) = self.events.publish(&event).await {
+            tracing::error!("Failed to publish event {:?}: {:?}", event, e);
+        }
+    }
+
+    /// Broadcasts a revocation to the services verifying tokens locally, and records it for
+    /// the identities cached by `forward_auth`. Failing to do so is logged rather than
+    /// returned, like domain events.
+    pub(crate) async fn broadcast_revocation(&self, revocation: SessionRevocation) {
+        if let Err(e) = self.cache.record_revocation(&revocation).await {
+            tracing::error!("Failed to record revocation {:?}: {:?}", revocation, e);
+        }
+        if let Err(e) = self.events.publish_revocation(&revocation).await {
+            tracing::error!("Failed to broadcast revocation {:?}: {:?}", revocation, e);
+        }
+    }
+
+    /// When an access token whose `exp` is `expires_at` stops being accepted anyway. A token
+    /// whose `exp` is unknown may live for as long as any access token.
+    pub(crate) fn access_token_expiry(&self, expires_at: Option<i64>) -> i64 {
+        expires_at.unwrap_or_else(|| {
+            chrono::Utc::now().timestamp() + self.config.access_token_max_age * 60
+        })
+    }
+
+    /// Broadcasts that every token of `user_id` issued so far is revoked, once their sessions
+    /// were revoked.
+    pub(crate) async fn broadcast_user_revocation(&self, user_id: uuid::Uuid) {
+        self.broadcast_revocation(SessionRevocation::user(
+            user_id,
+            self.config.access_token_max_age * 60,
+        ))
+        .await;
+    }
+
+    /// Queues a webhook event that has no database change to be queued with. Failing to do so
+    /// is logged rather than returned, like audit events.
+    async fn emit_webhook_event(&self, event: &WebhookEvent) {
+        if let Err(e) = self.repo.enqueue_webhook_event(event).await {
+            tracing::error!("Failed to queue webhook event {:?}: {:?}", event, e);
+        }
+    }
+}
+
+/// The audit event of an action of type `event_type` that ended with `result`, whose error is
+/// recorded as the reason it failed.
+fn audit_event<T, E: std::fmt::Display>(
+    event_type: AuditEventType,
+    result: &Result<T, E>,
+    context: &RequestContext,
+) -> NewAuditEvent {
+    match result {
+        Ok(_) => NewAuditEvent::success(event_type, context),
+        Err(e) => NewAuditEvent::failure(event_type, &e.to_string(), context),
+    }
+}
+
+/// Key of the counter of failed logins for the email of `request`.
+fn login_failures_key(request: &LoginUserRequest) -> String {
+    format!("login_failures:{}", request.email.get().to_lowercase())
 }
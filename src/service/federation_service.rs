@@ -0,0 +1,210 @@
+use anyhow::anyhow;
+
+use crate::{
+    api::utils::{
+        oidc_client::{authorization_url, discover, exchange_code, verify_id_token},
+        pkce::code_challenge,
+        security::{generate_random_token, hash_token},
+    },
+    domain::{
+        federation_service::FederationService,
+        model::{
+            auth_repo_errors::AuthRepositoryError,
+            authentication_context::AuthenticationContext,
+            federation::{
+                FederatedCallbackRequest, FederatedCallbackResponse, FederatedIdentity,
+                FederatedLoginRequest, FederatedLoginResponse, FederatedProvider, FederationError,
+                FederationState,
+            },
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> FederationService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn federated_login(
+        &self,
+        request: &FederatedLoginRequest,
+    ) -> Result<FederatedLoginResponse, FederationError> {
+        let provider = self.federated_provider(&request.provider)?;
+
+        if let Some(return_to) = &request.return_to {
+            if !is_local_path(return_to) {
+                return Err(FederationError::InvalidRequest {
+                    reason: "return_to must be a path on this service".to_string(),
+                });
+            }
+        }
+
+        let discovery = discover(provider).await?;
+
+        let state = FederationState {
+            state: generate_random_token(),
+            provider: provider.name.clone(),
+            nonce: generate_random_token(),
+            code_verifier: generate_random_token(),
+            return_to: request.return_to.clone(),
+            max_age: self.config.federation_state_max_age_seconds,
+        };
+
+        let authorization_url = authorization_url(
+            &discovery,
+            provider,
+            &self.federation_redirect_uri(provider),
+            &state.state,
+            &state.nonce,
+            &code_challenge(&state.code_verifier),
+        )?;
+
+        self.cache
+            .save_federation_state(&state)
+            .await
+            .map_err(|e| {
+                anyhow!(e).context("Failed redis operation while saving federation state")
+            })?;
+
+        Ok(FederatedLoginResponse {
+            authorization_url,
+            state_digest: hash_token(&state.state),
+            state_max_age: state.max_age,
+        })
+    }
+
+    async fn federated_callback(
+        &self,
+        request: &FederatedCallbackRequest,
+    ) -> Result<FederatedCallbackResponse, FederationError> {
+        let provider = self.federated_provider(&request.provider)?;
+
+        check_state_digest(&request.state, request.state_digest.as_deref())?;
+        let state = self.cache.take_federation_state(&request.state).await?;
+        if state.provider != provider.name {
+            return Err(FederationError::Rejected {
+                reason: "Login state was issued for another provider".to_string(),
+            });
+        }
+
+        if let Some(error) = &request.error {
+            return Err(FederationError::Rejected {
+                reason: format!("{} returned {}", provider.name, error),
+            });
+        }
+
+        let code = request
+            .code
+            .as_deref()
+            .ok_or_else(|| FederationError::InvalidRequest {
+                reason: "Missing code parameter".to_string(),
+            })?;
+
+        let discovery = discover(provider).await?;
+
+        let id_token = exchange_code(
+            &discovery,
+            provider,
+            code,
+            &self.federation_redirect_uri(provider),
+            &state.code_verifier,
+        )
+        .await
+        .map_err(|e| FederationError::Rejected {
+            reason: format!("{:#}", e),
+        })?;
+
+        let identity = verify_id_token(&discovery, provider, &id_token, &state.nonce)
+            .await
+            .map_err(|e| FederationError::Rejected {
+                reason: format!("{:#}", e),
+            })?;
+
+        let user = match self.repo.fetch_user_by_identity(&identity).await {
+            Ok(user) => user,
+            Err(AuthRepositoryError::InvalidCredentials { .. }) => {
+                let may_register = self.may_register_identity(&identity);
+                self.repo.link_identity(&identity, may_register).await?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let login = self
+            .issue_login_tokens(&user, &AuthenticationContext::federated(&identity.amr))
+            .await?;
+
+        Ok(FederatedCallbackResponse {
+            login,
+            return_to: state.return_to,
+        })
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    fn federated_provider(&self, name: &str) -> Result<&FederatedProvider, FederationError> {
+        self.config
+            .federated_providers
+            .iter()
+            .find(|provider| provider.name == name)
+            .ok_or_else(|| FederationError::UnknownProvider {
+                provider: name.to_string(),
+            })
+    }
+
+    /// Whether the registration mode lets a new account be created for `identity`, which
+    /// federated and SAML sign ins are held to just like `/api/register`.
+    pub(crate) fn may_register_identity(&self, identity: &FederatedIdentity) -> bool {
+        identity
+            .email
+            .as_deref()
+            .is_some_and(|email| self.config.registration_mode.allows(email, false))
+    }
+
+    /// The callback registered with `provider`, under this service's issuer URL.
+    fn federation_redirect_uri(&self, provider: &FederatedProvider) -> String {
+        format!(
+            "{}/api/federation/{}/callback",
+            self.config.oidc_issuer.trim_end_matches('/'),
+            provider.name
+        )
+    }
+}
+
+/// Whether `path` is a path on this service rather than a URL that could send the user to
+/// another site, which would make the callback an open redirect.
+///
+/// Browsers drop tabs and newlines from URLs and treat `\` as `/`, so `/\t/evil.example` is
+/// followed as `//evil.example`. Paths with whitespace, control characters or backslashes are
+/// refused for that reason.
+pub(crate) fn is_local_path(path: &str) -> bool {
+    path.starts_with('/')
+        && !path.starts_with("//")
+        && !path
+            .chars()
+            .any(|c| c == '\\' || c.is_whitespace() || c.is_control())
+}
+
+/// Checks that the login state came back to the browser that started the login, which holds
+/// its digest in a cookie. Otherwise an attacker could have a victim's browser complete a
+/// login the attacker started, signing the victim in to the attacker's account.
+pub(crate) fn check_state_digest(state: &str, digest: Option<&str>) -> Result<(), FederationError> {
+    if digest != Some(hash_token(state).as_str()) {
+        return Err(FederationError::Rejected {
+            reason: "Login was not started in this browser".to_string(),
+        });
+    }
+    Ok(())
+}
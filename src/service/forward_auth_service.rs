@@ -0,0 +1,99 @@
+use crate::{
+    api::utils::jwt::verify_jwt,
+    domain::{
+        auth_service::AuthService,
+        forward_auth_service::ForwardAuthService,
+        model::{
+            auth::{AuthRequest, AuthorizationError},
+            forward_auth::{ForwardAuthIdentity, ForwardAuthResponse},
+            token_uuid::TokenUuid,
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> ForwardAuthService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    /// Authenticates the request with `auth`, or with the identity cached for its access token
+    /// when `FORWARD_AUTH_CACHE_SECONDS` is set.
+    ///
+    /// Cached identities skip the database, so they are only used while no revocation of the
+    /// token's session or user was recorded since the token was issued, see
+    /// `broadcast_revocation`. A logout drops the entry right away, and the signature and
+    /// expiry of the token are checked either way. Personal access tokens are never cached.
+    async fn forward_auth(
+        &self,
+        request: &AuthRequest,
+    ) -> Result<ForwardAuthResponse, AuthorizationError> {
+        let cached_token = (self.config.forward_auth_cache_seconds > 0)
+            .then(|| {
+                verify_jwt(
+                    &self.config.access_token_public_key,
+                    request.access_token.get(),
+                )
+                .ok()
+            })
+            .flatten();
+
+        if let Some(token) = &cached_token {
+            match self.cache.fetch_forward_auth(token).await {
+                Ok(Some(identity)) => return Ok(self.forward_auth_response(&identity)),
+                Ok(None) => {}
+                Err(e) => tracing::error!("Failed to fetch forward auth identity: {:?}", e),
+            }
+        }
+
+        let identity = ForwardAuthIdentity::from(&self.auth(request).await?);
+
+        if let Some(token) = &cached_token {
+            if let Err(e) = self
+                .cache
+                .save_forward_auth(
+                    &TokenUuid::new(token.token_uuid),
+                    &identity,
+                    self.config.forward_auth_cache_seconds,
+                )
+                .await
+            {
+                tracing::error!("Failed to cache forward auth identity: {:?}", e);
+            }
+        }
+
+        Ok(self.forward_auth_response(&identity))
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    /// Names the identity headers as configured. The email is left out for OAuth clients, and
+    /// roles are comma separated.
+    fn forward_auth_response(&self, identity: &ForwardAuthIdentity) -> ForwardAuthResponse {
+        let mut headers = vec![(
+            self.config.forward_auth_user_id_header.clone(),
+            identity.user_id.to_string(),
+        )];
+        if let Some(email) = &identity.email {
+            headers.push((self.config.forward_auth_email_header.clone(), email.clone()));
+        }
+        headers.push((
+            self.config.forward_auth_roles_header.clone(),
+            identity.roles.join(","),
+        ));
+
+        ForwardAuthResponse { headers }
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::anyhow;
+
+use crate::{
+    domain::{
+        impersonation_service::ImpersonationService,
+        model::{
+            impersonation::{
+                Actor, ImpersonationError, ImpersonationResponse, StartImpersonationRequest,
+                IMPERSONATION_MAX_AGE_MINUTES,
+            },
+            session_revocation::SessionRevocation,
+            token::CacheToken,
+            token_uuid::TokenUuid,
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> ImpersonationService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn start_impersonation(
+        &self,
+        request: &StartImpersonationRequest,
+    ) -> Result<ImpersonationResponse, ImpersonationError> {
+        if request.actor_id.get() == request.user_id.get() {
+            return Err(ImpersonationError::InvalidRequest {
+                reason: "administrators cannot impersonate themselves".to_string(),
+            });
+        }
+
+        let user = self.repo.fetch_user_by_id(&request.user_id).await?;
+        if user.is_service_account() || user.is_admin() || user.is_suspended() {
+            return Err(ImpersonationError::Forbidden {
+                reason: "only active users without the admin role can be impersonated".to_string(),
+            });
+        }
+
+        // The session starts in the user's oldest organization, as their own logins do.
+        let memberships = self.repo.list_memberships(&request.user_id).await?;
+        let mut claims = self
+            .claims
+            .enrich(&user)
+            .map_err(|e| anyhow!(e).context("Failed to build impersonation token claims"))?;
+        if let Some(membership) = memberships.first() {
+            membership.insert_into(&mut claims);
+        }
+        Actor {
+            sub: *request.actor_id.get(),
+        }
+        .insert_into(&mut claims);
+
+        let max_age = IMPERSONATION_MAX_AGE_MINUTES.min(self.config.access_token_max_age);
+        let access_token_details = self.sign_access_token(user.id, max_age, claims)?;
+
+        self.cache
+            .save_token_data(&CacheToken::new(
+                access_token_details.token_uuid,
+                access_token_details.user_id,
+                max_age,
+            ))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to save impersonation token"))?;
+
+        let access_token = access_token_details
+            .token
+            .ok_or_else(|| anyhow!("Failed to generate impersonation token"))?;
+
+        Ok(ImpersonationResponse {
+            access_token,
+            access_token_max_age: max_age,
+        })
+    }
+
+    async fn end_impersonation(
+        &self,
+        access_token_uuid: &TokenUuid,
+    ) -> Result<(), ImpersonationError> {
+        self.cache
+            .delete_token(access_token_uuid)
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to revoke impersonation token"))?;
+        self.broadcast_revocation(SessionRevocation::token(
+            *access_token_uuid.get(),
+            self.access_token_expiry(None),
+        ))
+        .await;
+
+        Ok(())
+    }
+}
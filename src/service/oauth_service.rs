@@ -0,0 +1,859 @@
+use anyhow::anyhow;
+
+use crate::{
+    api::utils::{
+        jwt::{generate_jwt_with_claims, verify_jwt},
+        pkce::verify_code_challenge,
+        security::{generate_random_token, generate_user_code, is_valid, normalize_user_code},
+    },
+    domain::{
+        model::{
+            auth_repo_errors::AuthRepositoryError,
+            authentication_context::AuthenticationContext,
+            authorize::{
+                append_query, AuthorizationCode, AuthorizeError, AuthorizeRequest,
+                AuthorizeResponse,
+            },
+            cache_errors::CacheOperationError,
+            custom_claims::{CustomClaims, AUDIENCE_CLAIM, PRINCIPAL_TYPE_CLAIM},
+            device_authorization::{
+                DeviceAuthorization, DeviceAuthorizationRequest, DeviceAuthorizationResponse,
+                DeviceAuthorizationStatus, DeviceVerificationRequest, DeviceVerificationResponse,
+            },
+            domain_event::{DomainEvent, DomainEventType},
+            introspection::{IntrospectionRequest, IntrospectionResponse},
+            oauth_client::{ClientAuthentication, ClientId, ClientType, OAuthClient},
+            oauth_errors::OAuthError,
+            oauth_token::{TokenGrant, TokenRequest, TokenResponse},
+            principal::PrincipalType,
+            revocation::RevocationRequest,
+            scope::Scopes,
+            session_revocation::SessionRevocation,
+            token::{CacheToken, TokenDetails},
+            token_uuid::TokenUuid,
+            user::User,
+            user_id::UserId,
+        },
+        oauth_service::OAuthService,
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> OAuthService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn authorize(
+        &self,
+        request: &AuthorizeRequest,
+    ) -> Result<AuthorizeResponse, AuthorizeError> {
+        let client = self
+            .fetch_client(&request.client_id)
+            .await
+            .map_err(AuthorizeError::new)?;
+
+        let redirect_uri = client
+            .resolve_redirect_uri(request.redirect_uri.as_deref())
+            .ok_or_else(|| {
+                AuthorizeError::new(OAuthError::InvalidRequest {
+                    description: "Redirect URI is not registered for this client".to_string(),
+                })
+            })?;
+
+        let state = request.state.as_deref();
+
+        if request.response_type != "code" {
+            return Err(AuthorizeError::redirect(
+                OAuthError::UnsupportedResponseType {
+                    description: "Only the authorization code flow is supported".to_string(),
+                },
+                &redirect_uri,
+                state,
+            ));
+        }
+
+        let code_challenge = match (&request.code_challenge, &request.code_challenge_method) {
+            (Some(challenge), Some(method)) if method == "S256" => challenge,
+            (Some(_), Some(_)) => {
+                return Err(AuthorizeError::redirect(
+                    OAuthError::InvalidRequest {
+                        description: "Only the S256 code challenge method is supported".to_string(),
+                    },
+                    &redirect_uri,
+                    state,
+                ))
+            }
+            _ => {
+                return Err(AuthorizeError::redirect(
+                    OAuthError::InvalidRequest {
+                        description: "PKCE code challenge is required".to_string(),
+                    },
+                    &redirect_uri,
+                    state,
+                ))
+            }
+        };
+
+        let scope = if request.scope.is_empty() {
+            Scopes::parse(&client.allowed_scopes.join(" "))
+        } else {
+            request.scope.clone()
+        };
+
+        if !client.allows_scopes(&scope) {
+            return Err(AuthorizeError::redirect(
+                OAuthError::InvalidScope {
+                    description: "Requested scope is not allowed for this client".to_string(),
+                },
+                &redirect_uri,
+                state,
+            ));
+        }
+
+        let prompt: Vec<&str> = request
+            .prompt
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect();
+        if prompt.contains(&"none") && prompt.len() > 1 {
+            return Err(AuthorizeError::redirect(
+                OAuthError::InvalidRequest {
+                    description: "prompt=none cannot be combined with other values".to_string(),
+                },
+                &redirect_uri,
+                state,
+            ));
+        }
+
+        let session = request.session.as_ref().filter(|session| {
+            !prompt.contains(&"login")
+                && request.max_age.is_none_or(|max_age| {
+                    session
+                        .context
+                        .as_ref()
+                        .is_some_and(|context| context.age() <= max_age)
+                })
+        });
+
+        let Some(session) = session else {
+            return Err(if prompt.contains(&"none") {
+                AuthorizeError::redirect(
+                    OAuthError::LoginRequired {
+                        description: "User must log in to continue".to_string(),
+                    },
+                    &redirect_uri,
+                    state,
+                )
+            } else {
+                AuthorizeError::login_required(self.config.oidc_login_url.as_deref())
+            });
+        };
+
+        let code = AuthorizationCode {
+            code: generate_random_token(),
+            client_id: client.client_id,
+            user_id: session.user_id,
+            redirect_uri: redirect_uri.to_string(),
+            redirect_uri_requested: request.redirect_uri.is_some(),
+            scope: scope.to_string(),
+            code_challenge: code_challenge.to_string(),
+            max_age: self.config.oauth_code_max_age_seconds,
+            nonce: request.nonce.clone(),
+            context: session.context.clone(),
+        };
+
+        self.cache
+            .save_authorization_code(&code)
+            .await
+            .map_err(|e| {
+                AuthorizeError::new(OAuthError::Unknown(
+                    anyhow!(e).context("Failed redis operation while saving authorization code"),
+                ))
+            })?;
+
+        Ok(AuthorizeResponse {
+            redirect_uri,
+            code: code.code,
+            state: request.state.clone(),
+        })
+    }
+
+    async fn token(&self, request: &TokenRequest) -> Result<TokenResponse, OAuthError> {
+        let client = self.authenticate_client(&request.client).await?;
+
+        match &request.grant {
+            TokenGrant::AuthorizationCode {
+                code,
+                redirect_uri,
+                code_verifier,
+            } => {
+                let grant = self.cache.take_authorization_code(code).await?;
+
+                if grant.client_id != client.client_id {
+                    return Err(OAuthError::InvalidGrant {
+                        description: "Authorization code was issued to another client".to_string(),
+                    });
+                }
+
+                match redirect_uri {
+                    Some(uri) if *uri != grant.redirect_uri => {
+                        return Err(OAuthError::InvalidGrant {
+                            description: "Redirect URI does not match the authorization request"
+                                .to_string(),
+                        });
+                    }
+                    None if grant.redirect_uri_requested => {
+                        return Err(OAuthError::InvalidGrant {
+                            description:
+                                "Redirect URI is required as the authorization request included one"
+                                    .to_string(),
+                        });
+                    }
+                    _ => {}
+                }
+
+                if !verify_code_challenge(code_verifier, &grant.code_challenge) {
+                    return Err(OAuthError::InvalidGrant {
+                        description: "PKCE verification failed".to_string(),
+                    });
+                }
+
+                let user = self
+                    .repo
+                    .fetch_user_by_id(&UserId::new(grant.user_id))
+                    .await?;
+
+                let scope = Scopes::parse(&grant.scope);
+                let mut response = self
+                    .issue_tokens(&user, &client, &scope, grant.context.as_ref(), true)
+                    .await?;
+
+                if scope.contains("openid") {
+                    response.id_token = Some(self.issue_id_token(&user, &grant)?);
+                }
+
+                Ok(response)
+            }
+            TokenGrant::RefreshToken { refresh_token } => {
+                let refresh_token_details =
+                    verify_jwt(&self.config.refresh_token_public_key, refresh_token).map_err(
+                        |_| OAuthError::InvalidGrant {
+                            description: "Refresh token no longer valid".to_string(),
+                        },
+                    )?;
+
+                self.cache
+                    .verify_active_session(&refresh_token_details)
+                    .await?;
+
+                let claims = &refresh_token_details.claims;
+                if claims.get("client_id").and_then(|id| id.as_str())
+                    != Some(client.client_id.as_str())
+                {
+                    return Err(OAuthError::InvalidGrant {
+                        description: "Refresh token was issued to another client".to_string(),
+                    });
+                }
+
+                let scope = Scopes::parse(
+                    claims
+                        .get("scope")
+                        .and_then(|scope| scope.as_str())
+                        .unwrap_or_default(),
+                );
+
+                let user = self
+                    .repo
+                    .fetch_user_by_id(&UserId::new(refresh_token_details.user_id))
+                    .await?;
+                if user.is_session_revoked(refresh_token_details.issued_at) {
+                    return Err(OAuthError::InvalidGrant {
+                        description: "Refresh token no longer valid".to_string(),
+                    });
+                }
+
+                let context = AuthenticationContext::from_claims(claims);
+                self.issue_tokens(&user, &client, &scope, context.as_ref(), false)
+                    .await
+            }
+            TokenGrant::ClientCredentials { scope } => {
+                if client.client_type() != ClientType::Confidential {
+                    return Err(OAuthError::UnauthorizedClient {
+                        description:
+                            "Only confidential clients may use the client credentials grant"
+                                .to_string(),
+                    });
+                }
+
+                let scope = if scope.is_empty() {
+                    Scopes::parse(&client.allowed_scopes.join(" "))
+                } else {
+                    scope.clone()
+                };
+
+                if !client.allows_scopes(&scope) {
+                    return Err(OAuthError::InvalidScope {
+                        description: "Requested scope is not allowed for this client".to_string(),
+                    });
+                }
+
+                self.issue_client_token(&client, &scope).await
+            }
+            TokenGrant::DeviceCode { device_code } => {
+                self.poll_device_authorization(&client, device_code).await
+            }
+        }
+    }
+
+    async fn introspect(
+        &self,
+        request: &IntrospectionRequest,
+    ) -> Result<IntrospectionResponse, OAuthError> {
+        self.enforce_rate_limit("introspect", &request.client.client_id)
+            .await?;
+
+        let client = self.authenticate_client(&request.client).await?;
+        if client.client_type() != ClientType::Confidential {
+            return Err(OAuthError::UnauthorizedClient {
+                description: "Only confidential clients may introspect tokens".to_string(),
+            });
+        }
+
+        let Some((token_details, token_kind)) = self
+            .active_token(&request.token, request.token_type_hint.as_deref())
+            .await?
+        else {
+            return Ok(IntrospectionResponse::inactive());
+        };
+
+        let claim = |name: &str| {
+            token_details
+                .claims
+                .get(name)
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string())
+        };
+
+        Ok(IntrospectionResponse {
+            active: true,
+            scope: claim("scope"),
+            client_id: claim("client_id"),
+            token_type: (token_kind == TokenKind::Access).then(|| "Bearer".to_string()),
+            exp: token_details.expires_in,
+            sub: Some(token_details.user_id.to_string()),
+            aud: token_details.claims.get("aud").cloned(),
+            jti: Some(token_details.token_uuid.to_string()),
+            principal_type: Some(token_details.principal_type()),
+        })
+    }
+
+    async fn revoke(&self, request: &RevocationRequest) -> Result<(), OAuthError> {
+        self.enforce_rate_limit("revoke", &request.client.client_id)
+            .await?;
+
+        let client = self.authenticate_client(&request.client).await?;
+
+        let Some((token_details, _)) = self
+            .active_token(&request.token, request.token_type_hint.as_deref())
+            .await?
+        else {
+            return Ok(());
+        };
+
+        if token_details
+            .claims
+            .get("client_id")
+            .and_then(|id| id.as_str())
+            != Some(client.client_id.as_str())
+        {
+            return Err(OAuthError::UnauthorizedClient {
+                description: "Token was not issued to this client".to_string(),
+            });
+        }
+
+        self.cache
+            .delete_token(&TokenUuid::new(token_details.token_uuid))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed redis operation while revoking token"))?;
+        self.broadcast_revocation(SessionRevocation::token(
+            token_details.token_uuid,
+            self.access_token_expiry(token_details.expires_in),
+        ))
+        .await;
+
+        self.publish_event(
+            DomainEvent::new(DomainEventType::TokenRevoked, Some(token_details.user_id)).with_data(
+                serde_json::json!({
+                    "token_uuid": token_details.token_uuid,
+                    "client_id": client.client_id,
+                }),
+            ),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    async fn device_authorization(
+        &self,
+        request: &DeviceAuthorizationRequest,
+    ) -> Result<DeviceAuthorizationResponse, OAuthError> {
+        let client = self.authenticate_client(&request.client).await?;
+
+        let scope = if request.scope.is_empty() {
+            Scopes::parse(&client.allowed_scopes.join(" "))
+        } else {
+            request.scope.clone()
+        };
+
+        if !client.allows_scopes(&scope) {
+            return Err(OAuthError::InvalidScope {
+                description: "Requested scope is not allowed for this client".to_string(),
+            });
+        }
+
+        let authorization = DeviceAuthorization {
+            device_code: generate_random_token(),
+            user_code: generate_user_code(),
+            client_id: client.client_id,
+            scope: scope.to_string(),
+            status: DeviceAuthorizationStatus::Pending,
+            interval: self.config.oauth_device_poll_interval_seconds,
+            last_polled_at: None,
+            expires_at: chrono::Utc::now().timestamp()
+                + self.config.oauth_device_code_max_age_seconds,
+        };
+
+        self.cache
+            .save_device_authorization(&authorization)
+            .await
+            .map_err(|e| {
+                anyhow!(e).context("Failed redis operation while saving device authorization")
+            })?;
+
+        let verification_uri = self.device_verification_uri();
+        let user_code = format!(
+            "{}-{}",
+            &authorization.user_code[..4],
+            &authorization.user_code[4..]
+        );
+
+        Ok(DeviceAuthorizationResponse {
+            verification_uri_complete: append_query(
+                &verification_uri,
+                &[("user_code", &user_code)],
+            ),
+            verification_uri,
+            device_code: authorization.device_code,
+            user_code,
+            expires_in: self.config.oauth_device_code_max_age_seconds,
+            interval: authorization.interval,
+        })
+    }
+
+    async fn verify_device(
+        &self,
+        request: &DeviceVerificationRequest,
+    ) -> Result<DeviceVerificationResponse, OAuthError> {
+        self.enforce_rate_limit("device_verification", &request.user_id.to_string())
+            .await?;
+
+        let authorization = self
+            .cache
+            .fetch_device_authorization_by_user_code(&normalize_user_code(&request.user_code))
+            .await?;
+
+        let status = if request.approve {
+            DeviceAuthorizationStatus::Approved {
+                user_id: request.user_id,
+                context: request.context.clone(),
+            }
+        } else {
+            DeviceAuthorizationStatus::Denied
+        };
+
+        let decided = authorization.status == DeviceAuthorizationStatus::Pending
+            && self
+                .cache
+                .decide_device_authorization(&authorization, &status)
+                .await?;
+        if !decided {
+            return Err(OAuthError::InvalidGrant {
+                description: "User code has already been used".to_string(),
+            });
+        }
+
+        Ok(DeviceVerificationResponse {
+            client_id: authorization.client_id,
+            scope: authorization.scope,
+            approved: request.approve,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TokenKind {
+    Access,
+    Refresh,
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn fetch_client(&self, client_id: &str) -> Result<OAuthClient, OAuthError> {
+        self.repo
+            .fetch_oauth_client(&ClientId::new(client_id))
+            .await
+            .map_err(|e| match e {
+                AuthRepositoryError::InvalidCredentials { reason } => OAuthError::InvalidClient {
+                    description: reason,
+                },
+                e => OAuthError::from(e),
+            })
+    }
+
+    /// Authenticates the client calling the token endpoint.
+    ///
+    /// Confidential clients must present their secret, through either `client_secret_basic`
+    /// or `client_secret_post`, and it is checked against the stored Argon2 hash. Public
+    /// clients have no secret and are identified by their `client_id` alone.
+    async fn authenticate_client(
+        &self,
+        credentials: &ClientAuthentication,
+    ) -> Result<OAuthClient, OAuthError> {
+        let client = self.fetch_client(&credentials.client_id).await?;
+
+        match (client.client_type(), &credentials.client_secret) {
+            (ClientType::Confidential, Some(secret)) => {
+                let authenticated = client
+                    .client_secret_hash
+                    .as_deref()
+                    .is_some_and(|hash| is_valid(secret, hash));
+
+                if !authenticated {
+                    return Err(OAuthError::InvalidClient {
+                        description: "Client authentication failed".to_string(),
+                    });
+                }
+            }
+            (ClientType::Confidential, None) => {
+                return Err(OAuthError::InvalidClient {
+                    description: "Confidential clients must authenticate".to_string(),
+                })
+            }
+            (ClientType::Public, Some(_)) => {
+                return Err(OAuthError::InvalidClient {
+                    description: "Public clients cannot authenticate with a secret".to_string(),
+                })
+            }
+            (ClientType::Public, None) => {}
+        }
+
+        Ok(client)
+    }
+
+    /// Handles a device polling the token endpoint with its device code, per RFC 8628
+    /// section 3.5.
+    ///
+    /// Polling faster than the current interval answers `slow_down` and adds five seconds to
+    /// the interval. Once the user has decided, the authorization is taken from the cache, so
+    /// tokens are issued at most once.
+    async fn poll_device_authorization(
+        &self,
+        client: &OAuthClient,
+        device_code: &str,
+    ) -> Result<TokenResponse, OAuthError> {
+        let mut authorization = self
+            .cache
+            .fetch_device_authorization(device_code)
+            .await
+            .map_err(|e| match e {
+                CacheOperationError::Invalid { .. } => OAuthError::ExpiredToken {
+                    description: "Device code is invalid or has expired".to_string(),
+                },
+                e => OAuthError::from(e),
+            })?;
+
+        if authorization.client_id != client.client_id {
+            return Err(OAuthError::InvalidGrant {
+                description: "Device code was issued to another client".to_string(),
+            });
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let too_fast = authorization
+            .last_polled_at
+            .is_some_and(|last_polled_at| now - last_polled_at < authorization.interval);
+        if too_fast {
+            authorization.interval += 5;
+        }
+        authorization.last_polled_at = Some(now);
+
+        if too_fast || authorization.status == DeviceAuthorizationStatus::Pending {
+            self.cache
+                .update_device_polling(&authorization)
+                .await
+                .map_err(|e| {
+                    anyhow!(e).context("Failed redis operation while saving device authorization")
+                })?;
+
+            return Err(if too_fast {
+                OAuthError::SlowDown {
+                    description: format!(
+                        "Polling too fast, wait {} seconds between requests",
+                        authorization.interval
+                    ),
+                }
+            } else {
+                OAuthError::AuthorizationPending {
+                    description: "User has not yet approved the device".to_string(),
+                }
+            });
+        }
+
+        let authorization = self
+            .cache
+            .take_device_authorization(device_code)
+            .await
+            .map_err(|e| match e {
+                CacheOperationError::Invalid { .. } => OAuthError::ExpiredToken {
+                    description: "Device code is invalid or has expired".to_string(),
+                },
+                e => OAuthError::from(e),
+            })?;
+
+        match authorization.status {
+            DeviceAuthorizationStatus::Approved { user_id, context } => {
+                let user = self.repo.fetch_user_by_id(&UserId::new(user_id)).await?;
+
+                self.issue_tokens(
+                    &user,
+                    client,
+                    &Scopes::parse(&authorization.scope),
+                    context.as_ref(),
+                    true,
+                )
+                .await
+            }
+            DeviceAuthorizationStatus::Denied => Err(OAuthError::AccessDenied {
+                description: "User denied the device authorization".to_string(),
+            }),
+            DeviceAuthorizationStatus::Pending => Err(OAuthError::AuthorizationPending {
+                description: "User has not yet approved the device".to_string(),
+            }),
+        }
+    }
+
+    /// The page where users enter a user code: `OAUTH_DEVICE_VERIFICATION_URL` if set,
+    /// otherwise the service's own verification endpoint.
+    fn device_verification_uri(&self) -> String {
+        self.config
+            .oauth_device_verification_url
+            .clone()
+            .unwrap_or_else(|| {
+                format!(
+                    "{}/oauth/device",
+                    self.config.oidc_issuer.trim_end_matches('/')
+                )
+            })
+    }
+
+    /// Counts a request from `client_id` to a rate limited endpoint.
+    ///
+    /// The limit is keyed by the presented `client_id` rather than the authenticated client,
+    /// so repeated attempts with a wrong secret are throttled as well.
+    async fn enforce_rate_limit(&self, endpoint: &str, client_id: &str) -> Result<(), OAuthError> {
+        let count = self
+            .cache
+            .count_request(&format!("{}:{}", endpoint, client_id), 60)
+            .await?;
+
+        if count > self.config.oauth_rate_limit_per_minute {
+            return Err(OAuthError::TooManyRequests {
+                description: "Rate limit exceeded, try again later".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a token against the access and refresh token keys and checks that its session
+    /// is still active. The `token_type_hint` only changes the order in which keys are tried.
+    ///
+    /// # Returns
+    ///
+    /// The token details and kind, or `None` if the token is invalid, expired or revoked.
+    async fn active_token(
+        &self,
+        token: &str,
+        token_type_hint: Option<&str>,
+    ) -> Result<Option<(TokenDetails, TokenKind)>, OAuthError> {
+        let access = (TokenKind::Access, &self.config.access_token_public_key);
+        let refresh = (TokenKind::Refresh, &self.config.refresh_token_public_key);
+        let candidates = match token_type_hint {
+            Some("refresh_token") => [refresh, access],
+            _ => [access, refresh],
+        };
+
+        let Some((token_details, token_kind)) = candidates
+            .into_iter()
+            .find_map(|(kind, key)| verify_jwt(key, token).ok().map(|details| (details, kind)))
+        else {
+            return Ok(None);
+        };
+
+        match self.cache.verify_active_session(&token_details).await {
+            Ok(()) => Ok(Some((token_details, token_kind))),
+            Err(CacheOperationError::Invalid { .. }) => Ok(None),
+            Err(e) => Err(OAuthError::Unknown(
+                anyhow!(e).context("Failed redis operation while verifying token"),
+            )),
+        }
+    }
+
+    /// Issues an access token, and optionally a refresh token, bound to an OAuth client.
+    ///
+    /// The access token carries the usual custom claims from the claims pipeline plus the
+    /// granted `scope`, the `client_id` and the user's authentication context, and has the
+    /// client as its `aud`. The refresh token only carries the scope, client and context, so the token endpoint can check who is refreshing,
+    /// with which scope, and keep the context across refreshes.
+    async fn issue_tokens(
+        &self,
+        user: &User,
+        client: &OAuthClient,
+        scope: &Scopes,
+        context: Option<&AuthenticationContext>,
+        with_refresh_token: bool,
+    ) -> Result<TokenResponse, OAuthError> {
+        if user.is_suspended() {
+            return Err(OAuthError::InvalidGrant {
+                description: "The user account is suspended".to_string(),
+            });
+        }
+
+        let mut grant_claims = CustomClaims::default();
+        grant_claims.insert("scope", serde_json::json!(scope.to_string()));
+        grant_claims.insert("client_id", serde_json::json!(client.client_id));
+        if let Some(context) = context {
+            context.insert_into(&mut grant_claims);
+        }
+
+        let mut claims = self.claims.enrich(user)?;
+        for (name, value) in grant_claims.iter() {
+            claims.insert(name, value.clone());
+        }
+        claims.insert(AUDIENCE_CLAIM, serde_json::json!(client.client_id));
+
+        let access_token_details =
+            self.sign_access_token(user.id, self.config.access_token_max_age, claims)?;
+
+        let access_cache_token = CacheToken::new(
+            access_token_details.token_uuid,
+            access_token_details.user_id,
+            self.config.access_token_max_age,
+        );
+
+        let refresh_token = if with_refresh_token {
+            let refresh_token_details = generate_jwt_with_claims(
+                user.id,
+                self.config.refresh_token_max_age,
+                &self.config.refresh_token_private_key,
+                grant_claims,
+            )?;
+
+            self.cache
+                .save_tokens_data(
+                    &access_cache_token,
+                    &CacheToken::new(
+                        refresh_token_details.token_uuid,
+                        refresh_token_details.user_id,
+                        self.config.refresh_token_max_age,
+                    ),
+                )
+                .await
+                .map_err(|e| anyhow!(e).context("Failed redis operation while saving tokens"))?;
+
+            Some(
+                refresh_token_details
+                    .token
+                    .ok_or_else(|| anyhow!("Failed to generate refresh token"))?,
+            )
+        } else {
+            self.cache
+                .save_token_data(&access_cache_token)
+                .await
+                .map_err(|e| anyhow!(e).context("Failed redis operation while saving token"))?;
+            None
+        };
+
+        let access_token = access_token_details
+            .token
+            .ok_or_else(|| anyhow!("Failed to generate access token"))?;
+
+        Ok(TokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.config.access_token_max_age * 60,
+            refresh_token,
+            scope: scope.to_string(),
+            id_token: None,
+        })
+    }
+
+    /// Issues an access token for the client itself, as the result of a client credentials grant.
+    ///
+    /// The token's `sub` is the client's id, and it carries the granted `scope`, the `client_id`
+    /// as both `client_id` and `aud`, and a `principal_type` of `client`, so `auth` resolves it to the client rather than a user.
+    /// No refresh token is issued, as the client can always request a new access token.
+    async fn issue_client_token(
+        &self,
+        client: &OAuthClient,
+        scope: &Scopes,
+    ) -> Result<TokenResponse, OAuthError> {
+        let mut claims = CustomClaims::default();
+        claims.insert("scope", serde_json::json!(scope.to_string()));
+        claims.insert("client_id", serde_json::json!(client.client_id));
+        claims.insert(AUDIENCE_CLAIM, serde_json::json!(client.client_id));
+        claims.insert(
+            PRINCIPAL_TYPE_CLAIM,
+            serde_json::json!(PrincipalType::Client),
+        );
+
+        let access_token_details =
+            self.sign_access_token(client.id, self.config.access_token_max_age, claims)?;
+
+        self.cache
+            .save_token_data(&CacheToken::new(
+                access_token_details.token_uuid,
+                access_token_details.user_id,
+                self.config.access_token_max_age,
+            ))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed redis operation while saving token"))?;
+
+        let access_token = access_token_details
+            .token
+            .ok_or_else(|| anyhow!("Failed to generate access token"))?;
+
+        Ok(TokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.config.access_token_max_age * 60,
+            refresh_token: None,
+            scope: scope.to_string(),
+            id_token: None,
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::anyhow;
+
+use crate::{
+    api::utils::{jwk::public_jwk, jwt::generate_id_token},
+    domain::{
+        model::{
+            auth_middleware::AuthMiddleware,
+            authorize::AuthorizationCode,
+            id_token::IdTokenClaims,
+            jwks::JwkSet,
+            oauth_errors::OAuthError,
+            provider_metadata::ProviderMetadata,
+            scope::Scopes,
+            user::{FilteredUser, User},
+            userinfo::UserInfo,
+        },
+        oidc_service::OidcService,
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> OidcService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn userinfo(&self, auth: &AuthMiddleware) -> Result<UserInfo, OAuthError> {
+        let scope = Scopes::parse(
+            auth.claims
+                .get("scope")
+                .and_then(|scope| scope.as_str())
+                .unwrap_or_default(),
+        );
+
+        let user = auth
+            .user()
+            .filter(|_| scope.contains("openid"))
+            .ok_or_else(|| OAuthError::InsufficientScope {
+                description: "Access token was not granted the openid scope".to_string(),
+            })?;
+
+        Ok(UserInfo::new(&FilteredUser::from(user), &scope))
+    }
+
+    async fn provider_metadata(&self) -> ProviderMetadata {
+        ProviderMetadata::new(&self.config.oidc_issuer)
+    }
+
+    async fn jwks(&self) -> Result<JwkSet, OAuthError> {
+        let jwk = public_jwk(&self.config.access_token_public_key)
+            .map_err(|e| e.context("Failed to build JWKS"))?;
+
+        Ok(JwkSet { keys: vec![jwk] })
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    /// Issues an ID token for the user who approved `grant`, signed with the access token key.
+    ///
+    /// The token's audience is the client the code was issued to, and it echoes the `nonce`
+    /// from the authorization request along with the authentication context of the session.
+    pub(crate) fn issue_id_token(
+        &self,
+        user: &User,
+        grant: &AuthorizationCode,
+    ) -> Result<String, OAuthError> {
+        let kid = public_jwk(&self.config.access_token_public_key)
+            .map_err(|e| e.context("Failed to derive signing key id"))?
+            .kid;
+
+        let now = chrono::Utc::now();
+        let context = grant.context.as_ref();
+
+        let claims = IdTokenClaims {
+            iss: self.config.oidc_issuer.trim_end_matches('/').to_string(),
+            sub: user.id.to_string(),
+            aud: grant.client_id.to_string(),
+            exp: (now + chrono::Duration::minutes(self.config.access_token_max_age)).timestamp(),
+            iat: now.timestamp(),
+            auth_time: context.map(|c| c.auth_time),
+            nonce: grant.nonce.clone(),
+            amr: context.map(|c| c.amr.clone()),
+            acr: context.map(|c| c.acr.clone()),
+            email: user.email.to_string(),
+            email_verified: user.email_verified,
+        };
+
+        generate_id_token(&claims, &self.config.access_token_private_key, &kid)
+            .map_err(|e| OAuthError::Unknown(anyhow!(e).context("Failed to sign ID token")))
+    }
+}
@@ -0,0 +1,222 @@
+use anyhow::anyhow;
+use chrono::{Duration, Utc};
+
+use crate::{
+    api::utils::security::{generate_random_token, hash_token},
+    domain::{
+        model::{
+            auth_repo_errors::AuthRepositoryError,
+            authentication_context::AuthenticationContext,
+            login_response::LoginResponse,
+            organization::{
+                AcceptInvitationRequest, CreateInvitationResponse, CreateOrganizationRequest,
+                InviteMemberRequest, NewInvitation, NewOrganization, Organization,
+                OrganizationError, OrganizationMember, OrganizationMembersRequest,
+                OrganizationMembership, OrganizationRole, RemoveMemberRequest,
+                SwitchOrganizationRequest, INVITATION_MAX_AGE_DAYS,
+            },
+            session_revocation::SessionRevocation,
+            token_uuid::TokenUuid,
+            user_email::UserEmail,
+            user_id::UserId,
+        },
+        organization_service::OrganizationService,
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+/// Longest name an organization can be given, matching the `name` column.
+const MAX_NAME_LENGTH: usize = 100;
+
+impl<R, C, A, P> OrganizationService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn create_organization(
+        &self,
+        request: &CreateOrganizationRequest,
+    ) -> Result<Organization, OrganizationError> {
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(OrganizationError::InvalidRequest {
+                reason: format!("name must be between 1 and {} characters", MAX_NAME_LENGTH),
+            });
+        }
+
+        if !request.is_valid_slug() {
+            return Err(OrganizationError::InvalidRequest {
+                reason: "slug must be 3 to 63 lowercase letters, digits or hyphens, starting with a letter"
+                    .to_string(),
+            });
+        }
+
+        self.repo
+            .create_organization(&NewOrganization {
+                name: name.to_string(),
+                slug: request.slug.clone(),
+                owner_id: *request.user_id.get(),
+            })
+            .await
+            .map_err(|e| match e {
+                AuthRepositoryError::Conflict { .. } => OrganizationError::Duplicate {
+                    slug: request.slug.clone(),
+                },
+                e => e.into(),
+            })
+    }
+
+    async fn list_organizations(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<OrganizationMembership>, OrganizationError> {
+        Ok(self.repo.list_memberships(user_id).await?)
+    }
+
+    async fn list_members(
+        &self,
+        request: &OrganizationMembersRequest,
+    ) -> Result<Vec<OrganizationMember>, OrganizationError> {
+        self.repo
+            .fetch_membership(&request.actor_id, &request.organization_id)
+            .await?;
+
+        Ok(self
+            .repo
+            .list_organization_members(&request.organization_id)
+            .await?)
+    }
+
+    async fn invite_member(
+        &self,
+        request: &InviteMemberRequest,
+    ) -> Result<CreateInvitationResponse, OrganizationError> {
+        let email =
+            UserEmail::new(&request.email).map_err(|e| OrganizationError::InvalidRequest {
+                reason: e.to_string(),
+            })?;
+
+        let actor = self
+            .repo
+            .fetch_membership(&request.actor_id, &request.organization_id)
+            .await?;
+        if !actor.role.can_manage(request.role) {
+            return Err(OrganizationError::Forbidden {
+                reason: format!(
+                    "the {} role cannot invite members as {}",
+                    actor.role.as_str(),
+                    request.role.as_str()
+                ),
+            });
+        }
+
+        let token = generate_random_token();
+        let invitation = self
+            .repo
+            .create_invitation(&NewInvitation {
+                organization_id: request.organization_id,
+                email: email.get().to_lowercase(),
+                role: request.role,
+                token_hash: hash_token(&token),
+                invited_by: *request.actor_id.get(),
+                expires_at: Utc::now() + Duration::days(INVITATION_MAX_AGE_DAYS),
+            })
+            .await?;
+
+        Ok(CreateInvitationResponse { token, invitation })
+    }
+
+    async fn accept_invitation(
+        &self,
+        request: &AcceptInvitationRequest,
+    ) -> Result<OrganizationMembership, OrganizationError> {
+        Ok(self
+            .repo
+            .accept_invitation(
+                &hash_token(&request.token),
+                &request.user_id,
+                &request.email.to_lowercase(),
+            )
+            .await?)
+    }
+
+    async fn remove_member(&self, request: &RemoveMemberRequest) -> Result<(), OrganizationError> {
+        let actor = self
+            .repo
+            .fetch_membership(&request.actor_id, &request.organization_id)
+            .await?;
+        let is_leaving = request.actor_id.get() == request.user_id.get();
+        let member = if is_leaving {
+            actor.clone()
+        } else {
+            self.repo
+                .fetch_membership(&request.user_id, &request.organization_id)
+                .await?
+        };
+
+        if !is_leaving && !actor.role.can_manage(member.role) {
+            return Err(OrganizationError::Forbidden {
+                reason: format!(
+                    "the {} role cannot remove a member who is {}",
+                    actor.role.as_str(),
+                    member.role.as_str()
+                ),
+            });
+        }
+
+        if member.role == OrganizationRole::Owner {
+            let owners = self
+                .repo
+                .list_organization_members(&request.organization_id)
+                .await?
+                .iter()
+                .filter(|member| member.role == OrganizationRole::Owner)
+                .count();
+            if owners <= 1 {
+                return Err(OrganizationError::Forbidden {
+                    reason: "an organization must keep at least one owner".to_string(),
+                });
+            }
+        }
+
+        Ok(self
+            .repo
+            .delete_membership(&request.organization_id, &request.user_id)
+            .await?)
+    }
+
+    async fn switch_organization(
+        &self,
+        request: &SwitchOrganizationRequest,
+    ) -> Result<LoginResponse, OrganizationError> {
+        let membership = self
+            .repo
+            .fetch_membership(&request.user_id, &request.organization_id)
+            .await?;
+        let user = self.repo.fetch_user_by_id(&request.user_id).await?;
+
+        let context = AuthenticationContext::from_claims(&request.claims)
+            .unwrap_or_else(AuthenticationContext::password);
+        let response = self
+            .issue_session_tokens(&user, &context, Some(&membership))
+            .await?;
+
+        self.cache
+            .delete_token(&TokenUuid::new(request.access_token_uuid))
+            .await
+            .map_err(|e| anyhow!(e).context("Failed to revoke the previous access token"))?;
+        self.broadcast_revocation(SessionRevocation::token(
+            request.access_token_uuid,
+            self.access_token_expiry(None),
+        ))
+        .await;
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::anyhow;
+
+use crate::{
+    api::utils::security::generate_random_token,
+    domain::{
+        model::{
+            auth::AuthorizationError,
+            auth_middleware::AuthMiddleware,
+            personal_access_token::{
+                CreatePersonalAccessTokenRequest, CreatePersonalAccessTokenResponse,
+                NewPersonalAccessToken, PersonalAccessToken, PersonalAccessTokenError,
+                PersonalAccessTokenSecret, RevokePersonalAccessTokenRequest,
+                PERSONAL_ACCESS_TOKEN_PREFIX,
+            },
+            principal::Principal,
+            user_id::UserId,
+        },
+        personal_access_token_service::PersonalAccessTokenService,
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+/// Longest name a token can be given, matching the `name` column.
+const MAX_NAME_LENGTH: usize = 100;
+
+impl<R, C, A, P> PersonalAccessTokenService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn create_personal_access_token(
+        &self,
+        request: &CreatePersonalAccessTokenRequest,
+    ) -> Result<CreatePersonalAccessTokenResponse, PersonalAccessTokenError> {
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(PersonalAccessTokenError::InvalidRequest {
+                reason: format!("name must be between 1 and {} characters", MAX_NAME_LENGTH),
+            });
+        }
+
+        if !request
+            .scopes
+            .is_subset_of(&self.config.personal_access_token_scopes)
+        {
+            return Err(PersonalAccessTokenError::InvalidRequest {
+                reason: format!(
+                    "scope must be a subset of {}",
+                    self.config.personal_access_token_scopes.join(" ")
+                ),
+            });
+        }
+
+        let secret = PersonalAccessTokenSecret::new(&format!(
+            "{}{}",
+            PERSONAL_ACCESS_TOKEN_PREFIX,
+            generate_random_token()
+        ));
+
+        let details = self
+            .repo
+            .create_personal_access_token(&NewPersonalAccessToken {
+                user_id: *request.user_id.get(),
+                name: name.to_string(),
+                token_prefix: secret.visible_prefix(),
+                token_hash: secret.hash(),
+                scopes: request.scopes.get().to_vec(),
+                expires_at: request.expires_at,
+            })
+            .await?;
+
+        Ok(CreatePersonalAccessTokenResponse {
+            token: secret.get().to_string(),
+            details,
+        })
+    }
+
+    async fn list_personal_access_tokens(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<PersonalAccessToken>, PersonalAccessTokenError> {
+        Ok(self.repo.list_personal_access_tokens(user_id).await?)
+    }
+
+    async fn revoke_personal_access_token(
+        &self,
+        request: &RevokePersonalAccessTokenRequest,
+    ) -> Result<(), PersonalAccessTokenError> {
+        Ok(self
+            .repo
+            .delete_personal_access_token(&request.user_id, &request.token_id)
+            .await?)
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    /// Resolves a personal access token to its owner, for `AuthService::auth`.
+    ///
+    /// The claims are the owner's custom claims plus the token's `scope`, exactly as an OAuth
+    /// access token would carry them, so scope checks apply to both kinds of token alike.
+    pub(crate) async fn personal_access_token_auth(
+        &self,
+        secret: &PersonalAccessTokenSecret,
+    ) -> Result<AuthMiddleware, AuthorizationError> {
+        let token = self.repo.use_personal_access_token(&secret.hash()).await?;
+        let user = self
+            .repo
+            .fetch_user_by_id(&UserId::new(token.user_id))
+            .await?;
+        if user.is_suspended() {
+            return Err(AuthorizationError::InvalidCredentials {
+                reason: "The owner of this personal access token is suspended".to_string(),
+            });
+        }
+
+        let mut claims = self
+            .claims
+            .enrich(&user)
+            .map_err(|e| anyhow!(e).context("Failed to build personal access token claims"))?;
+        claims.insert("scope", serde_json::json!(token.scopes.join(" ")));
+
+        Ok(AuthMiddleware::new(Principal::User(user), token.id, claims))
+    }
+}
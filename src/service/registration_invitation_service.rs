@@ -0,0 +1,81 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    api::utils::security::generate_random_token,
+    domain::{
+        model::{
+            auth_repo_errors::AuthRepositoryError,
+            registration::{
+                CreateRegistrationInvitationRequest, CreateRegistrationInvitationResponse,
+                NewRegistrationInvitation, RegistrationInvitation, RegistrationInvitationError,
+                RegistrationInvitationToken, REGISTRATION_INVITATION_MAX_AGE_DAYS,
+            },
+            user_email::UserEmail,
+        },
+        registration_invitation_service::RegistrationInvitationService,
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> RegistrationInvitationService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn create_registration_invitation(
+        &self,
+        request: &CreateRegistrationInvitationRequest,
+    ) -> Result<CreateRegistrationInvitationResponse, RegistrationInvitationError> {
+        let email = UserEmail::new(&request.email)
+            .map_err(|e| RegistrationInvitationError::InvalidRequest {
+                reason: e.to_string(),
+            })?
+            .get()
+            .to_ascii_lowercase();
+
+        let token = generate_random_token();
+
+        let invitation = self
+            .repo
+            .create_registration_invitation(&NewRegistrationInvitation {
+                id: uuid::Uuid::new_v4(),
+                email,
+                organization_id: request.organization_id,
+                role: request.role,
+                token_hash: RegistrationInvitationToken::new(&token).hash(),
+                created_by: *request.created_by.get(),
+                expires_at: Utc::now() + Duration::days(REGISTRATION_INVITATION_MAX_AGE_DAYS),
+            })
+            .await
+            .map_err(|e| match e {
+                AuthRepositoryError::InvalidCredentials { reason } => {
+                    RegistrationInvitationError::InvalidRequest { reason }
+                }
+                e => e.into(),
+            })?;
+
+        Ok(CreateRegistrationInvitationResponse { token, invitation })
+    }
+
+    async fn list_registration_invitations(
+        &self,
+    ) -> Result<Vec<RegistrationInvitation>, RegistrationInvitationError> {
+        Ok(self.repo.list_registration_invitations().await?)
+    }
+
+    async fn revoke_registration_invitation(
+        &self,
+        invitation_id: &uuid::Uuid,
+    ) -> Result<(), RegistrationInvitationError> {
+        Ok(self
+            .repo
+            .delete_registration_invitation(invitation_id)
+            .await?)
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::anyhow;
+
+use crate::{
+    api::utils::{
+        saml::{authn_request_url, sp_metadata, validate_response, ExpectedResponse},
+        security::{generate_random_token, hash_token},
+    },
+    domain::{
+        model::{
+            auth_repo_errors::AuthRepositoryError,
+            authentication_context::AuthenticationContext,
+            federation::{
+                FederatedCallbackResponse, FederatedIdentity, FederatedLoginRequest,
+                FederatedLoginResponse, FederationError,
+            },
+            saml::{SamlAssertionRequest, SamlIdentityProvider, SamlRequestState},
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+        saml_service::SamlService,
+    },
+    service::{
+        auth_service::Service,
+        federation_service::{check_state_digest, is_local_path},
+    },
+};
+
+impl<R, C, A, P> SamlService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn saml_metadata(&self, provider: &str) -> Result<String, FederationError> {
+        let provider = self.saml_identity_provider(provider)?;
+
+        Ok(sp_metadata(
+            &self.saml_entity_id(provider),
+            &self.saml_acs_url(provider),
+        ))
+    }
+
+    async fn saml_login(
+        &self,
+        request: &FederatedLoginRequest,
+    ) -> Result<FederatedLoginResponse, FederationError> {
+        let provider = self.saml_identity_provider(&request.provider)?;
+
+        if let Some(return_to) = &request.return_to {
+            if !is_local_path(return_to) {
+                return Err(FederationError::InvalidRequest {
+                    reason: "return_to must be a path on this service".to_string(),
+                });
+            }
+        }
+
+        // IDs must be XML names, which cannot start with the digits a random token may.
+        let state = SamlRequestState {
+            relay_state: generate_random_token(),
+            provider: provider.name.clone(),
+            request_id: format!("_{}", generate_random_token()),
+            return_to: request.return_to.clone(),
+            max_age: self.config.federation_state_max_age_seconds,
+        };
+
+        let entity_id = self.saml_entity_id(provider);
+        let acs_url = self.saml_acs_url(provider);
+        let authorization_url = authn_request_url(
+            provider,
+            &ExpectedResponse {
+                entity_id: &entity_id,
+                acs_url: &acs_url,
+                request_id: &state.request_id,
+            },
+            &state.relay_state,
+        )?;
+
+        self.cache
+            .save_saml_request(&state)
+            .await
+            .map_err(|e| anyhow!(e).context("Failed redis operation while saving SAML request"))?;
+
+        Ok(FederatedLoginResponse {
+            authorization_url,
+            state_digest: hash_token(&state.relay_state),
+            state_max_age: state.max_age,
+        })
+    }
+
+    async fn saml_assertion(
+        &self,
+        request: &SamlAssertionRequest,
+    ) -> Result<FederatedCallbackResponse, FederationError> {
+        let provider = self.saml_identity_provider(&request.provider)?;
+
+        let relay_state =
+            request
+                .relay_state
+                .as_deref()
+                .ok_or_else(|| FederationError::InvalidRequest {
+                    reason: "Missing RelayState, unsolicited responses are not accepted"
+                        .to_string(),
+                })?;
+
+        check_state_digest(relay_state, request.state_digest.as_deref())?;
+        let state = self.cache.take_saml_request(relay_state).await?;
+        if state.provider != provider.name {
+            return Err(FederationError::Rejected {
+                reason: "SAML request was issued for another identity provider".to_string(),
+            });
+        }
+
+        let entity_id = self.saml_entity_id(provider);
+        let acs_url = self.saml_acs_url(provider);
+        let assertion = validate_response(
+            provider,
+            &request.saml_response,
+            &ExpectedResponse {
+                entity_id: &entity_id,
+                acs_url: &acs_url,
+                request_id: &state.request_id,
+            },
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| FederationError::Rejected {
+            reason: format!("{:#}", e),
+        })?;
+
+        self.cache
+            .record_saml_assertion(&assertion.id, assertion.expires_at)
+            .await?;
+
+        let email = match &provider.email_attribute {
+            Some(attribute) => assertion
+                .attributes
+                .get(attribute)
+                .and_then(|values| values.first())
+                .cloned(),
+            None => Some(assertion.name_id.clone()),
+        };
+
+        let identity = FederatedIdentity {
+            provider: format!("saml:{}", provider.name),
+            subject: assertion.name_id,
+            email,
+            email_verified: provider.trust_email,
+            amr: vec![],
+        };
+
+        let user = match self.repo.fetch_user_by_identity(&identity).await {
+            Ok(user) => user,
+            Err(AuthRepositoryError::InvalidCredentials { .. }) => {
+                let may_register = self.may_register_identity(&identity);
+                self.repo.link_identity(&identity, may_register).await?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let login = self
+            .issue_login_tokens(&user, &AuthenticationContext::federated(&identity.amr))
+            .await?;
+
+        Ok(FederatedCallbackResponse {
+            login,
+            return_to: state.return_to,
+        })
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    fn saml_identity_provider(&self, name: &str) -> Result<&SamlIdentityProvider, FederationError> {
+        self.config
+            .saml_identity_providers
+            .iter()
+            .find(|provider| provider.name == name)
+            .ok_or_else(|| FederationError::UnknownProvider {
+                provider: name.to_string(),
+            })
+    }
+
+    /// Our entity ID towards `provider`, which is also the URL of our metadata for it.
+    fn saml_entity_id(&self, provider: &SamlIdentityProvider) -> String {
+        format!(
+            "{}/api/saml/{}/metadata",
+            self.config.oidc_issuer.trim_end_matches('/'),
+            provider.name
+        )
+    }
+
+    fn saml_acs_url(&self, provider: &SamlIdentityProvider) -> String {
+        format!(
+            "{}/api/saml/{}/acs",
+            self.config.oidc_issuer.trim_end_matches('/'),
+            provider.name
+        )
+    }
+}
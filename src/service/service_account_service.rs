@@ -0,0 +1,176 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    domain::{
+        model::{
+            auth_repo_errors::AuthRepositoryError,
+            personal_access_token::{
+                CreatePersonalAccessTokenRequest, CreatePersonalAccessTokenResponse,
+                PersonalAccessToken, RevokePersonalAccessTokenRequest,
+            },
+            scope::Scopes,
+            service_account::{
+                CreateServiceAccountRequest, NewServiceAccount, RotateServiceAccountKeyRequest,
+                ServiceAccountError,
+            },
+            user::{FilteredUser, User},
+            user_id::UserId,
+        },
+        personal_access_token_service::PersonalAccessTokenService,
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+        service_account_service::ServiceAccountService,
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> ServiceAccountService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn create_service_account(
+        &self,
+        request: &CreateServiceAccountRequest,
+    ) -> Result<FilteredUser, ServiceAccountError> {
+        if !request.is_valid_name() {
+            return Err(ServiceAccountError::InvalidRequest {
+                reason: "name must be 3 to 63 lowercase letters, digits or hyphens, starting with a letter"
+                    .to_string(),
+            });
+        }
+
+        let owner = match self.repo.fetch_user_by_id(&request.owner_id).await {
+            Ok(owner) if !owner.is_service_account() => owner,
+            Ok(_) | Err(AuthRepositoryError::InvalidCredentials { .. }) => {
+                return Err(ServiceAccountError::InvalidRequest {
+                    reason: "owner must be an existing human user".to_string(),
+                })
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let account = self
+            .repo
+            .create_service_account(&NewServiceAccount {
+                email: request.email(),
+                owner_id: owner.id,
+            })
+            .await?;
+
+        Ok(FilteredUser::from(&account))
+    }
+
+    async fn list_service_accounts(&self) -> Result<Vec<FilteredUser>, ServiceAccountError> {
+        Ok(self
+            .repo
+            .list_service_accounts()
+            .await?
+            .iter()
+            .map(FilteredUser::from)
+            .collect())
+    }
+
+    async fn delete_service_account(&self, account_id: &UserId) -> Result<(), ServiceAccountError> {
+        Ok(self.repo.delete_service_account(account_id).await?)
+    }
+
+    async fn create_service_account_key(
+        &self,
+        request: &CreatePersonalAccessTokenRequest,
+    ) -> Result<CreatePersonalAccessTokenResponse, ServiceAccountError> {
+        self.service_account(&request.user_id).await?;
+
+        Ok(self.create_personal_access_token(request).await?)
+    }
+
+    async fn list_service_account_keys(
+        &self,
+        account_id: &UserId,
+    ) -> Result<Vec<PersonalAccessToken>, ServiceAccountError> {
+        self.service_account(account_id).await?;
+
+        Ok(self.list_personal_access_tokens(account_id).await?)
+    }
+
+    async fn revoke_service_account_key(
+        &self,
+        request: &RevokePersonalAccessTokenRequest,
+    ) -> Result<(), ServiceAccountError> {
+        self.service_account(&request.user_id).await?;
+
+        Ok(self.revoke_personal_access_token(request).await?)
+    }
+
+    async fn rotate_service_account_key(
+        &self,
+        request: &RotateServiceAccountKeyRequest,
+    ) -> Result<CreatePersonalAccessTokenResponse, ServiceAccountError> {
+        let grace_period = Some(request.grace_period_seconds)
+            .filter(|seconds| *seconds >= 0)
+            .and_then(Duration::try_seconds)
+            .ok_or_else(|| ServiceAccountError::InvalidRequest {
+                reason: "grace_period_seconds must be a non-negative number of seconds".to_string(),
+            })?;
+
+        self.service_account(&request.service_account_id).await?;
+
+        let old_key = self
+            .repo
+            .list_personal_access_tokens(&request.service_account_id)
+            .await?
+            .into_iter()
+            .find(|key| key.id == request.key_id)
+            .ok_or(ServiceAccountError::NotFound)?;
+
+        // The replacement keeps the old key's name, scopes and lifetime.
+        let lifetime = old_key
+            .expires_at
+            .zip(old_key.created_at)
+            .map(|(expires_at, created_at)| expires_at - created_at);
+        let new_key = self
+            .create_personal_access_token(&CreatePersonalAccessTokenRequest {
+                user_id: UserId::new(*request.service_account_id.get()),
+                name: old_key.name.clone(),
+                scopes: Scopes::parse(&old_key.scopes.join(" ")),
+                expires_at: lifetime.map(|lifetime| Utc::now() + lifetime),
+            })
+            .await?;
+
+        if grace_period.is_zero() {
+            self.repo
+                .delete_personal_access_token(&request.service_account_id, &old_key.id)
+                .await?;
+        } else {
+            self.repo
+                .expire_personal_access_token(
+                    &request.service_account_id,
+                    &old_key.id,
+                    &(Utc::now() + grace_period),
+                )
+                .await?;
+        }
+
+        Ok(new_key)
+    }
+}
+
+impl<R, C, A, P> Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn service_account(&self, account_id: &UserId) -> Result<User, ServiceAccountError> {
+        let account = self.repo.fetch_user_by_id(account_id).await?;
+        if !account.is_service_account() {
+            return Err(ServiceAccountError::NotFound);
+        }
+        Ok(account)
+    }
+}
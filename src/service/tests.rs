@@ -1,25 +1,114 @@
 #[cfg(test)]
 mod test {
+    use base64::{engine::general_purpose, Engine};
     use dotenv::dotenv;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
 
     use crate::{
-        api::utils::{jwt::generate_jwt, security::hash_password},
+        api::utils::{
+            jwt::{generate_jwt, generate_jwt_with_claims, verify_jwt},
+            pkce::code_challenge,
+            security::{hash_password, hash_token},
+            webhook_client::{sign_payload, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+        },
+        claims::pipeline::ClaimsPipeline,
         domain::{
+            admin_user_service::AdminUserService,
+            audit_service::AuditService,
             auth_service::AuthService,
+            federation_service::FederationService,
+            forward_auth_service::ForwardAuthService,
+            impersonation_service::ImpersonationService,
             model::{
-                auth::AuthRequest,
-                login_user::LoginUserRequest,
+                admin_audit::AdminAuditEntry,
+                admin_user::{
+                    AdminUserError, DeleteUserRequest, ListUsersRequest, UpdateUserRequest,
+                },
+                audit::{
+                    AuditCheckpoint, AuditError, AuditEvent, AuditEventType, AuditOutcome,
+                    BrokenAuditLink, ListAuditEventsRequest, RequestContext,
+                },
+                auth::{AuthRequest, AuthorizationError},
+                auth_middleware::AuthMiddleware,
+                authentication_context::AuthenticationContext,
+                authorize::{AuthorizationCode, AuthorizeRequest, AuthorizeSession},
+                change_password::{ChangePasswordError, ChangePasswordRequest},
+                custom_claims::{CustomClaims, PRINCIPAL_TYPE_CLAIM},
+                device_authorization::{
+                    DeviceAuthorization, DeviceAuthorizationRequest, DeviceAuthorizationStatus,
+                    DeviceVerificationRequest,
+                },
+                domain_event::DomainEventType,
+                federation::{
+                    FederatedCallbackRequest, FederatedLoginRequest, FederatedProvider,
+                    FederationError,
+                },
+                forward_auth::ForwardAuthIdentity,
+                id_token::IdTokenClaims,
+                impersonation::{
+                    Actor, ImpersonationError, StartImpersonationRequest,
+                    IMPERSONATION_MAX_AGE_MINUTES,
+                },
+                introspection::IntrospectionRequest,
+                ldap::{LdapConfig, LdapMode},
+                login_user::{LoginUserError, LoginUserRequest},
                 logout::LogoutRequest,
-                refresh_token::RefreshRequest,
-                register_user::{HashedUserPassword, RegisterUserRequest},
+                oauth_client::{ClientAuthentication, ClientType, OAuthClient},
+                oauth_errors::OAuthError,
+                oauth_token::{TokenGrant, TokenRequest},
+                organization::{
+                    active_organization, CreateOrganizationRequest, InviteMemberRequest,
+                    OrganizationError, OrganizationMembersRequest, OrganizationMembership,
+                    OrganizationRole, RemoveMemberRequest, SwitchOrganizationRequest,
+                    ORG_ROLE_CLAIM,
+                },
+                personal_access_token::{
+                    CreatePersonalAccessTokenRequest, PersonalAccessToken, PersonalAccessTokenError,
+                },
+                principal::{Principal, PrincipalType},
+                refresh_token::{RefreshRequest, RefreshTokenError},
+                register_user::{HashedUserPassword, RegisterUserError, RegisterUserRequest},
+                registration::{
+                    CreateRegistrationInvitationRequest, RegistrationInvitationToken,
+                    RegistrationMode,
+                },
+                revocation::RevocationRequest,
+                saml::{SamlAssertionRequest, SamlIdentityProvider},
+                scope::Scopes,
+                service_account::{
+                    CreateServiceAccountRequest, RotateServiceAccountKeyRequest,
+                    ServiceAccountError,
+                },
+                session_revocation::{session_id, SESSION_ID_CLAIM},
+                user::{User, UserKind, UserStatus},
                 user_email::UserEmail,
+                user_id::UserId,
                 user_password::UserPassword,
+                webhook::{
+                    CreateWebhookRequest, PendingWebhookDelivery, WebhookAttemptOutcome,
+                    WebhookError, WebhookEventType,
+                },
             },
+            oauth_service::OAuthService,
+            oidc_service::OidcService,
+            organization_service::OrganizationService,
+            personal_access_token_service::PersonalAccessTokenService,
+            registration_invitation_service::RegistrationInvitationService,
+            saml_service::SamlService,
+            service_account_service::ServiceAccountService,
+            webhook_service::WebhookService,
         },
         helper::config::Config,
-        repositories::test_helpers::{
-            mock_auth_repository::test_helpers::MockAuthRepository,
-            mock_cache_repository::test_helpers::MockCacheRepository,
+        repositories::{
+            event_publisher::InMemoryEventPublisher,
+            test_helpers::{
+                mock_audit_sink::test_helpers::MockAuditSink,
+                mock_auth_repository::test_helpers::{
+                    MockAuthRepository, TEST_CLIENT_ID, TEST_REDIRECT_URI,
+                },
+                mock_cache_repository::test_helpers::MockCacheRepository,
+            },
         },
         service::auth_service::Service,
     };
@@ -37,6 +126,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -63,6 +155,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -90,6 +185,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -104,6 +202,40 @@ mod test {
         assert!(!result.access_token.is_empty())
     }
 
+    #[tokio::test]
+    async fn test_login_embeds_custom_claims() {
+        let email = "adrian@email.com";
+        let password = "password";
+        let hashed_password = hash_password(password).unwrap();
+
+        let repo = MockAuthRepository::success(email, &hashed_password);
+        let cache = MockCacheRepository::success();
+        dotenv().ok();
+        let config = Config::init();
+        let public_key = config.access_token_public_key.clone();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .login(&LoginUserRequest::new(
+                UserEmail::new(email).unwrap(),
+                UserPassword::new(password).unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let details = verify_jwt(&public_key, &result.access_token).unwrap();
+
+        assert_eq!(details.claims.get("email"), Some(&serde_json::json!(email)));
+    }
+
     #[tokio::test]
     async fn test_login_invalid_password_failure() {
         let email = "adrian@email.com";
@@ -117,6 +249,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -143,6 +278,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -169,6 +307,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -202,6 +343,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -225,6 +369,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -232,7 +379,34 @@ mod test {
             .auth(&AuthRequest::new("Invalid token".to_string()))
             .await;
 
-        assert!(result.is_err())
+        assert!(matches!(
+            result,
+            Err(AuthorizationError::InvalidCredentials { .. })
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_auth_expired_token_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let access_token_details =
+            generate_jwt(uuid::Uuid::new_v4(), -5, &config.access_token_private_key).unwrap();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .auth(&AuthRequest::new(access_token_details.token.unwrap()))
+            .await;
+
+        assert!(matches!(result, Err(AuthorizationError::TokenExpired)))
     }
 
     #[tokio::test]
@@ -253,6 +427,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -283,6 +460,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -293,6 +473,111 @@ mod test {
         assert!(result.is_err())
     }
 
+    #[tokio::test]
+    async fn test_forward_auth_success() {
+        dotenv().ok();
+        let mut config = Config::init();
+        config.forward_auth_user_id_header = "x-auth-subject".to_string();
+
+        let access_token_details = generate_jwt(
+            uuid::Uuid::new_v4(),
+            config.access_token_max_age,
+            &config.access_token_private_key,
+        )
+        .unwrap();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let response = state
+            .forward_auth(&AuthRequest::new(access_token_details.token.unwrap()))
+            .await
+            .unwrap();
+
+        let header = |name: &str| {
+            response
+                .headers
+                .iter()
+                .find(|(header, _)| header == name)
+                .map(|(_, value)| value.clone())
+        };
+        assert!(header("x-auth-subject").is_some());
+        assert_eq!(header("x-user-email"), Some("adrian@email.com".to_string()));
+        assert!(header("x-user-roles").is_some());
+        assert_eq!(header("x-user-id"), None);
+    }
+
+    #[tokio::test]
+    async fn test_forward_auth_cached_identity() {
+        dotenv().ok();
+        let mut config = Config::init();
+        config.forward_auth_cache_seconds = 60;
+
+        let access_token_details = generate_jwt(
+            uuid::Uuid::new_v4(),
+            config.access_token_max_age,
+            &config.access_token_private_key,
+        )
+        .unwrap();
+        let identity = ForwardAuthIdentity {
+            user_id: uuid::Uuid::new_v4(),
+            email: Some("cached@email.com".to_string()),
+            roles: vec!["admin".to_string(), "user".to_string()],
+        };
+
+        // The repository fails, so only a cached identity can authenticate the request.
+        let state = Service {
+            repo: MockAuthRepository::failure(),
+            cache: MockCacheRepository::success().with_forward_auth(identity.clone()),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let response = state
+            .forward_auth(&AuthRequest::new(access_token_details.token.unwrap()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.headers,
+            vec![
+                ("x-user-id".to_string(), identity.user_id.to_string()),
+                ("x-user-email".to_string(), "cached@email.com".to_string()),
+                ("x-user-roles".to_string(), "admin,user".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forward_auth_invalid_token_failure() {
+        dotenv().ok();
+        let mut config = Config::init();
+        config.forward_auth_cache_seconds = 60;
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .forward_auth(&AuthRequest::new("Invalid token".to_string()))
+            .await;
+
+        assert!(result.is_err())
+    }
+
     #[tokio::test]
     async fn test_logout_success() {
         let email = "adrian@email.com";
@@ -306,6 +591,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -329,6 +617,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -350,6 +641,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -377,6 +671,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -402,6 +699,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -412,6 +712,63 @@ mod test {
         assert!(result.is_err())
     }
 
+    #[tokio::test]
+    async fn test_refresh_token_expired_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let token = generate_jwt(uuid::Uuid::new_v4(), -5, &config.refresh_token_private_key);
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .refresh(&RefreshRequest::new(token.unwrap().token.unwrap()))
+            .await;
+
+        assert!(matches!(result, Err(RefreshTokenError::TokenExpired)))
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_oauth_grant_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let mut claims = CustomClaims::default();
+        claims.insert("client_id", serde_json::json!(TEST_CLIENT_ID));
+        claims.insert("scope", serde_json::json!("openid"));
+        let token = generate_jwt_with_claims(
+            uuid::Uuid::new_v4(),
+            10,
+            &config.refresh_token_private_key,
+            claims,
+        );
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .refresh(&RefreshRequest::new(token.unwrap().token.unwrap()))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RefreshTokenError::InvalidCredentials { .. })
+        ))
+    }
+
     #[tokio::test]
     async fn test_refresh_token_repo_failure() {
         dotenv().ok();
@@ -426,6 +783,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -452,6 +812,9 @@ mod test {
         let state = Service {
             repo,
             cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
             config,
         };
 
@@ -461,4 +824,2997 @@ mod test {
 
         assert!(result.is_err())
     }
+
+    const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    fn authorize_request() -> AuthorizeRequest {
+        AuthorizeRequest {
+            session: Some(AuthorizeSession {
+                user_id: uuid::Uuid::new_v4(),
+                context: Some(AuthenticationContext::password()),
+            }),
+            response_type: "code".to_string(),
+            client_id: TEST_CLIENT_ID.to_string(),
+            redirect_uri: Some(TEST_REDIRECT_URI.to_string()),
+            scope: Scopes::parse("openid email"),
+            state: Some("xyz".to_string()),
+            code_challenge: Some(code_challenge(CODE_VERIFIER)),
+            code_challenge_method: Some("S256".to_string()),
+            nonce: Some("n-0S6_WzA2Mj".to_string()),
+            prompt: None,
+            max_age: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authorize_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state.authorize(&authorize_request()).await.unwrap();
+
+        assert!(result.location().starts_with(TEST_REDIRECT_URI));
+        assert!(result.location().contains("state=xyz"));
+    }
+
+    #[tokio::test]
+    async fn test_authorize_unregistered_redirect_uri_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let mut request = authorize_request();
+        request.redirect_uri = Some("https://evil.example.com/callback".to_string());
+
+        let result = state.authorize(&request).await.unwrap_err();
+
+        assert!(result.location().is_none());
+        assert_eq!(result.error.code(), "invalid_request");
+    }
+
+    #[tokio::test]
+    async fn test_authorize_missing_code_challenge_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let mut request = authorize_request();
+        request.code_challenge = None;
+
+        let result = state.authorize(&request).await.unwrap_err();
+
+        assert!(result.location().unwrap().contains("error=invalid_request"));
+    }
+
+    #[tokio::test]
+    async fn test_authorize_prompt_none_without_session_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let mut request = authorize_request();
+        request.session = None;
+        request.prompt = Some("none".to_string());
+
+        let result = state.authorize(&request).await.unwrap_err();
+
+        assert!(result.location().unwrap().contains("error=login_required"));
+    }
+
+    #[tokio::test]
+    async fn test_authorize_prompt_login_requires_login() {
+        dotenv().ok();
+        let mut config = Config::init();
+        config.oidc_login_url = Some("https://app.example.com/login".to_string());
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let mut request = authorize_request();
+        request.prompt = Some("login".to_string());
+
+        let result = state.authorize(&request).await.unwrap_err();
+
+        assert!(result.location().is_none());
+        assert_eq!(result.error.code(), "login_required");
+        assert_eq!(
+            result.login_location("/oauth/authorize?client_id=test-client"),
+            Some(
+                "https://app.example.com/login?return_to=%2Foauth%2Fauthorize%3Fclient_id%3Dtest-client"
+                    .to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authorize_max_age_exceeded_requires_login() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let mut context = AuthenticationContext::password();
+        context.auth_time -= 600;
+
+        let mut request = authorize_request();
+        request.session = Some(AuthorizeSession {
+            user_id: uuid::Uuid::new_v4(),
+            context: Some(context),
+        });
+        request.max_age = Some(300);
+
+        let result = state.authorize(&request).await.unwrap_err();
+
+        assert!(result.location().is_none());
+        assert_eq!(result.error.code(), "login_required");
+
+        request.max_age = Some(900);
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            ..state
+        };
+
+        assert!(state.authorize(&request).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_token_authorization_code_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success().with_authorization_code(AuthorizationCode {
+            code: "code".to_string(),
+            client_id: TEST_CLIENT_ID.to_string(),
+            user_id: uuid::Uuid::new_v4(),
+            redirect_uri: TEST_REDIRECT_URI.to_string(),
+            redirect_uri_requested: true,
+            scope: "openid email".to_string(),
+            code_challenge: code_challenge(CODE_VERIFIER),
+            max_age: 60,
+            nonce: Some("n-0S6_WzA2Mj".to_string()),
+            context: Some(AuthenticationContext::password()),
+        });
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .token(&TokenRequest {
+                client: ClientAuthentication::new(TEST_CLIENT_ID, None),
+                grant: TokenGrant::AuthorizationCode {
+                    code: "code".to_string(),
+                    redirect_uri: Some(TEST_REDIRECT_URI.to_string()),
+                    code_verifier: CODE_VERIFIER.to_string(),
+                },
+            })
+            .await
+            .unwrap();
+
+        let public_key = String::from_utf8(
+            general_purpose::STANDARD
+                .decode(&state.config.access_token_public_key)
+                .unwrap(),
+        )
+        .unwrap();
+        let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256);
+        validation.set_audience(&[TEST_CLIENT_ID]);
+        let id_token = jsonwebtoken::decode::<IdTokenClaims>(
+            &result.id_token.unwrap(),
+            &jsonwebtoken::DecodingKey::from_rsa_pem(public_key.as_bytes()).unwrap(),
+            &validation,
+        )
+        .unwrap()
+        .claims;
+
+        assert_eq!(result.scope, "openid email");
+        assert!(result.refresh_token.is_some());
+        assert_eq!(id_token.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+        assert_eq!(id_token.amr, Some(vec!["pwd".to_string()]));
+        assert!(id_token.auth_time.is_some());
+        assert_eq!(id_token.email, "adrian@email.com");
+    }
+
+    #[tokio::test]
+    async fn test_token_invalid_code_verifier_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success().with_authorization_code(AuthorizationCode {
+            code: "code".to_string(),
+            client_id: TEST_CLIENT_ID.to_string(),
+            user_id: uuid::Uuid::new_v4(),
+            redirect_uri: TEST_REDIRECT_URI.to_string(),
+            redirect_uri_requested: false,
+            scope: "openid".to_string(),
+            code_challenge: code_challenge(CODE_VERIFIER),
+            max_age: 60,
+            nonce: None,
+            context: None,
+        });
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .token(&TokenRequest {
+                client: ClientAuthentication::new(TEST_CLIENT_ID, None),
+                grant: TokenGrant::AuthorizationCode {
+                    code: "code".to_string(),
+                    redirect_uri: None,
+                    code_verifier: "a".repeat(43),
+                },
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::InvalidGrant { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_token_missing_redirect_uri_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success().with_authorization_code(AuthorizationCode {
+            code: "code".to_string(),
+            client_id: TEST_CLIENT_ID.to_string(),
+            user_id: uuid::Uuid::new_v4(),
+            redirect_uri: TEST_REDIRECT_URI.to_string(),
+            redirect_uri_requested: true,
+            scope: "openid".to_string(),
+            code_challenge: code_challenge(CODE_VERIFIER),
+            max_age: 60,
+            nonce: None,
+            context: None,
+        });
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .token(&TokenRequest {
+                client: ClientAuthentication::new(TEST_CLIENT_ID, None),
+                grant: TokenGrant::AuthorizationCode {
+                    code: "code".to_string(),
+                    redirect_uri: None,
+                    code_verifier: CODE_VERIFIER.to_string(),
+                },
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::InvalidGrant { .. })));
+    }
+
+    fn confidential_client(secret: &str) -> OAuthClient {
+        OAuthClient::new(
+            "backend-job",
+            ClientType::Confidential,
+            vec![],
+            vec!["reports:read".to_string(), "reports:write".to_string()],
+        )
+        .with_client_secret_hash(&hash_password(secret).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_token_client_credentials_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let client = confidential_client("s3cr3t");
+        let client_uuid = client.id;
+        let repo =
+            MockAuthRepository::success("adrian@email.com", "password").with_oauth_client(client);
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .token(&TokenRequest {
+                client: ClientAuthentication::new("backend-job", Some("s3cr3t")),
+                grant: TokenGrant::ClientCredentials {
+                    scope: Scopes::parse("reports:read"),
+                },
+            })
+            .await
+            .unwrap();
+
+        let access_token_details =
+            verify_jwt(&state.config.access_token_public_key, &result.access_token).unwrap();
+
+        assert_eq!(result.scope, "reports:read");
+        assert!(result.refresh_token.is_none());
+        assert_eq!(access_token_details.user_id, client_uuid);
+        assert_eq!(access_token_details.principal_type(), PrincipalType::Client);
+    }
+
+    #[tokio::test]
+    async fn test_auth_client_token_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let client = confidential_client("s3cr3t");
+        let mut claims = CustomClaims::default();
+        claims.insert("client_id", serde_json::json!(client.client_id));
+        claims.insert(PRINCIPAL_TYPE_CLAIM, serde_json::json!("client"));
+
+        let access_token_details = generate_jwt_with_claims(
+            client.id,
+            config.access_token_max_age,
+            &config.access_token_private_key,
+            claims,
+        )
+        .unwrap();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password")
+            .with_oauth_client(client.clone());
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .auth(&AuthRequest::new(access_token_details.token.unwrap()))
+            .await
+            .unwrap();
+
+        assert_eq!(result.principal_type(), PrincipalType::Client);
+        assert_eq!(result.principal.id(), client.id);
+        assert!(result.user().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_client_credentials_invalid_secret_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password")
+            .with_oauth_client(confidential_client("s3cr3t"));
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .token(&TokenRequest {
+                client: ClientAuthentication::new("backend-job", Some("wrong")),
+                grant: TokenGrant::ClientCredentials {
+                    scope: Scopes::parse(""),
+                },
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::InvalidClient { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_token_client_credentials_public_client_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .token(&TokenRequest {
+                client: ClientAuthentication::new(TEST_CLIENT_ID, None),
+                grant: TokenGrant::ClientCredentials {
+                    scope: Scopes::parse(""),
+                },
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::UnauthorizedClient { .. })));
+    }
+
+    fn client_token(config: &Config, client_id: &str) -> String {
+        let mut claims = CustomClaims::default();
+        claims.insert("client_id", serde_json::json!(client_id));
+        claims.insert("scope", serde_json::json!("reports:read"));
+
+        generate_jwt_with_claims(
+            uuid::Uuid::new_v4(),
+            config.access_token_max_age,
+            &config.access_token_private_key,
+            claims,
+        )
+        .unwrap()
+        .token
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_introspect_active_token_success() {
+        dotenv().ok();
+        let config = Config::init();
+        let token = client_token(&config, "backend-job");
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password")
+            .with_oauth_client(confidential_client("s3cr3t"));
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .introspect(&IntrospectionRequest {
+                client: ClientAuthentication::new("backend-job", Some("s3cr3t")),
+                token,
+                token_type_hint: None,
+            })
+            .await
+            .unwrap();
+
+        assert!(result.active);
+        assert_eq!(result.scope.as_deref(), Some("reports:read"));
+        assert_eq!(result.client_id.as_deref(), Some("backend-job"));
+        assert_eq!(result.token_type.as_deref(), Some("Bearer"));
+        assert!(result.exp.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_introspect_invalid_token_inactive() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password")
+            .with_oauth_client(confidential_client("s3cr3t"));
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .introspect(&IntrospectionRequest {
+                client: ClientAuthentication::new("backend-job", Some("s3cr3t")),
+                token: "not-a-token".to_string(),
+                token_type_hint: Some("refresh_token".to_string()),
+            })
+            .await
+            .unwrap();
+
+        assert!(!result.active);
+        assert!(result.sub.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_introspect_public_client_failure() {
+        dotenv().ok();
+        let config = Config::init();
+        let token = client_token(&config, TEST_CLIENT_ID);
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password");
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .introspect(&IntrospectionRequest {
+                client: ClientAuthentication::new(TEST_CLIENT_ID, None),
+                token,
+                token_type_hint: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::UnauthorizedClient { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_introspect_rate_limited_failure() {
+        dotenv().ok();
+        let config = Config::init();
+        let token = client_token(&config, "backend-job");
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password")
+            .with_oauth_client(confidential_client("s3cr3t"));
+        let cache = MockCacheRepository::success()
+            .with_request_count(config.oauth_rate_limit_per_minute + 1);
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .introspect(&IntrospectionRequest {
+                client: ClientAuthentication::new("backend-job", Some("s3cr3t")),
+                token,
+                token_type_hint: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::TooManyRequests { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_success() {
+        dotenv().ok();
+        let config = Config::init();
+        let token = client_token(&config, "backend-job");
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password")
+            .with_oauth_client(confidential_client("s3cr3t"));
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .revoke(&RevocationRequest {
+                client: ClientAuthentication::new("backend-job", Some("s3cr3t")),
+                token,
+                token_type_hint: Some("access_token".to_string()),
+            })
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_token_of_another_client_failure() {
+        dotenv().ok();
+        let config = Config::init();
+        let token = client_token(&config, "another-client");
+
+        let repo = MockAuthRepository::success("adrian@email.com", "password")
+            .with_oauth_client(confidential_client("s3cr3t"));
+        let cache = MockCacheRepository::success();
+
+        let state = Service {
+            repo,
+            cache,
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .revoke(&RevocationRequest {
+                client: ClientAuthentication::new("backend-job", Some("s3cr3t")),
+                token,
+                token_type_hint: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::UnauthorizedClient { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_userinfo_requires_openid_scope() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let mut claims = CustomClaims::default();
+        claims.insert("scope", serde_json::json!("openid email"));
+        let auth = AuthMiddleware::new(
+            Principal::User(User::new("adrian@email.com", "password")),
+            uuid::Uuid::new_v4(),
+            claims,
+        );
+
+        let userinfo = state.userinfo(&auth).await.unwrap();
+        assert_eq!(userinfo.email.as_deref(), Some("adrian@email.com"));
+
+        let auth = AuthMiddleware::new(
+            auth.principal,
+            auth.access_token_uuid,
+            CustomClaims::default(),
+        );
+        let result = state.userinfo(&auth).await;
+        assert!(matches!(result, Err(OAuthError::InsufficientScope { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_device_authorization_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let response = state
+            .device_authorization(&DeviceAuthorizationRequest {
+                client: ClientAuthentication::new(TEST_CLIENT_ID, None),
+                scope: Scopes::parse("openid"),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.user_code.len(), 9);
+        assert_eq!(response.user_code.chars().nth(4), Some('-'));
+        assert!(response.verification_uri_complete.contains("user_code="));
+        assert_eq!(
+            response.interval,
+            state.config.oauth_device_poll_interval_seconds
+        );
+    }
+
+    fn device_authorization(status: DeviceAuthorizationStatus) -> DeviceAuthorization {
+        DeviceAuthorization {
+            device_code: "device-code".to_string(),
+            user_code: "BCDFGHJK".to_string(),
+            client_id: TEST_CLIENT_ID.to_string(),
+            scope: "openid".to_string(),
+            status,
+            interval: 5,
+            last_polled_at: None,
+            expires_at: chrono::Utc::now().timestamp() + 600,
+        }
+    }
+
+    fn device_code_request() -> TokenRequest {
+        TokenRequest {
+            client: ClientAuthentication::new(TEST_CLIENT_ID, None),
+            grant: TokenGrant::DeviceCode {
+                device_code: "device-code".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_device_code_pending_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success().with_device_authorization(device_authorization(
+                DeviceAuthorizationStatus::Pending,
+            )),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state.token(&device_code_request()).await;
+
+        assert!(matches!(
+            result,
+            Err(OAuthError::AuthorizationPending { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_token_device_code_slow_down_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let mut authorization = device_authorization(DeviceAuthorizationStatus::Pending);
+        authorization.last_polled_at = Some(chrono::Utc::now().timestamp());
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success().with_device_authorization(authorization),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state.token(&device_code_request()).await;
+
+        assert!(matches!(result, Err(OAuthError::SlowDown { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_token_device_code_approved_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let authorization = device_authorization(DeviceAuthorizationStatus::Approved {
+            user_id: uuid::Uuid::new_v4(),
+            context: Some(AuthenticationContext::password()),
+        });
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success().with_device_authorization(authorization),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let response = state.token(&device_code_request()).await.unwrap();
+
+        assert!(response.refresh_token.is_some());
+        assert_eq!(response.scope, "openid");
+
+        let access_token = verify_jwt(
+            &state.config.access_token_public_key,
+            &response.access_token,
+        )
+        .unwrap();
+        assert_eq!(
+            access_token.claims.get("client_id"),
+            Some(&serde_json::json!(TEST_CLIENT_ID))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_device_code_denied_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success()
+                .with_device_authorization(device_authorization(DeviceAuthorizationStatus::Denied)),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state.token(&device_code_request()).await;
+
+        assert!(matches!(result, Err(OAuthError::AccessDenied { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_verify_device_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let response = state
+            .verify_device(&DeviceVerificationRequest {
+                user_id: uuid::Uuid::new_v4(),
+                context: Some(AuthenticationContext::password()),
+                user_code: "bcdf-ghjk".to_string(),
+                approve: true,
+            })
+            .await
+            .unwrap();
+
+        assert!(response.approved);
+        assert_eq!(response.client_id, TEST_CLIENT_ID);
+    }
+
+    #[tokio::test]
+    async fn test_verify_device_already_decided_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        // Another decision was recorded after the authorization was fetched as pending.
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository {
+                decide_device_authorization_result: Arc::new(Mutex::new(Ok(false))),
+                ..MockCacheRepository::success()
+            },
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .verify_device(&DeviceVerificationRequest {
+                user_id: uuid::Uuid::new_v4(),
+                context: Some(AuthenticationContext::password()),
+                user_code: "bcdf-ghjk".to_string(),
+                approve: true,
+            })
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::InvalidGrant { .. })));
+    }
+
+    fn federation_config() -> Config {
+        let mut config = Config::init();
+        config.federated_providers = vec![FederatedProvider {
+            name: "test-provider".to_string(),
+            issuer: "http://127.0.0.1:9".to_string(),
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            scope: "openid email".to_string(),
+        }];
+        config
+    }
+
+    #[tokio::test]
+    async fn test_federated_login_unknown_provider_failure() {
+        dotenv().ok();
+        let config = federation_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .federated_login(&FederatedLoginRequest {
+                provider: "unknown".to_string(),
+                return_to: None,
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(FederationError::UnknownProvider { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_federated_login_external_return_to_failure() {
+        dotenv().ok();
+        let config = federation_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        for return_to in [
+            "//evil.example.com/",
+            "/\\evil.example.com/",
+            "/\t/evil.example.com/",
+            "/\n/evil.example.com/",
+            "/ /evil.example.com/",
+            "https://evil.example.com/",
+        ] {
+            let result = state
+                .federated_login(&FederatedLoginRequest {
+                    provider: "test-provider".to_string(),
+                    return_to: Some(return_to.to_string()),
+                })
+                .await;
+
+            assert!(
+                matches!(result, Err(FederationError::InvalidRequest { .. })),
+                "{:?} was accepted",
+                return_to
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_federated_callback_provider_error_failure() {
+        dotenv().ok();
+        let config = federation_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .federated_callback(&FederatedCallbackRequest {
+                provider: "test-provider".to_string(),
+                state: "state".to_string(),
+                state_digest: Some(hash_token("state")),
+                code: None,
+                error: Some("access_denied".to_string()),
+            })
+            .await;
+
+        assert!(matches!(result, Err(FederationError::Rejected { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_federated_callback_state_of_another_provider_failure() {
+        dotenv().ok();
+        let mut config = federation_config();
+        let mut other = config.federated_providers[0].clone();
+        other.name = "other-provider".to_string();
+        config.federated_providers.push(other);
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .federated_callback(&FederatedCallbackRequest {
+                provider: "other-provider".to_string(),
+                state: "state".to_string(),
+                state_digest: Some(hash_token("state")),
+                code: Some("code".to_string()),
+                error: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(FederationError::Rejected { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_federated_callback_in_another_browser_failure() {
+        dotenv().ok();
+        let config = federation_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        for state_digest in [None, Some(hash_token("another state"))] {
+            let result = state
+                .federated_callback(&FederatedCallbackRequest {
+                    provider: "test-provider".to_string(),
+                    state: "state".to_string(),
+                    state_digest,
+                    code: Some("code".to_string()),
+                    error: None,
+                })
+                .await;
+
+            assert!(matches!(
+                result,
+                Err(FederationError::Rejected { reason }) if reason.contains("browser")
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_ldap_unreachable_failure() {
+        let email = "adrian@email.com";
+        let password = "password";
+        let hashed_password = hash_password(password).unwrap();
+
+        dotenv().ok();
+        let mut config = Config::init();
+        config.ldap = Some(LdapConfig {
+            mode: LdapMode::First,
+            url: "ldap://127.0.0.1:9".to_string(),
+            bind_dn: None,
+            bind_password: String::new(),
+            base_dn: "dc=example,dc=com".to_string(),
+            user_filter: "(mail={login})".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            group_roles: Default::default(),
+            grant_admin: false,
+        });
+
+        let state = Service {
+            repo: MockAuthRepository::success(email, &hashed_password),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .login(&LoginUserRequest::new(
+                UserEmail::new(email).unwrap(),
+                UserPassword::new(password).unwrap(),
+            ))
+            .await;
+
+        // A directory outage must not fall back to local passwords.
+        assert!(matches!(result, Err(LoginUserError::Unknown(_))));
+    }
+
+    fn saml_config() -> Config {
+        let mut config = Config::init();
+        config.saml_identity_providers = vec![SamlIdentityProvider {
+            name: "test-idp".to_string(),
+            entity_id: "https://idp.example.com/saml".to_string(),
+            sso_url: "https://idp.example.com/saml/sso".to_string(),
+            certificate: include_str!("../../tests/fixtures/saml/idp_certificate.pem").to_string(),
+            email_attribute: None,
+            trust_email: true,
+        }];
+        config
+    }
+
+    #[tokio::test]
+    async fn test_saml_login_success() {
+        dotenv().ok();
+        let config = saml_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .saml_login(&FederatedLoginRequest {
+                provider: "test-idp".to_string(),
+                return_to: Some("/oauth/authorize?client_id=test".to_string()),
+            })
+            .await
+            .unwrap();
+
+        let url = url::Url::parse(&result.authorization_url).unwrap();
+        assert_eq!(url.path(), "/saml/sso");
+        assert!(url.query_pairs().any(|(name, _)| name == "SAMLRequest"));
+        assert!(url.query_pairs().any(|(name, _)| name == "RelayState"));
+    }
+
+    #[tokio::test]
+    async fn test_saml_metadata_unknown_provider_failure() {
+        dotenv().ok();
+        let config = saml_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state.saml_metadata("unknown").await;
+
+        assert!(matches!(
+            result,
+            Err(FederationError::UnknownProvider { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_saml_assertion_unsolicited_response_failure() {
+        dotenv().ok();
+        let config = saml_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .saml_assertion(&SamlAssertionRequest {
+                provider: "test-idp".to_string(),
+                saml_response: "PHNhbWxwOlJlc3BvbnNlLz4=".to_string(),
+                relay_state: None,
+                state_digest: None,
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(FederationError::InvalidRequest { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_saml_assertion_response_for_another_service_failure() {
+        dotenv().ok();
+        let config = saml_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .saml_assertion(&SamlAssertionRequest {
+                provider: "test-idp".to_string(),
+                saml_response: general_purpose::STANDARD.encode(include_str!(
+                    "../../tests/fixtures/saml/signed_response.xml"
+                )),
+                relay_state: Some("relay-state".to_string()),
+                state_digest: Some(hash_token("relay-state")),
+            })
+            .await;
+
+        assert!(matches!(result, Err(FederationError::Rejected { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_saml_assertion_in_another_browser_failure() {
+        dotenv().ok();
+        let config = saml_config();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .saml_assertion(&SamlAssertionRequest {
+                provider: "test-idp".to_string(),
+                saml_response: "PHNhbWxwOlJlc3BvbnNlLz4=".to_string(),
+                relay_state: Some("relay-state".to_string()),
+                state_digest: None,
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(FederationError::Rejected { reason }) if reason.contains("browser")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auth_personal_access_token_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::failure(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .auth(&AuthRequest::new("pat_secret".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(result.user().unwrap().email, "adrian@email.com");
+        assert_eq!(
+            result.claims.get("scope"),
+            Some(&serde_json::json!("profile"))
+        );
+        assert!(!result.is_login_session());
+    }
+
+    #[tokio::test]
+    async fn test_auth_personal_access_token_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::failure(),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .auth(&AuthRequest::new("pat_secret".to_string()))
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    fn personal_access_token_request(scope: &str) -> CreatePersonalAccessTokenRequest {
+        CreatePersonalAccessTokenRequest {
+            user_id: UserId::new(uuid::Uuid::new_v4()),
+            name: "deploy script".to_string(),
+            scopes: Scopes::parse(scope),
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_personal_access_token_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .create_personal_access_token(&personal_access_token_request("profile"))
+            .await
+            .unwrap();
+
+        assert!(result.token.starts_with("pat_"));
+        assert!(result.token.len() > result.details.token_prefix.len());
+    }
+
+    #[tokio::test]
+    async fn test_create_personal_access_token_disallowed_scope_failure() {
+        dotenv().ok();
+        let mut config = Config::init();
+        config.personal_access_token_scopes = vec!["profile".to_string()];
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .create_personal_access_token(&personal_access_token_request("profile admin"))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PersonalAccessTokenError::InvalidRequest { .. })
+        ));
+    }
+
+    fn service_account() -> User {
+        User {
+            kind: UserKind::Service,
+            owner_id: Some(uuid::Uuid::new_v4()),
+            ..User::new("ci-deployer@serviceaccount.invalid", "")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_service_account_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password")
+                .with_user(service_account()),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .login(&LoginUserRequest::new(
+                UserEmail::new("ci-deployer@serviceaccount.invalid").unwrap(),
+                UserPassword::new("password").unwrap(),
+            ))
+            .await;
+
+        assert!(matches!(result, Err(LoginUserError::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_create_service_account_invalid_name_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .create_service_account(&CreateServiceAccountRequest {
+                name: "CI Deployer".to_string(),
+                owner_id: UserId::new(uuid::Uuid::new_v4()),
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ServiceAccountError::InvalidRequest { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_service_account_key_for_human_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .create_service_account_key(&personal_access_token_request("profile"))
+            .await;
+
+        assert!(matches!(result, Err(ServiceAccountError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_service_account_key_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let account = service_account();
+        let old_key = PersonalAccessToken {
+            expires_at: Some(chrono::Utc::now() + chrono::Duration::days(30)),
+            ..PersonalAccessToken::new(account.id, "deploy", &["profile"])
+        };
+
+        let state = Service {
+            repo: MockAuthRepository {
+                list_personal_access_tokens_result: Arc::new(Mutex::new(Ok(vec![old_key.clone()]))),
+                ..MockAuthRepository::success("adrian@email.com", "password")
+                    .with_user(account.clone())
+            },
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .rotate_service_account_key(&RotateServiceAccountKeyRequest {
+                service_account_id: UserId::new(account.id),
+                key_id: old_key.id,
+                grace_period_seconds: 3600,
+            })
+            .await
+            .unwrap();
+
+        assert!(result.token.starts_with("pat_"));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_service_account_key_unknown_key_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let account = service_account();
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password")
+                .with_user(account.clone()),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .rotate_service_account_key(&RotateServiceAccountKeyRequest {
+                service_account_id: UserId::new(account.id),
+                key_id: uuid::Uuid::new_v4(),
+                grace_period_seconds: 0,
+            })
+            .await;
+
+        assert!(matches!(result, Err(ServiceAccountError::NotFound)));
+    }
+
+    fn membership(role: OrganizationRole) -> OrganizationMembership {
+        OrganizationMembership {
+            organization_id: uuid::Uuid::new_v4(),
+            name: "Acme".to_string(),
+            slug: "acme".to_string(),
+            role,
+            created_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_starts_in_first_organization_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let hashed_password = hash_password("password").unwrap();
+        let user = User::new("adrian@email.com", &hashed_password);
+        let member = membership(OrganizationRole::Admin);
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password")
+                .with_user(user)
+                .with_membership(member.clone()),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config: config.clone(),
+        };
+
+        let request = LoginUserRequest::new(
+            UserEmail::new("adrian@email.com").unwrap(),
+            UserPassword::new("password").unwrap(),
+        );
+        let response = state.login(&request).await.unwrap();
+
+        let claims = verify_jwt(&config.access_token_public_key, &response.access_token)
+            .unwrap()
+            .claims;
+        assert_eq!(active_organization(&claims), Some(member.organization_id));
+        assert_eq!(
+            claims.get(ORG_ROLE_CLAIM),
+            Some(&serde_json::json!("admin"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auth_removed_from_organization_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let mut claims = CustomClaims::default();
+        membership(OrganizationRole::Member).insert_into(&mut claims);
+        let access_token_details = generate_jwt_with_claims(
+            uuid::Uuid::new_v4(),
+            config.access_token_max_age,
+            &config.access_token_private_key,
+            claims,
+        )
+        .unwrap();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password").without_memberships(),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .auth(&AuthRequest::new(access_token_details.token.unwrap()))
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_organization_invalid_slug_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .create_organization(&CreateOrganizationRequest {
+                user_id: UserId::new(uuid::Uuid::new_v4()),
+                name: "Acme".to_string(),
+                slug: "Acme Inc".to_string(),
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(OrganizationError::InvalidRequest { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_members_of_another_organization_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password").without_memberships(),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .list_members(&OrganizationMembersRequest {
+                organization_id: uuid::Uuid::new_v4(),
+                actor_id: UserId::new(uuid::Uuid::new_v4()),
+            })
+            .await;
+
+        assert!(matches!(result, Err(OrganizationError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_invite_member_as_member_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let member = membership(OrganizationRole::Member);
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password")
+                .with_membership(member.clone()),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .invite_member(&InviteMemberRequest {
+                organization_id: member.organization_id,
+                actor_id: UserId::new(uuid::Uuid::new_v4()),
+                email: "invitee@email.com".to_string(),
+                role: OrganizationRole::Member,
+            })
+            .await;
+
+        assert!(matches!(result, Err(OrganizationError::Forbidden { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_remove_last_owner_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let user_id = uuid::Uuid::new_v4();
+        let owner = membership(OrganizationRole::Owner);
+        let state = Service {
+            repo: MockAuthRepository {
+                fetch_membership_result: Arc::new(Mutex::new(Ok(owner.clone()))),
+                ..MockAuthRepository::success("adrian@email.com", "password")
+            },
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .remove_member(&RemoveMemberRequest {
+                organization_id: owner.organization_id,
+                actor_id: UserId::new(user_id),
+                user_id: UserId::new(user_id),
+            })
+            .await;
+
+        assert!(matches!(result, Err(OrganizationError::Forbidden { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_switch_organization_success() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let member = membership(OrganizationRole::Member);
+        let mut claims = CustomClaims::default();
+        AuthenticationContext::password().insert_into(&mut claims);
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password")
+                .with_membership(member.clone()),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config: config.clone(),
+        };
+
+        let response = state
+            .switch_organization(&SwitchOrganizationRequest {
+                user_id: UserId::new(uuid::Uuid::new_v4()),
+                organization_id: member.organization_id,
+                access_token_uuid: uuid::Uuid::new_v4(),
+                claims,
+            })
+            .await
+            .unwrap();
+
+        let details = verify_jwt(&config.access_token_public_key, &response.access_token).unwrap();
+        assert_eq!(
+            active_organization(&details.claims),
+            Some(member.organization_id)
+        );
+        assert!(AuthenticationContext::from_claims(&details.claims).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_switch_to_another_organization_failure() {
+        dotenv().ok();
+        let config = Config::init();
+
+        let state = Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password").without_memberships(),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        };
+
+        let result = state
+            .switch_organization(&SwitchOrganizationRequest {
+                user_id: UserId::new(uuid::Uuid::new_v4()),
+                organization_id: uuid::Uuid::new_v4(),
+                access_token_uuid: uuid::Uuid::new_v4(),
+                claims: CustomClaims::default(),
+            })
+            .await;
+
+        assert!(matches!(result, Err(OrganizationError::NotFound)));
+    }
+
+    fn register_request(email: &str) -> RegisterUserRequest {
+        RegisterUserRequest::new(
+            UserEmail::new(email).unwrap(),
+            HashedUserPassword::new(UserPassword::new("password").unwrap()).unwrap(),
+        )
+    }
+
+    fn registration_service(
+        mode: RegistrationMode,
+    ) -> Service<MockAuthRepository, MockCacheRepository, MockAuditSink, InMemoryEventPublisher>
+    {
+        dotenv().ok();
+        let mut config = Config::init();
+        config.registration_mode = mode;
+
+        Service {
+            repo: MockAuthRepository::success("adrian@email.com", "password"),
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_invite_only_without_invitation_failure() {
+        let state = registration_service(RegistrationMode::InviteOnly);
+
+        let result = state.register(&register_request("adrian@email.com")).await;
+
+        assert!(matches!(result, Err(RegisterUserError::NotAllowed { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_register_invite_only_with_invitation_success() {
+        let state = registration_service(RegistrationMode::InviteOnly);
+
+        let invitation = state
+            .create_registration_invitation(&CreateRegistrationInvitationRequest {
+                email: "Adrian@email.com".to_string(),
+                organization_id: None,
+                role: OrganizationRole::Member,
+                created_by: UserId::new(uuid::Uuid::new_v4()),
+            })
+            .await
+            .unwrap();
+
+        let result = state
+            .register(
+                &register_request("adrian@email.com")
+                    .with_invitation(RegistrationInvitationToken::new(&invitation.token)),
+            )
+            .await;
+
+        assert_eq!(result.unwrap().email, "adrian@email.com");
+    }
+
+    #[tokio::test]
+    async fn test_register_domain_allowlist_success() {
+        let state = registration_service(RegistrationMode::DomainAllowlist(vec![
+            "email.com".to_string()
+        ]));
+
+        let result = state.register(&register_request("adrian@email.com")).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_register_closed_failure() {
+        let state = registration_service(RegistrationMode::Closed);
+
+        let result = state.register(&register_request("adrian@email.com")).await;
+
+        assert!(matches!(result, Err(RegisterUserError::NotAllowed { .. })));
+    }
+
+    fn admin_service(
+        repo: MockAuthRepository,
+    ) -> Service<MockAuthRepository, MockCacheRepository, MockAuditSink, InMemoryEventPublisher>
+    {
+        dotenv().ok();
+        let config = Config::init();
+
+        Service {
+            repo,
+            cache: MockCacheRepository::success(),
+            audit: MockAuditSink::success(),
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        }
+    }
+
+    fn list_users_request(per_page: i64) -> ListUsersRequest {
+        ListUsersRequest {
+            page: 1,
+            per_page,
+            email: Some("adrian".to_string()),
+            status: None,
+            kind: None,
+            role: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_list_users_success() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state.list_users(&list_users_request(50)).await.unwrap();
+
+        assert_eq!(result.total, 1);
+        assert_eq!(result.users[0].email, "adrian@email.com");
+    }
+
+    #[tokio::test]
+    async fn test_admin_list_users_page_size_failure() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state.list_users(&list_users_request(1000)).await;
+
+        assert!(matches!(result, Err(AdminUserError::InvalidRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_admin_suspend_user_success() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state
+            .update_user(&UpdateUserRequest {
+                actor_id: UserId::new(uuid::Uuid::new_v4()),
+                user_id: UserId::new(uuid::Uuid::new_v4()),
+                email: None,
+                status: Some(UserStatus::Suspended),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result.status, UserStatus::Suspended);
+    }
+
+    #[tokio::test]
+    async fn test_admin_suspend_self_failure() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+        let admin_id = uuid::Uuid::new_v4();
+
+        let result = state
+            .update_user(&UpdateUserRequest {
+                actor_id: UserId::new(admin_id),
+                user_id: UserId::new(admin_id),
+                email: None,
+                status: Some(UserStatus::Suspended),
+            })
+            .await;
+
+        assert!(matches!(result, Err(AdminUserError::InvalidRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_admin_delete_self_failure() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+        let admin_id = uuid::Uuid::new_v4();
+
+        let result = state
+            .delete_user(&DeleteUserRequest {
+                actor_id: UserId::new(admin_id),
+                user_id: UserId::new(admin_id),
+            })
+            .await;
+
+        assert!(matches!(result, Err(AdminUserError::InvalidRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_admin_force_password_reset_success() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state
+            .force_password_reset(&UserId::new(uuid::Uuid::new_v4()))
+            .await
+            .unwrap();
+
+        assert!(!result.token.is_empty());
+        assert!(result.expires_at > chrono::Utc::now());
+        assert_eq!(state.events.revocations().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_admin_revoke_sessions_broadcasts_revocation() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+        let user_id = uuid::Uuid::new_v4();
+        let before = chrono::Utc::now().timestamp();
+
+        state.revoke_sessions(&UserId::new(user_id)).await.unwrap();
+
+        let revocations = state.events.revocations().await;
+        assert_eq!(revocations.len(), 1);
+        assert_eq!(revocations[0].token_uuid, None);
+        assert_eq!(revocations[0].session_id, None);
+        assert_eq!(revocations[0].user_id, Some(user_id));
+        assert!(revocations[0].revoked_at >= before);
+        assert_eq!(
+            revocations[0].expires_at,
+            revocations[0].revoked_at + state.config.access_token_max_age * 60
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_delete_user_broadcasts_revocation() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+        let user_id = uuid::Uuid::new_v4();
+
+        state
+            .delete_user(&DeleteUserRequest {
+                actor_id: UserId::new(uuid::Uuid::new_v4()),
+                user_id: UserId::new(user_id),
+            })
+            .await
+            .unwrap();
+
+        let revocations = state.events.revocations().await;
+        assert_eq!(revocations.len(), 1);
+        assert_eq!(revocations[0].user_id, Some(user_id));
+    }
+
+    #[tokio::test]
+    async fn test_login_password_reset_required_failure() {
+        let password = "password";
+        let user = User {
+            password_reset_required: true,
+            ..User::new("adrian@email.com", &hash_password(password).unwrap())
+        };
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", password).with_user(user),
+        );
+
+        let result = state
+            .login(&LoginUserRequest::new(
+                UserEmail::new("adrian@email.com").unwrap(),
+                UserPassword::new(password).unwrap(),
+            ))
+            .await;
+
+        assert!(matches!(result, Err(LoginUserError::PasswordResetRequired)));
+    }
+
+    #[tokio::test]
+    async fn test_login_suspended_user_failure() {
+        let password = "password";
+        let user = User {
+            status: UserStatus::Suspended,
+            ..User::new("adrian@email.com", &hash_password(password).unwrap())
+        };
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", password).with_user(user),
+        );
+
+        let result = state
+            .login(&LoginUserRequest::new(
+                UserEmail::new("adrian@email.com").unwrap(),
+                UserPassword::new(password).unwrap(),
+            ))
+            .await;
+
+        assert!(matches!(result, Err(LoginUserError::Suspended)));
+    }
+
+    #[tokio::test]
+    async fn test_auth_suspended_user_failure() {
+        let user = User {
+            status: UserStatus::Suspended,
+            ..User::new("adrian@email.com", "password")
+        };
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", "password").with_user(user.clone()),
+        );
+        let access_token = generate_jwt(
+            user.id,
+            state.config.access_token_max_age,
+            &state.config.access_token_private_key,
+        )
+        .unwrap()
+        .token
+        .unwrap();
+
+        let result = state.auth(&AuthRequest::new(access_token)).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auth_revoked_session_failure() {
+        let user = User {
+            sessions_revoked_at: Some(chrono::Utc::now()),
+            ..User::new("adrian@email.com", "password")
+        };
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", "password").with_user(user.clone()),
+        );
+        let access_token = generate_jwt(
+            user.id,
+            state.config.access_token_max_age,
+            &state.config.access_token_private_key,
+        )
+        .unwrap()
+        .token
+        .unwrap();
+
+        let result = state.auth(&AuthRequest::new(access_token)).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_impersonation_success() {
+        let user = User::new("adrian@email.com", "password");
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", "password").with_user(user.clone()),
+        );
+        let admin_id = uuid::Uuid::new_v4();
+
+        let result = state
+            .start_impersonation(&StartImpersonationRequest {
+                actor_id: UserId::new(admin_id),
+                user_id: UserId::new(user.id),
+            })
+            .await
+            .unwrap();
+        let token_details =
+            verify_jwt(&state.config.access_token_public_key, &result.access_token).unwrap();
+
+        assert!(result.access_token_max_age <= IMPERSONATION_MAX_AGE_MINUTES);
+        assert_eq!(token_details.user_id, user.id);
+        assert_eq!(
+            Actor::from_claims(&token_details.claims),
+            Some(Actor { sub: admin_id })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_impersonation_of_admin_failure() {
+        let user = User {
+            roles: vec!["admin".to_string()],
+            ..User::new("adrian@email.com", "password")
+        };
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", "password").with_user(user.clone()),
+        );
+
+        let result = state
+            .start_impersonation(&StartImpersonationRequest {
+                actor_id: UserId::new(uuid::Uuid::new_v4()),
+                user_id: UserId::new(user.id),
+            })
+            .await;
+
+        assert!(matches!(result, Err(ImpersonationError::Forbidden { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_start_impersonation_of_self_failure() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+        let admin_id = uuid::Uuid::new_v4();
+
+        let result = state
+            .start_impersonation(&StartImpersonationRequest {
+                actor_id: UserId::new(admin_id),
+                user_id: UserId::new(admin_id),
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ImpersonationError::InvalidRequest { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auth_impersonation_token_exposes_actor() {
+        let user = User::new("adrian@email.com", "password");
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", "password").with_user(user.clone()),
+        );
+        let admin_id = uuid::Uuid::new_v4();
+        let mut claims = CustomClaims::default();
+        Actor { sub: admin_id }.insert_into(&mut claims);
+        let access_token = generate_jwt_with_claims(
+            user.id,
+            state.config.access_token_max_age,
+            &state.config.access_token_private_key,
+            claims,
+        )
+        .unwrap()
+        .token
+        .unwrap();
+
+        let result = state.auth(&AuthRequest::new(access_token)).await.unwrap();
+
+        assert_eq!(result.subject(), user.id);
+        assert_eq!(result.actor(), Some(admin_id));
+        assert!(result.is_impersonated());
+    }
+
+    #[tokio::test]
+    async fn test_change_password_success() {
+        let password = "password";
+        let user_id = uuid::Uuid::new_v4();
+        let state = admin_service(MockAuthRepository::success(
+            "adrian@email.com",
+            &hash_password(password).unwrap(),
+        ));
+
+        let result = state
+            .change_password(&ChangePasswordRequest {
+                user_id: UserId::new(user_id),
+                current_password: UserPassword::new(password).unwrap(),
+                new_password: HashedUserPassword::new(UserPassword::new("new-password").unwrap())
+                    .unwrap(),
+                context: RequestContext::default(),
+            })
+            .await;
+
+        assert!(result.is_ok());
+        let revocations = state.events.revocations().await;
+        assert_eq!(revocations.len(), 1);
+        assert_eq!(revocations[0].user_id, Some(user_id));
+    }
+
+    #[tokio::test]
+    async fn test_change_password_wrong_current_password_failure() {
+        let state = admin_service(MockAuthRepository::success(
+            "adrian@email.com",
+            &hash_password("password").unwrap(),
+        ));
+
+        let result = state
+            .change_password(&ChangePasswordRequest {
+                user_id: UserId::new(uuid::Uuid::new_v4()),
+                current_password: UserPassword::new("wrong-password").unwrap(),
+                new_password: HashedUserPassword::new(UserPassword::new("new-password").unwrap())
+                    .unwrap(),
+                context: RequestContext::default(),
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ChangePasswordError::InvalidCredentials)
+        ));
+        assert!(state.events.revocations().await.is_empty());
+    }
+
+    fn audited_service(
+        repo: MockAuthRepository,
+        cache: MockCacheRepository,
+        audit: MockAuditSink,
+    ) -> Service<MockAuthRepository, MockCacheRepository, MockAuditSink, InMemoryEventPublisher>
+    {
+        dotenv().ok();
+        let config = Config::init();
+
+        Service {
+            repo,
+            cache,
+            audit,
+            events: InMemoryEventPublisher::default(),
+            claims: ClaimsPipeline::from_config(&config),
+            config,
+        }
+    }
+
+    fn audited_login_request(password: &str) -> LoginUserRequest {
+        LoginUserRequest::new(
+            UserEmail::new("adrian@email.com").unwrap(),
+            UserPassword::new(password).unwrap(),
+        )
+        .with_context(RequestContext {
+            ip_address: Some("127.0.0.1".to_string()),
+            user_agent: Some("curl/8.0".to_string()),
+            request_id: Some("request-1".to_string()),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_register_records_audit_event() {
+        let audit = MockAuditSink::success();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            audit.clone(),
+        );
+
+        let user = state
+            .register(&RegisterUserRequest::new(
+                UserEmail::new("adrian@email.com").unwrap(),
+                HashedUserPassword::new(UserPassword::new("password").unwrap()).unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let events = audit.recorded().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, AuditEventType::Register);
+        assert_eq!(events[0].outcome, AuditOutcome::Success);
+        assert_eq!(events[0].user_id, Some(user.id));
+    }
+
+    #[tokio::test]
+    async fn test_login_records_audit_event_with_request_context() {
+        let audit = MockAuditSink::success();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            audit.clone(),
+        );
+        let request = audited_login_request("password");
+
+        state.login(&request).await.unwrap();
+
+        let events = audit.recorded().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, AuditEventType::Login);
+        assert_eq!(events[0].outcome, AuditOutcome::Success);
+        assert_eq!(events[0].context, request.context);
+        assert!(events[0].user_id.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_login_failure_records_audit_event_with_reason() {
+        let audit = MockAuditSink::success();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            audit.clone(),
+        );
+
+        let result = state.login(&audited_login_request("wrong-password")).await;
+
+        assert!(matches!(result, Err(LoginUserError::InvalidCredentials)));
+        let events = audit.recorded().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].outcome, AuditOutcome::Failure);
+        assert_eq!(
+            events[0].reason,
+            Some(LoginUserError::InvalidCredentials.to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_locked_out_failure() {
+        let audit = MockAuditSink::success();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success().with_counted_requests(i64::MAX),
+            audit.clone(),
+        );
+
+        let result = state.login(&audited_login_request("password")).await;
+
+        assert!(matches!(result, Err(LoginUserError::LockedOut)));
+        let events = audit.recorded().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, AuditEventType::Login);
+        assert_eq!(events[0].outcome, AuditOutcome::Failure);
+    }
+
+    #[tokio::test]
+    async fn test_login_records_lockout_when_threshold_reached() {
+        dotenv().ok();
+        let threshold = Config::init().login_lockout_threshold;
+        let audit = MockAuditSink::success();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success().with_request_count(threshold),
+            audit.clone(),
+        );
+
+        let result = state.login(&audited_login_request("wrong-password")).await;
+
+        assert!(matches!(result, Err(LoginUserError::InvalidCredentials)));
+        let events = audit.recorded().await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event_type, AuditEventType::Lockout);
+        assert_eq!(events[1].details["failed_attempts"], threshold);
+    }
+
+    #[tokio::test]
+    async fn test_login_audit_sink_failure_still_succeeds() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            MockAuditSink::failure(),
+        );
+
+        let result = state.login(&audited_login_request("password")).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_list_audit_events_success() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state
+            .list_audit_events(&ListAuditEventsRequest {
+                page: 1,
+                per_page: 50,
+                ..ListAuditEventsRequest::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result.events.len(), 1);
+        assert_eq!(result.total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_audit_events_invalid_range_failure() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+        let now = chrono::Utc::now();
+
+        let result = state
+            .list_audit_events(&ListAuditEventsRequest {
+                page: 1,
+                per_page: 50,
+                since: Some(now),
+                until: Some(now - chrono::Duration::hours(1)),
+                ..ListAuditEventsRequest::default()
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuditError::InvalidRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_list_audit_events_page_size_failure() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state
+            .list_audit_events(&ListAuditEventsRequest {
+                page: 1,
+                per_page: 1000,
+                ..ListAuditEventsRequest::default()
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuditError::InvalidRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_list_recent_activity_success() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state
+            .list_recent_activity(&UserId::new(uuid::Uuid::new_v4()))
+            .await;
+
+        assert_eq!(result.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_record_admin_action_failure_outcome() {
+        let audit = MockAuditSink::success();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            audit.clone(),
+        );
+        let actor_id = uuid::Uuid::new_v4();
+
+        state
+            .record_admin_action(&AdminAuditEntry {
+                actor_id,
+                subject_id: None,
+                method: "DELETE".to_string(),
+                route: "/api/admin/users/:user_id".to_string(),
+                path: "/api/admin/users/1".to_string(),
+                status: 404,
+                context: RequestContext::default(),
+            })
+            .await
+            .unwrap();
+
+        let events = audit.recorded().await;
+        assert_eq!(events[0].event_type, AuditEventType::AdminAction);
+        assert_eq!(events[0].outcome, AuditOutcome::Failure);
+        assert_eq!(events[0].reason, Some("HTTP 404".to_string()));
+        assert_eq!(events[0].actor_id, Some(actor_id));
+        assert_eq!(events[0].details["route"], "/api/admin/users/:user_id");
+    }
+
+    /// Records `count` events through a service that checkpoints every other event, and
+    /// returns what was stored.
+    async fn recorded_chain(count: usize) -> (Vec<AuditEvent>, Vec<AuditCheckpoint>) {
+        let audit = MockAuditSink::success();
+        let mut state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            audit.clone(),
+        );
+        state.config.audit_checkpoint_interval = 2;
+
+        for _ in 0..count {
+            state
+                .record_admin_action(&AdminAuditEntry {
+                    actor_id: uuid::Uuid::new_v4(),
+                    subject_id: None,
+                    method: "GET".to_string(),
+                    route: "/api/admin/users".to_string(),
+                    path: "/api/admin/users".to_string(),
+                    status: 200,
+                    context: RequestContext::default(),
+                })
+                .await
+                .unwrap();
+        }
+
+        (audit.chained().await, audit.saved_checkpoints().await)
+    }
+
+    fn audit_chain_service(
+        events: Vec<AuditEvent>,
+        checkpoints: Vec<AuditCheckpoint>,
+    ) -> Service<MockAuthRepository, MockCacheRepository, MockAuditSink, InMemoryEventPublisher>
+    {
+        admin_service(
+            MockAuthRepository::success("adrian@email.com", "password")
+                .with_audit_chain(events, checkpoints),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_record_event_saves_signed_checkpoints() {
+        let (events, checkpoints) = recorded_chain(5).await;
+
+        assert_eq!(
+            checkpoints
+                .iter()
+                .map(|checkpoint| checkpoint.sequence)
+                .collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(Some(checkpoints[1].hash.clone()), events[3].hash);
+    }
+
+    #[tokio::test]
+    async fn test_verify_audit_chain_success() {
+        let (events, checkpoints) = recorded_chain(5).await;
+        let state = audit_chain_service(events, checkpoints);
+
+        let report = state.verify_audit_chain().await.unwrap();
+
+        assert!(report.intact);
+        assert_eq!(report.events_checked, 5);
+        assert_eq!(report.checkpoints_checked, 2);
+    }
+
+    #[tokio::test]
+    async fn test_verify_audit_chain_detects_deleted_event() {
+        let (mut events, checkpoints) = recorded_chain(5).await;
+        events.remove(2);
+        let state = audit_chain_service(events, checkpoints);
+
+        let report = state.verify_audit_chain().await.unwrap();
+
+        assert!(!report.intact);
+        assert_eq!(report.first_broken_link.unwrap().sequence, 4);
+    }
+
+    #[tokio::test]
+    async fn test_verify_audit_chain_detects_modified_event() {
+        let (mut events, checkpoints) = recorded_chain(5).await;
+        events[2].outcome = AuditOutcome::Failure;
+        let state = audit_chain_service(events, checkpoints);
+
+        let report = state.verify_audit_chain().await.unwrap();
+
+        assert!(!report.intact);
+        assert_eq!(report.first_broken_link.unwrap().sequence, 3);
+    }
+
+    #[tokio::test]
+    async fn test_verify_audit_chain_detects_forged_checkpoint() {
+        let (mut events, mut checkpoints) = recorded_chain(5).await;
+        events[0].reason = Some("Edited".to_string());
+        events[0].hash = Some(events[0].chain_hash());
+        for index in 1..events.len() {
+            events[index].previous_hash = events[index - 1].hash.clone();
+            events[index].hash = Some(events[index].chain_hash());
+        }
+        checkpoints[0].hash = events[1].hash.clone().unwrap();
+        let state = audit_chain_service(events, checkpoints);
+
+        let report = state.verify_audit_chain().await.unwrap();
+
+        assert_eq!(
+            report.first_broken_link,
+            Some(BrokenAuditLink::new(2, "Checkpoint signature is invalid"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_audit_chain_repo_failure() {
+        let state = admin_service(MockAuthRepository::failure());
+
+        let result = state.verify_audit_chain().await;
+
+        assert!(matches!(result, Err(AuditError::Unknown(_))));
+    }
+
+    fn create_webhook_request(events: &[&str]) -> CreateWebhookRequest {
+        CreateWebhookRequest {
+            url: "https://crm.example.com/hooks".to_string(),
+            events: events.iter().map(|event| event.to_string()).collect(),
+            created_by: UserId::new(uuid::Uuid::new_v4()),
+        }
+    }
+
+    fn pending_webhook_delivery(url: &str, attempts: i32) -> PendingWebhookDelivery {
+        PendingWebhookDelivery {
+            id: uuid::Uuid::new_v4(),
+            url: url.to_string(),
+            secret: "whsec_secret".to_string(),
+            event_type: "user.registered".to_string(),
+            payload: serde_json::json!({ "type": "user.registered" }),
+            attempts,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_success() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let response = state
+            .create_webhook(&create_webhook_request(&["user.registered"]))
+            .await
+            .unwrap();
+
+        assert!(response.secret.starts_with("whsec_"));
+        assert_eq!(response.subscription.events, vec!["user.registered"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_unknown_event_failure() {
+        let state = admin_service(MockAuthRepository::success("adrian@email.com", "password"));
+
+        let result = state
+            .create_webhook(&create_webhook_request(&["user.deleted"]))
+            .await;
+
+        assert!(matches!(result, Err(WebhookError::InvalidRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_list_webhooks_repo_failure() {
+        let state = admin_service(MockAuthRepository::failure());
+
+        let result = state.list_webhooks().await;
+
+        assert!(matches!(result, Err(WebhookError::Unknown(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failed_login_enqueues_webhook_event() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        let result = state.login(&audited_login_request("wrong-password")).await;
+        assert!(matches!(result, Err(LoginUserError::InvalidCredentials)));
+
+        let events = state.repo.enqueued_webhook_events().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, WebhookEventType::UserLoginFailed);
+        assert_eq!(events[0].data["email"], "adrian@email.com");
+    }
+
+    #[tokio::test]
+    async fn test_logout_enqueues_webhook_event() {
+        let user_id = uuid::Uuid::new_v4();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        state
+            .logout(&LogoutRequest::new(uuid::Uuid::new_v4()).with_user(Some(user_id)))
+            .await
+            .unwrap();
+
+        let events = state.repo.enqueued_webhook_events().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, WebhookEventType::SessionRevoked);
+        assert_eq!(events[0].data["user_id"], serde_json::json!(user_id));
+        assert_eq!(events[0].data["all_sessions"], false);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_pending_webhooks_signs_payload() {
+        let received = Arc::new(Mutex::new(vec![]));
+        let receiver = axum::Router::new().route(
+            "/hooks",
+            axum::routing::post({
+                let received = received.clone();
+                move |headers: axum::http::HeaderMap, body: axum::body::Bytes| async move {
+                    received.lock().await.push((headers, body));
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, receiver).await });
+
+        let delivery = pending_webhook_delivery(&url, 0);
+        let state = admin_service(
+            MockAuthRepository::success("adrian@email.com", "password")
+                .with_pending_webhook_deliveries(vec![delivery.clone()]),
+        );
+
+        let attempted = state.deliver_pending_webhooks().await.unwrap();
+
+        assert_eq!(attempted, 1);
+        let attempts = state.repo.recorded_webhook_attempts().await;
+        assert_eq!(attempts[0].outcome, WebhookAttemptOutcome::Delivered);
+        assert_eq!(attempts[0].attempts, 1);
+
+        let received = received.lock().await;
+        let (headers, body) = &received[0];
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            format!("sha256={}", sign_payload("whsec_secret", timestamp, body))
+        );
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(body).unwrap(),
+            delivery.payload
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deliver_pending_webhooks_dead_letters_last_attempt() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        drop(listener);
+
+        let mut state = admin_service(
+            MockAuthRepository::success("adrian@email.com", "password")
+                .with_pending_webhook_deliveries(vec![
+                    pending_webhook_delivery(&url, 0),
+                    pending_webhook_delivery(&url, 2),
+                ]),
+        );
+        state.config.webhook_max_attempts = 3;
+
+        let attempted = state.deliver_pending_webhooks().await.unwrap();
+
+        assert_eq!(attempted, 2);
+        let attempts = state.repo.recorded_webhook_attempts().await;
+        assert!(matches!(
+            attempts[0].outcome,
+            WebhookAttemptOutcome::Retry { .. }
+        ));
+        assert_eq!(attempts[1].attempts, 3);
+        assert!(matches!(
+            attempts[1].outcome,
+            WebhookAttemptOutcome::Dead { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_pending_webhooks_repo_failure() {
+        let state = admin_service(MockAuthRepository::failure());
+
+        let result = state.deliver_pending_webhooks().await;
+
+        assert!(matches!(result, Err(WebhookError::Unknown(_))));
+    }
+
+    #[tokio::test]
+    async fn test_register_publishes_event() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        let user = state
+            .register(&RegisterUserRequest::new(
+                UserEmail::new("adrian@email.com").unwrap(),
+                HashedUserPassword::new(UserPassword::new("password").unwrap()).unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let events = state.events.published().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, DomainEventType::UserRegistered);
+        assert_eq!(events[0].user_id, Some(user.id));
+    }
+
+    #[tokio::test]
+    async fn test_login_publishes_event() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        state
+            .login(&audited_login_request("password"))
+            .await
+            .unwrap();
+
+        let events = state.events.published().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, DomainEventType::UserLoggedIn);
+        assert_eq!(events[0].data["email"], "adrian@email.com");
+    }
+
+    #[tokio::test]
+    async fn test_failed_login_publishes_no_event() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        let result = state.login(&audited_login_request("wrong-password")).await;
+
+        assert!(result.is_err());
+        assert!(state.events.published().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_logout_publishes_event() {
+        let user_id = uuid::Uuid::new_v4();
+        let token_uuid = uuid::Uuid::new_v4();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        state
+            .logout(&LogoutRequest::new(token_uuid).with_user(Some(user_id)))
+            .await
+            .unwrap();
+
+        let events = state.events.published().await;
+        assert_eq!(events[0].event_type, DomainEventType::UserLoggedOut);
+        assert_eq!(events[0].user_id, Some(user_id));
+        assert_eq!(events[0].data["token_uuid"], token_uuid.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_login_tokens_share_session_id() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        let response = state
+            .login(&audited_login_request("password"))
+            .await
+            .unwrap();
+
+        let access_token = verify_jwt(
+            &state.config.access_token_public_key,
+            &response.access_token,
+        )
+        .unwrap();
+        let refresh_token = verify_jwt(
+            &state.config.refresh_token_public_key,
+            &response.refresh_token,
+        )
+        .unwrap();
+        assert!(session_id(&access_token.claims).is_some());
+        assert_eq!(
+            access_token.claims.get(SESSION_ID_CLAIM),
+            refresh_token.claims.get(SESSION_ID_CLAIM)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_access_token_names_issuer() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", &hash_password("password").unwrap()),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        let response = state
+            .login(&audited_login_request("password"))
+            .await
+            .unwrap();
+
+        let access_token = verify_jwt(
+            &state.config.access_token_public_key,
+            &response.access_token,
+        )
+        .unwrap();
+        assert_eq!(
+            access_token.claims.get("iss"),
+            Some(&serde_json::json!(state
+                .config
+                .oidc_issuer
+                .trim_end_matches('/')))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_logout_broadcasts_revocation() {
+        let user_id = uuid::Uuid::new_v4();
+        let token_uuid = uuid::Uuid::new_v4();
+        let session_id = uuid::Uuid::new_v4();
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+
+        state
+            .logout(
+                &LogoutRequest::new(token_uuid)
+                    .with_user(Some(user_id))
+                    .with_session(Some(session_id), Some(1_900_000_000)),
+            )
+            .await
+            .unwrap();
+
+        let revocations = state.events.revocations().await;
+        assert_eq!(revocations.len(), 1);
+        assert_eq!(revocations[0].token_uuid, Some(token_uuid));
+        assert_eq!(revocations[0].session_id, Some(session_id));
+        assert_eq!(revocations[0].user_id, Some(user_id));
+        assert_eq!(revocations[0].expires_at, 1_900_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_logout_broadcasts_session_revocation_until_max_age() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+        let now = chrono::Utc::now().timestamp();
+
+        state
+            .logout(
+                &LogoutRequest::new(uuid::Uuid::new_v4())
+                    .with_session(Some(uuid::Uuid::new_v4()), Some(now + 1)),
+            )
+            .await
+            .unwrap();
+
+        // Access tokens refreshed earlier in the session may outlive the one logged out.
+        let revocations = state.events.revocations().await;
+        assert!(revocations[0].expires_at >= now + state.config.access_token_max_age * 60);
+    }
+
+    #[tokio::test]
+    async fn test_logout_without_expiry_broadcasts_revocation_until_max_age() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::success(),
+            MockAuditSink::success(),
+        );
+        let before = chrono::Utc::now().timestamp();
+
+        state
+            .logout(&LogoutRequest::new(uuid::Uuid::new_v4()))
+            .await
+            .unwrap();
+
+        let revocations = state.events.revocations().await;
+        assert!(revocations[0].expires_at >= before + state.config.access_token_max_age * 60);
+    }
+
+    #[tokio::test]
+    async fn test_failed_logout_broadcasts_no_revocation() {
+        let state = audited_service(
+            MockAuthRepository::success("adrian@email.com", "password"),
+            MockCacheRepository::failure(),
+            MockAuditSink::success(),
+        );
+
+        let result = state
+            .logout(&LogoutRequest::new(uuid::Uuid::new_v4()))
+            .await;
+
+        assert!(result.is_err());
+        assert!(state.events.revocations().await.is_empty());
+    }
 }
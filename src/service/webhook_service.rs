@@ -0,0 +1,102 @@
+use crate::{
+    api::utils::{security::generate_random_token, webhook_client::send_webhook},
+    domain::{
+        model::webhook::{
+            CreateWebhookRequest, CreateWebhookResponse, ListWebhookDeliveriesRequest,
+            NewWebhookSubscription, WebhookAttempt, WebhookDelivery, WebhookError,
+            WebhookSubscription, WEBHOOK_BATCH_SIZE, WEBHOOK_LEASE_SECONDS, WEBHOOK_SECRET_PREFIX,
+        },
+        repositories::{
+            audit_sink::AuditSink, auth_repository::AuthRepository,
+            cache_repository::CacheRepository, event_publisher::EventPublisher,
+        },
+        webhook_service::WebhookService,
+    },
+    service::auth_service::Service,
+};
+
+impl<R, C, A, P> WebhookService for Service<R, C, A, P>
+where
+    R: AuthRepository,
+    C: CacheRepository,
+    A: AuditSink,
+    P: EventPublisher,
+{
+    async fn create_webhook(
+        &self,
+        request: &CreateWebhookRequest,
+    ) -> Result<CreateWebhookResponse, WebhookError> {
+        request.validate()?;
+
+        let secret = format!("{}{}", WEBHOOK_SECRET_PREFIX, generate_random_token());
+        let subscription = self
+            .repo
+            .create_webhook_subscription(&NewWebhookSubscription {
+                url: request.url.clone(),
+                events: request.events.clone(),
+                secret: secret.clone(),
+                created_by: *request.created_by.get(),
+            })
+            .await?;
+
+        Ok(CreateWebhookResponse {
+            secret,
+            subscription,
+        })
+    }
+
+    async fn list_webhooks(&self) -> Result<Vec<WebhookSubscription>, WebhookError> {
+        Ok(self.repo.list_webhook_subscriptions().await?)
+    }
+
+    async fn delete_webhook(&self, subscription_id: &uuid::Uuid) -> Result<(), WebhookError> {
+        Ok(self
+            .repo
+            .delete_webhook_subscription(subscription_id)
+            .await?)
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        request: &ListWebhookDeliveriesRequest,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        Ok(self.repo.list_webhook_deliveries(request).await?)
+    }
+
+    async fn retry_webhook_delivery(
+        &self,
+        subscription_id: &uuid::Uuid,
+        delivery_id: &uuid::Uuid,
+    ) -> Result<WebhookDelivery, WebhookError> {
+        Ok(self
+            .repo
+            .retry_webhook_delivery(subscription_id, delivery_id)
+            .await?)
+    }
+
+    async fn deliver_pending_webhooks(&self) -> Result<usize, WebhookError> {
+        let deliveries = self
+            .repo
+            .claim_webhook_deliveries(WEBHOOK_BATCH_SIZE, WEBHOOK_LEASE_SECONDS)
+            .await?;
+
+        for delivery in &deliveries {
+            let attempt = match send_webhook(delivery).await {
+                Ok(()) => WebhookAttempt::delivered(delivery),
+                Err(e) => WebhookAttempt::failed(
+                    delivery,
+                    &format!("{:#}", e),
+                    self.config.webhook_max_attempts,
+                    self.config.webhook_backoff_seconds,
+                ),
+            };
+
+            // A delivery whose attempt cannot be recorded is sent again once its lease ends.
+            if let Err(e) = self.repo.record_webhook_attempt(&attempt).await {
+                tracing::error!("Failed to record webhook attempt {:?}: {:?}", attempt, e);
+            }
+        }
+
+        Ok(deliveries.len())
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap},
+};
+use axum_extra::extract::CookieJar;
+use serde_json::Value;
+
+use crate::{
+    domain::model::{
+        custom_claims::CustomClaims, introspection::IntrospectionResponse,
+        principal::PrincipalType, token::TokenDetails,
+    },
+    verifier::{token_verifier::TokenVerifier, verify_error::VerifyError},
+};
+
+/// The subject of a verified access token, as seen by a downstream service.
+///
+/// Handlers take it as an extractor. It is read from the request extensions, where
+/// `VerifierLayer` put it, or else verified with the `TokenVerifier` added to the router as an
+/// `Extension<Arc<TokenVerifier>>`.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedUser {
+    /// The `sub` of the token: a user id, or a client id for client credentials tokens.
+    pub subject: uuid::Uuid,
+    pub token_uuid: uuid::Uuid,
+    pub principal_type: PrincipalType,
+    pub scopes: Vec<String>,
+    pub audience: Vec<String>,
+    pub expires_at: Option<i64>,
+    /// The custom claims of the token. Tokens checked by introspection only carry those the
+    /// introspection endpoint returns, `scope`, `client_id` and `aud`.
+    pub claims: CustomClaims,
+}
+
+impl AuthenticatedUser {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope)
+    }
+
+    pub(crate) fn from_token_details(details: TokenDetails) -> AuthenticatedUser {
+        AuthenticatedUser {
+            subject: details.user_id,
+            token_uuid: details.token_uuid,
+            principal_type: details.principal_type(),
+            scopes: scopes(details.claims.get("scope")),
+            audience: audience(details.claims.get("aud")),
+            expires_at: details.expires_in,
+            claims: details.claims,
+        }
+    }
+
+    pub(crate) fn from_introspection(
+        response: IntrospectionResponse,
+    ) -> Result<AuthenticatedUser, VerifyError> {
+        let invalid = |reason: &str| VerifyError::InvalidToken {
+            reason: reason.to_string(),
+        };
+        if !response.active {
+            return Err(invalid("Access token is not active"));
+        }
+        let subject = response
+            .sub
+            .as_deref()
+            .and_then(|sub| uuid::Uuid::parse_str(sub).ok())
+            .ok_or_else(|| invalid("Introspection did not return a subject"))?;
+        let token_uuid = response
+            .jti
+            .as_deref()
+            .and_then(|jti| uuid::Uuid::parse_str(jti).ok())
+            .ok_or_else(|| invalid("Introspection did not return a token id"))?;
+
+        let mut claims = CustomClaims::default();
+        if let Some(scope) = &response.scope {
+            claims.insert("scope", serde_json::json!(scope));
+        }
+        if let Some(client_id) = &response.client_id {
+            claims.insert("client_id", serde_json::json!(client_id));
+        }
+        if let Some(aud) = &response.aud {
+            claims.insert("aud", aud.clone());
+        }
+
+        Ok(AuthenticatedUser {
+            subject,
+            token_uuid,
+            principal_type: response.principal_type.unwrap_or(PrincipalType::User),
+            scopes: scopes(claims.get("scope")),
+            audience: audience(claims.get("aud")),
+            expires_at: response.exp,
+            claims,
+        })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthenticatedUser
+where
+    S: Send + Sync,
+{
+    type Rejection = VerifyError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(user) = parts.extensions.get::<AuthenticatedUser>() {
+            return Ok(user.clone());
+        }
+
+        let verifier = parts
+            .extensions
+            .get::<Arc<TokenVerifier>>()
+            .cloned()
+            .ok_or_else(|| {
+                VerifyError::Unknown(anyhow!(
+                    "AuthenticatedUser needs a VerifierLayer or an Extension<Arc<TokenVerifier>>"
+                ))
+            })?;
+
+        verifier.verify(&bearer_token(&parts.headers)?).await
+    }
+}
+
+/// Reads the access token from the `Authorization: Bearer` header, or from the `access_token`
+/// cookie the authentication service sets on login.
+pub fn bearer_token(headers: &HeaderMap) -> Result<String, VerifyError> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|auth_header| auth_header.to_str().ok())
+        .and_then(|auth_value| auth_value.strip_prefix("Bearer "))
+        .map(|token| token.to_string())
+        .or_else(|| {
+            CookieJar::from_headers(headers)
+                .get("access_token")
+                .map(|cookie| cookie.value().to_string())
+        })
+        .ok_or(VerifyError::MissingToken)
+}
+
+/// Splits a space separated `scope` claim.
+fn scopes(scope: Option<&Value>) -> Vec<String> {
+    scope
+        .and_then(|scope| scope.as_str())
+        .map(|scope| scope.split_whitespace().map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Reads an `aud` claim, which is either a single audience or an array of them.
+fn audience(aud: Option<&Value>) -> Vec<String> {
+    match aud {
+        Some(Value::String(aud)) => vec![aud.clone()],
+        Some(Value::Array(auds)) => auds
+            .iter()
+            .filter_map(|aud| aud.as_str().map(|aud| aud.to_string()))
+            .collect(),
+        _ => vec![],
+    }
+}
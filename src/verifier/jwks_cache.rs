@@ -0,0 +1,81 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::{
+    api::utils::http_client::http_client,
+    domain::model::jwks::{Jwk, JwkSet},
+};
+
+/// Shortest time between two fetches of the JWKS, so tokens with made up `kid`s cannot turn
+/// every request into a request to the authentication service.
+pub const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The signing keys published at a JWKS endpoint, fetched on first use and kept by `kid`.
+///
+/// A token signed with a `kid` that is not cached yet triggers a new fetch, which is how key
+/// rotations are picked up, but no more often than `JWKS_MIN_REFRESH_INTERVAL`.
+#[derive(Debug)]
+pub struct JwksCache {
+    url: String,
+    keys: RwLock<HashMap<String, Jwk>>,
+    refreshed_at: Mutex<Option<Instant>>,
+}
+
+impl JwksCache {
+    pub fn new(url: &str) -> JwksCache {
+        JwksCache {
+            url: url.to_string(),
+            keys: RwLock::new(HashMap::new()),
+            refreshed_at: Mutex::new(None),
+        }
+    }
+
+    /// Returns the key named `kid`, fetching the JWKS again when it is unknown, or `None` when
+    /// the JWKS does not hold it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JWKS cannot be fetched.
+    pub async fn key(&self, kid: &str) -> Result<Option<Jwk>> {
+        if let Some(key) = self.keys.read().await.get(kid) {
+            return Ok(Some(key.clone()));
+        }
+
+        self.refresh().await?;
+
+        Ok(self.keys.read().await.get(kid).cloned())
+    }
+
+    async fn refresh(&self) -> Result<()> {
+        // Holding the lock while fetching lets concurrent requests with the same new kid wait
+        // for one fetch instead of each starting their own.
+        let mut refreshed_at = self.refreshed_at.lock().await;
+        if refreshed_at.is_some_and(|at| at.elapsed() < JWKS_MIN_REFRESH_INTERVAL) {
+            return Ok(());
+        }
+
+        let jwks: JwkSet = http_client()
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch JWKS from {}", self.url))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid JWKS from {}", self.url))?;
+
+        *self.keys.write().await = jwks
+            .keys
+            .into_iter()
+            .map(|key| (key.kid.clone(), key))
+            .collect();
+        *refreshed_at = Some(Instant::now());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+use crate::verifier::token_verifier::TokenVerifier;
+
+/// A tower layer that verifies the access token of every request with a `TokenVerifier`.
+///
+/// Requests with a valid token reach the inner service with an `AuthenticatedUser` in their
+/// extensions, for handlers to extract. Others are answered right away, with 401 Unauthorized,
+/// or 403 Forbidden when the token lacks a required scope.
+#[derive(Clone, Debug)]
+pub struct VerifierLayer {
+    verifier: Arc<TokenVerifier>,
+}
+
+impl VerifierLayer {
+    pub fn new(verifier: Arc<TokenVerifier>) -> VerifierLayer {
+        VerifierLayer { verifier }
+    }
+}
+
+impl<S> Layer<S> for VerifierLayer {
+    type Service = VerifierService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        VerifierService {
+            inner,
+            verifier: self.verifier.clone(),
+        }
+    }
+}
+
+/// The service wrapped by `VerifierLayer`.
+#[derive(Clone, Debug)]
+pub struct VerifierService<S> {
+    inner: S,
+    verifier: Arc<TokenVerifier>,
+}
+
+impl<S> Service<Request> for VerifierService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let verifier = self.verifier.clone();
+        // The clone was not polled for readiness, so the ready service is the one kept for
+        // this request, as the tower documentation recommends.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            match verifier.authenticate(req.headers()).await {
+                Ok(user) => {
+                    req.extensions_mut().insert(user);
+                    inner.call(req).await
+                }
+                Err(e) => Ok(e.into_response()),
+            }
+        })
+    }
+}
@@ -0,0 +1,5 @@
+pub mod authenticated_user;
+pub mod jwks_cache;
+pub mod layer;
+pub mod token_verifier;
+pub mod verify_error;
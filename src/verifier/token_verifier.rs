@@ -0,0 +1,281 @@
+use anyhow::Context;
+use axum::http::HeaderMap;
+use url::form_urlencoded;
+
+use crate::{
+    api::utils::{
+        http_client::http_client,
+        jwk::decoding_key,
+        jwt::{token_validation, verify_jwt_with_key},
+    },
+    domain::model::{
+        introspection::IntrospectionResponse, session_revocation::RevocationDenylist,
+        token::TokenDetails,
+    },
+    verifier::{
+        authenticated_user::{bearer_token, AuthenticatedUser},
+        jwks_cache::JwksCache,
+        verify_error::VerifyError,
+    },
+};
+
+/// How a `TokenVerifier` checks access tokens.
+#[derive(Debug)]
+pub enum TokenSource {
+    /// Verifies signatures locally, with the keys published at the JWKS endpoint of the
+    /// authentication service, and requires the `iss` of tokens to be its `issuer`. Tokens
+    /// revoked before they expire are still accepted, unless the verifier has a
+    /// `RevocationDenylist`.
+    Jwks { keys: JwksCache, issuer: String },
+    /// Asks the introspection endpoint of the authentication service on every request,
+    /// authenticating as a confidential OAuth client. Revoked tokens are rejected immediately.
+    Introspection {
+        url: String,
+        client_id: String,
+        client_secret: String,
+    },
+}
+
+/// Verifies the access tokens issued by the authentication service, for downstream services.
+///
+/// On top of the checks of `verify_jwt`, tokens verified with the JWKS must name the
+/// authentication service as their `iss`. A verifier can also require an audience, which must
+/// be one of the token's `aud`, and scopes, which must all be in its `scope`. Tokens issued to
+/// OAuth clients have the `client_id` of the client as their `aud`, while the tokens of user
+/// logins and personal access tokens have none, and never pass an audience check.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use authentication_service::verifier::{
+///     authenticated_user::AuthenticatedUser, layer::VerifierLayer,
+///     token_verifier::TokenVerifier,
+/// };
+/// use axum::{routing::get, Router};
+///
+/// async fn orders(user: AuthenticatedUser) -> String {
+///     format!("Orders of {}", user.subject)
+/// }
+///
+/// let verifier = TokenVerifier::jwks(
+///     "https://auth.example.com",
+///     "https://auth.example.com/.well-known/jwks.json",
+/// )
+///     .with_audience("orders")
+///     .with_required_scopes(&["orders:read"]);
+///
+/// let app: Router = Router::new()
+///     .route("/orders", get(orders))
+///     .layer(VerifierLayer::new(Arc::new(verifier)));
+/// ```
+#[derive(Debug)]
+pub struct TokenVerifier {
+    source: TokenSource,
+    audience: Option<String>,
+    required_scopes: Vec<String>,
+    denylist: Option<RevocationDenylist>,
+}
+
+impl TokenVerifier {
+    pub fn new(source: TokenSource) -> TokenVerifier {
+        TokenVerifier {
+            source,
+            audience: None,
+            required_scopes: vec![],
+            denylist: None,
+        }
+    }
+
+    /// A verifier checking signatures with the keys published at `jwks_url`, for the tokens
+    /// issued by `issuer`, the `OIDC_ISSUER` of the authentication service.
+    pub fn jwks(issuer: &str, jwks_url: &str) -> TokenVerifier {
+        TokenVerifier::new(TokenSource::Jwks {
+            keys: JwksCache::new(jwks_url),
+            issuer: issuer.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// A verifier asking the introspection endpoint at `url`, as the OAuth client `client_id`.
+    pub fn introspection(url: &str, client_id: &str, client_secret: &str) -> TokenVerifier {
+        TokenVerifier::new(TokenSource::Introspection {
+            url: url.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+        })
+    }
+
+    /// Only accepts the tokens issued to the OAuth client `audience`.
+    pub fn with_audience(self, audience: &str) -> TokenVerifier {
+        TokenVerifier {
+            audience: Some(audience.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_required_scopes(self, scopes: &[&str]) -> TokenVerifier {
+        TokenVerifier {
+            required_scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            ..self
+        }
+    }
+
+    /// Rejects the tokens revoked in `denylist`, kept up to date by a `RevocationSubscriber`.
+    /// Introspection already reports revoked tokens as inactive, so only tokens verified with
+    /// the JWKS are checked against it.
+    pub fn with_denylist(self, denylist: RevocationDenylist) -> TokenVerifier {
+        TokenVerifier {
+            denylist: Some(denylist),
+            ..self
+        }
+    }
+
+    /// Verifies the access token of a request, read by `bearer_token`.
+    pub async fn authenticate(
+        &self,
+        headers: &HeaderMap,
+    ) -> Result<AuthenticatedUser, VerifyError> {
+        self.verify(&bearer_token(headers)?).await
+    }
+
+    /// Verifies `token` and returns its subject.
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerifyError::InvalidToken` for tokens that are malformed, signed with an unknown
+    /// key, expired, inactive or revoked, `VerifyError::InvalidAudience` and
+    /// `VerifyError::InsufficientScope` for tokens failing the configured checks, and
+    /// `VerifyError::Unknown` when the authentication service cannot be reached.
+    pub async fn verify(&self, token: &str) -> Result<AuthenticatedUser, VerifyError> {
+        let user = match &self.source {
+            TokenSource::Jwks { keys, issuer } => {
+                let details =
+                    verify_with_jwks(keys, issuer, self.audience.as_deref(), token).await?;
+                if self
+                    .denylist
+                    .as_ref()
+                    .is_some_and(|denylist| denylist.is_revoked(&details))
+                {
+                    return Err(VerifyError::InvalidToken {
+                        reason: "Access token was revoked".to_string(),
+                    });
+                }
+
+                AuthenticatedUser::from_token_details(details)
+            }
+            TokenSource::Introspection {
+                url,
+                client_id,
+                client_secret,
+            } => {
+                let user = verify_with_introspection(url, client_id, client_secret, token).await?;
+                if let Some(audience) = &self.audience {
+                    if !user.audience.contains(audience) {
+                        return Err(VerifyError::InvalidAudience {
+                            audience: audience.clone(),
+                        });
+                    }
+                }
+
+                user
+            }
+        };
+
+        if let Some(scope) = self
+            .required_scopes
+            .iter()
+            .find(|scope| !user.has_scope(scope))
+        {
+            return Err(VerifyError::InsufficientScope {
+                scope: scope.clone(),
+            });
+        }
+
+        Ok(user)
+    }
+}
+
+/// Verifies the signature, `exp`, `iss` and, when one is required, `aud` of `token`.
+async fn verify_with_jwks(
+    jwks: &JwksCache,
+    issuer: &str,
+    audience: Option<&str>,
+    token: &str,
+) -> Result<TokenDetails, VerifyError> {
+    let invalid = |reason: String| VerifyError::InvalidToken { reason };
+
+    let header = jsonwebtoken::decode_header(token)
+        .map_err(|e| invalid(format!("Malformed token: {}", e)))?;
+    let kid = header
+        .kid
+        .ok_or_else(|| invalid("Access token does not name its signing key".to_string()))?;
+    let jwk = jwks
+        .key(&kid)
+        .await?
+        .ok_or_else(|| invalid(format!("Unknown signing key {}", kid)))?;
+
+    let mut validation = token_validation();
+    validation.set_issuer(&[issuer]);
+    match audience {
+        Some(audience) => {
+            validation.set_audience(&[audience]);
+            validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+            validation.validate_aud = true;
+        }
+        None => validation.set_required_spec_claims(&["exp", "iss"]),
+    }
+
+    verify_jwt_with_key(&decoding_key(&jwk)?, token, &validation).map_err(|e| {
+        use jsonwebtoken::errors::ErrorKind;
+
+        match e
+            .downcast_ref::<jsonwebtoken::errors::Error>()
+            .map(|e| e.kind())
+        {
+            Some(ErrorKind::InvalidAudience) => VerifyError::InvalidAudience {
+                audience: audience.unwrap_or_default().to_string(),
+            },
+            Some(ErrorKind::MissingRequiredClaim(claim)) if claim == "aud" => {
+                VerifyError::InvalidAudience {
+                    audience: audience.unwrap_or_default().to_string(),
+                }
+            }
+            _ => invalid(e.to_string()),
+        }
+    })
+}
+
+async fn verify_with_introspection(
+    url: &str,
+    client_id: &str,
+    client_secret: &str,
+    token: &str,
+) -> Result<AuthenticatedUser, VerifyError> {
+    // RFC 6749 section 2.3.1 form encodes client credentials before HTTP Basic encodes them.
+    let form_encode = |value: &str| form_urlencoded::byte_serialize(value.as_bytes()).collect();
+
+    let response: IntrospectionResponse = http_client()
+        .post(url)
+        .basic_auth(
+            form_encode(client_id),
+            Some::<String>(form_encode(client_secret)),
+        )
+        .form(&[("token", token), ("token_type_hint", "access_token")])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to introspect token at {}", url))?
+        .json()
+        .await
+        .with_context(|| format!("Invalid introspection response from {}", url))?;
+
+    // Refresh tokens are active too, but do not authorize requests.
+    if response.active && response.token_type.as_deref() != Some("Bearer") {
+        return Err(VerifyError::InvalidToken {
+            reason: "Token is not an access token".to_string(),
+        });
+    }
+
+    AuthenticatedUser::from_introspection(response)
+}
@@ -0,0 +1,24 @@
+use axum::response::IntoResponse;
+use thiserror::Error;
+
+use crate::api::model::api_error::ApiError;
+
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    #[error("You are not logged in")]
+    MissingToken,
+    #[error("Invalid access token: {reason}")]
+    InvalidToken { reason: String },
+    #[error("Access token is not intended for {audience}")]
+    InvalidAudience { audience: String },
+    #[error("Access token is missing the {scope} scope")]
+    InsufficientScope { scope: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for VerifyError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
@@ -1,14 +1,50 @@
 use authentication_service::{
-    api::utils::status::Status, application::run, domain::model::user::FilteredUser,
+    api::utils::{
+        jwk::public_jwk,
+        jwt::{generate_id_token, verify_jwt},
+        pkce::code_challenge,
+        security::hash_password,
+        status::Status,
+        webhook_client::sign_payload,
+    },
+    application::run,
+    domain::model::{
+        federation::FederatedProvider,
+        id_token::IdTokenClaims,
+        ldap::{LdapConfig, LdapMode},
+        registration::RegistrationMode,
+        saml::SamlIdentityProvider,
+        session_revocation::RevocationDenylist,
+        user::{FilteredUser, UserKind},
+    },
+    grpc::proto::{
+        authentication_client::AuthenticationClient, GetUserRequest, IntrospectRequest,
+        RevokeSessionRequest, VerifyTokenRequest,
+    },
     helper::config::Config,
+    repositories::revocation_subscriber::RevocationSubscriber,
+};
+#[cfg(feature = "verifier")]
+use authentication_service::{
+    domain::model::principal::PrincipalType,
+    verifier::{
+        authenticated_user::AuthenticatedUser, layer::VerifierLayer, token_verifier::TokenVerifier,
+        verify_error::VerifyError,
+    },
 };
 use dotenv::dotenv;
 use redis::{AsyncCommands, Client};
-use reqwest::{header::AUTHORIZATION, StatusCode};
+use reqwest::{
+    header::{AUTHORIZATION, COOKIE},
+    StatusCode,
+};
 use serde::Deserialize;
 use sqlx::{postgres::PgPoolOptions, Executor, Pool, Postgres};
 use std::net::SocketAddr;
+#[cfg(feature = "verifier")]
+use std::sync::Arc;
 use tokio::net::TcpListener;
+use tonic::{transport::Channel, Code};
 
 #[tokio::test]
 async fn test_register_success() {
@@ -119,9 +155,69 @@ async fn test_login_failure() {
         "password": "12345678"
     });
 
-    let response = client.post(&login_url).json(&body).send().await.unwrap();
+    let response = client
+        .post(&login_url)
+        .header("x-request-id", "login-failure-request")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = response.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(body["status"], "Failure");
+    assert_eq!(body["error"]["code"], "login.invalid_credentials");
+    assert_eq!(body["error"]["message"], "Invalid credentials");
+    assert_eq!(body["error"]["request_id"], "login-failure-request");
+}
+
+#[tokio::test]
+async fn test_json_rejections_are_api_errors() {
+    let address = spawn_server().await;
+
+    let register_url = format!("http://{}/api/register", address);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(&register_url)
+        .json(&serde_json::json!({ "email": "rejected@test.com" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let request_id = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = response.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(body["error"]["code"], "request.invalid_body");
+    assert_eq!(body["error"]["field_errors"][0]["field"], "password");
+    assert_eq!(body["error"]["request_id"], request_id);
+
+    let response = client
+        .post(&register_url)
+        .body("email=rejected@test.com")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let body = response.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(body["error"]["code"], "request.unsupported_media_type");
+}
+
+#[tokio::test]
+async fn test_missing_access_token_error_code() {
+    let address = spawn_server().await;
+
+    let response = reqwest::get(format!("http://{}/api/users/me", address))
+        .await
+        .unwrap();
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = response.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(body["error"]["code"], "auth.missing_credentials");
 }
 
 #[tokio::test]
@@ -370,87 +466,4754 @@ async fn test_logout_success() {
 }
 
 #[tokio::test]
-async fn test_healthcheck() {
+async fn test_oauth_authorization_code_flow_success() {
     let address = spawn_server().await;
 
-    let url = format!("http://{}/api/healthcheck", address);
+    let client_id = "oauth_code_flow_success_client";
+    let redirect_uri = "http://localhost:8080/callback";
+    create_oauth_client(client_id, redirect_uri).await;
 
-    let response = reqwest::get(url).await.unwrap();
+    let client = reqwest::Client::builder()
+        .cookie_store(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-}
+    let email = "oauth_code_flow_success@test.com";
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
 
-#[cfg(test)]
-async fn spawn_server() -> SocketAddr {
-    let listener = TcpListener::bind("0.0.0.0:0").await.unwrap();
-    let address = listener.local_addr().unwrap();
-    dotenv().ok();
-    let config = Config::init();
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+    let _ = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await;
 
-    tokio::spawn(async move {
-        run(listener, config).await.expect("Failed to run app");
-    });
+    let code_verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    let response = client
+        .get(format!("http://{}/oauth/authorize", address))
+        .query(&[
+            ("response_type", "code"),
+            ("client_id", client_id),
+            ("redirect_uri", redirect_uri),
+            ("scope", "openid email profile"),
+            ("state", "xyz"),
+            ("code_challenge", &code_challenge(code_verifier)),
+            ("code_challenge_method", "S256"),
+        ])
+        .send()
+        .await
+        .unwrap();
 
-    address
-}
+    let location = url::Url::parse(response.headers()["location"].to_str().unwrap()).unwrap();
+    let code = location
+        .query_pairs()
+        .find(|(name, _)| name == "code")
+        .map(|(_, value)| value.to_string())
+        .unwrap();
 
-#[cfg(test)]
-async fn revoke_token_from_redis(access_token: &str) {
-    use authentication_service::api::utils::jwt::verify_jwt;
+    let token_form = [
+        ("grant_type", "authorization_code"),
+        ("client_id", client_id),
+        ("code", &code),
+        ("redirect_uri", redirect_uri),
+        ("code_verifier", code_verifier),
+    ];
 
-    let config = Config::init();
-    let access_token_uuid = verify_jwt(&config.access_token_public_key, access_token)
+    let response: OAuthTokenData = client
+        .post(format!("http://{}/oauth/token", address))
+        .form(&token_form)
+        .send()
+        .await
         .unwrap()
-        .token_uuid;
-    let mut redis_client = Client::open(config.redis_url.to_owned())
+        .json()
+        .await
+        .unwrap();
+
+    let reused_code_response = client
+        .post(format!("http://{}/oauth/token", address))
+        .form(&token_form)
+        .send()
+        .await
+        .unwrap();
+
+    let me: GenericResponse<FilteredUser> = reqwest::Client::new()
+        .get(format!("http://{}/api/users/me", address))
+        .header(AUTHORIZATION, format!("Bearer {}", response.access_token))
+        .send()
+        .await
         .unwrap()
-        .get_multiplexed_async_connection()
+        .json()
         .await
         .unwrap();
-    let _: i64 = redis_client
-        .del(access_token_uuid.to_string())
+
+    // The client's scoped refresh token cannot be exchanged for a login session.
+    let session_refresh_response = reqwest::Client::new()
+        .get(format!("http://{}/api/refresh", address))
+        .header(
+            COOKIE,
+            format!("refresh_token={}", response.refresh_token.clone().unwrap()),
+        )
+        .send()
         .await
         .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(session_refresh_response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        location
+            .query_pairs()
+            .find(|(n, _)| n == "state")
+            .unwrap()
+            .1,
+        "xyz"
+    );
+    assert_eq!(response.scope, "openid email profile");
+    assert_eq!(reused_code_response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(me.data.unwrap().email, email);
 }
 
-#[cfg(test)]
-async fn clean_up_db<F, Fut>(query: F)
-where
-    F: Fn(Pool<Postgres>) -> Fut,
-    Fut: std::future::Future<Output = ()>,
-{
-    let config = Config::init();
-    let db = connect_to_database(&config).await;
-    query(db).await;
+#[tokio::test]
+async fn test_oauth_authorize_unregistered_redirect_uri_failure() {
+    let address = spawn_server().await;
+
+    let client_id = "oauth_redirect_uri_failure_client";
+    create_oauth_client(client_id, "http://localhost:8080/callback").await;
+
+    let client = reqwest::Client::builder()
+        .cookie_store(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let email = "oauth_redirect_uri_failure@test.com";
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
+
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+    let _ = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await;
+
+    let response = client
+        .get(format!("http://{}/oauth/authorize", address))
+        .query(&[
+            ("response_type", "code"),
+            ("client_id", client_id),
+            ("redirect_uri", "https://evil.example.com/callback"),
+            ("code_challenge", "challenge"),
+            ("code_challenge_method", "S256"),
+        ])
+        .send()
+        .await
+        .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(response.headers().get("location").is_none());
 }
 
-#[cfg(test)]
-pub async fn connect_to_database(config: &Config) -> Pool<Postgres> {
-    match PgPoolOptions::new()
-        .max_connections(10)
-        .connect(&config.database_url)
+#[tokio::test]
+async fn test_oauth_token_unsupported_grant_type_failure() {
+    let address = spawn_server().await;
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/oauth/token", address))
+        .form(&[("grant_type", "password"), ("client_id", "any")])
+        .send()
         .await
-    {
-        Ok(pool) => {
-            println!("Enstablished db connection");
-            pool
-        }
-        Err(err) => {
-            println!("Failed to connecto to db with error: {:?}", err);
-            std::process::exit(1)
-        }
-    }
+        .unwrap();
+
+    let status = response.status();
+    let error: OAuthErrorData = response.json().await.unwrap();
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error.error, "unsupported_grant_type");
 }
 
-#[cfg(test)]
-#[derive(Debug, Deserialize)]
-struct GenericResponse<T> {
-    status: Status,
-    data: Option<T>,
+#[tokio::test]
+async fn test_oauth_client_credentials_success() {
+    let address = spawn_server().await;
+    let client_id = "client_credentials_success";
+    let client_secret = "client credentials secret";
+
+    create_confidential_client(client_id, client_secret).await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("http://{}/oauth/token", address))
+        .basic_auth(client_id, Some(client_secret.replace(' ', "+")))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("scope", "reports:read"),
+        ])
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let token: OAuthTokenData = response.json().await.unwrap();
+    assert_eq!(token.scope, "reports:read");
+
+    let me_response = client
+        .get(format!("http://{}/api/users/me", address))
+        .header(AUTHORIZATION, format!("Bearer {}", token.access_token))
+        .send()
+        .await
+        .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(me_response.status(), StatusCode::FORBIDDEN);
 }
 
-#[cfg(test)]
-#[derive(Debug, Deserialize)]
-struct AccessTokenData {
-    access_token: String,
+#[tokio::test]
+async fn test_oauth_client_credentials_invalid_secret_failure() {
+    let address = spawn_server().await;
+    let client_id = "client_credentials_invalid_secret";
+
+    create_confidential_client(client_id, "client secret").await;
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/oauth/token", address))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", client_id),
+            ("client_secret", "wrong secret"),
+        ])
+        .send()
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let error: OAuthErrorData = response.json().await.unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(error.error, "invalid_client");
+}
+
+#[tokio::test]
+async fn test_oauth_introspect_and_revoke_success() {
+    let address = spawn_server().await;
+    let client_id = "introspect_and_revoke_success";
+    let client_secret = "introspection secret";
+
+    create_confidential_client(client_id, client_secret).await;
+
+    let client = reqwest::Client::new();
+
+    let token: OAuthTokenData = client
+        .post(format!("http://{}/oauth/token", address))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let introspect = |token: String| {
+        client
+            .post(format!("http://{}/oauth/introspect", address))
+            .basic_auth(client_id, Some(client_secret.replace(' ', "+")))
+            .form(&[("token", token)])
+            .send()
+    };
+
+    let active: IntrospectionData = introspect(token.access_token.clone())
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let revoke_response = client
+        .post(format!("http://{}/oauth/revoke", address))
+        .form(&[
+            ("token", token.access_token.as_str()),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .unwrap();
+
+    let revoked: IntrospectionData = introspect(token.access_token)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert!(active.active);
+    assert_eq!(active.client_id.as_deref(), Some(client_id));
+    assert_eq!(active.scope.as_deref(), Some("reports:read reports:write"));
+    assert_eq!(revoke_response.status(), StatusCode::OK);
+    assert!(!revoked.active);
+    assert!(revoked.client_id.is_none());
+}
+
+#[tokio::test]
+async fn test_oauth_introspect_without_client_authentication_failure() {
+    let address = spawn_server().await;
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/oauth/introspect", address))
+        .form(&[("token", "any")])
+        .send()
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let error: OAuthErrorData = response.json().await.unwrap();
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error.error, "invalid_request");
+}
+
+#[tokio::test]
+async fn test_oidc_relying_party_flow_success() {
+    let address = spawn_server().await;
+    let email = "oidc_relying_party_flow_success@test.com";
+    let rp = RelyingParty::new(address, "oidc_relying_party_flow_success_client").await;
+    rp.login(email).await;
+
+    let metadata = rp.discover().await;
+    assert_eq!(metadata.issuer, format!("http://{}", address));
+
+    let location = rp
+        .authorize(
+            &metadata,
+            &[("scope", "openid email profile"), ("nonce", "n-0S6_WzA2Mj")],
+        )
+        .await;
+    let code = query_param(&location, "code").unwrap();
+    let tokens = rp.exchange(&metadata, &code).await;
+    let id_token = rp
+        .verify_id_token(&metadata, &tokens.id_token.unwrap())
+        .await;
+
+    let userinfo: serde_json::Value = rp
+        .client
+        .get(&metadata.userinfo_endpoint)
+        .header(AUTHORIZATION, format!("Bearer {}", tokens.access_token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    rp.clean_up(email).await;
+
+    let now = chrono::Utc::now().timestamp();
+    assert_eq!(
+        query_param(&location, "state").as_deref(),
+        Some("af0ifjsldkj")
+    );
+    assert_eq!(id_token["nonce"], "n-0S6_WzA2Mj");
+    assert_eq!(id_token["email"], email);
+    assert_eq!(id_token["email_verified"], false);
+    assert_eq!(id_token["amr"], serde_json::json!(["pwd"]));
+    assert!(id_token["acr"].is_string());
+    assert!(id_token["auth_time"].as_i64().unwrap() <= now);
+    assert_eq!(userinfo["sub"], id_token["sub"]);
+    assert_eq!(userinfo["email"], email);
+    assert!(userinfo["updated_at"].is_i64());
+}
+
+#[tokio::test]
+async fn test_oidc_userinfo_filtered_by_scope() {
+    let address = spawn_server().await;
+    let email = "oidc_userinfo_filtered_by_scope@test.com";
+    let rp = RelyingParty::new(address, "oidc_userinfo_filtered_by_scope_client").await;
+    rp.login(email).await;
+
+    let metadata = rp.discover().await;
+    let location = rp.authorize(&metadata, &[("scope", "openid")]).await;
+    let tokens = rp
+        .exchange(&metadata, &query_param(&location, "code").unwrap())
+        .await;
+
+    let userinfo: serde_json::Value = rp
+        .client
+        .get(&metadata.userinfo_endpoint)
+        .header(AUTHORIZATION, format!("Bearer {}", tokens.access_token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    rp.clean_up(email).await;
+
+    assert!(userinfo["sub"].is_string());
+    assert!(userinfo.get("email").is_none());
+    assert!(userinfo.get("updated_at").is_none());
+}
+
+#[tokio::test]
+async fn test_oidc_prompt_none_without_session_failure() {
+    let address = spawn_server().await;
+    let rp = RelyingParty::new(address, "oidc_prompt_none_without_session_client").await;
+
+    let metadata = rp.discover().await;
+    let location = rp
+        .authorize(&metadata, &[("scope", "openid"), ("prompt", "none")])
+        .await;
+
+    rp.clean_up("oidc_prompt_none_without_session@test.com")
+        .await;
+
+    assert!(location.starts_with(RelyingParty::REDIRECT_URI));
+    assert_eq!(
+        query_param(&location, "error").as_deref(),
+        Some("login_required")
+    );
+    assert_eq!(
+        query_param(&location, "state").as_deref(),
+        Some("af0ifjsldkj")
+    );
+}
+
+#[tokio::test]
+async fn test_oauth_authorize_with_scoped_token_failure() {
+    let address = spawn_server().await;
+    let email = "oauth_authorize_scoped_token@test.com";
+    let rp = RelyingParty::new(address, "oauth_authorize_scoped_token_client").await;
+    rp.login(email).await;
+
+    let metadata = rp.discover().await;
+    let location = rp.authorize(&metadata, &[("scope", "openid")]).await;
+    let tokens = rp
+        .exchange(&metadata, &query_param(&location, "code").unwrap())
+        .await;
+
+    let response = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+        .get(&metadata.authorization_endpoint)
+        .header(AUTHORIZATION, format!("Bearer {}", tokens.access_token))
+        .query(&[
+            ("response_type", "code"),
+            ("client_id", rp.client_id),
+            ("redirect_uri", RelyingParty::REDIRECT_URI),
+            ("scope", "openid email profile"),
+            ("state", "af0ifjsldkj"),
+            (
+                "code_challenge",
+                &code_challenge(RelyingParty::CODE_VERIFIER),
+            ),
+            ("code_challenge_method", "S256"),
+            ("prompt", "none"),
+        ])
+        .send()
+        .await
+        .unwrap();
+    let location = response.headers()["location"].to_str().unwrap().to_string();
+
+    rp.clean_up(email).await;
+
+    assert_eq!(
+        query_param(&location, "error").as_deref(),
+        Some("login_required")
+    );
+    assert_eq!(query_param(&location, "code"), None);
+}
+
+#[tokio::test]
+async fn test_oidc_prompt_login_redirects_to_login_page() {
+    let address = spawn_server().await;
+    let email = "oidc_prompt_login@test.com";
+    let rp = RelyingParty::new(address, "oidc_prompt_login_client").await;
+    rp.login(email).await;
+
+    let metadata = rp.discover().await;
+    let prompt_login = rp
+        .authorize(&metadata, &[("scope", "openid"), ("prompt", "login")])
+        .await;
+    let max_age = rp
+        .authorize(&metadata, &[("scope", "openid"), ("max_age", "3600")])
+        .await;
+
+    rp.clean_up(email).await;
+
+    assert!(prompt_login.starts_with(TEST_LOGIN_URL));
+    let return_to = query_param(&prompt_login, "return_to").unwrap();
+    assert!(return_to.starts_with("/oauth/authorize?"));
+    assert!(!return_to.contains("prompt="));
+    assert!(query_param(&max_age, "code").is_some());
+}
+
+#[tokio::test]
+async fn test_oauth_device_authorization_flow_success() {
+    let address = spawn_server().await;
+    let email = "device_flow_success@test.com";
+    let rp = RelyingParty::new(address, "device_flow_success").await;
+    rp.login(email).await;
+
+    let device: DeviceAuthorizationData = rp
+        .client
+        .post(format!("http://{}/oauth/device_authorization", address))
+        .form(&[("client_id", rp.client_id), ("scope", "openid email")])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let poll = || async {
+        rp.client
+            .post(format!("http://{}/oauth/token", address))
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("device_code", &device.device_code),
+                ("client_id", rp.client_id),
+            ])
+            .send()
+            .await
+            .unwrap()
+    };
+
+    let pending_response = poll().await;
+    let pending_status = pending_response.status();
+    let pending: OAuthErrorData = pending_response.json().await.unwrap();
+
+    let verification_status = rp
+        .browser
+        .post(format!("http://{}/oauth/device", address))
+        .json(&serde_json::json!({
+            "user_code": device.user_code.to_lowercase(),
+            "approve": true
+        }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let token_response = poll().await;
+    let token_status = token_response.status();
+    let token: OAuthTokenData = token_response.json().await.unwrap();
+
+    let reused: OAuthErrorData = poll().await.json().await.unwrap();
+
+    rp.clean_up(email).await;
+
+    assert_eq!(
+        device.verification_uri,
+        format!("http://{}/oauth/device", address)
+    );
+    assert_eq!(pending_status, StatusCode::BAD_REQUEST);
+    assert_eq!(pending.error, "authorization_pending");
+    assert_eq!(verification_status, StatusCode::OK);
+    assert_eq!(token_status, StatusCode::OK);
+    assert_eq!(token.scope, "openid email");
+    assert_eq!(reused.error, "expired_token");
+}
+
+#[tokio::test]
+async fn test_oauth_device_verification_with_scoped_token_failure() {
+    let address = spawn_server().await;
+    let email = "device_scoped_token@test.com";
+    let rp = RelyingParty::new(address, "device_scoped_token").await;
+    rp.login(email).await;
+
+    let metadata = rp.discover().await;
+    let location = rp.authorize(&metadata, &[("scope", "openid")]).await;
+    let tokens = rp
+        .exchange(&metadata, &query_param(&location, "code").unwrap())
+        .await;
+
+    let device: DeviceAuthorizationData = rp
+        .client
+        .post(format!("http://{}/oauth/device_authorization", address))
+        .form(&[
+            ("client_id", rp.client_id),
+            ("scope", "openid email profile"),
+        ])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/oauth/device", address))
+        .header(AUTHORIZATION, format!("Bearer {}", tokens.access_token))
+        .json(&serde_json::json!({
+            "user_code": device.user_code,
+            "approve": true
+        }))
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    let error: OAuthErrorData = response.json().await.unwrap();
+
+    rp.clean_up(email).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error.error, "access_denied");
+}
+
+#[tokio::test]
+async fn test_federated_login_success() {
+    let email = "federated_login_success@test.com";
+    let provider = MockIdentityProvider::spawn("federated-subject", email, true).await;
+    let address = spawn_server_with(|config| provider.configure(config)).await;
+
+    let browser = reqwest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    let login_url = format!(
+        "http://{}/api/federation/{}/login",
+        address,
+        MockIdentityProvider::NAME
+    );
+
+    let first_status = browser.get(&login_url).send().await.unwrap().status();
+
+    let me: GenericResponse<FilteredUser> = browser
+        .get(format!("http://{}/api/users/me", address))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let second_login: GenericResponse<AccessTokenData> = browser
+        .get(&login_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let config = Config::init();
+    let second_user_id = authentication_service::api::utils::jwt::verify_jwt(
+        &config.access_token_public_key,
+        &second_login.data.unwrap().access_token,
+    )
+    .unwrap()
+    .user_id;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(first_status, StatusCode::OK);
+    let me = me.data.unwrap();
+    assert_eq!(me.email, email);
+    assert!(me.email_verified);
+    assert_eq!(second_user_id, me.id);
+}
+
+#[tokio::test]
+async fn test_federated_login_existing_email_conflict_failure() {
+    let email = "federated_login_conflict@test.com";
+    // A verified email does not let a provider take over the local account either.
+    let provider = MockIdentityProvider::spawn("conflict-subject", email, true).await;
+    let address = spawn_server_with(|config| provider.configure(config)).await;
+
+    let client = reqwest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&serde_json::json!({ "email": email, "password": "12345678" }))
+        .send()
+        .await;
+
+    let status = client
+        .get(format!(
+            "http://{}/api/federation/{}/login",
+            address,
+            MockIdentityProvider::NAME
+        ))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_federated_callback_in_another_browser_failure() {
+    let email = "federated_other_browser@test.com";
+    let provider = MockIdentityProvider::spawn("other-browser-subject", email, true).await;
+    let address = spawn_server_with(|config| provider.configure(config)).await;
+
+    // The attacker starts a login and stops at the provider's redirect back to the service.
+    let attacker = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let login = attacker
+        .get(format!(
+            "http://{}/api/federation/{}/login",
+            address,
+            MockIdentityProvider::NAME
+        ))
+        .send()
+        .await
+        .unwrap();
+    let authorize_url = login.headers()["location"].to_str().unwrap().to_string();
+    let authorize = attacker.get(&authorize_url).send().await.unwrap();
+    let callback_url = authorize.headers()["location"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // The victim's browser is then sent to the callback, without the login state cookie.
+    let status = reqwest::get(&callback_url).await.unwrap().status();
+
+    let db = connect_to_database(&Config::init()).await;
+    let users = sqlx::query_scalar!("SELECT COUNT(*) FROM users WHERE email = $1", email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(users, Some(0));
+}
+
+#[tokio::test]
+async fn test_federated_login_closed_registration_failure() {
+    let email = "federated_closed_registration@test.com";
+
+    let (status, users) = federated_registration(email, RegistrationMode::Closed).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(users, Some(0));
+}
+
+#[tokio::test]
+async fn test_federated_login_invite_only_registration_failure() {
+    let email = "federated_invite_only_registration@test.com";
+
+    let (status, users) = federated_registration(email, RegistrationMode::InviteOnly).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(users, Some(0));
+}
+
+#[tokio::test]
+async fn test_federated_login_domain_allowlist_registration() {
+    let allowed = "federated_allowed_domain@test.com";
+    let refused = "federated_refused_domain@test.com";
+
+    let (allowed_status, allowed_users) = federated_registration(
+        allowed,
+        RegistrationMode::DomainAllowlist(vec!["test.com".to_string()]),
+    )
+    .await;
+    let (refused_status, refused_users) = federated_registration(
+        refused,
+        RegistrationMode::DomainAllowlist(vec!["example.com".to_string()]),
+    )
+    .await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", allowed))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(allowed_status, StatusCode::OK);
+    assert_eq!(allowed_users, Some(1));
+    assert_eq!(refused_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(refused_users, Some(0));
+}
+
+/// Signs `email` in for the first time through a mock provider under registration `mode`,
+/// returning the callback status and how many users with `email` exist afterwards.
+#[cfg(test)]
+async fn federated_registration(email: &str, mode: RegistrationMode) -> (StatusCode, Option<i64>) {
+    let provider = MockIdentityProvider::spawn(email, email, true).await;
+    let address = spawn_server_with(|config| {
+        provider.configure(config);
+        config.registration_mode = mode;
+    })
+    .await;
+
+    let status = reqwest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap()
+        .get(format!(
+            "http://{}/api/federation/{}/login",
+            address,
+            MockIdentityProvider::NAME
+        ))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let db = connect_to_database(&Config::init()).await;
+    let users = sqlx::query_scalar!("SELECT COUNT(*) FROM users WHERE email = $1", email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    (status, users)
+}
+
+#[tokio::test]
+async fn test_ldap_login_provisions_user_success() {
+    let email = "ldap_login_success@test.com";
+    let directory =
+        MockLdapServer::spawn(email, "directory password", &[MockLdapServer::ADMINS]).await;
+    let address =
+        spawn_server_with(|config| directory.configure(config, LdapMode::Exclusive)).await;
+
+    let client = reqwest::Client::new();
+    let login = || {
+        client
+            .post(format!("http://{}/api/login", address))
+            .json(&serde_json::json!({ "email": email, "password": "directory password" }))
+            .send()
+    };
+
+    let first_login: GenericResponse<AccessTokenData> =
+        login().await.unwrap().json().await.unwrap();
+    let second_login: GenericResponse<AccessTokenData> =
+        login().await.unwrap().json().await.unwrap();
+
+    let config = Config::init();
+    let verify = |response: GenericResponse<AccessTokenData>| {
+        authentication_service::api::utils::jwt::verify_jwt(
+            &config.access_token_public_key,
+            &response.data.unwrap().access_token,
+        )
+        .unwrap()
+    };
+    let first_token = verify(first_login);
+    let second_token = verify(second_login);
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(first_token.user_id, second_token.user_id);
+    assert_eq!(
+        first_token.claims.get("roles"),
+        Some(&serde_json::json!(["admin"]))
+    );
+}
+
+#[tokio::test]
+async fn test_ldap_login_invalid_password_failure() {
+    let email = "ldap_login_failure@test.com";
+    let directory = MockLdapServer::spawn(email, "directory password", &[]).await;
+    let address =
+        spawn_server_with(|config| directory.configure(config, LdapMode::Exclusive)).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/api/login", address))
+        .json(&serde_json::json!({ "email": email, "password": "wrong password" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_ldap_login_does_not_take_over_local_account_failure() {
+    let email = "ldap_local_account@test.com";
+    let directory =
+        MockLdapServer::spawn(email, "directory password", &[MockLdapServer::ADMINS]).await;
+    let address =
+        spawn_server_with(|config| directory.configure(config, LdapMode::Exclusive)).await;
+
+    let client = reqwest::Client::new();
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&serde_json::json!({ "email": email, "password": "12345678" }))
+        .send()
+        .await;
+
+    let status = client
+        .post(format!("http://{}/api/login", address))
+        .json(&serde_json::json!({ "email": email, "password": "directory password" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let db = connect_to_database(&Config::init()).await;
+    let roles = sqlx::query_scalar!("SELECT roles FROM users WHERE email = $1", email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+        .await
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(roles.is_empty());
+}
+
+#[tokio::test]
+async fn test_ldap_login_closed_registration_failure() {
+    let email = "ldap_closed_registration@test.com";
+    let directory = MockLdapServer::spawn(email, "directory password", &[]).await;
+    let address = spawn_server_with(|config| {
+        directory.configure(config, LdapMode::Exclusive);
+        config.registration_mode = RegistrationMode::Closed;
+    })
+    .await;
+
+    let status = reqwest::Client::new()
+        .post(format!("http://{}/api/login", address))
+        .json(&serde_json::json!({ "email": email, "password": "directory password" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let db = connect_to_database(&Config::init()).await;
+    let users = sqlx::query_scalar!("SELECT COUNT(*) FROM users WHERE email = $1", email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(users, Some(0));
+}
+
+#[tokio::test]
+async fn test_ldap_first_mode_falls_back_to_local_password() {
+    let email = "ldap_fallback@test.com";
+    let directory =
+        MockLdapServer::spawn("ldap_someone_else@test.com", "directory password", &[]).await;
+    let address = spawn_server_with(|config| directory.configure(config, LdapMode::First)).await;
+
+    let client = reqwest::Client::new();
+    let body = serde_json::json!({ "email": email, "password": "12345678" });
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+
+    let status = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_saml_login_success() {
+    let email = "saml_login_success@test.com";
+    let address = spawn_server_with(MockSamlIdentityProvider::configure).await;
+
+    let browser = reqwest::Client::builder()
+        .cookie_store(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let metadata = browser
+        .get(format!(
+            "http://{}/api/saml/{}/metadata",
+            address,
+            MockSamlIdentityProvider::NAME
+        ))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    let login = browser
+        .get(format!(
+            "http://{}/api/saml/{}/login?return_to=/api/users/me",
+            address,
+            MockSamlIdentityProvider::NAME
+        ))
+        .send()
+        .await
+        .unwrap();
+    let location = login.headers()["location"].to_str().unwrap().to_string();
+    let relay_state = query_param(&location, "RelayState").unwrap();
+
+    let form = [
+        (
+            "SAMLResponse",
+            MockSamlIdentityProvider::respond(&address, &location, email),
+        ),
+        ("RelayState", relay_state),
+    ];
+    let acs_url = format!(
+        "http://{}/api/saml/{}/acs",
+        address,
+        MockSamlIdentityProvider::NAME
+    );
+    let assertion = browser.post(&acs_url).form(&form).send().await.unwrap();
+    let assertion_status = assertion.status();
+    let return_to = assertion.headers()["location"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let me: GenericResponse<FilteredUser> = browser
+        .get(format!("http://{}{}", address, return_to))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let replay_status = browser
+        .post(&acs_url)
+        .form(&form)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert!(metadata.contains(&format!(
+        "http://{}/api/saml/{}/acs",
+        address,
+        MockSamlIdentityProvider::NAME
+    )));
+    assert_eq!(assertion_status, StatusCode::SEE_OTHER);
+    assert_eq!(return_to, "/api/users/me");
+    assert_eq!(me.data.unwrap().email, email);
+    assert_eq!(replay_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_saml_login_untrusted_email_failure() {
+    let email = "saml_untrusted_email@test.com";
+    let address = spawn_server_with(|config| {
+        MockSamlIdentityProvider::configure(config);
+        config.saml_identity_providers[0].trust_email = false;
+    })
+    .await;
+
+    let browser = reqwest::Client::builder()
+        .cookie_store(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let _ = browser
+        .post(format!("http://{}/api/register", address))
+        .json(&serde_json::json!({ "email": email, "password": "12345678" }))
+        .send()
+        .await;
+
+    let login = browser
+        .get(format!(
+            "http://{}/api/saml/{}/login",
+            address,
+            MockSamlIdentityProvider::NAME
+        ))
+        .send()
+        .await
+        .unwrap();
+    let location = login.headers()["location"].to_str().unwrap().to_string();
+    let relay_state = query_param(&location, "RelayState").unwrap();
+
+    let status = browser
+        .post(format!(
+            "http://{}/api/saml/{}/acs",
+            address,
+            MockSamlIdentityProvider::NAME
+        ))
+        .form(&[
+            (
+                "SAMLResponse",
+                MockSamlIdentityProvider::respond(&address, &location, email),
+            ),
+            ("RelayState", relay_state),
+        ])
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let db = connect_to_database(&Config::init()).await;
+    let identities = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM user_identities JOIN users ON users.id = user_identities.user_id WHERE users.email = $1",
+        email
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+        .await
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(identities, Some(0));
+}
+
+#[tokio::test]
+async fn test_saml_login_closed_registration_failure() {
+    let email = "saml_closed_registration@test.com";
+    let address = spawn_server_with(|config| {
+        MockSamlIdentityProvider::configure(config);
+        config.registration_mode = RegistrationMode::Closed;
+    })
+    .await;
+
+    let browser = reqwest::Client::builder()
+        .cookie_store(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let login = browser
+        .get(format!(
+            "http://{}/api/saml/{}/login",
+            address,
+            MockSamlIdentityProvider::NAME
+        ))
+        .send()
+        .await
+        .unwrap();
+    let location = login.headers()["location"].to_str().unwrap().to_string();
+    let relay_state = query_param(&location, "RelayState").unwrap();
+
+    let status = browser
+        .post(format!(
+            "http://{}/api/saml/{}/acs",
+            address,
+            MockSamlIdentityProvider::NAME
+        ))
+        .form(&[
+            (
+                "SAMLResponse",
+                MockSamlIdentityProvider::respond(&address, &location, email),
+            ),
+            ("RelayState", relay_state),
+        ])
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let db = connect_to_database(&Config::init()).await;
+    let users = sqlx::query_scalar!("SELECT COUNT(*) FROM users WHERE email = $1", email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(users, Some(0));
+}
+
+#[tokio::test]
+async fn test_personal_access_token_lifecycle_success() {
+    let address = spawn_server().await;
+
+    let register_url = format!("http://{}/api/register", address);
+    let login_url = format!("http://{}/api/login", address);
+    let get_me_url = format!("http://{}/api/users/me", address);
+    let tokens_url = format!("http://{}/api/users/me/tokens", address);
+    let client = reqwest::Client::new();
+
+    let email = "personal_access_token_success@test.com";
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
+
+    let _ = client.post(&register_url).json(&body).send().await;
+
+    let response: GenericResponse<AccessTokenData> = client
+        .post(&login_url)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let session_token = response.data.unwrap().access_token;
+
+    let response: GenericResponse<serde_json::Value> = client
+        .post(&tokens_url)
+        .header(AUTHORIZATION, format!("Bearer {}", session_token))
+        .json(&serde_json::json!({
+            "name": "deploy script",
+            "scope": "profile",
+            "expires_in_days": 30
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let created = response.data.unwrap();
+    let personal_access_token = created["token"].as_str().unwrap().to_string();
+    let token_id = created["id"].as_str().unwrap().to_string();
+
+    let get_me_with_api_key: GenericResponse<FilteredUser> = client
+        .get(&get_me_url)
+        .header("X-API-Key", &personal_access_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let create_with_personal_access_token = client
+        .post(&tokens_url)
+        .header(AUTHORIZATION, format!("Bearer {}", personal_access_token))
+        .json(&serde_json::json!({ "name": "escalation" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let listed: GenericResponse<Vec<serde_json::Value>> = client
+        .get(&tokens_url)
+        .header(AUTHORIZATION, format!("Bearer {}", session_token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let revoke_status = client
+        .delete(format!("{}/{}", tokens_url, token_id))
+        .header(AUTHORIZATION, format!("Bearer {}", session_token))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let get_me_after_revoke = client
+        .get(&get_me_url)
+        .header(AUTHORIZATION, format!("Bearer {}", personal_access_token))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert!(personal_access_token.starts_with(created["token_prefix"].as_str().unwrap()));
+    assert_eq!(get_me_with_api_key.data.unwrap().email, email);
+    assert_eq!(create_with_personal_access_token, StatusCode::FORBIDDEN);
+    let listed = listed.data.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(listed[0].get("token").is_none());
+    assert!(listed[0]["last_used_at"].is_string());
+    assert_eq!(revoke_status, StatusCode::OK);
+    assert_eq!(get_me_after_revoke, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_personal_access_token_without_scope_failure() {
+    let address = spawn_server().await;
+
+    let client = reqwest::Client::new();
+    let tokens_url = format!("http://{}/api/users/me/tokens", address);
+
+    let email = "personal_access_token_scope@test.com";
+    let token = register_and_login(address, email).await;
+
+    let response: GenericResponse<serde_json::Value> = client
+        .post(&tokens_url)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .json(&serde_json::json!({ "name": "email only", "scope": "email" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let personal_access_token = response.data.unwrap()["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let request = |path: &str| {
+        client
+            .get(format!("http://{}{}", address, path))
+            .header(AUTHORIZATION, format!("Bearer {}", personal_access_token))
+            .send()
+    };
+    let get_me_status = request("/api/users/me").await.unwrap().status();
+    let activity_status = request("/api/users/me/activity").await.unwrap().status();
+    let userinfo_status = request("/userinfo").await.unwrap().status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(get_me_status, StatusCode::FORBIDDEN);
+    assert_eq!(activity_status, StatusCode::FORBIDDEN);
+    assert_eq!(userinfo_status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_service_account_lifecycle_success() {
+    let address = spawn_server().await;
+
+    let register_url = format!("http://{}/api/register", address);
+    let login_url = format!("http://{}/api/login", address);
+    let get_me_url = format!("http://{}/api/users/me", address);
+    let accounts_url = format!("http://{}/api/admin/service-accounts", address);
+    let client = reqwest::Client::new();
+
+    let email = "service_account_admin@test.com";
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
+
+    let _ = client.post(&register_url).json(&body).send().await;
+    let config = Config::init();
+    connect_to_database(&config)
+        .await
+        .execute(sqlx::query!(
+            "UPDATE users SET roles = '{admin}' WHERE email = $1",
+            email
+        ))
+        .await
+        .unwrap();
+
+    let response: GenericResponse<AccessTokenData> = client
+        .post(&login_url)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let admin_token = format!("Bearer {}", response.data.unwrap().access_token);
+
+    let account: GenericResponse<FilteredUser> = client
+        .post(&accounts_url)
+        .header(AUTHORIZATION, &admin_token)
+        .json(&serde_json::json!({ "name": "integration-deployer" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let account = account.data.unwrap();
+    let keys_url = format!("{}/{}/keys", accounts_url, account.id);
+
+    let key: GenericResponse<serde_json::Value> = client
+        .post(&keys_url)
+        .header(AUTHORIZATION, &admin_token)
+        .json(&serde_json::json!({ "name": "deploy", "scope": "profile" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let key = key.data.unwrap();
+    let old_key = key["token"].as_str().unwrap().to_string();
+
+    let get_me_with_key: GenericResponse<FilteredUser> = client
+        .get(&get_me_url)
+        .header("X-API-Key", &old_key)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let password_login_status = client
+        .post(&login_url)
+        .json(&serde_json::json!({
+            "email": account.email,
+            "password": "12345678"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let admin_with_key_status = client
+        .get(&accounts_url)
+        .header("X-API-Key", &old_key)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let rotated: GenericResponse<serde_json::Value> = client
+        .post(format!(
+            "{}/{}/rotate",
+            keys_url,
+            key["id"].as_str().unwrap()
+        ))
+        .header(AUTHORIZATION, &admin_token)
+        .json(&serde_json::json!({ "grace_period_seconds": 0 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let new_key = rotated.data.unwrap()["token"].as_str().unwrap().to_string();
+
+    let old_key_status = client
+        .get(&get_me_url)
+        .header("X-API-Key", &old_key)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let new_key_status = client
+        .get(&get_me_url)
+        .header("X-API-Key", &new_key)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let delete_status = client
+        .delete(format!("{}/{}", accounts_url, account.id))
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(account.kind, UserKind::Service);
+    assert_eq!(get_me_with_key.data.unwrap().id, account.id);
+    assert_eq!(password_login_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(admin_with_key_status, StatusCode::FORBIDDEN);
+    assert_eq!(old_key_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(new_key_status, StatusCode::OK);
+    assert_eq!(delete_status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_organization_tenant_isolation_success() {
+    let address = spawn_server().await;
+
+    let register_url = format!("http://{}/api/register", address);
+    let login_url = format!("http://{}/api/login", address);
+    let organizations_url = format!("http://{}/api/organizations", address);
+    let client = reqwest::Client::new();
+
+    let mut tokens = vec![];
+    for email in ["tenant_alice@test.com", "tenant_bob@test.com"] {
+        let body = serde_json::json!({
+            "email": email,
+            "password": "12345678"
+        });
+        let _ = client.post(&register_url).json(&body).send().await;
+        let response: GenericResponse<AccessTokenData> = client
+            .post(&login_url)
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        tokens.push(format!("Bearer {}", response.data.unwrap().access_token));
+    }
+    let (alice_token, bob_token) = (tokens[0].clone(), tokens[1].clone());
+
+    let mut organization_ids = vec![];
+    for (token, slug) in [(&alice_token, "tenant-alice"), (&bob_token, "tenant-bob")] {
+        let organization: GenericResponse<serde_json::Value> = client
+            .post(&organizations_url)
+            .header(AUTHORIZATION, token)
+            .json(&serde_json::json!({ "name": slug, "slug": slug }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        organization_ids.push(
+            organization.data.unwrap()["id"]
+                .as_str()
+                .unwrap()
+                .to_string(),
+        );
+    }
+    let (alice_org, bob_org) = (organization_ids[0].clone(), organization_ids[1].clone());
+
+    let cross_tenant_members_status = client
+        .get(format!("{}/{}/members", organizations_url, alice_org))
+        .header(AUTHORIZATION, &bob_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let cross_tenant_switch_status = client
+        .post(format!("{}/{}/switch", organizations_url, bob_org))
+        .header(AUTHORIZATION, &alice_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let invitation: GenericResponse<serde_json::Value> = client
+        .post(format!("{}/{}/invitations", organizations_url, alice_org))
+        .header(AUTHORIZATION, &alice_token)
+        .json(&serde_json::json!({ "email": "tenant_bob@test.com" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let invitation_token = invitation.data.unwrap()["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let accept_status = client
+        .post(format!("{}/invitations/accept", organizations_url))
+        .header(AUTHORIZATION, &bob_token)
+        .json(&serde_json::json!({ "token": invitation_token }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let switched: GenericResponse<AccessTokenData> = client
+        .post(format!("{}/{}/switch", organizations_url, alice_org))
+        .header(AUTHORIZATION, &bob_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let switched_token = switched.data.unwrap().access_token;
+    let config = Config::init();
+    let switched_claims = verify_jwt(&config.access_token_public_key, &switched_token)
+        .unwrap()
+        .claims;
+    let switched_token = format!("Bearer {}", switched_token);
+
+    let previous_token_status = client
+        .get(&organizations_url)
+        .header(AUTHORIZATION, &bob_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let members: GenericResponse<Vec<serde_json::Value>> = client
+        .get(format!("{}/{}/members", organizations_url, alice_org))
+        .header(AUTHORIZATION, &switched_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let bob_id = members
+        .data
+        .unwrap()
+        .iter()
+        .find(|member| member["email"] == "tenant_bob@test.com")
+        .map(|member| member["user_id"].as_str().unwrap().to_string())
+        .unwrap();
+
+    let remove_status = client
+        .delete(format!(
+            "{}/{}/members/{}",
+            organizations_url, alice_org, bob_id
+        ))
+        .header(AUTHORIZATION, &alice_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let removed_member_status = client
+        .get(&organizations_url)
+        .header(AUTHORIZATION, &switched_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM organizations WHERE slug IN ('tenant-alice', 'tenant-bob')"
+        ))
+        .await
+        .unwrap();
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email IN ('tenant_alice@test.com', 'tenant_bob@test.com')"
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(cross_tenant_members_status, StatusCode::NOT_FOUND);
+    assert_eq!(cross_tenant_switch_status, StatusCode::NOT_FOUND);
+    assert_eq!(accept_status, StatusCode::OK);
+    assert_eq!(
+        switched_claims.get("org_id"),
+        Some(&serde_json::json!(alice_org))
+    );
+    assert_eq!(
+        switched_claims.get("org_role"),
+        Some(&serde_json::json!("member"))
+    );
+    assert_eq!(previous_token_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(remove_status, StatusCode::OK);
+    assert_eq!(removed_member_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_invite_only_registration_success() {
+    let open_address = spawn_server().await;
+    let address =
+        spawn_server_with(|config| config.registration_mode = RegistrationMode::InviteOnly).await;
+
+    let register_url = format!("http://{}/api/register", address);
+    let login_url = format!("http://{}/api/login", address);
+    let invitations_url = format!("http://{}/api/admin/invitations", address);
+    let client = reqwest::Client::new();
+
+    let admin_email = "registration_admin@test.com";
+    let invitee_email = "registration_invitee@test.com";
+    let admin_body = serde_json::json!({
+        "email": admin_email,
+        "password": "12345678"
+    });
+
+    let _ = client
+        .post(format!("http://{}/api/register", open_address))
+        .json(&admin_body)
+        .send()
+        .await;
+    let config = Config::init();
+    connect_to_database(&config)
+        .await
+        .execute(sqlx::query!(
+            "UPDATE users SET roles = '{admin}' WHERE email = $1",
+            admin_email
+        ))
+        .await
+        .unwrap();
+
+    let response: GenericResponse<AccessTokenData> = client
+        .post(&login_url)
+        .json(&admin_body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let admin_token = format!("Bearer {}", response.data.unwrap().access_token);
+
+    let organization: GenericResponse<serde_json::Value> = client
+        .post(format!("http://{}/api/organizations", address))
+        .header(AUTHORIZATION, &admin_token)
+        .json(&serde_json::json!({ "name": "Invited", "slug": "registration-invited" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let organization_id = organization.data.unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let mut invitations = vec![];
+    for _ in 0..2 {
+        let invitation: GenericResponse<serde_json::Value> = client
+            .post(&invitations_url)
+            .header(AUTHORIZATION, &admin_token)
+            .json(&serde_json::json!({
+                "email": invitee_email,
+                "organization_id": organization_id,
+                "role": "admin"
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        invitations.push(invitation.data.unwrap());
+    }
+
+    let revoke_status = client
+        .delete(format!(
+            "{}/{}",
+            invitations_url,
+            invitations[1]["id"].as_str().unwrap()
+        ))
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let register = |invitation_token: Option<&serde_json::Value>| {
+        client
+            .post(&register_url)
+            .json(&serde_json::json!({
+                "email": invitee_email,
+                "password": "12345678",
+                "invitation_token": invitation_token
+            }))
+            .send()
+    };
+    let uninvited_status = register(None).await.unwrap().status();
+    let revoked_status = register(Some(&invitations[1]["token"]))
+        .await
+        .unwrap()
+        .status();
+    let other_email_status = client
+        .post(&register_url)
+        .json(&serde_json::json!({
+            "email": "registration_other@test.com",
+            "password": "12345678",
+            "invitation_token": invitations[0]["token"]
+        }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let access_token = admin_token.trim_start_matches("Bearer ").to_string();
+    let access_token_status = register(Some(&serde_json::json!(access_token)))
+        .await
+        .unwrap()
+        .status();
+    let invited_status = register(Some(&invitations[0]["token"]))
+        .await
+        .unwrap()
+        .status();
+
+    let pending: GenericResponse<Vec<serde_json::Value>> = client
+        .get(&invitations_url)
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let pending = pending
+        .data
+        .unwrap()
+        .iter()
+        .filter(|invitation| invitation["email"] == invitee_email)
+        .count();
+
+    let response: GenericResponse<AccessTokenData> = client
+        .post(&login_url)
+        .json(&serde_json::json!({
+            "email": invitee_email,
+            "password": "12345678"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let claims = verify_jwt(
+        &config.access_token_public_key,
+        &response.data.unwrap().access_token,
+    )
+    .unwrap()
+    .claims;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM organizations WHERE slug = 'registration-invited'"
+        ))
+        .await
+        .unwrap();
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email IN ($1, $2)",
+            admin_email,
+            invitee_email
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(revoke_status, StatusCode::OK);
+    assert_eq!(uninvited_status, StatusCode::FORBIDDEN);
+    assert_eq!(revoked_status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(other_email_status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(access_token_status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(invited_status, StatusCode::OK);
+    assert_eq!(pending, 0);
+    assert_eq!(
+        claims.get("org_id"),
+        Some(&serde_json::json!(organization_id))
+    );
+    assert_eq!(claims.get("org_role"), Some(&serde_json::json!("admin")));
+}
+
+#[tokio::test]
+async fn test_admin_user_management_success() {
+    let address = spawn_server().await;
+
+    let login_url = format!("http://{}/api/login", address);
+    let me_url = format!("http://{}/api/users/me", address);
+    let users_url = format!("http://{}/api/admin/users", address);
+    let client = reqwest::Client::new();
+
+    let admin_email = "user_admin@test.com";
+    let user_email = "managed_user@test.com";
+    let admin_body = serde_json::json!({
+        "email": admin_email,
+        "password": "12345678"
+    });
+    let user_body = serde_json::json!({
+        "email": user_email,
+        "password": "12345678"
+    });
+
+    for body in [&admin_body, &user_body] {
+        let _ = client
+            .post(format!("http://{}/api/register", address))
+            .json(body)
+            .send()
+            .await;
+    }
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    db.execute(sqlx::query!(
+        "UPDATE users SET roles = '{admin}' WHERE email = $1",
+        admin_email
+    ))
+    .await
+    .unwrap();
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", admin_email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    let login = |body: serde_json::Value| client.post(&login_url).json(&body).send();
+    let access_token = |response: reqwest::Response| async move {
+        let response: GenericResponse<AccessTokenData> = response.json().await.unwrap();
+        format!("Bearer {}", response.data.unwrap().access_token)
+    };
+    let admin_token = access_token(login(admin_body.clone()).await.unwrap()).await;
+    let user_token = access_token(login(user_body.clone()).await.unwrap()).await;
+    let me_status = |token: String| {
+        let request = client.get(&me_url).header(AUTHORIZATION, token);
+        async move { request.send().await.unwrap().status() }
+    };
+
+    let forbidden_status = client
+        .get(&users_url)
+        .header(AUTHORIZATION, &user_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let search: GenericResponse<serde_json::Value> = client
+        .get(format!("{}?email=MANAGED_user&per_page=10", users_url))
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let search = search.data.unwrap();
+    let user_id = search["users"][0]["id"].as_str().unwrap().to_string();
+    let user_url = format!("{}/{}", users_url, user_id);
+
+    let fetched: GenericResponse<serde_json::Value> = client
+        .get(&user_url)
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let revoke_status = client
+        .delete(format!("{}/sessions", user_url))
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let revoked_me_status = me_status(user_token.clone()).await;
+
+    let reset: GenericResponse<serde_json::Value> = client
+        .post(format!("{}/password-reset", user_url))
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let reset_token = reset.data.unwrap()["token"].clone();
+    let reset_required_status = login(user_body.clone()).await.unwrap().status();
+
+    let reset_password = || {
+        client
+            .post(format!("http://{}/api/password/reset", address))
+            .json(&serde_json::json!({ "token": reset_token, "password": "87654321" }))
+            .send()
+    };
+    let reset_status = reset_password().await.unwrap().status();
+    let reused_reset_status = reset_password().await.unwrap().status();
+
+    // Tokens issued in the second sessions were revoked are revoked too.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let new_user_body = serde_json::json!({ "email": user_email, "password": "87654321" });
+    let new_user_token = access_token(login(new_user_body.clone()).await.unwrap()).await;
+    let new_me_status = me_status(new_user_token.clone()).await;
+
+    let suspended: GenericResponse<serde_json::Value> = client
+        .patch(&user_url)
+        .header(AUTHORIZATION, &admin_token)
+        .json(&serde_json::json!({ "status": "suspended" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let suspended_me_status = me_status(new_user_token).await;
+    let suspended_login_status = login(new_user_body).await.unwrap().status();
+
+    let suspend_self_status = client
+        .patch(format!("{}/{}", users_url, admin_id))
+        .header(AUTHORIZATION, &admin_token)
+        .json(&serde_json::json!({ "status": "suspended" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let delete_status = client
+        .delete(&user_url)
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let deleted_status = client
+        .get(&user_url)
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let audited_routes = sqlx::query_scalar!(
+        "SELECT details->>'route' AS \"route!\" FROM audit_events \
+         WHERE event_type = 'admin_action' AND actor_id = $1 ORDER BY created_at",
+        admin_id
+    )
+    .fetch_all(&db)
+    .await
+    .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email IN ($1, $2)",
+            admin_email,
+            user_email
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(forbidden_status, StatusCode::FORBIDDEN);
+    assert_eq!(search["total"], 1);
+    assert_eq!(fetched.data.unwrap()["email"], user_email);
+    assert_eq!(revoke_status, StatusCode::OK);
+    assert_eq!(revoked_me_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(reset_required_status, StatusCode::FORBIDDEN);
+    assert_eq!(reset_status, StatusCode::OK);
+    assert_eq!(reused_reset_status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(new_me_status, StatusCode::OK);
+    assert_eq!(suspended.data.unwrap()["status"], "suspended");
+    assert_eq!(suspended_me_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(suspended_login_status, StatusCode::FORBIDDEN);
+    assert_eq!(suspend_self_status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(delete_status, StatusCode::OK);
+    assert_eq!(deleted_status, StatusCode::NOT_FOUND);
+    assert_eq!(audited_routes.len(), 8);
+    assert!(audited_routes.contains(&"/api/admin/users/:user_id/sessions".to_string()));
+}
+
+#[tokio::test]
+async fn test_admin_impersonation_success() {
+    let address = spawn_server().await;
+
+    let login_url = format!("http://{}/api/login", address);
+    let me_url = format!("http://{}/api/users/me", address);
+    let password_url = format!("http://{}/api/users/me/password", address);
+    let impersonation_url = format!("http://{}/api/impersonation", address);
+    let client = reqwest::Client::new();
+
+    let admin_email = "impersonation_admin@test.com";
+    let user_email = "impersonated_user@test.com";
+    let admin_body = serde_json::json!({
+        "email": admin_email,
+        "password": "12345678"
+    });
+    let user_body = serde_json::json!({
+        "email": user_email,
+        "password": "12345678"
+    });
+
+    for body in [&admin_body, &user_body] {
+        let _ = client
+            .post(format!("http://{}/api/register", address))
+            .json(body)
+            .send()
+            .await;
+    }
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    db.execute(sqlx::query!(
+        "UPDATE users SET roles = '{admin}' WHERE email = $1",
+        admin_email
+    ))
+    .await
+    .unwrap();
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", admin_email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", user_email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    let login = |body: serde_json::Value| {
+        let request = client.post(&login_url).json(&body);
+        async move {
+            let response: GenericResponse<AccessTokenData> =
+                request.send().await.unwrap().json().await.unwrap();
+            format!("Bearer {}", response.data.unwrap().access_token)
+        }
+    };
+    let admin_token = login(admin_body).await;
+
+    let impersonation: GenericResponse<AccessTokenData> = client
+        .post(format!(
+            "http://{}/api/admin/users/{}/impersonate",
+            address, user_id
+        ))
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let impersonation_token = format!("Bearer {}", impersonation.data.unwrap().access_token);
+    let claims = verify_jwt(
+        &config.access_token_public_key,
+        impersonation_token.trim_start_matches("Bearer "),
+    )
+    .unwrap()
+    .claims;
+
+    let me: GenericResponse<serde_json::Value> = client
+        .get(&me_url)
+        .header(AUTHORIZATION, &impersonation_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let password_body = serde_json::json!({
+        "current_password": "12345678",
+        "new_password": "87654321"
+    });
+    let impersonated_password_status = client
+        .post(&password_url)
+        .header(AUTHORIZATION, &impersonation_token)
+        .json(&password_body)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let impersonated_token_status = client
+        .post(format!("http://{}/api/users/me/tokens", address))
+        .header(AUTHORIZATION, &impersonation_token)
+        .json(&serde_json::json!({ "name": "impersonated", "scopes": ["read"] }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let impersonated_admin_status = client
+        .get(format!("http://{}/api/admin/users", address))
+        .header(AUTHORIZATION, &impersonation_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let organizations_url = format!("http://{}/api/organizations", address);
+    let impersonated_organizations_status = client
+        .get(&organizations_url)
+        .header(AUTHORIZATION, &impersonation_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let impersonated_create_organization_status = client
+        .post(&organizations_url)
+        .header(AUTHORIZATION, &impersonation_token)
+        .json(&serde_json::json!({ "name": "Impersonated", "slug": "impersonated" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let impersonated_accept_invitation_status = client
+        .post(format!("{}/invitations/accept", organizations_url))
+        .header(AUTHORIZATION, &impersonation_token)
+        .json(&serde_json::json!({ "token": "invitation" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let end_own_session_status = client
+        .delete(&impersonation_url)
+        .header(AUTHORIZATION, &admin_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let end_status = client
+        .delete(&impersonation_url)
+        .header(AUTHORIZATION, &impersonation_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let ended_me_status = client
+        .get(&me_url)
+        .header(AUTHORIZATION, &impersonation_token)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let user_token = login(user_body).await;
+    let password_status = client
+        .post(&password_url)
+        .header(AUTHORIZATION, &user_token)
+        .json(&password_body)
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let audit_log = sqlx::query!(
+        "SELECT details->>'route' AS \"route!\", (details->>'status')::int AS \"status!\", \
+         user_id FROM audit_events \
+         WHERE event_type = 'admin_action' AND actor_id = $1 ORDER BY created_at",
+        admin_id
+    )
+    .fetch_all(&db)
+    .await
+    .unwrap();
+    let audit_log = audit_log
+        .into_iter()
+        .map(|entry| (entry.route, entry.status, entry.user_id))
+        .collect::<Vec<_>>();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email IN ($1, $2)",
+            admin_email,
+            user_email
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(
+        claims.get("act"),
+        Some(&serde_json::json!({ "sub": admin_id }))
+    );
+    assert_eq!(me.data.unwrap()["email"], user_email);
+    assert_eq!(impersonated_password_status, StatusCode::FORBIDDEN);
+    assert_eq!(impersonated_token_status, StatusCode::FORBIDDEN);
+    assert_eq!(impersonated_admin_status, StatusCode::FORBIDDEN);
+    assert_eq!(impersonated_organizations_status, StatusCode::OK);
+    assert_eq!(
+        impersonated_create_organization_status,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(impersonated_accept_invitation_status, StatusCode::FORBIDDEN);
+    assert_eq!(end_own_session_status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(end_status, StatusCode::OK);
+    assert_eq!(ended_me_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(password_status, StatusCode::OK);
+    assert_eq!(
+        audit_log,
+        vec![
+            (
+                "/api/admin/users/:user_id/impersonate".to_string(),
+                200,
+                None
+            ),
+            ("/api/impersonation".to_string(), 422, None),
+            ("/api/impersonation".to_string(), 200, Some(user_id)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_audit_events_success() {
+    let address = spawn_server().await;
+
+    let register_url = format!("http://{}/api/register", address);
+    let login_url = format!("http://{}/api/login", address);
+    let client = reqwest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap();
+
+    let admin_email = "audit_admin@test.com";
+    let user_email = "audit_user@test.com";
+    let admin_body = serde_json::json!({ "email": admin_email, "password": "12345678" });
+    let user_body = serde_json::json!({ "email": user_email, "password": "12345678" });
+    let wrong_body = serde_json::json!({ "email": user_email, "password": "87654321" });
+    // The audit log is never cleaned up, so the request id must not match earlier runs.
+    let request_id = format!("audit-login-{}", uuid::Uuid::new_v4());
+
+    for body in [&admin_body, &user_body] {
+        let _ = client.post(&register_url).json(body).send().await;
+    }
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    db.execute(sqlx::query!(
+        "UPDATE users SET roles = '{admin}' WHERE email = $1",
+        admin_email
+    ))
+    .await
+    .unwrap();
+    let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", user_email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    let failed_login_status = client
+        .post(&login_url)
+        .json(&wrong_body)
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let login = client
+        .post(&login_url)
+        .header("user-agent", "audit-test/1.0")
+        .header("x-request-id", &request_id)
+        .json(&user_body)
+        .send()
+        .await
+        .unwrap();
+    let echoed_request_id = login.headers().get("x-request-id").cloned();
+    let user_token = login
+        .json::<GenericResponse<AccessTokenData>>()
+        .await
+        .unwrap()
+        .data
+        .unwrap()
+        .access_token;
+    let generated_request_id = client
+        .get(format!("http://{}/api/refresh", address))
+        .send()
+        .await
+        .unwrap()
+        .headers()
+        .get("x-request-id")
+        .cloned();
+    let _ = client
+        .get(format!("http://{}/api/logout", address))
+        .header(AUTHORIZATION, format!("Bearer {}", user_token))
+        .send()
+        .await;
+
+    let user_token = client
+        .post(&login_url)
+        .json(&user_body)
+        .send()
+        .await
+        .unwrap()
+        .json::<GenericResponse<AccessTokenData>>()
+        .await
+        .unwrap()
+        .data
+        .unwrap()
+        .access_token;
+    let activity: GenericResponse<Vec<serde_json::Value>> = client
+        .get(format!("http://{}/api/users/me/activity", address))
+        .header(AUTHORIZATION, format!("Bearer {}", user_token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let activity = activity
+        .data
+        .unwrap()
+        .iter()
+        .map(|event| {
+            format!(
+                "{} {}",
+                event["event_type"].as_str().unwrap(),
+                event["outcome"].as_str().unwrap()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let admin_token = client
+        .post(&login_url)
+        .json(&admin_body)
+        .send()
+        .await
+        .unwrap()
+        .json::<GenericResponse<AccessTokenData>>()
+        .await
+        .unwrap()
+        .data
+        .unwrap()
+        .access_token;
+    let audit_events_url = format!("http://{}/api/admin/audit-events", address);
+    let search = |query: String| {
+        let request = client
+            .get(format!("{}?{}", audit_events_url, query))
+            .header(AUTHORIZATION, format!("Bearer {}", admin_token));
+        async move { request.send().await.unwrap() }
+    };
+    let failures: GenericResponse<serde_json::Value> = search(format!(
+        "user_id={}&event_type=login&outcome=failure",
+        user_id
+    ))
+    .await
+    .json()
+    .await
+    .unwrap();
+    let by_request_id: GenericResponse<serde_json::Value> =
+        search(format!("request_id={}", request_id))
+            .await
+            .json()
+            .await
+            .unwrap();
+    let invalid_page_status = search("per_page=1000".to_string()).await.status();
+    // The cookie store holds the administrator's session by now.
+    let user_search_status = reqwest::Client::new()
+        .get(&audit_events_url)
+        .header(AUTHORIZATION, format!("Bearer {}", user_token))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email IN ($1, $2)",
+            admin_email,
+            user_email
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(failed_login_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(echoed_request_id.unwrap().to_str().unwrap(), request_id);
+    assert!(generated_request_id.is_some());
+    assert_eq!(
+        activity,
+        vec![
+            "login success",
+            "logout success",
+            "refresh success",
+            "login success",
+            "login failure",
+            "register success",
+        ]
+    );
+    let failures = failures.data.unwrap();
+    assert_eq!(failures["total"], 1);
+    assert_eq!(failures["events"][0]["details"]["email"], user_email);
+    assert!(failures["events"][0]["reason"].is_string());
+    let by_request_id = by_request_id.data.unwrap();
+    assert_eq!(by_request_id["total"], 1);
+    assert_eq!(by_request_id["events"][0]["user_agent"], "audit-test/1.0");
+    assert!(by_request_id["events"][0]["ip_address"].is_string());
+    assert_eq!(invalid_page_status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(user_search_status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_login_lockout_failure() {
+    let address = spawn_server_with(|config| config.login_lockout_threshold = 2).await;
+
+    let login_url = format!("http://{}/api/login", address);
+    let client = reqwest::Client::new();
+
+    // Lockouts outlive the test, so every run locks out a fresh address.
+    let email = format!("lockout_{}@test.com", uuid::Uuid::new_v4().simple());
+    let body = serde_json::json!({ "email": email, "password": "12345678" });
+    let wrong_body = serde_json::json!({ "email": email, "password": "87654321" });
+
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+    let mut statuses = vec![];
+    for body in [&wrong_body, &wrong_body, &body] {
+        statuses.push(
+            client
+                .post(&login_url)
+                .json(body)
+                .send()
+                .await
+                .unwrap()
+                .status(),
+        );
+    }
+
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    let lockouts = sqlx::query_scalar!(
+        "SELECT count(*) AS \"count!\" FROM audit_events \
+         WHERE event_type = 'lockout' AND details->>'email' = $1",
+        email
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+
+    let cleanup_email = email.clone();
+    clean_up_db(|db| {
+        let email = cleanup_email.clone();
+        async move {
+            db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+                .await
+                .unwrap();
+        }
+    })
+    .await;
+
+    assert_eq!(
+        statuses,
+        vec![
+            StatusCode::UNAUTHORIZED,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::FORBIDDEN
+        ]
+    );
+    assert_eq!(lockouts, 1);
+}
+
+#[tokio::test]
+async fn test_audit_chain_verification_detects_tampering() {
+    let address = spawn_server_with(|config| config.audit_checkpoint_interval = 1).await;
+
+    let login_url = format!("http://{}/api/login", address);
+    let verify_url = format!("http://{}/api/admin/audit-events/verify", address);
+    let client = reqwest::Client::new();
+
+    let admin_email = "audit_chain_admin@test.com";
+    let admin_body = serde_json::json!({ "email": admin_email, "password": "12345678" });
+
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&admin_body)
+        .send()
+        .await;
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    db.execute(sqlx::query!(
+        "UPDATE users SET roles = '{admin}' WHERE email = $1",
+        admin_email
+    ))
+    .await
+    .unwrap();
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", admin_email)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    let admin_token = client
+        .post(&login_url)
+        .json(&admin_body)
+        .send()
+        .await
+        .unwrap()
+        .json::<GenericResponse<AccessTokenData>>()
+        .await
+        .unwrap()
+        .data
+        .unwrap()
+        .access_token;
+    let verify = || {
+        let request = client
+            .get(&verify_url)
+            .header(AUTHORIZATION, format!("Bearer {}", admin_token));
+        async move {
+            request
+                .send()
+                .await
+                .unwrap()
+                .json::<GenericResponse<serde_json::Value>>()
+                .await
+                .unwrap()
+                .data
+                .unwrap()
+        }
+    };
+
+    let intact = verify().await;
+
+    // Tampers with the admin's login, which the verification above was chained after.
+    let login = sqlx::query!(
+        "SELECT id, sequence, previous_hash, hash, event_type, outcome, reason, user_id, \
+         actor_id, ip_address, user_agent, request_id, details, created_at FROM audit_events \
+         WHERE user_id = $1 AND event_type = 'login'",
+        admin_id
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    let checkpointed = sqlx::query_scalar!(
+        "SELECT COUNT(*) AS \"count!\" FROM audit_checkpoints WHERE sequence = $1",
+        login.sequence
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+
+    db.execute(sqlx::query!(
+        "UPDATE audit_events SET outcome = 'failure' WHERE id = $1",
+        login.id
+    ))
+    .await
+    .unwrap();
+    let modified = verify().await;
+    db.execute(sqlx::query!(
+        "UPDATE audit_events SET outcome = 'success' WHERE id = $1",
+        login.id
+    ))
+    .await
+    .unwrap();
+
+    db.execute(sqlx::query!(
+        "DELETE FROM audit_events WHERE id = $1",
+        login.id
+    ))
+    .await
+    .unwrap();
+    let deleted = verify().await;
+    db.execute(sqlx::query!(
+        "INSERT INTO audit_events (id, sequence, previous_hash, hash, event_type, outcome, \
+         reason, user_id, actor_id, ip_address, user_agent, request_id, details, created_at) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+        login.id,
+        login.sequence,
+        login.previous_hash,
+        login.hash,
+        login.event_type,
+        login.outcome,
+        login.reason,
+        login.user_id,
+        login.actor_id,
+        login.ip_address,
+        login.user_agent,
+        login.request_id,
+        login.details,
+        login.created_at
+    ))
+    .await
+    .unwrap();
+    let restored = verify().await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email = $1",
+            admin_email
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(intact["intact"], true);
+    assert_eq!(checkpointed, 1);
+    assert_eq!(modified["intact"], false);
+    assert_eq!(
+        modified["first_broken_link"],
+        serde_json::json!({
+            "sequence": login.sequence,
+            "reason": "Hash does not match the contents of the event"
+        })
+    );
+    assert_eq!(deleted["intact"], false);
+    assert_eq!(deleted["first_broken_link"]["sequence"], login.sequence + 1);
+    assert_eq!(restored["intact"], true);
+    assert!(restored["checkpoints_checked"].as_i64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_webhook_delivery_with_retries_and_dead_letter() {
+    type Requests =
+        std::sync::Arc<std::sync::Mutex<Vec<(String, reqwest::header::HeaderMap, Vec<u8>)>>>;
+
+    // A receiver at /flaky that fails the first request of every delivery, and at /broken
+    // that always fails.
+    let requests: Requests = Default::default();
+    let receiver = axum::Router::new().route(
+        "/:name",
+        axum::routing::post({
+            let requests = requests.clone();
+            move |axum::extract::Path(name): axum::extract::Path<String>,
+                  headers: axum::http::HeaderMap,
+                  body: axum::body::Bytes| async move {
+                let mut requests = requests.lock().unwrap();
+                let retried = requests
+                    .iter()
+                    .any(|(_, seen, _)| seen["x-webhook-id"] == headers["x-webhook-id"]);
+                requests.push((name.clone(), headers, body.to_vec()));
+                if name == "flaky" && retried {
+                    axum::http::StatusCode::OK
+                } else {
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR
+                }
+            }
+        }),
+    );
+    let receiver_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let receiver_address = receiver_listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(receiver_listener, receiver).await });
+
+    let address = spawn_server_with(|config| {
+        config.webhook_worker_enabled = true;
+        config.webhook_poll_interval_seconds = 1;
+        config.webhook_max_attempts = 3;
+        config.webhook_backoff_seconds = 0;
+    })
+    .await;
+    let webhooks_url = format!("http://{}/api/admin/webhooks", address);
+    let client = reqwest::Client::new();
+
+    let admin_email = "webhook_admin@test.com";
+    let admin_body = serde_json::json!({ "email": admin_email, "password": "12345678" });
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&admin_body)
+        .send()
+        .await;
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    db.execute(sqlx::query!(
+        "UPDATE users SET roles = '{admin}' WHERE email = $1",
+        admin_email
+    ))
+    .await
+    .unwrap();
+    let admin_token = client
+        .post(format!("http://{}/api/login", address))
+        .json(&admin_body)
+        .send()
+        .await
+        .unwrap()
+        .json::<GenericResponse<AccessTokenData>>()
+        .await
+        .unwrap()
+        .data
+        .unwrap()
+        .access_token;
+
+    let mut subscriptions = vec![];
+    for name in ["flaky", "broken"] {
+        let subscription = client
+            .post(&webhooks_url)
+            .header(AUTHORIZATION, format!("Bearer {}", admin_token))
+            .json(&serde_json::json!({
+                "url": format!("http://{}/{}", receiver_address, name),
+                "events": ["user.registered", "password.changed"],
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json::<GenericResponse<serde_json::Value>>()
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        subscriptions.push(subscription);
+    }
+    let unknown_event = client
+        .post(&webhooks_url)
+        .header(AUTHORIZATION, format!("Bearer {}", admin_token))
+        .json(&serde_json::json!({
+            "url": format!("http://{}/flaky", receiver_address),
+            "events": ["user.deleted"],
+        }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let email = "webhook_user@test.com";
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&serde_json::json!({ "email": email, "password": "12345678" }))
+        .send()
+        .await;
+
+    // Finds the delivery of this test's registration to a subscription, once it has `status`.
+    let delivery = |subscription: &serde_json::Value, status: &str| {
+        let request = client
+            .get(format!(
+                "{}/{}/deliveries?status={}",
+                webhooks_url,
+                subscription["id"].as_str().unwrap(),
+                status
+            ))
+            .header(AUTHORIZATION, format!("Bearer {}", admin_token));
+        async move {
+            request
+                .send()
+                .await
+                .unwrap()
+                .json::<GenericResponse<Vec<serde_json::Value>>>()
+                .await
+                .unwrap()
+                .data
+                .unwrap()
+                .into_iter()
+                .find(|delivery| delivery["payload"]["data"]["email"] == email)
+        }
+    };
+    let mut outcomes = (None, None);
+    for _ in 0..30 {
+        outcomes = (
+            delivery(&subscriptions[0], "delivered").await,
+            delivery(&subscriptions[1], "dead").await,
+        );
+        if outcomes.0.is_some() && outcomes.1.is_some() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    let (delivered, dead) = (outcomes.0.unwrap(), outcomes.1.unwrap());
+
+    let retry = client
+        .post(format!(
+            "{}/{}/deliveries/{}/retry",
+            webhooks_url,
+            subscriptions[1]["id"].as_str().unwrap(),
+            dead["id"].as_str().unwrap()
+        ))
+        .header(AUTHORIZATION, format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .unwrap()
+        .json::<GenericResponse<serde_json::Value>>()
+        .await
+        .unwrap()
+        .data
+        .unwrap();
+
+    let mut deleted = vec![];
+    for subscription in &subscriptions {
+        let response = client
+            .delete(format!(
+                "{}/{}",
+                webhooks_url,
+                subscription["id"].as_str().unwrap()
+            ))
+            .header(AUTHORIZATION, format!("Bearer {}", admin_token))
+            .send()
+            .await
+            .unwrap();
+        deleted.push(response.status());
+    }
+
+    clean_up_db(|db| async move {
+        for email in [email, admin_email] {
+            db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+                .await
+                .unwrap();
+        }
+    })
+    .await;
+
+    let requests = requests.lock().unwrap();
+    let delivery_requests = |id: &serde_json::Value| {
+        requests
+            .iter()
+            .filter(|(_, headers, _)| headers["x-webhook-id"] == id.as_str().unwrap())
+            .collect::<Vec<_>>()
+    };
+    let flaky_requests = delivery_requests(&delivered["id"]);
+    assert_eq!(flaky_requests.len(), 2);
+    assert_eq!(delivered["attempts"], 2);
+    assert_eq!(delivery_requests(&dead["id"]).len(), 3);
+    assert_eq!(dead["attempts"], 3);
+    assert_eq!(
+        dead["last_error"],
+        "Webhook receiver answered with HTTP 500 Internal Server Error"
+    );
+    assert_eq!(retry["status"], "pending");
+    assert_eq!(retry["attempts"], 0);
+    assert_eq!(unknown_event, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(deleted, vec![StatusCode::OK, StatusCode::OK]);
+
+    let (_, headers, body) = flaky_requests[1];
+    let payload = serde_json::from_slice::<serde_json::Value>(body).unwrap();
+    assert_eq!(payload["type"], "user.registered");
+    assert_eq!(headers["x-webhook-event"], "user.registered");
+    let timestamp = headers["x-webhook-timestamp"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        headers["x-webhook-signature"].to_str().unwrap(),
+        format!(
+            "sha256={}",
+            sign_payload(
+                subscriptions[0]["secret"].as_str().unwrap(),
+                timestamp,
+                body
+            )
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_login_publishes_event_to_redis_stream() {
+    let address = spawn_server().await;
+    let client = reqwest::Client::new();
+
+    let email = "event_stream@test.com";
+    let body = serde_json::json!({ "email": email, "password": "12345678" });
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+    let response = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let config = Config::init();
+    let mut redis_client = Client::open(config.redis_url.to_owned())
+        .unwrap()
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let entries: redis::streams::StreamRangeReply =
+        redis_client.xrange_all(&config.event_stream).await.unwrap();
+    let events = entries
+        .ids
+        .iter()
+        .map(|entry| {
+            let event: String = entry.get("event").unwrap();
+            serde_json::from_str::<serde_json::Value>(&event).unwrap()
+        })
+        .filter(|event| event["data"]["email"] == email)
+        .collect::<Vec<_>>();
+
+    let login = events
+        .iter()
+        .rev()
+        .find(|event| event["type"] == "user.logged_in")
+        .unwrap();
+    assert_eq!(login["version"], 1);
+    assert!(login["user_id"].is_string());
+    assert!(events
+        .iter()
+        .any(|event| event["type"] == "user.registered"));
+}
+
+#[tokio::test]
+async fn test_logout_broadcasts_revocation_to_subscribers() {
+    let address = spawn_server().await;
+    let client = reqwest::Client::new();
+    let config = Config::init();
+
+    let denylist = RevocationDenylist::default();
+    RevocationSubscriber::new(&config.redis_url, &config.revocation_channel)
+        .unwrap()
+        .spawn(denylist.clone());
+    // Give the subscriber time to subscribe, as pub/sub delivers no history.
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let email = "revocation_broadcast@test.com";
+    let body = serde_json::json!({ "email": email, "password": "12345678" });
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+    let response: GenericResponse<AccessTokenData> = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let token = response.data.unwrap().access_token;
+    let details = verify_jwt(&config.access_token_public_key, &token).unwrap();
+
+    assert!(!denylist.is_revoked(&details));
+
+    let response = client
+        .get(format!("http://{}/api/logout", address))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut revoked = false;
+    for _ in 0..50 {
+        revoked = denylist.is_revoked(&details);
+        if revoked {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(revoked);
+}
+
+#[cfg(feature = "verifier")]
+#[tokio::test]
+async fn test_verifier_layer_with_jwks_success() {
+    let address = spawn_server().await;
+    let client = reqwest::Client::new();
+
+    let email = "verifier_jwks@test.com";
+    let body = serde_json::json!({ "email": email, "password": "12345678" });
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+    let response: GenericResponse<AccessTokenData> = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let token = response.data.unwrap().access_token;
+
+    let config = Config::init();
+    let issuer = format!("http://{}", address);
+    let jwks_url = format!("http://{}/.well-known/jwks.json", address);
+    let verifier = Arc::new(TokenVerifier::jwks(&issuer, &jwks_url));
+    let downstream = axum::Router::new()
+        .route(
+            "/whoami",
+            axum::routing::get(|user: AuthenticatedUser| async move { user.subject.to_string() }),
+        )
+        .layer(VerifierLayer::new(verifier.clone()));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let downstream_address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, downstream).await.unwrap() });
+
+    let whoami = |token: Option<String>| {
+        let request = client.get(format!("http://{}/whoami", downstream_address));
+        match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+        .send()
+    };
+    let anonymous = whoami(None).await.unwrap();
+    let forged = whoami(Some(format!("{}x", token))).await.unwrap();
+    let authenticated = whoami(Some(token.clone())).await.unwrap();
+    let authenticated_status = authenticated.status();
+    let subject = authenticated.text().await.unwrap();
+
+    let verified = verifier.verify(&token).await;
+    let missing_scope = TokenVerifier::jwks(&issuer, &jwks_url)
+        .with_required_scopes(&["reports:read"])
+        .verify(&token)
+        .await;
+    let wrong_audience = TokenVerifier::jwks(&issuer, &jwks_url)
+        .with_audience("reports")
+        .verify(&token)
+        .await;
+    let wrong_issuer = TokenVerifier::jwks("https://other.example.com", &jwks_url)
+        .verify(&token)
+        .await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    let user_id = verify_jwt(&config.access_token_public_key, &token)
+        .unwrap()
+        .user_id;
+
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(authenticated_status, StatusCode::OK);
+    assert_eq!(subject, user_id.to_string());
+    assert_eq!(verified.unwrap().subject, user_id);
+    assert!(matches!(
+        missing_scope,
+        Err(VerifyError::InsufficientScope { .. })
+    ));
+    assert!(matches!(
+        wrong_audience,
+        Err(VerifyError::InvalidAudience { .. })
+    ));
+    assert!(matches!(
+        wrong_issuer,
+        Err(VerifyError::InvalidToken { .. })
+    ));
+}
+
+#[cfg(feature = "verifier")]
+#[tokio::test]
+async fn test_verifier_with_introspection_success() {
+    let address = spawn_server().await;
+    let client_id = "verifier_introspection_success";
+    let client_secret = "introspection secret";
+
+    create_confidential_client(client_id, client_secret).await;
+
+    let client = reqwest::Client::new();
+    let token: OAuthTokenData = client
+        .post(format!("http://{}/oauth/token", address))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let verifier = TokenVerifier::introspection(
+        &format!("http://{}/oauth/introspect", address),
+        client_id,
+        client_secret,
+    )
+    .with_required_scopes(&["reports:read"]);
+
+    let active = verifier.verify(&token.access_token).await;
+    let _ = client
+        .post(format!("http://{}/oauth/revoke", address))
+        .form(&[
+            ("token", token.access_token.as_str()),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .unwrap();
+    let revoked = verifier.verify(&token.access_token).await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    let active = active.unwrap();
+    assert_eq!(active.principal_type, PrincipalType::Client);
+    assert!(active.has_scope("reports:write"));
+    assert!(matches!(revoked, Err(VerifyError::InvalidToken { .. })));
+}
+
+#[cfg(feature = "verifier")]
+#[tokio::test]
+async fn test_verifier_with_audience_success() {
+    let address = spawn_server().await;
+    let client_id = "verifier_audience_success";
+    let client_secret = "audience secret";
+
+    create_confidential_client(client_id, client_secret).await;
+
+    let client = reqwest::Client::new();
+    let token: OAuthTokenData = client
+        .post(format!("http://{}/oauth/token", address))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let issuer = format!("http://{}", address);
+    let jwks_url = format!("http://{}/.well-known/jwks.json", address);
+    let own_audience = TokenVerifier::jwks(&issuer, &jwks_url)
+        .with_audience(client_id)
+        .verify(&token.access_token)
+        .await;
+    let other_audience = TokenVerifier::jwks(&issuer, &jwks_url)
+        .with_audience("reports")
+        .verify(&token.access_token)
+        .await;
+    let any_audience = TokenVerifier::jwks(&issuer, &jwks_url)
+        .verify(&token.access_token)
+        .await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(own_audience.unwrap().audience, vec![client_id.to_string()]);
+    assert!(matches!(
+        other_audience,
+        Err(VerifyError::InvalidAudience { .. })
+    ));
+    assert!(any_audience.is_ok());
+}
+
+#[tokio::test]
+async fn test_forward_auth_verify_success() {
+    let address = spawn_server().await;
+
+    let client = reqwest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    let verify_url = format!("http://{}/api/verify", address);
+
+    let email = "forward_auth_verify_success@test.com";
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
+
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+
+    let response: GenericResponse<AccessTokenData> = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let token = response.data.unwrap().access_token;
+
+    let bearer_response = reqwest::Client::new()
+        .get(&verify_url)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap();
+
+    // Envoy's HTTP ext_authz appends the path of the original request to its path prefix.
+    let cookie_response = client
+        .post(format!("{}/orders/42", verify_url))
+        .send()
+        .await
+        .unwrap();
+
+    let anonymous_response = reqwest::Client::new()
+        .get(&verify_url)
+        .send()
+        .await
+        .unwrap();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(bearer_response.status(), StatusCode::OK);
+    assert!(bearer_response.headers().contains_key("x-user-id"));
+    assert_eq!(bearer_response.headers()["x-user-email"], email);
+    assert!(bearer_response.headers().contains_key("x-user-roles"));
+    assert_eq!(cookie_response.status(), StatusCode::OK);
+    assert_eq!(
+        cookie_response.headers()["x-user-id"],
+        bearer_response.headers()["x-user-id"]
+    );
+    assert_eq!(anonymous_response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_forward_auth_cached_identity_cleared_on_logout() {
+    let address = spawn_server_with(|config| config.forward_auth_cache_seconds = 60).await;
+
+    let client = reqwest::Client::new();
+    let verify_url = format!("http://{}/api/verify", address);
+
+    let email = "forward_auth_cache_logout@test.com";
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
+
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+
+    let response: GenericResponse<AccessTokenData> = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let token = response.data.unwrap().access_token;
+
+    let verify = || {
+        client
+            .get(&verify_url)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .send()
+    };
+
+    let first_status = verify().await.unwrap().status();
+    let cached_status = verify().await.unwrap().status();
+
+    let _ = client
+        .get(format!("http://{}/api/logout", address))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .send()
+        .await;
+
+    let logged_out_status = verify().await.unwrap().status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(first_status, StatusCode::OK);
+    assert_eq!(cached_status, StatusCode::OK);
+    assert_eq!(logged_out_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_forward_auth_cached_identity_cleared_on_password_change() {
+    let address = spawn_server_with(|config| config.forward_auth_cache_seconds = 60).await;
+
+    let client = reqwest::Client::new();
+    let verify_url = format!("http://{}/api/verify", address);
+
+    let email = "forward_auth_cache_password@test.com";
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
+
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+
+    let mut tokens = Vec::new();
+    for _ in 0..2 {
+        let response: GenericResponse<AccessTokenData> = client
+            .post(format!("http://{}/api/login", address))
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        tokens.push(response.data.unwrap().access_token);
+    }
+    let (token, other_token) = (&tokens[0], &tokens[1]);
+
+    let verify = || {
+        client
+            .get(&verify_url)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .send()
+    };
+
+    let first_status = verify().await.unwrap().status();
+    let cached_status = verify().await.unwrap().status();
+
+    let password_status = client
+        .post(format!("http://{}/api/users/me/password", address))
+        .header(AUTHORIZATION, format!("Bearer {}", other_token))
+        .json(&serde_json::json!({
+            "current_password": "12345678",
+            "new_password": "87654321"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let revoked_status = verify().await.unwrap().status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(first_status, StatusCode::OK);
+    assert_eq!(cached_status, StatusCode::OK);
+    assert_eq!(password_status, StatusCode::OK);
+    assert_eq!(revoked_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_grpc_verify_token_and_get_user_success() {
+    let (address, grpc_address) = spawn_server_with_grpc().await;
+
+    let email = "grpc_verify_token@test.com";
+    let owner_email = "grpc_verify_token_owner@test.com";
+    let token = register_and_login(address, email).await;
+    let key = create_service_account_key(address, owner_email, "grpc-verify-token").await;
+    let mut grpc = connect_grpc(grpc_address).await;
+
+    let verified = grpc
+        .verify_token(VerifyTokenRequest {
+            access_token: token.clone(),
+        })
+        .await;
+    let invalid = grpc
+        .verify_token(VerifyTokenRequest {
+            access_token: "invalid token".to_string(),
+        })
+        .await;
+    let unknown_user = grpc
+        .get_user(with_api_key(
+            GetUserRequest {
+                user_id: uuid::Uuid::new_v4().to_string(),
+            },
+            &key,
+        ))
+        .await;
+
+    let verified = verified.unwrap().into_inner();
+    let user = grpc
+        .get_user(with_api_key(
+            GetUserRequest {
+                user_id: verified.subject.clone(),
+            },
+            &key,
+        ))
+        .await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email = ANY($1)",
+            &[email.to_string(), owner_email.to_string()]
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert_eq!(verified.user.unwrap().email, email);
+    assert!(verified.session_id.is_some());
+    assert_eq!(invalid.unwrap_err().code(), Code::Unauthenticated);
+    assert_eq!(unknown_user.unwrap_err().code(), Code::NotFound);
+    let user = user.unwrap().into_inner();
+    assert_eq!(user.id, verified.subject);
+    assert_eq!(user.email, email);
+    assert_eq!(user.status, "active");
+}
+
+#[tokio::test]
+async fn test_grpc_revoke_session_success() {
+    let (address, grpc_address) = spawn_server_with_grpc().await;
+
+    let email = "grpc_revoke_session@test.com";
+    let owner_email = "grpc_revoke_session_owner@test.com";
+    let token = register_and_login(address, email).await;
+    let key = create_service_account_key(address, owner_email, "grpc-revoke-session").await;
+    let mut grpc = connect_grpc(grpc_address).await;
+
+    let revoked = grpc
+        .revoke_session(with_api_key(
+            RevokeSessionRequest {
+                access_token: token.clone(),
+            },
+            &key,
+        ))
+        .await;
+    let verified = grpc
+        .verify_token(VerifyTokenRequest {
+            access_token: token.clone(),
+        })
+        .await;
+    let http_status = reqwest::Client::new()
+        .get(format!("http://{}/api/users/me", address))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email = ANY($1)",
+            &[email.to_string(), owner_email.to_string()]
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    assert!(revoked.is_ok());
+    assert_eq!(verified.unwrap_err().code(), Code::Unauthenticated);
+    assert_eq!(http_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_grpc_introspect_and_health_success() {
+    let (address, grpc_address) = spawn_server_with_grpc().await;
+    let client_id = "grpc_introspect_success";
+    let client_secret = "introspection secret";
+    let owner_email = "grpc_introspect_owner@test.com";
+
+    create_confidential_client(client_id, client_secret).await;
+    let key = create_service_account_key(address, owner_email, "grpc-introspect").await;
+
+    let token: OAuthTokenData = reqwest::Client::new()
+        .post(format!("http://{}/oauth/token", address))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let mut grpc = connect_grpc(grpc_address).await;
+    let introspection = grpc
+        .introspect(with_api_key(
+            IntrospectRequest {
+                token: token.access_token.clone(),
+                token_type_hint: None,
+                client_id: client_id.to_string(),
+                client_secret: client_secret.to_string(),
+            },
+            &key,
+        ))
+        .await;
+    let wrong_secret = grpc
+        .introspect(with_api_key(
+            IntrospectRequest {
+                token: token.access_token,
+                token_type_hint: None,
+                client_id: client_id.to_string(),
+                client_secret: "wrong secret".to_string(),
+            },
+            &key,
+        ))
+        .await;
+
+    let channel = Channel::from_shared(format!("http://{}", grpc_address))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let health = tonic_health::pb::health_client::HealthClient::new(channel)
+        .check(tonic_health::pb::HealthCheckRequest {
+            service: "auth.v1.Authentication".to_string(),
+        })
+        .await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!(
+            "DELETE FROM oauth_clients WHERE client_id = $1",
+            client_id
+        ))
+        .await
+        .unwrap();
+        db.execute(sqlx::query!(
+            "DELETE FROM users WHERE email = $1",
+            owner_email
+        ))
+        .await
+        .unwrap();
+    })
+    .await;
+
+    let introspection = introspection.unwrap().into_inner();
+    assert!(introspection.active);
+    assert_eq!(introspection.token_type.as_deref(), Some("Bearer"));
+    assert_eq!(wrong_secret.unwrap_err().code(), Code::Unauthenticated);
+    assert_eq!(
+        health.unwrap().into_inner().status,
+        tonic_health::pb::health_check_response::ServingStatus::Serving as i32
+    );
+}
+
+#[tokio::test]
+async fn test_grpc_unauthenticated_caller_failure() {
+    let (address, grpc_address) = spawn_server_with_grpc().await;
+
+    let email = "grpc_unauthenticated_caller@test.com";
+    let token = register_and_login(address, email).await;
+    let mut grpc = connect_grpc(grpc_address).await;
+
+    let user_id = grpc
+        .verify_token(VerifyTokenRequest {
+            access_token: token.clone(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .subject;
+    let anonymous_get_user = grpc
+        .get_user(GetUserRequest {
+            user_id: user_id.clone(),
+        })
+        .await;
+    let invalid_key_get_user = grpc
+        .get_user(with_api_key(
+            GetUserRequest {
+                user_id: user_id.clone(),
+            },
+            "pat_invalid",
+        ))
+        .await;
+    let user_get_user = grpc
+        .get_user(with_api_key(GetUserRequest { user_id }, &token))
+        .await;
+    let anonymous_revoke = grpc
+        .revoke_session(RevokeSessionRequest {
+            access_token: token.clone(),
+        })
+        .await;
+    let user_revoke = grpc
+        .revoke_session(with_api_key(
+            RevokeSessionRequest {
+                access_token: token.clone(),
+            },
+            &token,
+        ))
+        .await;
+    let anonymous_introspect = grpc
+        .introspect(IntrospectRequest {
+            token: token.clone(),
+            token_type_hint: None,
+            client_id: "grpc_unauthenticated_caller".to_string(),
+            client_secret: "secret".to_string(),
+        })
+        .await;
+    let still_verified = grpc
+        .verify_token(VerifyTokenRequest {
+            access_token: token,
+        })
+        .await;
+
+    clean_up_db(|db| async move {
+        db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    assert_eq!(
+        anonymous_get_user.unwrap_err().code(),
+        Code::Unauthenticated
+    );
+    assert_eq!(
+        invalid_key_get_user.unwrap_err().code(),
+        Code::Unauthenticated
+    );
+    assert_eq!(user_get_user.unwrap_err().code(), Code::PermissionDenied);
+    assert_eq!(anonymous_revoke.unwrap_err().code(), Code::Unauthenticated);
+    assert_eq!(user_revoke.unwrap_err().code(), Code::PermissionDenied);
+    assert_eq!(
+        anonymous_introspect.unwrap_err().code(),
+        Code::Unauthenticated
+    );
+    assert!(still_verified.is_ok());
+}
+
+#[tokio::test]
+async fn test_openapi_document_and_docs_served() {
+    let address = spawn_server().await;
+
+    let response = reqwest::get(format!("http://{}/api/openapi.json", address))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let spec = response.json::<serde_json::Value>().await.unwrap();
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+    assert!(spec["paths"]["/api/register"]["post"].is_object());
+    assert!(spec["paths"]["/api/admin/users/{user_id}"]["patch"].is_object());
+
+    let response = reqwest::get(format!("http://{}/api/docs/", address))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_openapi_documented_operations_are_routed() {
+    let address = spawn_server().await;
+    let client = reqwest::Client::new();
+
+    let spec = reqwest::get(format!("http://{}/api/openapi.json", address))
+        .await
+        .unwrap()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+
+    for (path, item) in spec["paths"].as_object().unwrap() {
+        // Every path parameter is an identifier or a provider name, so any segment will route.
+        let url = path
+            .split('/')
+            .map(|segment| match segment.starts_with('{') {
+                true => uuid::Uuid::nil().to_string(),
+                false => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        for (name, method) in [
+            ("get", reqwest::Method::GET),
+            ("post", reqwest::Method::POST),
+            ("put", reqwest::Method::PUT),
+            ("patch", reqwest::Method::PATCH),
+            ("delete", reqwest::Method::DELETE),
+        ] {
+            if item.get(name).is_none() {
+                continue;
+            }
+            let response = client
+                .request(method.clone(), format!("http://{}{}", address, url))
+                .send()
+                .await
+                .unwrap();
+
+            let status = response.status();
+            // Handlers answer 404 with an error body; only the router's fallback answers empty.
+            let unrouted =
+                status == StatusCode::NOT_FOUND && response.bytes().await.unwrap().is_empty();
+            assert!(
+                !unrouted && status != StatusCode::METHOD_NOT_ALLOWED,
+                "{} {} is documented but not routed ({})",
+                method,
+                path,
+                status
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_healthcheck() {
+    let address = spawn_server().await;
+
+    let url = format!("http://{}/api/healthcheck", address);
+
+    let response = reqwest::get(url).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[cfg(test)]
+async fn spawn_server() -> SocketAddr {
+    spawn_server_with(|_| {}).await
+}
+
+#[cfg(test)]
+async fn spawn_server_with(configure: impl FnOnce(&mut Config)) -> SocketAddr {
+    // Loopback addresses count as secure origins, so `Secure` cookies are sent back to them.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    dotenv().ok();
+    let mut config = Config::init();
+    config.oidc_issuer = format!("http://{}", address);
+    config.oidc_login_url = Some(TEST_LOGIN_URL.to_string());
+    // Lets device flow tests poll back to back without being told to slow down.
+    config.oauth_device_poll_interval_seconds = 0;
+    // Tests reuse their addresses across runs, so their failed logins must not lock them out.
+    config.login_lockout_threshold = i64::MAX;
+    // Only servers of webhook tests deliver webhooks, so no other server claims their deliveries.
+    config.webhook_worker_enabled = false;
+    configure(&mut config);
+
+    tokio::spawn(async move {
+        run(listener, config).await.expect("Failed to run app");
+    });
+
+    address
+}
+
+#[cfg(test)]
+async fn revoke_token_from_redis(access_token: &str) {
+    use authentication_service::api::utils::jwt::verify_jwt;
+
+    let config = Config::init();
+    let access_token_uuid = verify_jwt(&config.access_token_public_key, access_token)
+        .unwrap()
+        .token_uuid;
+    let mut redis_client = Client::open(config.redis_url.to_owned())
+        .unwrap()
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let _: i64 = redis_client
+        .del(access_token_uuid.to_string())
+        .await
+        .unwrap();
+}
+
+#[cfg(test)]
+async fn create_oauth_client(client_id: &str, redirect_uri: &str) {
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    db.execute(sqlx::query!(
+        "INSERT INTO oauth_clients (client_id, name, client_type, redirect_uris, allowed_scopes) VALUES ($1, $1, 'public', $2, $3)",
+        client_id,
+        &[redirect_uri.to_string()],
+        &["openid".to_string(), "email".to_string(), "profile".to_string()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[cfg(test)]
+async fn spawn_server_with_grpc() -> (SocketAddr, SocketAddr) {
+    // Picks a free port for the gRPC API, which the server binds itself.
+    let grpc_address = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let address = spawn_server_with(|config| config.grpc_address = Some(grpc_address)).await;
+    (address, grpc_address)
+}
+
+#[cfg(test)]
+async fn connect_grpc(grpc_address: SocketAddr) -> AuthenticationClient<Channel> {
+    // The gRPC API starts listening shortly after the server is spawned.
+    for _ in 0..50 {
+        if let Ok(client) = AuthenticationClient::connect(format!("http://{}", grpc_address)).await
+        {
+            return client;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("Failed to connect to the gRPC API at {}", grpc_address);
+}
+
+#[cfg(test)]
+async fn register_and_login(address: SocketAddr, email: &str) -> String {
+    let client = reqwest::Client::new();
+    let body = serde_json::json!({
+        "email": email,
+        "password": "12345678"
+    });
+
+    let _ = client
+        .post(format!("http://{}/api/register", address))
+        .json(&body)
+        .send()
+        .await;
+
+    let response: GenericResponse<AccessTokenData> = client
+        .post(format!("http://{}/api/login", address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    response.data.unwrap().access_token
+}
+
+/// Registers `owner_email` as an administrator, creates the service account `name` owned by
+/// them and returns an API key of it. Deleting the owner deletes the service account too.
+#[cfg(test)]
+async fn create_service_account_key(address: SocketAddr, owner_email: &str, name: &str) -> String {
+    let client = reqwest::Client::new();
+    let owner_token = format!("Bearer {}", register_and_login(address, owner_email).await);
+    connect_to_database(&Config::init())
+        .await
+        .execute(sqlx::query!(
+            "UPDATE users SET roles = '{admin}' WHERE email = $1",
+            owner_email
+        ))
+        .await
+        .unwrap();
+
+    let accounts_url = format!("http://{}/api/admin/service-accounts", address);
+    let account: GenericResponse<FilteredUser> = client
+        .post(&accounts_url)
+        .header(AUTHORIZATION, &owner_token)
+        .json(&serde_json::json!({ "name": name }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let key: GenericResponse<serde_json::Value> = client
+        .post(format!(
+            "{}/{}/keys",
+            accounts_url,
+            account.data.unwrap().id
+        ))
+        .header(AUTHORIZATION, &owner_token)
+        .json(&serde_json::json!({ "name": "grpc", "scope": "profile" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    key.data.unwrap()["token"].as_str().unwrap().to_string()
+}
+
+/// A gRPC request authenticated with the API key `key` of a service account.
+#[cfg(test)]
+fn with_api_key<T>(message: T, key: &str) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", format!("Bearer {}", key).parse().unwrap());
+    request
+}
+
+#[cfg(test)]
+async fn create_confidential_client(client_id: &str, client_secret: &str) {
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    db.execute(sqlx::query!(
+        "INSERT INTO oauth_clients (client_id, name, client_type, allowed_scopes, client_secret_hash) VALUES ($1, $1, 'confidential', $2, $3)",
+        client_id,
+        &["reports:read".to_string(), "reports:write".to_string()],
+        hash_password(client_secret).unwrap(),
+    ))
+    .await
+    .unwrap();
+}
+
+#[cfg(test)]
+async fn clean_up_db<F, Fut>(query: F)
+where
+    F: Fn(Pool<Postgres>) -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    let config = Config::init();
+    let db = connect_to_database(&config).await;
+    query(db).await;
+}
+
+#[cfg(test)]
+pub async fn connect_to_database(config: &Config) -> Pool<Postgres> {
+    match PgPoolOptions::new()
+        .max_connections(10)
+        .connect(&config.database_url)
+        .await
+    {
+        Ok(pool) => {
+            println!("Enstablished db connection");
+            pool
+        }
+        Err(err) => {
+            println!("Failed to connecto to db with error: {:?}", err);
+            std::process::exit(1)
+        }
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct GenericResponse<T> {
+    status: Status,
+    data: Option<T>,
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct AccessTokenData {
+    access_token: String,
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct OAuthTokenData {
+    access_token: String,
+    refresh_token: Option<String>,
+    scope: String,
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct OAuthErrorData {
+    error: String,
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct IntrospectionData {
+    active: bool,
+    client_id: Option<String>,
+    scope: Option<String>,
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorizationData {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+}
+
+#[cfg(test)]
+const TEST_LOGIN_URL: &str = "http://localhost:3000/login";
+
+/// A minimal OpenID Connect relying party, driving the provider the way a real client would:
+/// discovery, authorization code flow with PKCE, and ID token validation against the JWKS.
+/// `browser` plays the user agent holding the login cookies, `client` the relying party's backend.
+#[cfg(test)]
+struct RelyingParty {
+    browser: reqwest::Client,
+    client: reqwest::Client,
+    address: SocketAddr,
+    client_id: &'static str,
+}
+
+#[cfg(test)]
+impl RelyingParty {
+    const REDIRECT_URI: &'static str = "http://localhost:8080/oidc/callback";
+    const CODE_VERIFIER: &'static str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    async fn new(address: SocketAddr, client_id: &'static str) -> RelyingParty {
+        create_oauth_client(client_id, Self::REDIRECT_URI).await;
+
+        RelyingParty {
+            browser: reqwest::Client::builder()
+                .cookie_store(true)
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap(),
+            client: reqwest::Client::new(),
+            address,
+            client_id,
+        }
+    }
+
+    async fn login(&self, email: &str) {
+        let body = serde_json::json!({
+            "email": email,
+            "password": "12345678"
+        });
+
+        let _ = self
+            .browser
+            .post(format!("http://{}/api/register", self.address))
+            .json(&body)
+            .send()
+            .await;
+        let _ = self
+            .browser
+            .post(format!("http://{}/api/login", self.address))
+            .json(&body)
+            .send()
+            .await;
+    }
+
+    async fn discover(&self) -> ProviderMetadataData {
+        self.client
+            .get(format!(
+                "http://{}/.well-known/openid-configuration",
+                self.address
+            ))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    /// Sends the user agent to the authorization endpoint and returns the redirect location.
+    async fn authorize(&self, metadata: &ProviderMetadataData, params: &[(&str, &str)]) -> String {
+        let challenge = code_challenge(Self::CODE_VERIFIER);
+        let mut query = vec![
+            ("response_type", "code"),
+            ("client_id", self.client_id),
+            ("redirect_uri", Self::REDIRECT_URI),
+            ("state", "af0ifjsldkj"),
+            ("code_challenge", &challenge),
+            ("code_challenge_method", "S256"),
+        ];
+        query.extend_from_slice(params);
+
+        let response = self
+            .browser
+            .get(&metadata.authorization_endpoint)
+            .query(&query)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        response.headers()["location"].to_str().unwrap().to_string()
+    }
+
+    async fn exchange(&self, metadata: &ProviderMetadataData, code: &str) -> OidcTokenData {
+        self.client
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("client_id", self.client_id),
+                ("code", code),
+                ("redirect_uri", Self::REDIRECT_URI),
+                ("code_verifier", Self::CODE_VERIFIER),
+            ])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    /// Validates the ID token signature with the key named by its `kid`, plus `iss`, `aud` and `exp`.
+    async fn verify_id_token(
+        &self,
+        metadata: &ProviderMetadataData,
+        id_token: &str,
+    ) -> serde_json::Value {
+        let jwks: serde_json::Value = self
+            .client
+            .get(&metadata.jwks_uri)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        let kid = jsonwebtoken::decode_header(id_token).unwrap().kid.unwrap();
+        let jwk = jwks["keys"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|key| key["kid"] == kid.as_str())
+            .unwrap();
+
+        let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256);
+        validation.set_audience(&[self.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+
+        jsonwebtoken::decode::<serde_json::Value>(
+            id_token,
+            &jsonwebtoken::DecodingKey::from_rsa_components(
+                jwk["n"].as_str().unwrap(),
+                jwk["e"].as_str().unwrap(),
+            )
+            .unwrap(),
+            &validation,
+        )
+        .unwrap()
+        .claims
+    }
+
+    async fn clean_up(&self, email: &str) {
+        let email = email.to_string();
+        let client_id = self.client_id;
+        clean_up_db(|db| {
+            let email = email.clone();
+            async move {
+                db.execute(sqlx::query!("DELETE FROM users WHERE email = $1", email))
+                    .await
+                    .unwrap();
+                db.execute(sqlx::query!(
+                    "DELETE FROM oauth_clients WHERE client_id = $1",
+                    client_id
+                ))
+                .await
+                .unwrap();
+            }
+        })
+        .await;
+    }
+}
+
+#[cfg(test)]
+fn query_param(location: &str, name: &str) -> Option<String> {
+    url::Url::parse(location)
+        .unwrap()
+        .query_pairs()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value.to_string())
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct ProviderMetadataData {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+    jwks_uri: String,
+}
+
+#[cfg(test)]
+#[derive(Debug, Deserialize)]
+struct OidcTokenData {
+    access_token: String,
+    id_token: Option<String>,
+}
+
+/// A minimal external OpenID Connect provider for federated login tests.
+///
+/// Its authorization endpoint signs in `subject` without asking, and its ID tokens are signed
+/// with the refresh token key pair, which the service under test never trusts on its own, so
+/// they only validate through the provider's JWKS.
+#[cfg(test)]
+struct MockIdentityProvider {
+    issuer: String,
+}
+
+#[cfg(test)]
+struct MockIdentityProviderState {
+    issuer: String,
+    subject: String,
+    email: String,
+    email_verified: bool,
+    codes: tokio::sync::Mutex<std::collections::HashMap<String, (String, String)>>,
+}
+
+#[cfg(test)]
+impl MockIdentityProvider {
+    const NAME: &'static str = "mock";
+    const CLIENT_ID: &'static str = "federation-client";
+    const CLIENT_SECRET: &'static str = "federation secret";
+
+    async fn spawn(subject: &str, email: &str, email_verified: bool) -> MockIdentityProvider {
+        use axum::{
+            extract::{Form, Query, State},
+            response::Redirect,
+            routing::{get, post},
+            Json, Router,
+        };
+        use std::{collections::HashMap, sync::Arc};
+
+        type Params = HashMap<String, String>;
+        type ProviderState = State<Arc<MockIdentityProviderState>>;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let state = Arc::new(MockIdentityProviderState {
+            issuer: issuer.clone(),
+            subject: subject.to_string(),
+            email: email.to_string(),
+            email_verified,
+            codes: Default::default(),
+        });
+
+        let router = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(|State(state): ProviderState| async move {
+                    Json(serde_json::json!({
+                        "issuer": state.issuer,
+                        "authorization_endpoint": format!("{}/authorize", state.issuer),
+                        "token_endpoint": format!("{}/token", state.issuer),
+                        "jwks_uri": format!("{}/jwks", state.issuer),
+                    }))
+                }),
+            )
+            .route(
+                "/authorize",
+                get(
+                    |State(state): ProviderState, Query(params): Query<Params>| async move {
+                        let code = uuid::Uuid::new_v4().to_string();
+                        state.codes.lock().await.insert(
+                            code.clone(),
+                            (params["nonce"].clone(), params["code_challenge"].clone()),
+                        );
+
+                        let mut location = url::Url::parse(&params["redirect_uri"]).unwrap();
+                        location
+                            .query_pairs_mut()
+                            .append_pair("code", &code)
+                            .append_pair("state", &params["state"]);
+                        Redirect::to(location.as_str())
+                    },
+                ),
+            )
+            .route(
+                "/token",
+                post(
+                    |State(state): ProviderState, Form(params): Form<Params>| async move {
+                        let (nonce, challenge) =
+                            state.codes.lock().await.remove(&params["code"]).unwrap();
+                        if params["client_secret"] != Self::CLIENT_SECRET
+                            || code_challenge(&params["code_verifier"]) != challenge
+                        {
+                            return Json(serde_json::json!({ "error": "invalid_grant" }));
+                        }
+
+                        let config = Config::init();
+                        let now = chrono::Utc::now().timestamp();
+                        let claims = IdTokenClaims {
+                            iss: state.issuer.clone(),
+                            sub: state.subject.clone(),
+                            aud: Self::CLIENT_ID.to_string(),
+                            exp: now + 60,
+                            iat: now,
+                            auth_time: None,
+                            nonce: Some(nonce),
+                            amr: None,
+                            acr: None,
+                            email: state.email.clone(),
+                            email_verified: state.email_verified,
+                        };
+                        let kid = public_jwk(&config.refresh_token_public_key).unwrap().kid;
+                        let id_token =
+                            generate_id_token(&claims, &config.refresh_token_private_key, &kid)
+                                .unwrap();
+
+                        Json(serde_json::json!({
+                            "access_token": "provider-access-token",
+                            "token_type": "Bearer",
+                            "id_token": id_token,
+                        }))
+                    },
+                ),
+            )
+            .route(
+                "/jwks",
+                get(|| async {
+                    let config = Config::init();
+                    Json(serde_json::json!({
+                        "keys": [public_jwk(&config.refresh_token_public_key).unwrap()]
+                    }))
+                }),
+            )
+            .with_state(state);
+
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        MockIdentityProvider { issuer }
+    }
+
+    fn configure(&self, config: &mut Config) {
+        config.federated_providers = vec![FederatedProvider {
+            name: Self::NAME.to_string(),
+            issuer: self.issuer.clone(),
+            client_id: Self::CLIENT_ID.to_string(),
+            client_secret: Self::CLIENT_SECRET.to_string(),
+            scope: "openid email".to_string(),
+        }];
+    }
+}
+
+/// A minimal LDAP server holding one user entry, for testing directory logins without a real
+/// directory. It speaks just enough BER to answer the binds and subtree searches the service
+/// sends: searches return the entry when its email appears in the request.
+#[cfg(test)]
+struct MockLdapServer {
+    url: String,
+}
+
+#[cfg(test)]
+impl MockLdapServer {
+    const BASE_DN: &'static str = "dc=example,dc=com";
+    const ADMINS: &'static str = "cn=admins,ou=groups,dc=example,dc=com";
+
+    async fn spawn(email: &str, password: &str, groups: &[&str]) -> MockLdapServer {
+        use std::sync::Arc;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ldap://{}", listener.local_addr().unwrap());
+
+        let dn = format!("uid={},ou=people,{}", uuid::Uuid::new_v4(), Self::BASE_DN);
+        let entry = Arc::new((
+            dn,
+            email.to_string(),
+            password.to_string(),
+            groups
+                .iter()
+                .map(|group| group.to_string())
+                .collect::<Vec<_>>(),
+        ));
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(Self::serve(stream, entry.clone()));
+            }
+        });
+
+        MockLdapServer { url }
+    }
+
+    async fn serve(
+        mut stream: tokio::net::TcpStream,
+        entry: std::sync::Arc<(String, String, String, Vec<String>)>,
+    ) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (dn, email, password, groups) = entry.as_ref();
+
+        loop {
+            // LDAPMessage ::= SEQUENCE { messageID INTEGER, protocolOp CHOICE { ... }, ... }
+            let mut header = [0u8; 2];
+            if stream.read_exact(&mut header).await.is_err() {
+                return;
+            }
+            let length = match header[1] {
+                length if length < 0x80 => length as usize,
+                long => {
+                    let mut bytes = vec![0u8; (long & 0x7f) as usize];
+                    stream.read_exact(&mut bytes).await.unwrap();
+                    bytes.iter().fold(0, |n, byte| (n << 8) | *byte as usize)
+                }
+            };
+            let mut message = vec![0u8; length];
+            stream.read_exact(&mut message).await.unwrap();
+
+            let (message_id, rest) = ber_next(&message);
+            let (operation, _) = ber_next(rest);
+
+            let mut response = Vec::new();
+            match operation[0] {
+                // BindRequest ::= [APPLICATION 0] SEQUENCE { version, name, simple [0] }
+                0x60 => {
+                    let (_, fields) = ber_next(ber_content(operation));
+                    let (name, fields) = ber_next(fields);
+                    let (credentials, _) = ber_next(fields);
+                    let name = ber_content(name);
+                    let accepted = name.is_empty()
+                        || (name == dn.as_bytes()
+                            && ber_content(credentials) == password.as_bytes());
+                    let result_code = if accepted { 0 } else { 49 };
+                    response.extend(ber_message(message_id, 0x61, &ber_result(result_code)));
+                }
+                // SearchRequest ::= [APPLICATION 3] SEQUENCE { baseObject, ..., filter, ... }
+                0x63 => {
+                    if operation
+                        .windows(email.len())
+                        .any(|window| window == email.as_bytes())
+                    {
+                        let attribute = |name: &str, values: &[String]| {
+                            let values: Vec<u8> = values
+                                .iter()
+                                .flat_map(|value| ber(0x04, value.as_bytes()))
+                                .collect();
+                            ber(
+                                0x30,
+                                &[ber(0x04, name.as_bytes()), ber(0x31, &values)].concat(),
+                            )
+                        };
+                        let attributes = [
+                            attribute("mail", std::slice::from_ref(email)),
+                            attribute("memberOf", groups),
+                        ]
+                        .concat();
+                        let search_entry =
+                            [ber(0x04, dn.as_bytes()), ber(0x30, &attributes)].concat();
+                        response.extend(ber_message(message_id, 0x64, &search_entry));
+                    }
+                    response.extend(ber_message(message_id, 0x65, &ber_result(0)));
+                }
+                // UnbindRequest ::= [APPLICATION 2] NULL
+                _ => return,
+            }
+
+            if stream.write_all(&response).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn configure(&self, config: &mut Config, mode: LdapMode) {
+        config.ldap = Some(LdapConfig {
+            mode,
+            url: self.url.clone(),
+            bind_dn: None,
+            bind_password: String::new(),
+            base_dn: Self::BASE_DN.to_string(),
+            user_filter: "(&(objectClass=person)(mail={login}))".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            group_roles: [(Self::ADMINS.to_string(), "admin".to_string())].into(),
+            grant_admin: true,
+        });
+    }
+}
+
+/// Encodes a BER element with definite length.
+#[cfg(test)]
+fn ber(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut element = vec![tag];
+    match content.len() {
+        length if length < 0x80 => element.push(length as u8),
+        length => {
+            let bytes: Vec<u8> = length
+                .to_be_bytes()
+                .into_iter()
+                .skip_while(|byte| *byte == 0)
+                .collect();
+            element.push(0x80 | bytes.len() as u8);
+            element.extend(bytes);
+        }
+    }
+    element.extend(content);
+    element
+}
+
+/// Splits the first BER element off `bytes`, returning it whole and the bytes after it.
+#[cfg(test)]
+fn ber_next(bytes: &[u8]) -> (&[u8], &[u8]) {
+    let (header, length) = match bytes[1] {
+        length if length < 0x80 => (2, length as usize),
+        long => {
+            let count = (long & 0x7f) as usize;
+            let length = bytes[2..2 + count]
+                .iter()
+                .fold(0, |n, byte| (n << 8) | *byte as usize);
+            (2 + count, length)
+        }
+    };
+    bytes.split_at(header + length)
+}
+
+/// The content of a whole BER element.
+#[cfg(test)]
+fn ber_content(element: &[u8]) -> &[u8] {
+    let header = match element[1] {
+        length if length < 0x80 => 2,
+        long => 2 + (long & 0x7f) as usize,
+    };
+    &element[header..]
+}
+
+/// LDAPResult ::= SEQUENCE { resultCode ENUMERATED, matchedDN, diagnosticMessage }
+#[cfg(test)]
+fn ber_result(result_code: u8) -> Vec<u8> {
+    [ber(0x0a, &[result_code]), ber(0x04, b""), ber(0x04, b"")].concat()
+}
+
+#[cfg(test)]
+fn ber_message(message_id: &[u8], operation: u8, content: &[u8]) -> Vec<u8> {
+    ber(0x30, &[message_id, &ber(operation, content)].concat())
+}
+
+/// Signs SAML responses the way an identity provider would, with the key pair in
+/// `tests/fixtures/saml`. Assertions are written in canonical form, so their digest is taken
+/// over the text as is.
+#[cfg(test)]
+struct MockSamlIdentityProvider;
+
+#[cfg(test)]
+impl MockSamlIdentityProvider {
+    const NAME: &'static str = "mock";
+    const ENTITY_ID: &'static str = "https://idp.example.com/saml";
+
+    fn configure(config: &mut Config) {
+        config.saml_identity_providers = vec![SamlIdentityProvider {
+            name: Self::NAME.to_string(),
+            entity_id: Self::ENTITY_ID.to_string(),
+            sso_url: "https://idp.example.com/saml/sso".to_string(),
+            certificate: include_str!("fixtures/saml/idp_certificate.pem").to_string(),
+            email_attribute: Some("email".to_string()),
+            trust_email: true,
+        }];
+    }
+
+    /// Answers the `AuthnRequest` in the redirect `location` with a signed assertion for `email`.
+    fn respond(address: &SocketAddr, location: &str, email: &str) -> String {
+        use base64::{engine::general_purpose, Engine};
+        use rsa::{
+            pkcs1v15::SigningKey,
+            pkcs8::DecodePrivateKey,
+            signature::{SignatureEncoding, Signer},
+            RsaPrivateKey,
+        };
+        use sha2::{Digest, Sha256};
+        use std::io::Read;
+
+        let request = general_purpose::STANDARD
+            .decode(query_param(location, "SAMLRequest").unwrap())
+            .unwrap();
+        let mut request_xml = String::new();
+        flate2::read::DeflateDecoder::new(request.as_slice())
+            .read_to_string(&mut request_xml)
+            .unwrap();
+        let request_id = roxmltree::Document::parse(&request_xml)
+            .unwrap()
+            .root_element()
+            .attribute("ID")
+            .unwrap()
+            .to_string();
+
+        let acs_url = format!("http://{}/api/saml/{}/acs", address, Self::NAME);
+        let audience = format!("http://{}/api/saml/{}/metadata", address, Self::NAME);
+        let now = chrono::Utc::now();
+        let format =
+            |time: chrono::DateTime<chrono::Utc>| time.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let issue_instant = format(now);
+        let not_before = format(now - chrono::Duration::minutes(1));
+        let not_on_or_after = format(now + chrono::Duration::minutes(5));
+        let assertion_id = format!("_{}", uuid::Uuid::new_v4());
+
+        let assertion = format!(
+            r#"<saml:Assertion xmlns:saml="urn:oasis:names:tc:SAML:2.0:assertion" ID="{assertion_id}" IssueInstant="{issue_instant}" Version="2.0"><saml:Issuer>{issuer}</saml:Issuer><saml:Subject><saml:NameID>{email}</saml:NameID><saml:SubjectConfirmation Method="urn:oasis:names:tc:SAML:2.0:cm:bearer"><saml:SubjectConfirmationData InResponseTo="{request_id}" NotOnOrAfter="{not_on_or_after}" Recipient="{acs_url}"></saml:SubjectConfirmationData></saml:SubjectConfirmation></saml:Subject><saml:Conditions NotBefore="{not_before}" NotOnOrAfter="{not_on_or_after}"><saml:AudienceRestriction><saml:Audience>{audience}</saml:Audience></saml:AudienceRestriction></saml:Conditions><saml:AttributeStatement><saml:Attribute Name="email"><saml:AttributeValue>{email}</saml:AttributeValue></saml:Attribute></saml:AttributeStatement></saml:Assertion>"#,
+            issuer = Self::ENTITY_ID,
+        );
+        let digest = general_purpose::STANDARD.encode(Sha256::digest(&assertion));
+
+        let signed_info = format!(
+            r##"<ds:SignedInfo xmlns:ds="http://www.w3.org/2000/09/xmldsig#"><ds:CanonicalizationMethod Algorithm="http://www.w3.org/2001/10/xml-exc-c14n#"></ds:CanonicalizationMethod><ds:SignatureMethod Algorithm="http://www.w3.org/2001/04/xmldsig-more#rsa-sha256"></ds:SignatureMethod><ds:Reference URI="#{assertion_id}"><ds:Transforms><ds:Transform Algorithm="http://www.w3.org/2000/09/xmldsig#enveloped-signature"></ds:Transform><ds:Transform Algorithm="http://www.w3.org/2001/10/xml-exc-c14n#"></ds:Transform></ds:Transforms><ds:DigestMethod Algorithm="http://www.w3.org/2001/04/xmlenc#sha256"></ds:DigestMethod><ds:DigestValue>{digest}</ds:DigestValue></ds:Reference></ds:SignedInfo>"##
+        );
+        let key = RsaPrivateKey::from_pkcs8_pem(include_str!("fixtures/saml/idp_private_key.pem"))
+            .unwrap();
+        let signature = general_purpose::STANDARD.encode(
+            SigningKey::<Sha256>::new(key)
+                .sign(signed_info.as_bytes())
+                .to_bytes(),
+        );
+
+        let issuer_end = assertion.find("</saml:Issuer>").unwrap() + "</saml:Issuer>".len();
+        let signed_assertion = format!(
+            r#"{}<ds:Signature xmlns:ds="http://www.w3.org/2000/09/xmldsig#">{}<ds:SignatureValue>{}</ds:SignatureValue></ds:Signature>{}"#,
+            &assertion[..issuer_end],
+            signed_info.replace(r#" xmlns:ds="http://www.w3.org/2000/09/xmldsig#""#, ""),
+            signature,
+            &assertion[issuer_end..]
+        );
+
+        let response = format!(
+            r#"<samlp:Response xmlns:samlp="urn:oasis:names:tc:SAML:2.0:protocol" ID="_{}" InResponseTo="{request_id}" Version="2.0" IssueInstant="{issue_instant}" Destination="{acs_url}"><samlp:Status><samlp:StatusCode Value="urn:oasis:names:tc:SAML:2.0:status:Success"/></samlp:Status>{signed_assertion}</samlp:Response>"#,
+            uuid::Uuid::new_v4()
+        );
+
+        general_purpose::STANDARD.encode(response)
+    }
 }